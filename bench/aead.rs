@@ -145,10 +145,50 @@ fn open_in_place(c: &mut Criterion) {
     }
 }
 
+// A QUIC header protection mask is a single block operation, so the cost of
+// getting the CPU features on each call is significant.
+fn quic_new_mask(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aead");
+
+    static QUIC_ALGORITHMS: &[(&str, &aead::quic::Algorithm)] = &[
+        ("aes128", &aead::quic::AES_128),
+        ("chacha20", &aead::quic::CHACHA20),
+    ];
+
+    let rng = SystemRandom::new();
+
+    for &(alg_name, algorithm) in QUIC_ALGORITHMS {
+        let mut key_bytes = vec![0u8; algorithm.key_len()];
+        rng.fill(&mut key_bytes).unwrap();
+        let key = aead::quic::HeaderProtectionKey::new(algorithm, &key_bytes).unwrap();
+        let sample = vec![0u8; algorithm.sample_len()];
+
+        group.bench_function(format!("quic_{}::new_mask", alg_name), |b| {
+            b.iter(|| key.new_mask(black_box(&sample)).unwrap())
+        });
+
+        group.bench_function(
+            format!("quic_{}::new_mask_with_cpu_features", alg_name),
+            |b| {
+                let cpu_features = ring::cpu::features();
+                b.iter(|| {
+                    key.new_mask_with_cpu_features(black_box(&sample), cpu_features)
+                        .unwrap()
+                })
+            },
+        );
+    }
+}
+
 fn bench_id(func_name: &str, alg_name: &str, record_len: usize) -> BenchmarkId {
     BenchmarkId::new(format!("{}::{}", alg_name, func_name), record_len)
 }
 
-criterion_group!(aead, seal_in_place_separate_tag, open_in_place);
+criterion_group!(
+    aead,
+    seal_in_place_separate_tag,
+    open_in_place,
+    quic_new_mask
+);
 
 criterion_main!(aead);
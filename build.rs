@@ -454,6 +454,8 @@ fn build_c_code(
         })
         .collect::<Vec<_>>();
 
+    emit_asm_modules(asm_target, &asm_srcs, &obj_srcs, &core_srcs);
+
     let test_srcs = RING_TEST_SRCS.iter().map(PathBuf::from).collect::<Vec<_>>();
 
     let libs = [
@@ -493,6 +495,36 @@ fn build_c_code(
     );
 }
 
+// Keep in sync with `BuildInfo::asm_modules()` in src/build_info.rs.
+fn emit_asm_modules(
+    asm_target: Option<&AsmTarget>,
+    asm_srcs: &[PathBuf],
+    obj_srcs: &[PathBuf],
+    core_srcs: &[PathBuf],
+) {
+    let perlasm_suffix = asm_target.map(|asm_target| format!("-{}", asm_target.perlasm_format));
+    let mut asm_modules = asm_srcs
+        .iter()
+        .chain(obj_srcs)
+        .map(|p| {
+            let stem = p.file_stem().unwrap().to_str().unwrap();
+            perlasm_suffix
+                .as_deref()
+                .and_then(|suffix| stem.strip_suffix(suffix))
+                .unwrap_or(stem)
+        })
+        .chain(
+            core_srcs
+                .iter()
+                .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("S"))
+                .map(|p| p.file_stem().unwrap().to_str().unwrap()),
+        )
+        .collect::<Vec<_>>();
+    asm_modules.sort_unstable();
+    asm_modules.dedup();
+    println!("cargo:rustc-env=RING_ASM_MODULES={}", asm_modules.join(","));
+}
+
 fn new_build(target: &Target, c_root_dir: &Path, include_dir: &Path) -> cc::Build {
     let mut b = cc::Build::new();
     configure_cc(&mut b, target, c_root_dir, include_dir);
@@ -934,6 +966,7 @@ fn prefix_all_symbols(pp: char, prefix_prefix: &str, prefix: &str) -> String {
         "vpaes_encrypt_key_to_bsaes",
         "vpaes_set_encrypt_key",
         "x25519_NEON",
+        "x25519_edwards_y_from_montgomery_u",
         "x25519_fe_invert",
         "x25519_fe_isnegative",
         "x25519_fe_mul_ttt",
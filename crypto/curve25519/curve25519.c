@@ -1907,6 +1907,22 @@ void x25519_fe_tobytes(uint8_t s[32], const fe *h) {
   fe_tobytes(s, h);
 }
 
+// Computes the Edwards25519 y coordinate of the point with Curve25519 u
+// coordinate |u|, y = (u - 1) / (u + 1). The most significant bit of |u| is
+// ignored, and when u + 1 is zero the result is zero.
+void x25519_edwards_y_from_montgomery_u(uint8_t out_y[32],
+                                        const uint8_t u[32]) {
+  fe u_fe, one, uplus1_inv, y;
+  fe_loose uminus1, uplus1;
+  fe_frombytes(&u_fe, u);
+  fe_1(&one);
+  fe_sub(&uminus1, &u_fe, &one);
+  fe_add(&uplus1, &u_fe, &one);
+  fe_loose_invert(&uplus1_inv, &uplus1);
+  fe_mul_tlt(&y, &uminus1, &uplus1_inv);
+  fe_tobytes(out_y, &y);
+}
+
 void x25519_ge_double_scalarmult_vartime(ge_p2 *r, const uint8_t *a,
                                              const ge_p3 *A, const uint8_t *b) {
   ge_double_scalarmult_vartime(r, a, A, b);
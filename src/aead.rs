@@ -27,14 +27,25 @@ use crate::{
 };
 
 pub use self::{
-    algorithm::{Algorithm, AES_128_GCM, AES_256_GCM, CHACHA20_POLY1305},
+    algorithm::{
+        Algorithm, UsageLimits, AES_128_GCM, AES_128_GCM_SIV, AES_256_GCM, AES_256_GCM_SIV,
+        AES_256_GCM_TAG64, AES_256_GCM_TAG96, CHACHA20_POLY1305,
+    },
+    channel::{channel_keys, ChannelNonceSequence, ChannelSide},
+    forward_secure::{ForwardSecureOpener, ForwardSecureSealer, FORWARD_SECURE_MAX_RECORDS},
+    key_update::{KeyUpdate, TrafficNonceSequence},
     less_safe_key::LessSafeKey,
-    nonce::{Nonce, NONCE_LEN},
+    nonce::{nonce_from_counter, Nonce, SequenceNonce, NONCE_LEN},
     opening_key::OpeningKey,
     sealing_key::SealingKey,
+    tls_record::{tls12_record_aad, tls13_record_aad},
     unbound_key::UnboundKey,
+    xchacha20_poly1305::XCHACHA20_POLY1305,
 };
 
+#[cfg(feature = "std")]
+pub use self::stream::{OpeningReader, SealingWriter};
+
 /// A sequences of unique nonces.
 ///
 /// A given `NonceSequence` must never return the same `Nonce` twice from
@@ -121,6 +132,7 @@ impl<A> Eq for Aad<A> where A: Eq {}
 #[derive(Clone)]
 enum KeyInner {
     AesGcm(aes_gcm::Key),
+    AesGcmSiv(aes_gcm_siv::Key),
     ChaCha20Poly1305(chacha20_poly1305::Key),
 }
 
@@ -133,14 +145,51 @@ const fn max_input_len(block_len: usize, overhead_blocks_per_nonce: usize) -> us
 }
 
 /// A possibly valid authentication tag.
+///
+/// A tag is normally 128 bits, but it is shorter for the algorithms that use
+/// truncated tags, e.g. `AES_256_GCM_TAG96`. Use [`Tag::try_for_algorithm()`]
+/// to construct a truncated tag.
 #[must_use]
 #[repr(C)]
 #[derive(Clone, Copy)]
-pub struct Tag([u8; TAG_LEN]);
+pub struct Tag {
+    value: [u8; TAG_LEN],
+    len: u8,
+}
+
+impl Tag {
+    /// Constructs a tag for `algorithm` from `value`.
+    ///
+    /// Fails unless `value.len() == algorithm.tag_len()`. Unlike
+    /// `Tag::try_from()`, this accepts the shorter tags of the algorithms
+    /// that use truncated tags.
+    pub fn try_for_algorithm(
+        algorithm: &Algorithm,
+        value: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        if value.len() != algorithm.tag_len() {
+            return Err(error::Unspecified);
+        }
+        let mut tag = [0u8; TAG_LEN];
+        tag[..value.len()].copy_from_slice(value);
+        Ok(Self::from(tag).truncated(value.len()))
+    }
+
+    // Returns the first `len` bytes of the tag, as described in
+    // NIST SP 800-38D Section 5.2.1.2.
+    #[inline]
+    fn truncated(self, len: usize) -> Self {
+        debug_assert!(len <= usize::from(self.len));
+        // `len` is at most `TAG_LEN`, so it fits in a `u8`.
+        #[allow(clippy::cast_possible_truncation)]
+        let len = len as u8;
+        Self { len, ..self }
+    }
+}
 
 impl AsRef<[u8]> for Tag {
     fn as_ref(&self) -> &[u8] {
-        self.0.as_ref()
+        &self.value[..usize::from(self.len)]
     }
 }
 
@@ -156,30 +205,48 @@ impl TryFrom<&[u8]> for Tag {
 impl From<[u8; TAG_LEN]> for Tag {
     #[inline]
     fn from(value: [u8; TAG_LEN]) -> Self {
-        Self(value)
+        #[allow(clippy::cast_possible_truncation)]
+        const LEN: u8 = TAG_LEN as u8;
+        Self { value, len: LEN }
     }
 }
 
 const MAX_KEY_LEN: usize = 32;
 
-// All the AEADs we support use 128-bit tags.
+// All the AEADs we support use 128-bit tags, except for the truncated-tag
+// AES-GCM variants.
 const TAG_LEN: usize = 16;
+const TAG96_LEN: usize = 96 / 8;
+const TAG64_LEN: usize = 64 / 8;
 
 /// The maximum length of a tag for the algorithms in this module.
 pub const MAX_TAG_LEN: usize = TAG_LEN;
 
-mod aes;
-mod aes_gcm;
+pub(crate) mod aes;
+pub mod aes_gcm;
+mod aes_gcm_siv;
 mod algorithm;
+#[cfg(feature = "legacy-cbc")]
+pub mod cbc_hmac;
 mod chacha;
-mod chacha20_poly1305;
+pub mod chacha20_poly1305;
 pub mod chacha20_poly1305_openssh;
+pub mod chacha20_poly1305_seek;
+mod channel;
+pub mod committing;
+mod forward_secure;
 mod gcm;
+pub mod gmac;
+mod key_update;
 mod less_safe_key;
+pub mod multi_recipient;
 mod nonce;
 mod opening_key;
 mod poly1305;
 pub mod quic;
 mod sealing_key;
 mod shift;
+pub mod stream;
+mod tls_record;
 mod unbound_key;
+pub mod xchacha20_poly1305;
@@ -18,8 +18,13 @@ use cfg_if::cfg_if;
 use core::ops::RangeFrom;
 
 pub(super) use ffi::Counter;
+#[cfg(feature = "alloc")]
+pub(crate) use inv_cipher::DecryptKey;
+
 #[macro_use]
 mod ffi;
+#[cfg(feature = "alloc")]
+mod inv_cipher;
 
 cfg_if! {
     if #[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))] {
@@ -30,7 +35,7 @@ cfg_if! {
 }
 
 #[derive(Clone)]
-pub(super) struct Key {
+pub(crate) struct Key {
     inner: AES_KEY,
 }
 
@@ -219,8 +224,8 @@ impl Key {
         }
     }
 
-    pub fn new_mask(&self, sample: Sample) -> [u8; 5] {
-        let [b0, b1, b2, b3, b4, ..] = self.encrypt_block(sample, cpu::features());
+    pub fn new_mask(&self, sample: Sample, cpu_features: cpu::Features) -> [u8; 5] {
+        let [b0, b1, b2, b3, b4, ..] = self.encrypt_block(sample, cpu_features);
         [b0, b1, b2, b3, b4]
     }
 
@@ -255,6 +260,12 @@ impl Counter {
         Self(value)
     }
 
+    /// "Less safe" because the caller is responsible for ensuring that `j0`
+    /// is never reused and that the counter never wraps.
+    pub fn from_j0_less_safe(j0: Block) -> Self {
+        Self(j0)
+    }
+
     pub fn increment(&mut self) -> Iv {
         let iv = Iv(self.0);
         self.increment_by_less_safe(1);
@@ -288,10 +299,22 @@ impl Iv {
     }
 }
 
-pub(super) type Block = [u8; BLOCK_LEN];
-pub(super) const BLOCK_LEN: usize = 16;
+pub(crate) type Block = [u8; BLOCK_LEN];
+pub(crate) const BLOCK_LEN: usize = 16;
 pub(super) const ZERO_BLOCK: Block = [0u8; BLOCK_LEN];
 
+// Every implementation is constant-time; none of them uses lookup tables in
+// memory, whose access patterns would depend on the key and the data:
+//
+//  * HWAES uses the CPU's AES instructions.
+//  * VPAES_BSAES uses vpaes, which computes the S-box with SIMD byte
+//    shuffles of constants held in registers, and, on "arm", bsaes, which is
+//    bitsliced.
+//  * NOHW is bitsliced, using only general-purpose registers.
+//
+// Without AES instructions, VPAES_BSAES is used when SIMD is available,
+// unless the `force-constant-time-software-aes` feature is enabled, in which
+// case NOHW is always used.
 #[derive(Clone, Copy)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum Implementation {
@@ -310,6 +333,8 @@ pub enum Implementation {
     NOHW,
 }
 
+const FORCE_NOHW: bool = cfg!(feature = "force-constant-time-software-aes");
+
 fn detect_implementation(cpu_features: cpu::Features) -> Implementation {
     // `cpu_features` is only used for specific platforms.
     #[cfg(not(any(
@@ -336,14 +361,14 @@ fn detect_implementation(cpu_features: cpu::Features) -> Implementation {
 
     #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
     {
-        if cpu::intel::SSSE3.available(cpu_features) {
+        if !FORCE_NOHW && cpu::intel::SSSE3.available(cpu_features) {
             return Implementation::VPAES_BSAES;
         }
     }
 
     #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
     {
-        if cpu::arm::NEON.available(cpu_features) {
+        if !FORCE_NOHW && cpu::arm::NEON.available(cpu_features) {
             return Implementation::VPAES_BSAES;
         }
     }
@@ -429,6 +454,49 @@ mod tests {
         })
     }
 
+    // The bitsliced implementation is correct regardless of which
+    // implementation is selected, e.g. for the FIPS 197 Appendix C vectors.
+    #[test]
+    pub fn test_aes_nohw() {
+        let cpu_features = cpu::features();
+        test::run(test_file!("aes_tests.txt"), |section, test_case| {
+            assert_eq!(section, "");
+            let key = test_case.consume_bytes("Key");
+            let key = match key.len() {
+                16 => KeyBytes::AES_128(key[..].try_into().unwrap()),
+                32 => KeyBytes::AES_256(key[..].try_into().unwrap()),
+                _ => unreachable!(),
+            };
+            let input = test_case.consume_bytes("Input");
+            let block: Block = input.as_slice().try_into()?;
+            let expected_output = test_case.consume_bytes("Output");
+
+            // SAFETY: `aes_nohw_set_encrypt_key` satisfies the
+            // `set_encrypt_key!` contract.
+            let key = unsafe { set_encrypt_key!(aes_nohw_set_encrypt_key, key, cpu_features) }?;
+            // SAFETY: `key` was initialized with `aes_nohw_set_encrypt_key`.
+            let output = unsafe { encrypt_block!(aes_nohw_encrypt, block, &key) };
+            assert_eq!(output.as_ref(), &expected_output[..]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    pub fn test_force_constant_time_software_aes() {
+        let implementation = detect_implementation(cpu::features());
+        #[cfg(any(
+            target_arch = "aarch64",
+            target_arch = "arm",
+            target_arch = "x86_64",
+            target_arch = "x86"
+        ))]
+        if FORCE_NOHW {
+            assert!(!matches!(implementation, Implementation::VPAES_BSAES));
+        }
+        let _ = implementation;
+    }
+
     fn consume_key(test_case: &mut test::TestCase, name: &str) -> Key {
         let key = test_case.consume_bytes(name);
         let key = &key[..];
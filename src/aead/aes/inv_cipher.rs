@@ -0,0 +1,196 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The AES inverse cipher ([FIPS 197] Section 5.3).
+//!
+//! None of *ring*'s AEADs need to decrypt with AES, so the assembly language
+//! implementations don't provide it. This is a simple portable
+//! implementation, only used by CBC mode, in the legacy AEAD and for
+//! encrypted PKCS#8 keys. It doesn't use any lookup tables;
+//! the S-box is computed using arithmetic in GF(2**8), so its timing doesn't
+//! depend on the key or the data. It is slow, which is acceptable for legacy
+//! uses.
+//!
+//! [FIPS 197]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.197-upd1.pdf
+
+use super::{Block, KeyBytes, BLOCK_LEN};
+
+// The number of rounds for AES-256. AES-128 uses fewer.
+const MAX_ROUNDS: usize = 14;
+
+#[derive(Clone)]
+pub(crate) struct DecryptKey {
+    round_keys: [Block; MAX_ROUNDS + 1],
+    rounds: usize,
+}
+
+impl DecryptKey {
+    pub(crate) fn new(key: KeyBytes) -> Self {
+        let (key, rounds): (&[u8], usize) = match key {
+            KeyBytes::AES_128(key) => (key, 10),
+            KeyBytes::AES_256(key) => (key, 14),
+        };
+
+        // FIPS 197 Section 5.2, KeyExpansion(), one word at a time.
+        let nk = key.len() / 4;
+        let mut words = [[0u8; 4]; 4 * (MAX_ROUNDS + 1)];
+        words
+            .iter_mut()
+            .zip(key.chunks_exact(4))
+            .for_each(|(w, k)| w.copy_from_slice(k));
+        let mut rcon = 1u8;
+        for i in nk..(4 * (rounds + 1)) {
+            let mut temp = words[i - 1];
+            if i % nk == 0 {
+                temp = [temp[1], temp[2], temp[3], temp[0]];
+                temp.iter_mut().for_each(|b| *b = sub_byte(*b));
+                temp[0] ^= rcon;
+                rcon = xtime(rcon);
+            } else if nk > 6 && i % nk == 4 {
+                temp.iter_mut().for_each(|b| *b = sub_byte(*b));
+            }
+            for j in 0..4 {
+                words[i][j] = words[i - nk][j] ^ temp[j];
+            }
+        }
+
+        let mut round_keys = [[0u8; BLOCK_LEN]; MAX_ROUNDS + 1];
+        round_keys
+            .iter_mut()
+            .zip(words.chunks_exact(4))
+            .for_each(|(rk, w)| {
+                rk.chunks_exact_mut(4)
+                    .zip(w)
+                    .for_each(|(rk, w)| rk.copy_from_slice(w))
+            });
+        Self { round_keys, rounds }
+    }
+
+    pub(crate) fn decrypt_block(&self, input: Block) -> Block {
+        // FIPS 197 Section 5.3, InvCipher().
+        let mut state = input;
+        add_round_key(&mut state, &self.round_keys[self.rounds]);
+        for round in (1..self.rounds).rev() {
+            inv_shift_rows(&mut state);
+            state.iter_mut().for_each(|b| *b = inv_sub_byte(*b));
+            add_round_key(&mut state, &self.round_keys[round]);
+            inv_mix_columns(&mut state);
+        }
+        inv_shift_rows(&mut state);
+        state.iter_mut().for_each(|b| *b = inv_sub_byte(*b));
+        add_round_key(&mut state, &self.round_keys[0]);
+        state
+    }
+}
+
+fn add_round_key(state: &mut Block, round_key: &Block) {
+    state
+        .iter_mut()
+        .zip(round_key.iter())
+        .for_each(|(s, k)| *s ^= *k);
+}
+
+// The state is stored column by column, so byte `r + 4*c` is row `r`,
+// column `c`. Row `r` is rotated right by `r` positions.
+fn inv_shift_rows(state: &mut Block) {
+    let s = *state;
+    for c in 0..4 {
+        for r in 0..4 {
+            state[r + (4 * ((c + r) % 4))] = s[r + (4 * c)];
+        }
+    }
+}
+
+fn inv_mix_columns(state: &mut Block) {
+    for column in state.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        column[0] = mul(a0, 0x0e) ^ mul(a1, 0x0b) ^ mul(a2, 0x0d) ^ mul(a3, 0x09);
+        column[1] = mul(a0, 0x09) ^ mul(a1, 0x0e) ^ mul(a2, 0x0b) ^ mul(a3, 0x0d);
+        column[2] = mul(a0, 0x0d) ^ mul(a1, 0x09) ^ mul(a2, 0x0e) ^ mul(a3, 0x0b);
+        column[3] = mul(a0, 0x0b) ^ mul(a1, 0x0d) ^ mul(a2, 0x09) ^ mul(a3, 0x0e);
+    }
+}
+
+fn sub_byte(b: u8) -> u8 {
+    let x = inv(b);
+    x ^ x.rotate_left(1) ^ x.rotate_left(2) ^ x.rotate_left(3) ^ x.rotate_left(4) ^ 0x63
+}
+
+fn inv_sub_byte(b: u8) -> u8 {
+    inv(b.rotate_left(1) ^ b.rotate_left(3) ^ b.rotate_left(6) ^ 0x05)
+}
+
+// Returns `x**254`, which is `x**-1` for nonzero `x` and zero for zero.
+fn inv(x: u8) -> u8 {
+    let mut result = 1;
+    let mut square = x;
+    for _ in 1..8 {
+        square = mul(square, square);
+        result = mul(result, square);
+    }
+    result
+}
+
+// Multiplication in GF(2**8) modulo x**8 + x**4 + x**3 + x + 1, without
+// branches or table lookups that depend on the inputs.
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut r = 0;
+    for _ in 0..8 {
+        r ^= a & (b & 1).wrapping_neg();
+        a = xtime(a);
+        b >>= 1;
+    }
+    r
+}
+
+fn xtime(a: u8) -> u8 {
+    (a << 1) ^ (0x1b & (a >> 7).wrapping_neg())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_aes_decrypt() {
+        test::run(test_file!("../aes_tests.txt"), |section, test_case| {
+            assert_eq!(section, "");
+            let key = test_case.consume_bytes("Key");
+            let plaintext = test_case.consume_bytes("Input");
+            let ciphertext = test_case.consume_bytes("Output");
+
+            let key = match key.len() {
+                16 => KeyBytes::AES_128(key.as_slice().try_into()?),
+                32 => KeyBytes::AES_256(key.as_slice().try_into()?),
+                _ => unreachable!(),
+            };
+            let ciphertext: Block = ciphertext.as_slice().try_into()?;
+
+            let output = DecryptKey::new(key).decrypt_block(ciphertext);
+            assert_eq!(&output[..], &plaintext[..]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_sbox() {
+        // FIPS 197 Figure 7 and Figure 14.
+        assert_eq!(sub_byte(0x00), 0x63);
+        assert_eq!(sub_byte(0x53), 0xed);
+        assert_eq!(sub_byte(0xff), 0x16);
+        (0..=255).for_each(|b| assert_eq!(inv_sub_byte(sub_byte(b)), b));
+    }
+}
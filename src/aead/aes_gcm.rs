@@ -12,9 +12,14 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
+//! Low-level AES-GCM operations.
+//!
+//! Almost all users should use [`super::LessSafeKey`] or the other AEAD keys
+//! instead.
+
 use super::{
     aes::{self, Counter, BLOCK_LEN, ZERO_BLOCK},
-    gcm, shift, Aad, Nonce, Tag,
+    gcm, shift, Aad, KeyInner, LessSafeKey, Nonce, Tag,
 };
 use crate::{
     cpu, error,
@@ -23,7 +28,7 @@ use crate::{
 use core::ops::RangeFrom;
 
 #[derive(Clone)]
-pub struct Key {
+pub(super) struct Key {
     gcm_key: gcm::Key, // First because it has a large alignment requirement.
     aes_key: aes::Key,
 }
@@ -44,18 +49,75 @@ impl Key {
 
 const CHUNK_BLOCKS: usize = 3 * 1024 / 16;
 
+/// Encrypts and authenticates `in_out` using the given pre-counter block
+/// `j0`, as defined in [NIST SP 800-38D] Section 7.1, returning the tag.
+///
+/// This is for interoperability with hardware and other implementations that
+/// take J0 instead of a nonce; `seal_with_j0(key, nonce || 00000001, ..)` is
+/// equivalent to `key.seal_in_place_separate_tag(nonce, ..)`. It is
+/// dangerous: `j0` must be unique for every use of the key, and the values of
+/// the counter that are derived from `j0` must not overlap with those derived
+/// from any other J0 used with the key, which can't be checked here.
+///
+/// Fails if `key` isn't an AES-GCM key, if `in_out` is too long, or if the
+/// 32-bit counter in the last four bytes of `j0` would wrap around while
+/// encrypting `in_out`.
+///
+/// [NIST SP 800-38D]:
+///     http://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+pub fn seal_with_j0<A>(
+    key: &LessSafeKey,
+    j0: [u8; BLOCK_LEN],
+    aad: Aad<A>,
+    in_out: &mut [u8],
+) -> Result<Tag, error::Unspecified>
+where
+    A: AsRef<[u8]>,
+{
+    let aes_gcm_key = match key.inner() {
+        KeyInner::AesGcm(aes_gcm_key) => aes_gcm_key,
+        KeyInner::AesGcmSiv(_) | KeyInner::ChaCha20Poly1305(_) => return Err(error::Unspecified),
+    };
+
+    // One block for the tag, then one for each (partial) block of `in_out`.
+    let blocks = (in_out.len() / BLOCK_LEN) + usize::from(in_out.len() % BLOCK_LEN != 0) + 1;
+    let blocks = u32::try_from(blocks).map_err(|_| error::Unspecified)?;
+    let [.., c0, c1, c2, c3] = j0;
+    let _last: u32 = u32::from_be_bytes([c0, c1, c2, c3])
+        .checked_add(blocks)
+        .ok_or(error::Unspecified)?;
+
+    let tag = seal_(
+        aes_gcm_key,
+        Counter::from_j0_less_safe(j0),
+        Aad::from(aad.as_ref()),
+        in_out,
+        cpu::features(),
+    )?;
+    Ok(tag.truncated(key.algorithm().tag_len()))
+}
+
 pub(super) fn seal(
     key: &Key,
     nonce: Nonce,
     aad: Aad<&[u8]>,
     in_out: &mut [u8],
     cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    seal_(key, Counter::one(nonce), aad, in_out, cpu_features)
+}
+
+fn seal_(
+    key: &Key,
+    mut ctr: Counter,
+    aad: Aad<&[u8]>,
+    in_out: &mut [u8],
+    cpu_features: cpu::Features,
 ) -> Result<Tag, error::Unspecified> {
     let Key { gcm_key, aes_key } = key;
 
     let mut auth = gcm::Context::new(gcm_key, aad, in_out.len(), cpu_features)?;
 
-    let mut ctr = Counter::one(nonce);
     let tag_iv = ctr.increment();
 
     #[cfg(target_arch = "x86_64")]
@@ -311,7 +373,7 @@ pub(super) fn open(
 fn finish(aes_key: &aes::Key, gcm_ctx: gcm::Context, tag_iv: aes::Iv) -> Tag {
     // Finalize the tag and return it.
     gcm_ctx.pre_finish(|pre_tag, cpu_features| {
-        Tag(aes_key.encrypt_iv_xor_block(tag_iv, pre_tag, cpu_features))
+        Tag::from(aes_key.encrypt_iv_xor_block(tag_iv, pre_tag, cpu_features))
     })
 }
 
@@ -0,0 +1,233 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! AES-GCM-SIV, as specified in [RFC 8452].
+//!
+//! POLYVAL is computed with the GHASH implementations, as described in
+//! RFC 8452 Appendix A.
+//!
+//! [RFC 8452]: https://www.rfc-editor.org/rfc/rfc8452
+
+use super::{
+    aes::{self, Block, BLOCK_LEN, ZERO_BLOCK},
+    gcm, Aad, Nonce, Tag,
+};
+use crate::{
+    cpu, error,
+    polyfill::{u64_from_usize, usize_from_u64_saturated},
+};
+use core::ops::RangeFrom;
+
+// RFC 8452 Section 6: P_MAX and A_MAX.
+pub(super) const MAX_IN_OUT_LEN: usize = usize_from_u64_saturated(1 << 36);
+const MAX_AAD_LEN: usize = usize_from_u64_saturated(1 << 36);
+
+const CHUNK_BLOCKS: usize = 3 * 1024 / 16;
+
+#[derive(Clone, Copy)]
+#[allow(non_camel_case_types)]
+enum Variant {
+    AES_128,
+    AES_256,
+}
+
+#[derive(Clone)]
+pub(super) struct Key {
+    key_generating_key: aes::Key,
+    variant: Variant,
+}
+
+impl Key {
+    pub(super) fn new(
+        key: aes::KeyBytes,
+        cpu_features: cpu::Features,
+    ) -> Result<Self, error::Unspecified> {
+        let variant = match key {
+            aes::KeyBytes::AES_128(_) => Variant::AES_128,
+            aes::KeyBytes::AES_256(_) => Variant::AES_256,
+        };
+        Ok(Self {
+            key_generating_key: aes::Key::new(key, cpu_features)?,
+            variant,
+        })
+    }
+
+    // RFC 8452 Section 4: the message-authentication key and the
+    // message-encryption key are the first halves of the encryptions of
+    // `little_endian_uint32(i) || nonce`, concatenated.
+    fn derive_keys(
+        &self,
+        nonce: &Nonce,
+        cpu_features: cpu::Features,
+    ) -> Result<(gcm::Key, aes::Key), error::Unspecified> {
+        let mut derived = [0u8; BLOCK_LEN + aes::AES_256_KEY_LEN];
+        let derived = match self.variant {
+            Variant::AES_128 => &mut derived[..(BLOCK_LEN + aes::AES_128_KEY_LEN)],
+            Variant::AES_256 => &mut derived[..],
+        };
+
+        let mut block = ZERO_BLOCK;
+        block[4..].copy_from_slice(nonce.as_ref());
+        for (i, out) in (0u32..).zip(derived.chunks_mut(BLOCK_LEN / 2)) {
+            block[..4].copy_from_slice(&i.to_le_bytes());
+            let encrypted = self.key_generating_key.encrypt_block(block, cpu_features);
+            out.copy_from_slice(&encrypted[..(BLOCK_LEN / 2)]);
+        }
+
+        let (authentication_key, encryption_key) = derived.split_at(BLOCK_LEN);
+        let encryption_key = match self.variant {
+            Variant::AES_128 => aes::KeyBytes::AES_128(encryption_key.try_into()?),
+            Variant::AES_256 => aes::KeyBytes::AES_256(encryption_key.try_into()?),
+        };
+        let polyval_key = polyval_key(authentication_key.try_into()?, cpu_features);
+        let encryption_key = aes::Key::new(encryption_key, cpu_features)?;
+        Ok((polyval_key, encryption_key))
+    }
+}
+
+pub(super) fn seal(
+    key: &Key,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    in_out: &mut [u8],
+    cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    if in_out.len() > MAX_IN_OUT_LEN || aad.as_ref().len() > MAX_AAD_LEN {
+        return Err(error::Unspecified);
+    }
+
+    let (polyval_key, encryption_key) = key.derive_keys(&nonce, cpu_features)?;
+    let tag = calculate_tag(
+        &polyval_key,
+        &encryption_key,
+        &nonce,
+        aad.as_ref(),
+        in_out,
+        cpu_features,
+    );
+    ctr32_le_encrypt_within(&encryption_key, tag, in_out, 0.., cpu_features);
+    Ok(Tag::from(tag))
+}
+
+pub(super) fn open(
+    key: &Key,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    received_tag: &Tag,
+    in_out: &mut [u8],
+    src: RangeFrom<usize>,
+    cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    let in_out_len = in_out
+        .len()
+        .checked_sub(src.start)
+        .ok_or(error::Unspecified)?;
+    if in_out_len > MAX_IN_OUT_LEN || aad.as_ref().len() > MAX_AAD_LEN {
+        return Err(error::Unspecified);
+    }
+    // Decryption needs the whole tag, since it is the initial counter block.
+    let received_tag: Block = received_tag.as_ref().try_into()?;
+
+    let (polyval_key, encryption_key) = key.derive_keys(&nonce, cpu_features)?;
+    ctr32_le_encrypt_within(&encryption_key, received_tag, in_out, src, cpu_features);
+    let tag = calculate_tag(
+        &polyval_key,
+        &encryption_key,
+        &nonce,
+        aad.as_ref(),
+        &in_out[..in_out_len],
+        cpu_features,
+    );
+    Ok(Tag::from(tag))
+}
+
+// RFC 8452 Section 4.
+fn calculate_tag(
+    polyval_key: &gcm::Key,
+    encryption_key: &aes::Key,
+    nonce: &Nonce,
+    aad: &[u8],
+    plaintext: &[u8],
+    cpu_features: cpu::Features,
+) -> Block {
+    let mut polyval = gcm::Context::for_polyval(polyval_key, cpu_features);
+    polyval_update_padded(&mut polyval, aad);
+    polyval_update_padded(&mut polyval, plaintext);
+    let mut lengths = ZERO_BLOCK;
+    let (aad_bits, plaintext_bits) = lengths.split_at_mut(BLOCK_LEN / 2);
+    aad_bits.copy_from_slice(&(u64_from_usize(aad.len()) * 8).to_le_bytes());
+    plaintext_bits.copy_from_slice(&(u64_from_usize(plaintext.len()) * 8).to_le_bytes());
+    lengths.reverse();
+    polyval.update_block(lengths);
+
+    let mut s = polyval.into_ghash();
+    s.reverse();
+    s.iter_mut()
+        .zip(nonce.as_ref())
+        .for_each(|(s, nonce)| *s ^= *nonce);
+    s[BLOCK_LEN - 1] &= 0x7f;
+    encryption_key.encrypt_block(s, cpu_features)
+}
+
+// RFC 8452 Appendix A: POLYVAL(H, X_1, ..., X_n) =
+// ByteReverse(GHASH(mulX_GHASH(ByteReverse(H)), ByteReverse(X_1), ...,
+// ByteReverse(X_n))).
+fn polyval_key(h: Block, cpu_features: cpu::Features) -> gcm::Key {
+    // `ByteReverse(H)` as a big-endian integer is `H` as a little-endian one.
+    let h = u128::from_le_bytes(h);
+    let carry = h & 1;
+    let h = (h >> 1) ^ ((0xe1 << 120) & 0u128.wrapping_sub(carry));
+    gcm::Key::new(h.to_be_bytes(), cpu_features)
+}
+
+fn polyval_update_padded(polyval: &mut gcm::Context, input: &[u8]) {
+    let mut blocks = [ZERO_BLOCK; CHUNK_BLOCKS];
+    for chunk in input.chunks(CHUNK_BLOCKS * BLOCK_LEN) {
+        let chunk_blocks = chunk.chunks(BLOCK_LEN);
+        let num_blocks = chunk_blocks.len();
+        for (block, input) in blocks.iter_mut().zip(chunk_blocks) {
+            *block = ZERO_BLOCK;
+            block[..input.len()].copy_from_slice(input);
+            block.reverse();
+        }
+        polyval.update_blocks(&blocks[..num_blocks]);
+    }
+}
+
+// RFC 8452 Section 4: AES in counter mode with the tag, with its most
+// significant bit set, as the initial counter block. Unlike in AES-GCM, the
+// counter is the first 32 bits, little-endian, and it wraps around.
+fn ctr32_le_encrypt_within(
+    key: &aes::Key,
+    tag: Block,
+    in_out: &mut [u8],
+    src: RangeFrom<usize>,
+    cpu_features: cpu::Features,
+) {
+    let mut counter_block = tag;
+    counter_block[BLOCK_LEN - 1] |= 0x80;
+    let [c0, c1, c2, c3, ..] = counter_block;
+    let mut counter = u32::from_le_bytes([c0, c1, c2, c3]);
+
+    let in_out_len = in_out.len() - src.start;
+    for start in (0..in_out_len).step_by(BLOCK_LEN) {
+        counter_block[..4].copy_from_slice(&counter.to_le_bytes());
+        let key_stream = key.encrypt_block(counter_block, cpu_features);
+        let end = core::cmp::min(start + BLOCK_LEN, in_out_len);
+        for (i, k) in (start..end).zip(key_stream) {
+            in_out[i] = in_out[src.start + i] ^ k;
+        }
+        counter = counter.wrapping_add(1);
+    }
+}
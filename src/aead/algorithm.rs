@@ -12,13 +12,13 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use crate::{constant_time, cpu, error, hkdf};
+use crate::{constant_time, cpu, error, hkdf, polyfill::usize_from_u64_saturated};
 use core::ops::RangeFrom;
 
 use super::{
-    aes, aes_gcm, chacha20_poly1305,
+    aes, aes_gcm, aes_gcm_siv, chacha20_poly1305,
     nonce::{Nonce, NONCE_LEN},
-    Aad, KeyInner, Tag, TAG_LEN,
+    Aad, KeyInner, Tag, TAG64_LEN, TAG96_LEN, TAG_LEN,
 };
 
 impl hkdf::KeyType for &'static Algorithm {
@@ -43,15 +43,48 @@ pub struct Algorithm {
         key: &KeyInner,
         nonce: Nonce,
         aad: Aad<&[u8]>,
+        received_tag: &Tag,
         in_out: &mut [u8],
         src: RangeFrom<usize>,
         cpu_features: cpu::Features,
     ) -> Result<Tag, error::Unspecified>,
 
     key_len: usize,
+    tag_len: usize,
+    usage_limits: UsageLimits,
     id: AlgorithmID,
 }
 
+/// The bounds within which a single key of an AEAD algorithm can be used
+/// safely.
+///
+/// Nothing in *ring* enforces `max_messages`; the caller must count the
+/// messages sealed with each key (e.g. in its `NonceSequence`) and rotate the
+/// key before the limit is reached. Sealing and opening fail for messages
+/// longer than the algorithm's maximum input length, which may be larger
+/// than `max_bytes_per_message`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UsageLimits {
+    /// The maximum number of messages that may be sealed with one key.
+    pub max_messages: u64,
+
+    /// The maximum length, in bytes, of the plaintext of a single message.
+    pub max_bytes_per_message: u64,
+}
+
+// NIST SP 800-38D Section 8.3 limits the total number of invocations of the
+// authenticated encryption function with a given key to 2**32, because
+// nonces may be chosen randomly. Section 5.2.1.1 limits the plaintext to
+// 2**39 - 256 bits.
+const AES_GCM_USAGE_LIMITS: UsageLimits = UsageLimits {
+    max_messages: 1 << 32,
+    max_bytes_per_message: ((1 << 39) - 256) / 8,
+};
+
+const _AES_GCM_MAX_BYTES_PER_MESSAGE_MATCHES_MAX_IN_OUT_LEN: () = assert!(
+    usize_from_u64_saturated(AES_GCM_USAGE_LIMITS.max_bytes_per_message) == aes_gcm::MAX_IN_OUT_LEN
+);
+
 impl Algorithm {
     /// The length of the key.
     #[inline(always)]
@@ -64,7 +97,7 @@ impl Algorithm {
     /// See also `MAX_TAG_LEN`.
     #[inline(always)]
     pub fn tag_len(&self) -> usize {
-        TAG_LEN
+        self.tag_len
     }
 
     /// The length of the nonces.
@@ -73,6 +106,41 @@ impl Algorithm {
         NONCE_LEN
     }
 
+    /// The cryptographic limits on the use of a single key.
+    ///
+    /// See the documentation of each algorithm for where its limits come
+    /// from.
+    #[inline]
+    pub fn usage_limits(&self) -> UsageLimits {
+        self.usage_limits
+    }
+
+    /// The length of the output of sealing `plaintext_len` bytes with the
+    /// tag appended, i.e. `plaintext_len + self.tag_len()`.
+    ///
+    /// Returns `None` if that length would overflow `usize`. Sealing may still
+    /// fail for lengths that don't overflow, if they exceed the maximum
+    /// input length of the algorithm.
+    #[inline]
+    pub fn max_ciphertext_len(&self, plaintext_len: usize) -> Option<usize> {
+        plaintext_len.checked_add(self.tag_len())
+    }
+
+    /// The length of the plaintext that results from opening
+    /// `ciphertext_len` bytes of ciphertext followed by the tag, i.e.
+    /// `ciphertext_len - self.tag_len()`.
+    ///
+    /// Returns `None` if `ciphertext_len` is too short to contain a tag.
+    #[inline]
+    pub fn plaintext_len_from_ciphertext(&self, ciphertext_len: usize) -> Option<usize> {
+        ciphertext_len.checked_sub(self.tag_len())
+    }
+
+    #[inline]
+    pub(super) fn id(&self) -> &AlgorithmID {
+        &self.id
+    }
+
     pub(super) fn new_key(
         &self,
         key_bytes: &[u8],
@@ -93,15 +161,26 @@ impl Algorithm {
     ) -> Result<&'io mut [u8], error::Unspecified> {
         let ciphertext_len = in_out.get(src.clone()).ok_or(error::Unspecified)?.len();
 
-        let Tag(calculated_tag) = (self.open)(key, nonce, aad, in_out, src, cpu_features)?;
+        // A tag of any other length, e.g. a full-length tag for an algorithm
+        // that uses truncated tags, is never valid.
+        if received_tag.as_ref().len() != self.tag_len {
+            return Err(error::Unspecified);
+        }
+
+        let calculated_tag =
+            (self.open)(key, nonce, aad, &received_tag, in_out, src, cpu_features)?;
+        let calculated_tag = calculated_tag.truncated(self.tag_len);
 
+        // Only the truncated tag is compared.
         if constant_time::verify_slices_are_equal(calculated_tag.as_ref(), received_tag.as_ref())
             .is_err()
         {
             // Zero out the plaintext so that it isn't accidentally leaked or used
             // after verification fails. It would be safest if we could check the
             // tag before decrypting, but some `open` implementations interleave
-            // authentication with decryption for performance.
+            // authentication with decryption for performance. The public
+            // `open_*` functions document that `in_out` never contains any
+            // plaintext after a failure, so this must cover all of it.
             for b in &mut in_out[..ciphertext_len] {
                 *b = 0;
             }
@@ -121,7 +200,8 @@ impl Algorithm {
         in_out: &mut [u8],
         cpu_features: cpu::Features,
     ) -> Result<Tag, error::Unspecified> {
-        (self.seal)(key, nonce, aad, in_out, cpu_features)
+        let tag = (self.seal)(key, nonce, aad, in_out, cpu_features)?;
+        Ok(tag.truncated(self.tag_len))
     }
 }
 
@@ -131,6 +211,10 @@ derive_debug_via_id!(Algorithm);
 pub(super) enum AlgorithmID {
     AES_128_GCM,
     AES_256_GCM,
+    AES_256_GCM_TAG96,
+    AES_256_GCM_TAG64,
+    AES_128_GCM_SIV,
+    AES_256_GCM_SIV,
     CHACHA20_POLY1305,
 }
 
@@ -143,23 +227,96 @@ impl PartialEq for Algorithm {
 impl Eq for Algorithm {}
 
 /// AES-128 in GCM mode with 128-bit tags and 96 bit nonces.
+///
+/// Sealing and opening fail, without processing any of the input, if the
+/// plaintext (or the ciphertext, excluding the tag) is longer than
+/// 2<sup>36</sup> - 32 bytes, the limit in [NIST SP 800-38D] Section 5.2.1.1;
+/// beyond that, the 32-bit block counter would wrap around.
+///
+/// `usage_limits()` reports that limit and, from Section 8.3, at most
+/// 2<sup>32</sup> messages per key.
+///
+/// [NIST SP 800-38D]: https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
 pub static AES_128_GCM: Algorithm = Algorithm {
     key_len: aes::AES_128_KEY_LEN,
     init: aes_gcm_init_128,
     seal: aes_gcm_seal,
     open: aes_gcm_open,
+    tag_len: TAG_LEN,
+    usage_limits: AES_GCM_USAGE_LIMITS,
     id: AlgorithmID::AES_128_GCM,
 };
 
 /// AES-256 in GCM mode with 128-bit tags and 96 bit nonces.
+///
+/// Sealing and opening fail, without processing any of the input, if the
+/// plaintext (or the ciphertext, excluding the tag) is longer than
+/// 2<sup>36</sup> - 32 bytes, the limit in [NIST SP 800-38D] Section 5.2.1.1;
+/// beyond that, the 32-bit block counter would wrap around.
+///
+/// `usage_limits()` reports that limit and, from Section 8.3, at most
+/// 2<sup>32</sup> messages per key.
+///
+/// [NIST SP 800-38D]: https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
 pub static AES_256_GCM: Algorithm = Algorithm {
     key_len: aes::AES_256_KEY_LEN,
     init: aes_gcm_init_256,
     seal: aes_gcm_seal,
     open: aes_gcm_open,
+    tag_len: TAG_LEN,
+    usage_limits: AES_GCM_USAGE_LIMITS,
     id: AlgorithmID::AES_256_GCM,
 };
 
+/// AES-256 in GCM mode with 96-bit tags and 96 bit nonces.
+///
+/// The tag is the first 96 bits of the `AES_256_GCM` tag, as specified in
+/// [NIST SP 800-38D] Section 5.2.1.2. A forgery succeeds with probability
+/// about 2<sup>-96</sup> per attempt instead of 2<sup>-128</sup>. Use this
+/// only when a protocol requires it. The input length limit and the usage
+/// limits are the same as `AES_256_GCM`'s.
+///
+/// [NIST SP 800-38D]: https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+pub static AES_256_GCM_TAG96: Algorithm = Algorithm {
+    key_len: aes::AES_256_KEY_LEN,
+    init: aes_gcm_init_256,
+    seal: aes_gcm_seal,
+    open: aes_gcm_open,
+    tag_len: TAG96_LEN,
+    usage_limits: AES_GCM_USAGE_LIMITS,
+    id: AlgorithmID::AES_256_GCM_TAG96,
+};
+
+/// AES-256 in GCM mode with 64-bit tags and 96 bit nonces.
+///
+/// The tag is the first 64 bits of the `AES_256_GCM` tag, as specified in
+/// [NIST SP 800-38D] Section 5.2.1.2. 64-bit tags are much weaker than
+/// full-length tags: a forgery succeeds with probability about
+/// 2<sup>-64</sup> per attempt, and, as described in [NIST SP 800-38D]
+/// Appendix C, the probability grows with the length of the messages and the
+/// number of failed attempts. Appendix C limits the amount of data that may
+/// be processed with a key when 64-bit tags are used; the caller is
+/// responsible for enforcing those limits. Use this only when a protocol
+/// requires it. The input length limit is the same as `AES_256_GCM`'s, but
+/// `usage_limits()` reports a maximum message length of 2<sup>15</sup> bytes,
+/// the largest length that Appendix C Table 2 allows with 2<sup>32</sup>
+/// invocations; that limit applies to the ciphertext and the AAD combined.
+///
+/// [NIST SP 800-38D]: https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+pub static AES_256_GCM_TAG64: Algorithm = Algorithm {
+    key_len: aes::AES_256_KEY_LEN,
+    init: aes_gcm_init_256,
+    seal: aes_gcm_seal,
+    open: aes_gcm_open,
+    tag_len: TAG64_LEN,
+    usage_limits: UsageLimits {
+        // NIST SP 800-38D Appendix C, Table 2.
+        max_bytes_per_message: 1 << 15,
+        ..AES_GCM_USAGE_LIMITS
+    },
+    id: AlgorithmID::AES_256_GCM_TAG64,
+};
+
 fn aes_gcm_init_128(
     key: &[u8],
     cpu_features: cpu::Features,
@@ -200,6 +357,7 @@ pub(super) fn aes_gcm_open(
     key: &KeyInner,
     nonce: Nonce,
     aad: Aad<&[u8]>,
+    _received_tag: &Tag,
     in_out: &mut [u8],
     src: RangeFrom<usize>,
     cpu_features: cpu::Features,
@@ -211,9 +369,123 @@ pub(super) fn aes_gcm_open(
     aes_gcm::open(key, nonce, aad, in_out, src, cpu_features)
 }
 
+// RFC 8452 Section 9 allows many more messages per key than AES-GCM, even
+// with random nonces, but the bounds depend on the lengths of the messages;
+// NIST SP 800-38D's limit for AES-GCM is a conservative bound for all of
+// them.
+const AES_GCM_SIV_USAGE_LIMITS: UsageLimits = UsageLimits {
+    max_messages: 1 << 32,
+    // RFC 8452 Section 6.
+    max_bytes_per_message: 1 << 36,
+};
+
+const _AES_GCM_SIV_MAX_BYTES_PER_MESSAGE_MATCHES_MAX_IN_OUT_LEN: () = assert!(
+    usize_from_u64_saturated(AES_GCM_SIV_USAGE_LIMITS.max_bytes_per_message)
+        == aes_gcm_siv::MAX_IN_OUT_LEN
+);
+
+/// AES-128 in GCM-SIV mode with 128-bit tags and 96 bit nonces, as
+/// described in [RFC 8452].
+///
+/// AES-GCM-SIV is nonce-misuse resistant: if a nonce is ever reused with a
+/// key, the only thing revealed is whether the messages sealed with it (and
+/// their AAD) were identical. Nonces should still be unique; the security
+/// bounds in RFC 8452 Section 9 assume that they are, or that they are
+/// random. Sealing makes two passes over the input, and opening doesn't
+/// release any plaintext until the tag is verified.
+///
+/// Sealing and opening fail, without processing any of the input, if the
+/// plaintext (or the ciphertext, excluding the tag) or the AAD is longer than
+/// 2<sup>36</sup> bytes, the limit in RFC 8452 Section 6. `usage_limits()`
+/// reports that limit and, conservatively, the same maximum number of
+/// messages per key as for AES-GCM.
+///
+/// [RFC 8452]: https://www.rfc-editor.org/rfc/rfc8452
+pub static AES_128_GCM_SIV: Algorithm = Algorithm {
+    key_len: aes::AES_128_KEY_LEN,
+    init: aes_gcm_siv_init_128,
+    seal: aes_gcm_siv_seal,
+    open: aes_gcm_siv_open,
+    tag_len: TAG_LEN,
+    usage_limits: AES_GCM_SIV_USAGE_LIMITS,
+    id: AlgorithmID::AES_128_GCM_SIV,
+};
+
+/// AES-256 in GCM-SIV mode with 128-bit tags and 96 bit nonces, as
+/// described in [RFC 8452].
+///
+/// See `AES_128_GCM_SIV` for the properties of AES-GCM-SIV and its limits,
+/// which are the same.
+///
+/// [RFC 8452]: https://www.rfc-editor.org/rfc/rfc8452
+pub static AES_256_GCM_SIV: Algorithm = Algorithm {
+    key_len: aes::AES_256_KEY_LEN,
+    init: aes_gcm_siv_init_256,
+    seal: aes_gcm_siv_seal,
+    open: aes_gcm_siv_open,
+    tag_len: TAG_LEN,
+    usage_limits: AES_GCM_SIV_USAGE_LIMITS,
+    id: AlgorithmID::AES_256_GCM_SIV,
+};
+
+fn aes_gcm_siv_init_128(
+    key: &[u8],
+    cpu_features: cpu::Features,
+) -> Result<KeyInner, error::Unspecified> {
+    let key = key.try_into().map_err(|_| error::Unspecified)?;
+    Ok(KeyInner::AesGcmSiv(aes_gcm_siv::Key::new(
+        aes::KeyBytes::AES_128(key),
+        cpu_features,
+    )?))
+}
+
+fn aes_gcm_siv_init_256(
+    key: &[u8],
+    cpu_features: cpu::Features,
+) -> Result<KeyInner, error::Unspecified> {
+    let key = key.try_into().map_err(|_| error::Unspecified)?;
+    Ok(KeyInner::AesGcmSiv(aes_gcm_siv::Key::new(
+        aes::KeyBytes::AES_256(key),
+        cpu_features,
+    )?))
+}
+
+fn aes_gcm_siv_seal(
+    key: &KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    in_out: &mut [u8],
+    cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    let key = match key {
+        KeyInner::AesGcmSiv(key) => key,
+        _ => unreachable!(),
+    };
+    aes_gcm_siv::seal(key, nonce, aad, in_out, cpu_features)
+}
+
+fn aes_gcm_siv_open(
+    key: &KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    received_tag: &Tag,
+    in_out: &mut [u8],
+    src: RangeFrom<usize>,
+    cpu_features: cpu::Features,
+) -> Result<Tag, error::Unspecified> {
+    let key = match key {
+        KeyInner::AesGcmSiv(key) => key,
+        _ => unreachable!(),
+    };
+    aes_gcm_siv::open(key, nonce, aad, received_tag, in_out, src, cpu_features)
+}
+
 /// ChaCha20-Poly1305 as described in [RFC 8439].
 ///
-/// The keys are 256 bits long and the nonces are 96 bits long.
+/// The keys are 256 bits long and the nonces are 96 bits long. Messages are
+/// limited to 274,877,906,880 bytes (RFC 8439 Section 2.8); the number of
+/// messages per key is limited only by the nonce space, so
+/// `usage_limits().max_messages` is `u64::MAX`.
 ///
 /// [RFC 8439]: https://tools.ietf.org/html/rfc8439
 pub static CHACHA20_POLY1305: Algorithm = Algorithm {
@@ -221,6 +493,14 @@ pub static CHACHA20_POLY1305: Algorithm = Algorithm {
     init: chacha20_poly1305_init,
     seal: chacha20_poly1305_seal,
     open: chacha20_poly1305_open,
+    tag_len: TAG_LEN,
+    usage_limits: UsageLimits {
+        // The nonce space is larger than any count of messages that fits in
+        // a `u64`.
+        max_messages: u64::MAX,
+        // RFC 8439 Section 2.8.
+        max_bytes_per_message: 274_877_906_880,
+    },
     id: AlgorithmID::CHACHA20_POLY1305,
 };
 
@@ -251,6 +531,7 @@ fn chacha20_poly1305_open(
     key: &KeyInner,
     nonce: Nonce,
     aad: Aad<&[u8]>,
+    _received_tag: &Tag,
     in_out: &mut [u8],
     src: RangeFrom<usize>,
     cpu_features: cpu::Features,
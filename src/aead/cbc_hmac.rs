@@ -0,0 +1,231 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! AES-128-CBC with HMAC-SHA256, composed as encrypt-then-MAC.
+//!
+//! This is only for interoperability with legacy systems; new protocols
+//! should use [`super::AES_128_GCM`] or [`super::CHACHA20_POLY1305`]. It is
+//! only available with the `legacy-cbc` feature.
+//!
+//! The construction is `AEAD_AES_128_CBC_HMAC_SHA_256` as specified in
+//! [draft-mcgrew-aead-aes-cbc-hmac-sha2-05] and used by JWE ([RFC 7518
+//! Section 5.2]): the plaintext is padded using PKCS#7 padding and encrypted
+//! with AES-128-CBC under a random IV, and the tag is the first 16 bytes of
+//! HMAC-SHA256 over the associated data, the IV, the ciphertext, and the
+//! length of the associated data in bits.
+//!
+//! When opening, the tag is verified before anything is decrypted, and the
+//! padding is then checked in constant time, so no padding oracle ("Lucky
+//! Thirteen") is exposed.
+//!
+//! [draft-mcgrew-aead-aes-cbc-hmac-sha2-05]:
+//!     https://tools.ietf.org/html/draft-mcgrew-aead-aes-cbc-hmac-sha2-05
+//! [RFC 7518 Section 5.2]: https://tools.ietf.org/html/rfc7518#section-5.2
+
+use super::{
+    aes::{self, Block, DecryptKey, BLOCK_LEN},
+    Aad,
+};
+use crate::{constant_time, cpu, error, hmac, polyfill::u64_from_usize, rand};
+use alloc::vec::Vec;
+
+/// The length of a key: a 16-byte HMAC-SHA256 key followed by a 16-byte
+/// AES-128 key.
+pub const KEY_LEN: usize = MAC_KEY_LEN + aes::AES_128_KEY_LEN;
+
+/// The length of the IV that prefixes the ciphertext.
+pub const IV_LEN: usize = BLOCK_LEN;
+
+/// The length of the tag that suffixes the ciphertext.
+pub const TAG_LEN: usize = 16;
+
+const MAC_KEY_LEN: usize = 16;
+
+/// An AES-128-CBC-HMAC-SHA256 key.
+#[derive(Clone)]
+pub struct Aes128CbcHmacSha256 {
+    mac_key: hmac::Key,
+    encrypt_key: aes::Key,
+    decrypt_key: DecryptKey,
+}
+
+impl core::fmt::Debug for Aes128CbcHmacSha256 {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("Aes128CbcHmacSha256").finish()
+    }
+}
+
+impl Aes128CbcHmacSha256 {
+    /// Constructs a new key from `key_bytes`, which is the HMAC key followed
+    /// by the AES key.
+    pub fn new(key_bytes: &[u8; KEY_LEN]) -> Result<Self, error::Unspecified> {
+        let (mac_key, aes_key) = key_bytes.split_at(MAC_KEY_LEN);
+        let aes_key: &[u8; aes::AES_128_KEY_LEN] = aes_key.try_into()?;
+        Ok(Self {
+            mac_key: hmac::Key::new(hmac::HMAC_SHA256, mac_key),
+            encrypt_key: aes::Key::new(aes::KeyBytes::AES_128(aes_key), cpu::features())?,
+            decrypt_key: DecryptKey::new(aes::KeyBytes::AES_128(aes_key)),
+        })
+    }
+
+    /// Encrypts and signs `plaintext` with a random IV generated by `rng`.
+    ///
+    /// The result is `IV || ciphertext || tag`, where the ciphertext includes
+    /// between 1 and 16 bytes of padding.
+    pub fn seal<A: AsRef<[u8]>>(
+        &self,
+        rng: &dyn rand::SecureRandom,
+        aad: Aad<A>,
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, error::Unspecified> {
+        let cpu_features = cpu::features();
+
+        let iv: Block = rand::generate(rng)?.expose();
+
+        let padding_len = BLOCK_LEN - (plaintext.len() % BLOCK_LEN);
+        let mut out = Vec::with_capacity(IV_LEN + plaintext.len() + padding_len + TAG_LEN);
+        out.extend_from_slice(&iv);
+        out.extend_from_slice(plaintext);
+        #[allow(clippy::cast_possible_truncation)]
+        out.resize(out.len() + padding_len, padding_len as u8);
+
+        let mut chaining = iv;
+        for block in out[IV_LEN..].chunks_exact_mut(BLOCK_LEN) {
+            let input = constant_time::xor_16(chaining, block.try_into()?);
+            chaining = self.encrypt_key.encrypt_block(input, cpu_features);
+            block.copy_from_slice(&chaining);
+        }
+
+        let tag = self.tag(aad.as_ref(), &out)?;
+        out.extend_from_slice(&tag);
+        Ok(out)
+    }
+
+    /// Authenticates and decrypts `in_out`, which is `IV || ciphertext ||
+    /// tag`, in place, returning the plaintext.
+    ///
+    /// The plaintext is a subslice of `in_out` that starts after the IV. When
+    /// `open_in_place()` returns `Err(..)`, `in_out` may have been
+    /// overwritten in an unspecified way.
+    pub fn open_in_place<'io, A: AsRef<[u8]>>(
+        &self,
+        aad: Aad<A>,
+        in_out: &'io mut [u8],
+    ) -> Result<&'io mut [u8], error::Unspecified> {
+        let ciphertext_len = in_out
+            .len()
+            .checked_sub(IV_LEN + TAG_LEN)
+            .ok_or(error::Unspecified)?;
+        if ciphertext_len == 0 || ciphertext_len % BLOCK_LEN != 0 {
+            return Err(error::Unspecified);
+        }
+        let (in_out, received_tag) = in_out.split_at_mut(IV_LEN + ciphertext_len);
+
+        let tag = self.tag(aad.as_ref(), in_out)?;
+        constant_time::verify_slices_are_equal(&tag, received_tag)?;
+
+        let (iv, in_out) = in_out.split_at_mut(IV_LEN);
+        let mut chaining: Block = (&*iv).try_into()?;
+        for block in in_out.chunks_exact_mut(BLOCK_LEN) {
+            let ciphertext: Block = (&*block).try_into()?;
+            let plaintext =
+                constant_time::xor_16(chaining, self.decrypt_key.decrypt_block(ciphertext));
+            block.copy_from_slice(&plaintext);
+            chaining = ciphertext;
+        }
+
+        let last_block: &Block = in_out[(ciphertext_len - BLOCK_LEN)..].try_into()?;
+        let padding_len = padding_len_consttime(last_block)?;
+        Ok(&mut in_out[..(ciphertext_len - padding_len)])
+    }
+
+    fn tag(
+        &self,
+        aad: &[u8],
+        iv_and_ciphertext: &[u8],
+    ) -> Result<[u8; TAG_LEN], error::Unspecified> {
+        let aad_len_bits = u64_from_usize(aad.len())
+            .checked_mul(8)
+            .ok_or(error::Unspecified)?;
+        let mut ctx = hmac::Context::with_key(&self.mac_key);
+        ctx.update(aad);
+        ctx.update(iv_and_ciphertext);
+        ctx.update(&aad_len_bits.to_be_bytes());
+        let tag = ctx.sign();
+        let tag = tag.as_ref().get(..TAG_LEN).ok_or(error::Unspecified)?;
+        Ok(tag.try_into()?)
+    }
+}
+
+// Returns the length of the PKCS#7 padding at the end of `last_block`. Every
+// byte of the block is examined regardless of the padding length, and the
+// only secret-dependent branch is on whether the padding is valid.
+fn padding_len_consttime(last_block: &Block) -> Result<usize, error::Unspecified> {
+    let padding_len = last_block[BLOCK_LEN - 1];
+
+    // 0 < padding_len <= BLOCK_LEN, computed without branches.
+    #[allow(clippy::cast_possible_truncation)]
+    let mut bad = is_zero_mask(padding_len) | lt_mask(BLOCK_LEN as u8, padding_len);
+
+    last_block.iter().rev().enumerate().for_each(|(i, &b)| {
+        #[allow(clippy::cast_possible_truncation)]
+        let in_padding = lt_mask(i as u8, padding_len);
+        bad |= in_padding & (b ^ padding_len);
+    });
+
+    if bad != 0 {
+        return Err(error::Unspecified);
+    }
+    Ok(usize::from(padding_len))
+}
+
+// Returns 0xff if `a < b` and 0 otherwise.
+fn lt_mask(a: u8, b: u8) -> u8 {
+    let diff = u16::from(a).wrapping_sub(u16::from(b));
+    #[allow(clippy::cast_possible_truncation)]
+    let mask = (diff >> 8) as u8;
+    mask
+}
+
+// Returns 0xff if `a == 0` and 0 otherwise.
+fn is_zero_mask(a: u8) -> u8 {
+    lt_mask(a, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padding_len_consttime() {
+        let mut block = [0x55; BLOCK_LEN];
+        for padding_len in 1..=16u8 {
+            let start = BLOCK_LEN - usize::from(padding_len);
+            block[start..].fill(padding_len);
+            assert_eq!(padding_len_consttime(&block), Ok(usize::from(padding_len)));
+
+            // Any other value in the padding is rejected.
+            let mut bad = block;
+            bad[start] ^= 0x80;
+            assert!(padding_len_consttime(&bad).is_err());
+        }
+
+        block[BLOCK_LEN - 1] = 0;
+        assert!(padding_len_consttime(&block).is_err());
+        block.fill(17);
+        assert!(padding_len_consttime(&block).is_err());
+        block.fill(0xff);
+        assert!(padding_len_consttime(&block).is_err());
+    }
+}
@@ -124,6 +124,35 @@ impl Key {
         ChaCha20_ctr32(self, counter, in_out, src);
     }
 
+    /// HChaCha20, as specified in [draft-irtf-cfrg-xchacha Section 2.2].
+    ///
+    /// HChaCha20 is the ChaCha20 block function without the final addition
+    /// of the input state, keeping only words 0..4 and 12..16. Those words of
+    /// the input state are the constants and `input`, so they are subtracted
+    /// back out of a block of keystream.
+    ///
+    /// [draft-irtf-cfrg-xchacha Section 2.2]: https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03#section-2.2
+    pub fn hchacha20(&self, input: [u8; 16]) -> [u8; KEY_LEN] {
+        // "expand 32-byte k"
+        const SIGMA: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+        let iv = Iv::assume_unique_for_key(input);
+        let input_words = iv.0;
+        let mut block = [0u8; BLOCK_LEN];
+        self.encrypt_in_place(iv.into_counter_for_single_block_less_safe(), &mut block);
+
+        let mut out = [0u8; KEY_LEN];
+        let state = block[..16]
+            .chunks_exact(4)
+            .chain(block[48..].chunks_exact(4));
+        let initial = SIGMA.iter().chain(input_words.iter());
+        for ((out, state), initial) in out.chunks_exact_mut(4).zip(state).zip(initial) {
+            let word = u32::from_le_bytes(state.try_into().unwrap()).wrapping_sub(*initial);
+            out.copy_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+
     #[inline]
     pub(super) fn words_less_safe(&self) -> &[u32; KEY_LEN / 4] {
         &self.words
@@ -139,7 +168,7 @@ impl Counter {
         Self::from_nonce_and_ctr(nonce, 0)
     }
 
-    fn from_nonce_and_ctr(nonce: Nonce, ctr: u32) -> Self {
+    pub fn from_nonce_and_ctr(nonce: Nonce, ctr: u32) -> Self {
         let [n0, n1, n2] = nonce.as_ref().array_split_map(u32::from_le_bytes);
         Self([ctr, n0, n1, n2])
     }
@@ -183,7 +212,7 @@ impl Iv {
 
 pub const KEY_LEN: usize = 32;
 
-const BLOCK_LEN: usize = 64;
+pub const BLOCK_LEN: usize = 64;
 
 #[cfg(test)]
 mod tests {
@@ -223,6 +252,23 @@ mod tests {
         chacha20_test(MAX_ALIGNMENT_AND_OFFSET_SUBSET, fallback::ChaCha20_ctr32);
     }
 
+    // https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03#section-2.2.1
+    #[test]
+    fn hchacha20_test() {
+        let key =
+            test::from_hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+                .unwrap();
+        let key = Key::new(key.as_slice().try_into().unwrap());
+        let input = [
+            0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00, 0x31, 0x41,
+            0x59, 0x27,
+        ];
+        let expected =
+            test::from_hex("82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc")
+                .unwrap();
+        assert_eq!(&key.hchacha20(input)[..], &expected[..]);
+    }
+
     // Verifies the encryption is successful when done on overlapping buffers.
     //
     // On some branches of the 32-bit x86 and ARM assembly code the in-place
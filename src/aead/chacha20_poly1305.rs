@@ -12,6 +12,11 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
+//! Low-level ChaCha20-Poly1305 operations.
+//!
+//! Almost all users should use [`super::CHACHA20_POLY1305`] with
+//! [`super::LessSafeKey`] or the other AEAD keys instead.
+
 use super::{
     chacha::{self, Counter, Iv},
     poly1305, Aad, Nonce, Tag,
@@ -22,7 +27,11 @@ use crate::{
 };
 use core::ops::RangeFrom;
 
-pub(super) const KEY_LEN: usize = chacha::KEY_LEN;
+/// The length of a ChaCha20-Poly1305 key.
+pub const KEY_LEN: usize = chacha::KEY_LEN;
+
+/// The length of a Poly1305 one-time key.
+pub const POLY1305_KEY_LEN: usize = poly1305::KEY_LEN;
 
 const MAX_IN_OUT_LEN: usize = super::max_input_len(64, 1);
 // https://tools.ietf.org/html/rfc8439#section-2.8
@@ -107,7 +116,7 @@ pub(super) fn seal(
             &data.out
         };
 
-        return Ok(Tag(out.tag));
+        return Ok(Tag::from(out.tag));
     }
 
     let mut counter = Counter::zero(nonce);
@@ -192,7 +201,7 @@ pub(super) fn open(
             &data.out
         };
 
-        return Ok(Tag(out.tag));
+        return Ok(Tag::from(out.tag));
     }
 
     let mut counter = Counter::zero(nonce);
@@ -271,9 +280,27 @@ fn poly1305_update_padded_16(ctx: &mut poly1305::Context, input: &[u8]) {
     }
 }
 
+/// Returns the Poly1305 one-time key that ChaCha20-Poly1305 uses to
+/// authenticate the record sealed with `key` and `nonce`.
+///
+/// This is the first 32 bytes of ChaCha20 keystream block 0, as described in
+/// [RFC 8439 Section 2.6]. It is only useful for building other constructions
+/// on top of ChaCha20 and Poly1305. The result is secret, and it must be used
+/// to authenticate at most one message.
+///
+/// [RFC 8439 Section 2.6]: https://tools.ietf.org/html/rfc8439#section-2.6
+pub fn poly1305_key_for(key: &[u8; KEY_LEN], nonce: Nonce) -> [u8; POLY1305_KEY_LEN] {
+    let chacha_key = chacha::Key::new(*key);
+    derive_poly1305_key_bytes(&chacha_key, Counter::zero(nonce).increment())
+}
+
 // Also used by chacha20_poly1305_openssh.
 pub(super) fn derive_poly1305_key(chacha_key: &chacha::Key, iv: Iv) -> poly1305::Key {
-    let mut key_bytes = [0u8; poly1305::KEY_LEN];
+    poly1305::Key::new(derive_poly1305_key_bytes(chacha_key, iv))
+}
+
+fn derive_poly1305_key_bytes(chacha_key: &chacha::Key, iv: Iv) -> [u8; POLY1305_KEY_LEN] {
+    let mut key_bytes = [0u8; POLY1305_KEY_LEN];
     chacha_key.encrypt_iv_xor_in_place(iv, &mut key_bytes);
-    poly1305::Key::new(key_bytes)
+    key_bytes
 }
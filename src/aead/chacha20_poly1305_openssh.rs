@@ -78,16 +78,29 @@ impl SealingKey {
                 .encrypt_in_place(counter, data_and_padding_in_out);
         }
 
-        let Tag(tag) = poly1305::sign(poly_key, plaintext_in_ciphertext_out, cpu_features);
+        let Tag { value: tag, .. } =
+            poly1305::sign(poly_key, plaintext_in_ciphertext_out, cpu_features);
         *tag_out = tag;
     }
 }
 
+impl core::fmt::Debug for SealingKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("SealingKey").finish_non_exhaustive()
+    }
+}
+
 /// A key for opening packets.
 pub struct OpeningKey {
     key: Key,
 }
 
+impl core::fmt::Debug for OpeningKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("OpeningKey").finish_non_exhaustive()
+    }
+}
+
 impl OpeningKey {
     /// Constructs a new `OpeningKey`.
     pub fn new(key_material: &[u8; KEY_LEN]) -> Self {
@@ -175,6 +188,6 @@ pub const PACKET_LENGTH_LEN: usize = 4; // 32 bits
 pub const TAG_LEN: usize = super::TAG_LEN;
 
 fn verify(key: poly1305::Key, msg: &[u8], tag: &[u8; TAG_LEN]) -> Result<(), error::Unspecified> {
-    let Tag(calculated_tag) = poly1305::sign(key, msg, cpu::features());
+    let calculated_tag = poly1305::sign(key, msg, cpu::features());
     constant_time::verify_slices_are_equal(calculated_tag.as_ref(), tag)
 }
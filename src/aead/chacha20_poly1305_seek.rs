@@ -0,0 +1,120 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Random access to the ChaCha20 keystream of a ChaCha20-Poly1305 record.
+//!
+//! This is for implementing random-access encrypted storage on top of
+//! [`super::CHACHA20_POLY1305`]. [`KeystreamKey::apply_keystream_at`] encrypts
+//! or decrypts any part of a record's ciphertext, starting at any byte offset,
+//! without processing the rest of the record; the result is the same as the
+//! corresponding part of the ciphertext (or plaintext) produced by
+//! `CHACHA20_POLY1305` with the same key and nonce.
+//!
+//! **This provides no authentication.** The Poly1305 tag of a record covers
+//! the whole ciphertext, so it can't be checked using only part of it.
+//! Plaintext produced by `apply_keystream_at` is unauthenticated until the
+//! record's entire ciphertext has been opened with `CHACHA20_POLY1305`, and
+//! any modification of the ciphertext requires the whole record to be sealed
+//! again (with a new nonce) for it to be authenticated.
+
+use super::{
+    chacha::{self, Counter, BLOCK_LEN},
+    Nonce,
+};
+use crate::{
+    cpu, error,
+    polyfill::{u64_from_usize, usize_from_u64_saturated},
+};
+
+/// The length of a key.
+pub const KEY_LEN: usize = chacha::KEY_LEN;
+
+/// A key for seeking within the ChaCha20 keystream of ChaCha20-Poly1305
+/// records.
+pub struct KeystreamKey {
+    key: chacha::Key,
+}
+
+impl KeystreamKey {
+    /// Constructs a new `KeystreamKey`.
+    ///
+    /// `key_bytes` is the `CHACHA20_POLY1305` key.
+    pub fn new(key_bytes: &[u8; KEY_LEN]) -> Self {
+        Self {
+            key: chacha::Key::new(*key_bytes),
+        }
+    }
+
+    /// XORs `in_out` with the keystream of the record sealed with `nonce`,
+    /// starting at byte `offset` of the record's ciphertext.
+    ///
+    /// Fails if `offset + in_out.len()` is larger than the maximum length of
+    /// a `CHACHA20_POLY1305` record. See the module-level documentation for
+    /// why the output isn't authenticated.
+    pub fn apply_keystream_at(
+        &self,
+        nonce: Nonce,
+        offset: u64,
+        in_out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let end = offset
+            .checked_add(u64_from_usize(in_out.len()))
+            .ok_or(error::Unspecified)?;
+        if end > MAX_RECORD_LEN {
+            return Err(error::Unspecified);
+        }
+        if in_out.is_empty() {
+            return Ok(());
+        }
+
+        // Block 0 of the keystream is used for the Poly1305 key, so the
+        // ciphertext starts at block 1. The bound on `end` ensures the block
+        // numbers fit in 32 bits.
+        let block = u32::try_from(offset / u64_from_usize(BLOCK_LEN) + 1)
+            .map_err(|_| error::Unspecified)?;
+        let skip = usize_from_u64_saturated(offset % u64_from_usize(BLOCK_LEN));
+        let nonce = *nonce.as_ref();
+        let _ = cpu::features();
+        let counter =
+            |block| Counter::from_nonce_and_ctr(Nonce::assume_unique_for_key(nonce), block);
+
+        if skip == 0 {
+            self.key.encrypt_in_place(counter(block), in_out);
+            return Ok(());
+        }
+
+        // Handle the partial first block using a temporary block-sized buffer,
+        // then the rest starting at the next block boundary.
+        let partial_len = core::cmp::min(BLOCK_LEN - skip, in_out.len());
+        let (partial, rest) = in_out.split_at_mut(partial_len);
+        let mut buffer = [0u8; BLOCK_LEN];
+        buffer[skip..][..partial_len].copy_from_slice(partial);
+        self.key
+            .encrypt_in_place(counter(block), &mut buffer[..(skip + partial_len)]);
+        partial.copy_from_slice(&buffer[skip..][..partial_len]);
+        if !rest.is_empty() {
+            self.key.encrypt_in_place(counter(block + 1), rest);
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for KeystreamKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("KeystreamKey").finish_non_exhaustive()
+    }
+}
+
+// Blocks 1 through 2^32 - 1 of the keystream.
+const MAX_RECORD_LEN: u64 = ((1u64 << 32) - 1) * u64_from_usize(BLOCK_LEN);
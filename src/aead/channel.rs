@@ -0,0 +1,98 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{Algorithm, BoundKey, Nonce, NonceSequence, OpeningKey, SealingKey, UnboundKey};
+use crate::{error, hkdf};
+
+/// Which end of a bidirectional channel a party is.
+///
+/// The two parties of a channel must use opposite sides so that each party's
+/// sealing key is the other party's opening key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelSide {
+    /// The party that initiated the channel.
+    Initiator,
+
+    /// The party that responded to the initiator.
+    Responder,
+}
+
+/// The nonce sequence used by the keys returned by [`channel_keys()`].
+///
+/// The nonce for the `n`th record (counting from zero) is four zero bytes
+/// followed by `n` as a 64-bit big-endian integer. `advance()` fails once
+/// 2<sup>64</sup> - 1 nonces have been returned.
+pub struct ChannelNonceSequence {
+    next: u64,
+}
+
+impl NonceSequence for ChannelNonceSequence {
+    fn advance(&mut self) -> Result<Nonce, error::Unspecified> {
+        let counter = self.next;
+        self.next = self.next.checked_add(1).ok_or(error::Unspecified)?;
+        let mut nonce = [0u8; super::NONCE_LEN];
+        nonce[(super::NONCE_LEN - 8)..].copy_from_slice(&counter.to_be_bytes());
+        Ok(Nonce::assume_unique_for_key(nonce))
+    }
+}
+
+impl core::fmt::Debug for ChannelNonceSequence {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("ChannelNonceSequence")
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+/// Derives the keys for one party of a bidirectional channel from `master`.
+///
+/// Each direction gets its own key, derived using HKDF-Expand with a label
+/// for that direction, and its own `ChannelNonceSequence` starting at zero.
+/// Thus sealing with the `SealingKey` returned for one `side` can be opened
+/// with the `OpeningKey` returned for the other side, and a record can't be
+/// reflected back to its sender.
+///
+/// `master` must be used only for one channel, since the nonce sequences
+/// always start at zero.
+pub fn channel_keys(
+    master: &hkdf::Prk,
+    algorithm: &'static Algorithm,
+    side: ChannelSide,
+) -> (
+    SealingKey<ChannelNonceSequence>,
+    OpeningKey<ChannelNonceSequence>,
+) {
+    let (sealing_label, opening_label) = match side {
+        ChannelSide::Initiator => (INITIATOR_TO_RESPONDER_LABEL, RESPONDER_TO_INITIATOR_LABEL),
+        ChannelSide::Responder => (RESPONDER_TO_INITIATOR_LABEL, INITIATOR_TO_RESPONDER_LABEL),
+    };
+    let sealing_key = SealingKey::new(
+        derive_key(master, algorithm, sealing_label),
+        ChannelNonceSequence { next: 0 },
+    );
+    let opening_key = OpeningKey::new(
+        derive_key(master, algorithm, opening_label),
+        ChannelNonceSequence { next: 0 },
+    );
+    (sealing_key, opening_key)
+}
+
+fn derive_key(master: &hkdf::Prk, algorithm: &'static Algorithm, label: &[u8]) -> UnboundKey {
+    // The `unwrap()` won't fail since every AEAD key is much shorter than the
+    // maximum HKDF output length of any digest algorithm.
+    UnboundKey::from(master.expand(&[label], algorithm).unwrap())
+}
+
+const INITIATOR_TO_RESPONDER_LABEL: &[u8] = b"ring channel initiator to responder";
+const RESPONDER_TO_INITIATOR_LABEL: &[u8] = b"ring channel responder to initiator";
@@ -0,0 +1,224 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Key-committing AEADs.
+//!
+//! AES-GCM and ChaCha20-Poly1305 aren't key-committing: an attacker can
+//! construct a ciphertext that opens successfully under two (or more)
+//! different keys. That breaks protocols that assume a ciphertext can only be
+//! opened with the key that sealed it, e.g. password-based encryption and
+//! some envelope encryption schemes.
+//!
+//! The AEADs here add an explicit key commitment to the underlying AEAD.
+//! For each message, with key `K` and nonce `N`:
+//!
+//! * the commitment is `HMAC-SHA256(K, "ring committing AEAD commitment" || id || N)`;
+//! * the message is sealed with the underlying AEAD, nonce `N`, and the key
+//!   `HMAC-SHA256(K, "ring committing AEAD key" || id || N)`,
+//!
+//! where `id` is a byte identifying the underlying AEAD. The commitment is
+//! appended after the underlying AEAD's tag and is checked before anything
+//! is decrypted. Since HMAC-SHA256 is collision-resistant, no ciphertext has
+//! a valid commitment for two different keys.
+//!
+//! ```
+//! use ring::aead::{self, committing};
+//!
+//! let key = committing::Key::new(&committing::AES_256_GCM, &[0x42; 32])?;
+//!
+//! let mut in_out = b"hello, world".to_vec();
+//! let nonce = aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]);
+//! key.seal_in_place_append_tag(nonce, aead::Aad::empty(), &mut in_out)?;
+//!
+//! let nonce = aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]);
+//! let plaintext = key.open_in_place(nonce, aead::Aad::empty(), &mut in_out)?;
+//! assert_eq!(plaintext, b"hello, world");
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+
+use super::{Aad, LessSafeKey, Nonce, UnboundKey};
+use crate::{constant_time, error, hmac};
+
+/// A key-committing AEAD algorithm.
+pub struct Algorithm {
+    id: AlgorithmID,
+    aead: &'static super::Algorithm,
+}
+
+derive_debug_via_id!(Algorithm);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum AlgorithmID {
+    AES_256_GCM = 1,
+    CHACHA20_POLY1305 = 2,
+}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Algorithm {}
+
+impl Algorithm {
+    /// The length of the key.
+    #[inline]
+    pub fn key_len(&self) -> usize {
+        KEY_LEN
+    }
+
+    /// The length of the nonces.
+    #[inline]
+    pub fn nonce_len(&self) -> usize {
+        self.aead.nonce_len()
+    }
+
+    /// The number of bytes that sealing appends to the plaintext: the
+    /// underlying AEAD's tag followed by the commitment.
+    #[inline]
+    pub fn tag_len(&self) -> usize {
+        self.aead.tag_len() + COMMITMENT_LEN
+    }
+
+    /// The underlying (non-committing) AEAD algorithm.
+    #[inline]
+    pub fn aead_algorithm(&self) -> &'static super::Algorithm {
+        self.aead
+    }
+}
+
+/// AES-256-GCM with a key commitment.
+pub static AES_256_GCM: Algorithm = Algorithm {
+    id: AlgorithmID::AES_256_GCM,
+    aead: &super::AES_256_GCM,
+};
+
+/// ChaCha20-Poly1305 with a key commitment.
+pub static CHACHA20_POLY1305: Algorithm = Algorithm {
+    id: AlgorithmID::CHACHA20_POLY1305,
+    aead: &super::CHACHA20_POLY1305,
+};
+
+/// The length of the keys of all the committing AEADs.
+const KEY_LEN: usize = 32;
+
+/// The length of the commitment.
+pub const COMMITMENT_LEN: usize = 32;
+
+const COMMITMENT_LABEL: &[u8] = b"ring committing AEAD commitment";
+const KEY_LABEL: &[u8] = b"ring committing AEAD key";
+
+/// A key for a key-committing AEAD.
+///
+/// Like `LessSafeKey`, the caller must ensure that each nonce is used at most
+/// once with a given key.
+#[derive(Clone)]
+pub struct Key {
+    key: hmac::Key,
+    algorithm: &'static Algorithm,
+}
+
+impl Key {
+    /// Constructs a key from its raw bytes.
+    ///
+    /// Fails if `key_bytes.len() != algorithm.key_len()`.
+    pub fn new(
+        algorithm: &'static Algorithm,
+        key_bytes: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        if key_bytes.len() != KEY_LEN {
+            return Err(error::Unspecified);
+        }
+        Ok(Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, key_bytes),
+            algorithm,
+        })
+    }
+
+    /// The key's algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// Seals `in_out` in place, appending the tag and then the commitment.
+    ///
+    /// `nonce` must be unique for every use of the key to seal data.
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let commitment = self.derive(COMMITMENT_LABEL, &nonce);
+        self.message_key(&nonce)?
+            .seal_in_place_append_tag(nonce, aad, in_out)?;
+        in_out.extend(commitment.as_ref());
+        Ok(())
+    }
+
+    /// Opens `in_out` in place, where `in_out` is the ciphertext followed by
+    /// the tag and the commitment.
+    ///
+    /// The commitment is verified before anything is decrypted. When this
+    /// returns `Err(..)`, `in_out` never contains any of the plaintext.
+    pub fn open_in_place<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let commitment_offset = in_out
+            .len()
+            .checked_sub(COMMITMENT_LEN)
+            .ok_or(error::Unspecified)?;
+        let (in_out, received_commitment) = in_out.split_at_mut(commitment_offset);
+
+        let commitment = self.derive(COMMITMENT_LABEL, &nonce);
+        constant_time::verify_slices_are_equal(commitment.as_ref(), received_commitment)?;
+
+        self.message_key(&nonce)?.open_in_place(nonce, aad, in_out)
+    }
+
+    fn message_key(&self, nonce: &Nonce) -> Result<LessSafeKey, error::Unspecified> {
+        let key_bytes = self.derive(KEY_LABEL, nonce);
+        let key = UnboundKey::new(self.algorithm.aead, key_bytes.as_ref())?;
+        Ok(LessSafeKey::new(key))
+    }
+
+    fn derive(&self, label: &[u8], nonce: &Nonce) -> hmac::Tag {
+        let mut ctx = hmac::Context::with_key(&self.key);
+        ctx.update(label);
+        ctx.update(&[self.algorithm.id as u8]);
+        ctx.update(nonce.as_ref());
+        ctx.sign()
+    }
+}
+
+impl core::fmt::Debug for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Key")
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{Aad, Algorithm, LessSafeKey, Nonce, UnboundKey, NONCE_LEN};
+use crate::{error, hkdf};
+
+/// The maximum number of records in a forward-secure key chain.
+///
+/// Opening the record at index `n` recomputes `n` links of the chain, so the
+/// index given to [`ForwardSecureOpener::open_in_place`], which typically
+/// comes from the stored record, must be bounded.
+pub const FORWARD_SECURE_MAX_RECORDS: u64 = 1 << 20;
+
+/// Seals a sequence of records, e.g. log entries, each with the next key in a
+/// forward-secure key chain.
+///
+/// Each record is sealed with its own key. The keys come from a hash chain
+/// over a root key: the chain key for record 0 is the root key, and each
+/// chain key is used, with HKDF-Expand, to derive the key for its record and
+/// the chain key for the next record. A `ForwardSecureSealer` only keeps the
+/// chain key for the next record, so if its state is compromised after it
+/// has sealed records 0..n, the attacker can't decrypt or forge records
+/// 0..n; they can only derive the keys for records n and later.
+///
+/// The limits of this property:
+///
+/// * The root key can derive every key, so it must not be kept where the
+///   sealer's state is, e.g. it should be held offline by the party that
+///   will open the records.
+/// * Replaced chain keys are dropped, but not zeroized, so they may remain
+///   in memory that hasn't been reused yet.
+/// * Records are authenticated individually. Truncation of the sequence of
+///   records, or deletion of records from the end, isn't detected unless the
+///   application authenticates the number of records some other way.
+#[derive(Debug)]
+pub struct ForwardSecureSealer {
+    algorithm: &'static Algorithm,
+    chain_key: hkdf::Prk,
+    next_index: u64,
+}
+
+impl ForwardSecureSealer {
+    /// Constructs a `ForwardSecureSealer` that will seal records starting at
+    /// index 0 of the chain rooted at `root`.
+    ///
+    /// A given `root` must be used for only one `ForwardSecureSealer`.
+    pub fn new(algorithm: &'static Algorithm, root: hkdf::Prk) -> Self {
+        Self {
+            algorithm,
+            chain_key: root,
+            next_index: 0,
+        }
+    }
+
+    /// The AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// The index of the record that the next call to
+    /// `seal_in_place_append_tag` will seal.
+    #[inline]
+    pub fn next_index(&self) -> u64 {
+        self.next_index
+    }
+
+    /// Seals the next record and then ratchets the key forward, returning the
+    /// record's index.
+    ///
+    /// Fails once [`FORWARD_SECURE_MAX_RECORDS`] records have been sealed.
+    /// Otherwise, the key is ratcheted forward even if sealing fails.
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &mut self,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<u64, error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let index = self.next_index;
+        if index >= FORWARD_SECURE_MAX_RECORDS {
+            return Err(error::Unspecified);
+        }
+        self.next_index = index + 1;
+        let record_key = record_key(&self.chain_key, self.algorithm);
+        self.chain_key = next_chain_key(&self.chain_key);
+        record_key
+            .seal_in_place_append_tag(record_nonce(), aad, in_out)
+            .map(|()| index)
+    }
+}
+
+/// Opens records sealed by a [`ForwardSecureSealer`], given their indexes.
+///
+/// The root key must be kept separately from the sealer; see
+/// [`ForwardSecureSealer`].
+///
+/// Opening the record at index `n` recomputes the chain from the root, which
+/// takes time proportional to `n`; indexes of
+/// [`FORWARD_SECURE_MAX_RECORDS`] or more are rejected.
+#[derive(Debug)]
+pub struct ForwardSecureOpener {
+    algorithm: &'static Algorithm,
+    root: hkdf::Prk,
+}
+
+impl ForwardSecureOpener {
+    /// Constructs a `ForwardSecureOpener` for records sealed using the chain
+    /// rooted at `root`.
+    pub fn new(algorithm: &'static Algorithm, root: hkdf::Prk) -> Self {
+        Self { algorithm, root }
+    }
+
+    /// The AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// Authenticates and decrypts (“opens”) the record at index `index`,
+    /// returning the plaintext.
+    ///
+    /// Fails if `index` isn't less than [`FORWARD_SECURE_MAX_RECORDS`].
+    pub fn open_in_place<'in_out, A>(
+        &self,
+        index: u64,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        if index >= FORWARD_SECURE_MAX_RECORDS {
+            return Err(error::Unspecified);
+        }
+        let mut chain_key = self.root.clone();
+        for _ in 0..index {
+            chain_key = next_chain_key(&chain_key);
+        }
+        record_key(&chain_key, self.algorithm).open_in_place(record_nonce(), aad, in_out)
+    }
+}
+
+fn record_key(chain_key: &hkdf::Prk, algorithm: &'static Algorithm) -> LessSafeKey {
+    // The `unwrap()` won't fail since every AEAD key is much shorter than the
+    // maximum HKDF output length of any digest algorithm.
+    LessSafeKey::new(UnboundKey::from(
+        chain_key.expand(&[RECORD_KEY_LABEL], algorithm).unwrap(),
+    ))
+}
+
+fn next_chain_key(chain_key: &hkdf::Prk) -> hkdf::Prk {
+    // The `unwrap()` won't fail since the chain key is the length of the
+    // digest algorithm's output.
+    hkdf::Prk::from(
+        chain_key
+            .expand(&[CHAIN_KEY_LABEL], chain_key.algorithm())
+            .unwrap(),
+    )
+}
+
+// Each record key is used for exactly one record, so a fixed nonce is safe.
+fn record_nonce() -> Nonce {
+    Nonce::assume_unique_for_key([0; NONCE_LEN])
+}
+
+const RECORD_KEY_LABEL: &[u8] = b"ring forward-secure record key";
+const CHAIN_KEY_LABEL: &[u8] = b"ring forward-secure chain key";
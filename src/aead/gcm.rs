@@ -101,6 +101,19 @@ impl<'key> Context<'key> {
         Ok(ctx)
     }
 
+    /// Constructs a context for computing POLYVAL with GHASH, as described in
+    /// RFC 8452 Appendix A. The caller passes byte-reversed blocks and takes
+    /// the result from `into_ghash()`, so no block of lengths is added.
+    pub(super) fn for_polyval(key: &'key Key, cpu_features: cpu::Features) -> Self {
+        Self {
+            Xi: Xi(ZERO_BLOCK),
+            h_table: &key.h_table,
+            aad_len: BitLength::from_bits(0),
+            in_out_len: BitLength::from_bits(0),
+            cpu_features,
+        }
+    }
+
     #[cfg(all(target_arch = "aarch64", target_pointer_width = "64"))]
     pub(super) fn in_out_whole_block_bits(&self) -> BitLength<usize> {
         use crate::polyfill::usize_from_u64;
@@ -199,6 +212,11 @@ impl<'key> Context<'key> {
         f(self.Xi.into_block(), self.cpu_features)
     }
 
+    /// The GHASH of the blocks passed so far, for `for_polyval()` contexts.
+    pub(super) fn into_ghash(self) -> Block {
+        self.Xi.into_block()
+    }
+
     #[cfg(target_arch = "x86_64")]
     pub(super) fn is_avx(&self) -> bool {
         match detect_implementation(self.cpu_features) {
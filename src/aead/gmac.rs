@@ -0,0 +1,84 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! GMAC: AES-GCM authentication without encryption.
+//!
+//! GMAC is AES-GCM where all of the input is authenticated as associated data
+//! and nothing is encrypted, as specified in [NIST SP 800-38D] Section 3. The
+//! tag computed by [`Key::sign`] is the same as the tag of sealing an empty
+//! plaintext with `data` as the associated data.
+//!
+//! Like AES-GCM, GMAC is completely insecure if a nonce is ever used twice
+//! with the same key; a repeated nonce allows tags to be forged.
+//!
+//! [NIST SP 800-38D]:
+//!     http://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+
+use super::{aes_gcm, Aad, Algorithm, KeyInner, Nonce, Tag};
+use crate::{constant_time, cpu, error};
+
+/// A key for computing and verifying GMAC tags.
+pub struct Key {
+    inner: aes_gcm::Key,
+    algorithm: &'static Algorithm,
+}
+
+derive_debug_via_field!(Key, algorithm);
+
+impl Key {
+    /// Constructs a new GMAC key.
+    ///
+    /// `algorithm` must be one of the AES-GCM algorithms, e.g.
+    /// [`super::AES_128_GCM`] or [`super::AES_256_GCM`]; it determines the
+    /// AES key size and the tag length. Fails if `algorithm` isn't an AES-GCM
+    /// algorithm or if `key_bytes.len() != algorithm.key_len()`.
+    pub fn new(
+        algorithm: &'static Algorithm,
+        key_bytes: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        match algorithm.new_key(key_bytes, cpu::features())? {
+            KeyInner::AesGcm(inner) => Ok(Self { inner, algorithm }),
+            KeyInner::AesGcmSiv(_) | KeyInner::ChaCha20Poly1305(_) => Err(error::Unspecified),
+        }
+    }
+
+    /// The key's AES-GCM algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// Computes the GMAC tag of `data`.
+    ///
+    /// `nonce` must be unique for every use of the key to compute a tag.
+    pub fn sign(&self, nonce: Nonce, data: &[u8]) -> Result<Tag, error::Unspecified> {
+        let tag = aes_gcm::seal(
+            &self.inner,
+            nonce,
+            Aad::from(data),
+            &mut [],
+            cpu::features(),
+        )?;
+        Ok(tag.truncated(self.algorithm.tag_len()))
+    }
+
+    /// Verifies that `tag` is the GMAC tag of `data` for `nonce`.
+    ///
+    /// The comparison is done in constant time. Fails if `tag` isn't exactly
+    /// `self.algorithm().tag_len()` bytes long.
+    pub fn verify(&self, nonce: Nonce, data: &[u8], tag: &[u8]) -> Result<(), error::Unspecified> {
+        let calculated_tag = self.sign(nonce, data)?;
+        constant_time::verify_slices_are_equal(calculated_tag.as_ref(), tag)
+    }
+}
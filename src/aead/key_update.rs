@@ -0,0 +1,143 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{
+    Algorithm, BoundKey, Nonce, NonceSequence, OpeningKey, SealingKey, SequenceNonce, UnboundKey,
+    NONCE_LEN,
+};
+use crate::{error, hkdf};
+
+/// The keys for one direction of a TLS 1.3-style channel, derived from a
+/// traffic secret, with support for TLS 1.3's key update.
+///
+/// The key and IV are derived from the traffic secret as specified in
+/// [RFC 8446 Section 7.3], and `update()` replaces the traffic secret with
+/// the next one, `application_traffic_secret_N+1`, using
+/// HKDF-Expand-Label(secret, "traffic upd", "", Hash.length).
+///
+/// [RFC 8446 Section 7.3]: https://www.rfc-editor.org/rfc/rfc8446#section-7.3
+#[derive(Debug)]
+pub struct KeyUpdate {
+    algorithm: &'static Algorithm,
+    hkdf_algorithm: hkdf::Algorithm,
+    traffic_secret: hkdf::Prk,
+}
+
+impl KeyUpdate {
+    /// Constructs a `KeyUpdate` for `algorithm` from `traffic_secret`, which
+    /// is used as an HKDF PRK for `hkdf_algorithm`, i.e. the handshake hash.
+    pub fn new(
+        algorithm: &'static Algorithm,
+        hkdf_algorithm: hkdf::Algorithm,
+        traffic_secret: &[u8],
+    ) -> Self {
+        Self {
+            algorithm,
+            hkdf_algorithm,
+            traffic_secret: hkdf::Prk::new_less_safe(hkdf_algorithm, traffic_secret),
+        }
+    }
+
+    /// The AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// A sealing key for the current traffic secret, with a nonce sequence
+    /// starting at record sequence number zero.
+    pub fn sealing_key(&self) -> SealingKey<TrafficNonceSequence> {
+        SealingKey::new(self.derive_key(), self.derive_nonce_sequence())
+    }
+
+    /// An opening key for the current traffic secret, with a nonce sequence
+    /// starting at record sequence number zero.
+    pub fn opening_key(&self) -> OpeningKey<TrafficNonceSequence> {
+        OpeningKey::new(self.derive_key(), self.derive_nonce_sequence())
+    }
+
+    /// Replaces the traffic secret with the next one.
+    ///
+    /// Keys previously returned by `sealing_key()` and `opening_key()` are
+    /// unaffected; the caller must replace them with new ones.
+    pub fn update(&mut self) {
+        let traffic_secret = expand_label(
+            &self.traffic_secret,
+            b"traffic upd",
+            self.hkdf_algorithm,
+            |okm| hkdf::Prk::from(okm),
+        );
+        self.traffic_secret = traffic_secret;
+    }
+
+    fn derive_key(&self) -> UnboundKey {
+        expand_label(&self.traffic_secret, b"key", self.algorithm, |okm| {
+            UnboundKey::from(okm)
+        })
+    }
+
+    fn derive_nonce_sequence(&self) -> TrafficNonceSequence {
+        let iv = expand_label(&self.traffic_secret, b"iv", hkdf::Len(NONCE_LEN), |okm| {
+            let mut iv = [0; NONCE_LEN];
+            okm.fill(&mut iv).unwrap();
+            iv
+        });
+        TrafficNonceSequence(SequenceNonce::new(iv))
+    }
+}
+
+/// The nonce sequence used by the keys returned by [`KeyUpdate`].
+///
+/// As in TLS 1.3, the nonce for the record with sequence number `n` is the
+/// IV XORed with `n` encoded as a 96-bit big-endian integer; see
+/// [`SequenceNonce`]. `advance()` fails once 2<sup>64</sup> - 1 nonces have
+/// been returned.
+pub struct TrafficNonceSequence(SequenceNonce);
+
+impl NonceSequence for TrafficNonceSequence {
+    fn advance(&mut self) -> Result<Nonce, error::Unspecified> {
+        self.0.advance()
+    }
+}
+
+impl core::fmt::Debug for TrafficNonceSequence {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("TrafficNonceSequence")
+            .field("next", &self.0.sequence_number())
+            .finish()
+    }
+}
+
+/// HKDF-Expand-Label(secret, label, "", len) from RFC 8446 Section 7.1.
+fn expand_label<L: hkdf::KeyType, T>(
+    secret: &hkdf::Prk,
+    label: &[u8],
+    len: L,
+    f: impl FnOnce(hkdf::Okm<L>) -> T,
+) -> T {
+    const LABEL_PREFIX: &[u8] = b"tls13 ";
+
+    // The `unwrap()`s won't fail since all the lengths are tiny.
+    let output_len = u16::try_from(len.len()).unwrap().to_be_bytes();
+    let label_len = [u8::try_from(LABEL_PREFIX.len() + label.len()).unwrap()];
+    let context_len = [0];
+    let info = [
+        &output_len[..],
+        &label_len[..],
+        LABEL_PREFIX,
+        label,
+        &context_len[..],
+    ];
+    f(secret.expand(&info, len).unwrap())
+}
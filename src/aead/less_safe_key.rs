@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use super::{Aad, Algorithm, KeyInner, Nonce, Tag, UnboundKey, TAG_LEN};
+use super::{Aad, Algorithm, KeyInner, Nonce, Tag, UnboundKey};
 use crate::{cpu, error};
 use core::ops::RangeFrom;
 
@@ -20,6 +20,21 @@ use core::ops::RangeFrom;
 /// `NonceSequence` cannot reasonably be used.
 ///
 /// Prefer to use `OpeningKey`/`SealingKey` and `NonceSequence` when practical.
+///
+/// The key schedule is expanded once, when the key is constructed, and is
+/// never modified afterwards; all the methods take `&self`. A `LessSafeKey`
+/// is `Send` and `Sync`, so one key can be shared by many threads, e.g. by
+/// reference or in an `Arc`, and each thread can seal or open concurrently
+/// without re-expanding the key. Cloning copies the already-expanded key
+/// schedule. When sharing a key, the threads must coordinate so that a nonce
+/// is never used twice, e.g. by giving each thread its own disjoint range of
+/// nonces.
+///
+/// The key schedule isn't zeroized when the key is dropped, and there is no
+/// method to zeroize it explicitly. Like any Rust value, a key may be copied
+/// to a new location whenever it is moved, and the compiler leaves the old
+/// copies in place. Overwriting only the copy that a method like
+/// `zeroize(self)` receives would suggest a guarantee that can't be provided.
 #[derive(Clone)]
 pub struct LessSafeKey {
     inner: KeyInner,
@@ -46,6 +61,10 @@ impl LessSafeKey {
 
     /// Like [open_in_place](Self::open_in_place), except the authentication tag is
     /// passed separately.
+    ///
+    /// Some AEADs, AES-GCM in particular, decrypt before the tag can be
+    /// checked. Regardless, when this returns `Err(..)` the decrypted bytes
+    /// will have been zeroed, so `in_out` never contains any of the plaintext.
     #[inline]
     pub fn open_in_place_separate_tag<'in_out, A>(
         &self,
@@ -104,12 +123,12 @@ impl LessSafeKey {
     {
         let tag_offset = in_out
             .len()
-            .checked_sub(TAG_LEN)
+            .checked_sub(self.algorithm.tag_len())
             .ok_or(error::Unspecified)?;
 
         // Split the tag off the end of `in_out`.
         let (in_out, received_tag) = in_out.split_at_mut(tag_offset);
-        let received_tag = (*received_tag).try_into()?;
+        let received_tag = Tag::try_for_algorithm(self.algorithm, received_tag)?;
         let ciphertext = ciphertext_and_tag;
 
         self.open_in_place_separate_tag(nonce, aad, received_tag, in_out, ciphertext)
@@ -134,6 +153,29 @@ impl LessSafeKey {
             .map(|tag| in_out.extend(tag.as_ref()))
     }
 
+    /// Like [`Self::seal_in_place_append_tag()`], except it reserves exactly
+    /// enough capacity in `in_out` for the tag, if needed, before sealing.
+    ///
+    /// This ensures that appending the tag reallocates `in_out` at most once
+    /// and without the over-allocation that `Vec`'s growth strategy would
+    /// otherwise do.
+    ///
+    /// `nonce` must be unique for every use of the key to seal data.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn seal_in_place_vec<A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut alloc::vec::Vec<u8>,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        in_out.reserve_exact(self.algorithm.tag_len());
+        self.seal_in_place_append_tag(nonce, aad, in_out)
+    }
+
     /// Like `super::SealingKey::seal_in_place_separate_tag()`, except it
     /// accepts an arbitrary nonce.
     ///
@@ -157,12 +199,98 @@ impl LessSafeKey {
         )
     }
 
+    /// Seals the concatenation of the fragments of `plaintext`, writing the
+    /// ciphertext to `out` and returning the tag.
+    ///
+    /// This is for callers that assemble a message from separate pieces,
+    /// e.g. a packet header and payload fragments. The fragments may be
+    /// `&[u8]`, `Vec<u8>`, `std::io::IoSlice`, etc. Each fragment is copied
+    /// into `out` once and encrypted there, so the caller doesn't need to
+    /// concatenate them into a buffer of its own first.
+    ///
+    /// Fails if `out.len()` isn't the total length of the fragments.
+    ///
+    /// `nonce` must be unique for every use of the key to seal data.
+    pub fn seal_gather_separate_tag<A, P>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        plaintext: &[P],
+        out: &mut [u8],
+    ) -> Result<Tag, error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        P: core::ops::Deref<Target = [u8]>,
+    {
+        let plaintext_len = plaintext
+            .iter()
+            .try_fold(0usize, |len, fragment| len.checked_add(fragment.len()))
+            .ok_or(error::Unspecified)?;
+        if out.len() != plaintext_len {
+            return Err(error::Unspecified);
+        }
+        let mut remaining = &mut out[..];
+        for fragment in plaintext {
+            let (dst, rest) = remaining.split_at_mut(fragment.len());
+            dst.copy_from_slice(fragment);
+            remaining = rest;
+        }
+        self.seal_in_place_separate_tag(nonce, aad, out)
+    }
+
+    /// Like [`Self::seal_in_place_append_tag()`], except the nonce is
+    /// authenticated along with the data.
+    ///
+    /// The associated data is `nonce || extra_aad`. This is useful for
+    /// protocols that send the nonce in cleartext alongside the ciphertext.
+    /// Use [`Self::open_in_place_authenticating_nonce()`] to open the result.
+    ///
+    /// `nonce` must be unique for every use of the key to seal data.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn seal_in_place_authenticating_nonce<A, InOut>(
+        &self,
+        nonce: Nonce,
+        extra_aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let aad = aad_authenticating_nonce(&nonce, extra_aad.as_ref());
+        self.seal_in_place_append_tag(nonce, Aad::from(aad), in_out)
+    }
+
+    /// Opens data sealed with [`Self::seal_in_place_authenticating_nonce()`].
+    ///
+    /// Opening fails if `nonce` or `extra_aad` differ from those used when
+    /// sealing.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn open_in_place_authenticating_nonce<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        extra_aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let aad = aad_authenticating_nonce(&nonce, extra_aad.as_ref());
+        self.open_in_place(nonce, Aad::from(aad), in_out)
+    }
+
     /// The key's AEAD algorithm.
     #[inline]
     pub fn algorithm(&self) -> &'static Algorithm {
         self.algorithm
     }
 
+    pub(super) fn inner(&self) -> &KeyInner {
+        &self.inner
+    }
+
     pub(super) fn fmt_debug(
         &self,
         type_name: &'static str,
@@ -174,6 +302,16 @@ impl LessSafeKey {
     }
 }
 
+// The AEAD implementations require the associated data to be contiguous.
+#[cfg(feature = "alloc")]
+fn aad_authenticating_nonce(nonce: &Nonce, extra_aad: &[u8]) -> alloc::vec::Vec<u8> {
+    let nonce: &[u8] = nonce.as_ref();
+    let mut aad = alloc::vec::Vec::with_capacity(nonce.len() + extra_aad.len());
+    aad.extend_from_slice(nonce);
+    aad.extend_from_slice(extra_aad);
+    aad
+}
+
 impl core::fmt::Debug for LessSafeKey {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         self.fmt_debug("LessSafeKey", f)
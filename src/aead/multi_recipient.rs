@@ -0,0 +1,351 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Multi-recipient encryption: seal a payload once, then wrap its key for
+//! each recipient.
+//!
+//! The payload is sealed with a freshly-generated random *content key*. The
+//! content key is then *wrapped* separately for each recipient:
+//!
+//! 1. An ephemeral key pair is generated for the recipient's key agreement
+//!    algorithm, and a shared secret is agreed with the recipient's public
+//!    key.
+//! 2. A key-encryption key is derived from the shared secret using HKDF-SHA256
+//!    with an empty salt and an info string that binds a label, the content
+//!    key's AEAD algorithm, the ephemeral public key, and the recipient's
+//!    public key.
+//! 3. The content key is sealed with AES-256-GCM under the key-encryption key.
+//!
+//! A `WrappedKey` is the ephemeral public key followed by the sealed content
+//! key. Since the AEAD algorithm is bound into the key-encryption key, a
+//! content key can only be unwrapped for the algorithm it was generated for.
+//! Each key-encryption key wraps a single key, so AES-GCM gives the same
+//! protection as AES Key Wrap (RFC 3394) here, without needing another
+//! construction.
+//!
+//! Since both the content key and each key-encryption key are used to seal
+//! exactly one message, both are used with the all-zero nonce.
+//!
+//! ```
+//! use ring::{aead, aead::multi_recipient, agreement, rand};
+//!
+//! let rng = rand::SystemRandom::new();
+//! let alice = multi_recipient::RecipientPrivateKey::generate(&agreement::X25519, &rng)?;
+//! let bob = multi_recipient::RecipientPrivateKey::generate(&agreement::X25519, &rng)?;
+//!
+//! // The sender.
+//! let mut in_out = b"hello, world".to_vec();
+//! let content_key = multi_recipient::seal_in_place_append_tag(
+//!     &aead::AES_256_GCM,
+//!     aead::Aad::empty(),
+//!     &mut in_out,
+//!     &rng,
+//! )?;
+//! let wrapped_keys = [alice.public_key(), bob.public_key()]
+//!     .iter()
+//!     .map(|public_key| {
+//!         let public_key = agreement::UnparsedPublicKey::new(&agreement::X25519, public_key);
+//!         content_key.wrap(&public_key, &rng)
+//!     })
+//!     .collect::<Result<Vec<_>, _>>()?;
+//!
+//! // Bob.
+//! let content_key = bob.unwrap(&aead::AES_256_GCM, wrapped_keys[1].as_ref())?;
+//! let plaintext = content_key.open_in_place(aead::Aad::empty(), &mut in_out)?;
+//! assert_eq!(plaintext, b"hello, world");
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+
+use super::{
+    algorithm::AlgorithmID, Aad, Algorithm, LessSafeKey, Nonce, UnboundKey, AES_256_GCM,
+    MAX_TAG_LEN, NONCE_LEN,
+};
+use crate::{agreement, cpu, debug, ec, error, hkdf, rand};
+
+/// The maximum length of a content key, in bytes.
+const MAX_CONTENT_KEY_LEN: usize = 32;
+
+/// The maximum length of a `WrappedKey`, in bytes.
+pub const MAX_WRAPPED_KEY_LEN: usize = ec::PUBLIC_KEY_MAX_LEN + MAX_CONTENT_KEY_LEN + MAX_TAG_LEN;
+
+const KEK_INFO_LABEL: &[u8] = b"ring multi-recipient key wrap";
+
+/// Seals `in_out` with a newly-generated random content key, appending the
+/// tag, and returns the content key so that it can be wrapped for each
+/// recipient.
+///
+/// The content key is used for only this one message, so there is no nonce
+/// to manage.
+pub fn seal_in_place_append_tag<A, InOut>(
+    algorithm: &'static Algorithm,
+    aad: Aad<A>,
+    in_out: &mut InOut,
+    rng: &dyn rand::SecureRandom,
+) -> Result<ContentKey, error::Unspecified>
+where
+    A: AsRef<[u8]>,
+    InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+{
+    let key_len = algorithm.key_len();
+    if key_len > MAX_CONTENT_KEY_LEN {
+        return Err(error::Unspecified);
+    }
+    let mut content_key = ContentKey {
+        algorithm,
+        bytes: [0; MAX_CONTENT_KEY_LEN],
+    };
+    rng.fill(&mut content_key.bytes[..key_len])?;
+    content_key
+        .less_safe_key()?
+        .seal_in_place_append_tag(zero_nonce(), aad, in_out)?;
+    Ok(content_key)
+}
+
+/// The random key that a multi-recipient payload is sealed with.
+pub struct ContentKey {
+    algorithm: &'static Algorithm,
+    bytes: [u8; MAX_CONTENT_KEY_LEN],
+}
+
+derive_debug_via_field!(ContentKey, stringify!(ContentKey), algorithm);
+
+impl ContentKey {
+    /// The AEAD algorithm that the payload is sealed with.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// Wraps the content key for the recipient with the public key
+    /// `recipient`.
+    pub fn wrap<B: AsRef<[u8]>>(
+        &self,
+        recipient: &agreement::UnparsedPublicKey<B>,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<WrappedKey, error::Unspecified> {
+        let ephemeral_private_key =
+            agreement::EphemeralPrivateKey::generate(recipient.algorithm(), rng)?;
+        let ephemeral_public_key = ephemeral_private_key.compute_public_key()?;
+        let ephemeral_public_key = ephemeral_public_key.as_ref();
+
+        let key_len = self.algorithm.key_len();
+        let mut wrapped = WrappedKey {
+            bytes: [0; MAX_WRAPPED_KEY_LEN],
+            len: ephemeral_public_key.len() + key_len + MAX_TAG_LEN,
+        };
+        let (public_key_out, sealed) =
+            wrapped.bytes[..wrapped.len].split_at_mut(ephemeral_public_key.len());
+        public_key_out.copy_from_slice(ephemeral_public_key);
+        let (ciphertext, tag_out) = sealed.split_at_mut(key_len);
+        ciphertext.copy_from_slice(&self.bytes[..key_len]);
+
+        let kek = agreement::agree_ephemeral(ephemeral_private_key, recipient, |shared| {
+            key_encryption_key(
+                shared,
+                self.algorithm,
+                ephemeral_public_key,
+                recipient.bytes().as_ref(),
+            )
+        })??;
+        let tag = kek.seal_in_place_separate_tag(zero_nonce(), Aad::empty(), ciphertext)?;
+        tag_out.copy_from_slice(tag.as_ref());
+
+        Ok(wrapped)
+    }
+
+    /// Opens a payload that was sealed by `seal_in_place_append_tag`.
+    ///
+    /// `in_out` is the sealed payload followed by its tag. On success, the
+    /// plaintext is returned as a prefix of `in_out`.
+    pub fn open_in_place<A: AsRef<[u8]>>(
+        self,
+        aad: Aad<A>,
+        in_out: &mut [u8],
+    ) -> Result<&mut [u8], error::Unspecified> {
+        self.less_safe_key()?
+            .open_in_place(zero_nonce(), aad, in_out)
+    }
+
+    fn less_safe_key(&self) -> Result<LessSafeKey, error::Unspecified> {
+        let key = UnboundKey::new(self.algorithm, &self.bytes[..self.algorithm.key_len()])?;
+        Ok(LessSafeKey::new(key))
+    }
+}
+
+/// A content key wrapped for one recipient.
+#[derive(Clone)]
+pub struct WrappedKey {
+    bytes: [u8; MAX_WRAPPED_KEY_LEN],
+    len: usize,
+}
+
+impl AsRef<[u8]> for WrappedKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl core::fmt::Debug for WrappedKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_tuple("WrappedKey")
+            .field(&debug::HexStr(self.as_ref()))
+            .finish()
+    }
+}
+
+/// A recipient's long-term private key, which can unwrap any number of
+/// wrapped content keys.
+pub struct RecipientPrivateKey {
+    private_key: agreement::StaticPrivateKey,
+    public_key: agreement::PublicKey,
+}
+
+derive_debug_via_field!(
+    RecipientPrivateKey,
+    stringify!(RecipientPrivateKey),
+    public_key
+);
+
+impl RecipientPrivateKey {
+    /// Generates a new private key for the given key agreement algorithm.
+    pub fn generate(
+        alg: &'static agreement::Algorithm,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Self, error::Unspecified> {
+        let cpu = cpu::features();
+        let private_key = agreement::StaticPrivateKey::generate(alg, rng, cpu)?;
+        Self::new(private_key, cpu)
+    }
+
+    /// Constructs a private key from its encoding in the standard form for
+    /// `alg`: the 32-byte scalar for X25519, or the big-endian scalar for the
+    /// NIST curves.
+    pub fn from_private_key(
+        alg: &'static agreement::Algorithm,
+        private_key: &[u8],
+    ) -> Result<Self, error::KeyRejected> {
+        let cpu = cpu::features();
+        let private_key =
+            agreement::StaticPrivateKey::from_bytes(alg, untrusted::Input::from(private_key), cpu)
+                .map_err(|error::Unspecified| error::KeyRejected::invalid_component())?;
+        Self::new(private_key, cpu)
+            .map_err(|error::Unspecified| error::KeyRejected::unexpected_error())
+    }
+
+    fn new(
+        private_key: agreement::StaticPrivateKey,
+        cpu: cpu::Features,
+    ) -> Result<Self, error::Unspecified> {
+        let public_key = private_key.compute_public_key(cpu)?;
+        Ok(Self {
+            private_key,
+            public_key,
+        })
+    }
+
+    /// The public key that senders wrap content keys for.
+    #[inline]
+    pub fn public_key(&self) -> &agreement::PublicKey {
+        &self.public_key
+    }
+
+    /// The key agreement algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static agreement::Algorithm {
+        self.private_key.algorithm()
+    }
+
+    /// Unwraps a content key for `content_algorithm` that was wrapped for this
+    /// recipient.
+    ///
+    /// Fails if `wrapped` wasn't wrapped for this recipient, was modified, or
+    /// is a key for an algorithm other than `content_algorithm`.
+    pub fn unwrap(
+        &self,
+        content_algorithm: &'static Algorithm,
+        wrapped: &[u8],
+    ) -> Result<ContentKey, error::Unspecified> {
+        let key_len = content_algorithm.key_len();
+        if key_len > MAX_CONTENT_KEY_LEN {
+            return Err(error::Unspecified);
+        }
+        let public_key_len = self.public_key.as_ref().len();
+        if wrapped.len() != public_key_len + key_len + MAX_TAG_LEN {
+            return Err(error::Unspecified);
+        }
+        let (ephemeral_public_key, sealed) = wrapped.split_at(public_key_len);
+
+        let kek = self.private_key.agree(
+            agreement::UnparsedPublicKey::new(self.algorithm(), ephemeral_public_key),
+            |shared| {
+                key_encryption_key(
+                    shared,
+                    content_algorithm,
+                    ephemeral_public_key,
+                    self.public_key.as_ref(),
+                )
+            },
+            cpu::features(),
+        )??;
+
+        let mut content_key = ContentKey {
+            algorithm: content_algorithm,
+            bytes: [0; MAX_CONTENT_KEY_LEN],
+        };
+        let mut in_out = [0u8; MAX_CONTENT_KEY_LEN + MAX_TAG_LEN];
+        let in_out = &mut in_out[..sealed.len()];
+        in_out.copy_from_slice(sealed);
+        let unwrapped = kek.open_in_place(zero_nonce(), Aad::empty(), in_out)?;
+        content_key.bytes[..key_len].copy_from_slice(unwrapped);
+        Ok(content_key)
+    }
+}
+
+fn key_encryption_key(
+    shared_secret: &[u8],
+    content_algorithm: &'static Algorithm,
+    ephemeral_public_key: &[u8],
+    recipient_public_key: &[u8],
+) -> Result<LessSafeKey, error::Unspecified> {
+    let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, &[]).extract(shared_secret);
+    let info = [
+        KEK_INFO_LABEL,
+        &[content_algorithm_id(content_algorithm)],
+        ephemeral_public_key,
+        recipient_public_key,
+    ];
+    let okm = prk.expand(&info, &AES_256_GCM)?;
+    Ok(LessSafeKey::new(UnboundKey::from(okm)))
+}
+
+// The identifier of the content key's algorithm in the key-encryption key's
+// info string. These are part of the wrapped key format, so they must never
+// change.
+fn content_algorithm_id(algorithm: &Algorithm) -> u8 {
+    match algorithm.id() {
+        AlgorithmID::AES_128_GCM => 1,
+        AlgorithmID::AES_256_GCM => 2,
+        AlgorithmID::CHACHA20_POLY1305 => 3,
+        AlgorithmID::AES_256_GCM_TAG96 => 4,
+        AlgorithmID::AES_256_GCM_TAG64 => 5,
+        AlgorithmID::AES_128_GCM_SIV => 6,
+        AlgorithmID::AES_256_GCM_SIV => 7,
+    }
+}
+
+fn zero_nonce() -> Nonce {
+    Nonce::assume_unique_for_key([0; NONCE_LEN])
+}
@@ -12,7 +12,8 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use crate::error;
+use super::NonceSequence;
+use crate::{digest, error};
 
 /// A nonce for a single AEAD opening or sealing operation.
 ///
@@ -47,5 +48,86 @@ impl AsRef<[u8; NONCE_LEN]> for Nonce {
     }
 }
 
-/// All the AEADs we support use 96-bit nonces.
+/// All the AEADs we support use 96-bit nonces, except XChaCha20-Poly1305; see
+/// [`super::xchacha20_poly1305::NONCE_LEN`].
 pub const NONCE_LEN: usize = 96 / 8;
+
+/// A `NonceSequence` that derives the nonce for each record from the record's
+/// 64-bit sequence number, as in the TLS 1.3 record layer.
+///
+/// The nonce for the record with sequence number `n`, counting from zero, is
+/// a fixed per-key IV XORed with `n` encoded as a 96-bit big-endian integer,
+/// as specified in [RFC 8446 Section 5.3]. Thus the first four bytes of the
+/// IV are used verbatim and the sequence number is XORed into the last
+/// eight. `advance()` fails once 2<sup>64</sup> - 1 nonces have been
+/// returned.
+///
+/// The IV must never be used with the same key for another sequence, but it
+/// doesn't need to be secret.
+///
+/// [RFC 8446 Section 5.3]: https://www.rfc-editor.org/rfc/rfc8446#section-5.3
+pub struct SequenceNonce {
+    iv: [u8; NONCE_LEN],
+    next: u64,
+}
+
+impl SequenceNonce {
+    /// Constructs a new `SequenceNonce` that starts at sequence number zero.
+    pub fn new(iv: [u8; NONCE_LEN]) -> Self {
+        Self { iv, next: 0 }
+    }
+
+    /// The sequence number of the record that the next nonce is for.
+    pub fn sequence_number(&self) -> u64 {
+        self.next
+    }
+}
+
+impl NonceSequence for SequenceNonce {
+    fn advance(&mut self) -> Result<Nonce, error::Unspecified> {
+        let sequence_number = self.next;
+        self.next = self.next.checked_add(1).ok_or(error::Unspecified)?;
+        let mut nonce = self.iv;
+        nonce[(NONCE_LEN - 8)..]
+            .iter_mut()
+            .zip(sequence_number.to_be_bytes().iter())
+            .for_each(|(n, s)| *n ^= s);
+        Ok(Nonce::assume_unique_for_key(nonce))
+    }
+}
+
+impl core::fmt::Debug for SequenceNonce {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("SequenceNonce")
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+/// Derives the nonce for record number `counter` of the sequence identified by
+/// `key_id`.
+///
+/// The nonce is the first 32 bits of `SHA-256("ring nonce_from_counter" ||
+/// key_id)` followed by `counter` as a 64-bit big-endian integer. Thus, for a
+/// given `key_id`, distinct counters always result in distinct nonces, and
+/// the same counter always results in the same nonce.
+///
+/// This is useful when each record already has a unique sequence number, e.g.
+/// in log encryption, since no state other than the counter needs to be
+/// stored. The caller must ensure that a counter value is never reused with
+/// the same key, e.g. after a restart; `key_id` doesn't need to be secret,
+/// and it doesn't replace the need for nonces to be unique per key.
+pub fn nonce_from_counter(key_id: &[u8], counter: u64) -> Nonce {
+    let mut ctx = digest::Context::new(&digest::SHA256);
+    ctx.update(NONCE_FROM_COUNTER_LABEL);
+    ctx.update(key_id);
+    let prefix = ctx.finish();
+
+    let mut nonce = [0u8; NONCE_LEN];
+    let (nonce_prefix, nonce_counter) = nonce.split_at_mut(NONCE_LEN - 8);
+    nonce_prefix.copy_from_slice(&prefix.as_ref()[..nonce_prefix.len()]);
+    nonce_counter.copy_from_slice(&counter.to_be_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+const NONCE_FROM_COUNTER_LABEL: &[u8] = b"ring nonce_from_counter";
@@ -21,7 +21,7 @@
 //! [AEAD]: https://eprint.iacr.org/2000/025.pdf
 //! [`crypto.cipher.AEAD`]: https://golang.org/pkg/crypto/cipher/#AEAD
 
-use super::{Aad, Algorithm, BoundKey, LessSafeKey, NonceSequence, UnboundKey};
+use super::{Aad, Algorithm, BoundKey, LessSafeKey, NonceSequence, Tag, UnboundKey};
 use crate::error;
 use core::ops::RangeFrom;
 
@@ -66,7 +66,8 @@ impl<N: NonceSequence> OpeningKey<N> {
     /// plaintext without the tag.
     ///
     /// When `open_in_place()` returns `Err(..)`, `in_out` may have been
-    /// overwritten in an unspecified way.
+    /// overwritten in an unspecified way, except that it never contains any
+    /// of the plaintext.
     #[inline]
     pub fn open_in_place<'in_out, A>(
         &mut self,
@@ -80,6 +81,38 @@ impl<N: NonceSequence> OpeningKey<N> {
             .open_in_place(self.nonce_sequence.advance()?, aad, in_out)
     }
 
+    /// Like [`open_in_place()`](Self::open_in_place), except the
+    /// authentication tag is passed separately, for protocols that don't
+    /// store the tag right after the ciphertext.
+    ///
+    /// On input, `in_out[ciphertext]` must be the ciphertext. When
+    /// `open_in_place_separate_tag()` returns `Ok(plaintext)`, the plaintext
+    /// will be at `in_out[0..plaintext.len()]`, as with
+    /// [`open_within()`](Self::open_within).
+    ///
+    /// When `open_in_place_separate_tag()` returns `Err(..)`, `in_out` may
+    /// have been overwritten in an unspecified way, except that it never
+    /// contains any of the plaintext.
+    #[inline]
+    pub fn open_in_place_separate_tag<'in_out, A>(
+        &mut self,
+        aad: Aad<A>,
+        tag: Tag,
+        in_out: &'in_out mut [u8],
+        ciphertext: RangeFrom<usize>,
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.key.open_in_place_separate_tag(
+            self.nonce_sequence.advance()?,
+            aad,
+            tag,
+            in_out,
+            ciphertext,
+        )
+    }
+
     /// Authenticates and decrypts (“opens”) data in place, with a shift.
     ///
     /// `aad` is the additional authenticated data (AAD), if any.
@@ -104,8 +137,9 @@ impl<N: NonceSequence> OpeningKey<N> {
     /// Similarly, `key.open_within(aad, in_out, 0..)` is equivalent to
     /// `key.open_in_place(aad, in_out)`.
     ///
-    ///  When `open_in_place()` returns `Err(..)`, `in_out` may have been
-    /// overwritten in an unspecified way.
+    /// When `open_within()` returns `Err(..)`, `in_out` may have been
+    /// overwritten in an unspecified way, except that it never contains any
+    /// of the plaintext.
     ///
     /// The shifting feature is useful in the case where multiple packets are
     /// being reassembled in place. Consider this example where the peer has
@@ -100,13 +100,13 @@ impl Context {
     }
 
     pub(super) fn finish(mut self) -> Tag {
-        let mut tag = Tag([0u8; TAG_LEN]);
+        let mut tag = [0u8; TAG_LEN];
         dispatch!(
             self.cpu_features =>
             (CRYPTO_poly1305_finish | CRYPTO_poly1305_finish_neon)
             (statep: &mut poly1305_state, mac: &mut [u8; TAG_LEN])
-            (&mut self.state, &mut tag.0));
-        tag
+            (&mut self.state, &mut tag));
+        Tag::from(tag)
     }
 }
 
@@ -136,7 +136,7 @@ mod tests {
             let input = test_case.consume_bytes("Input");
             let expected_mac = test_case.consume_bytes("MAC");
             let key = Key::new(*key);
-            let Tag(actual_mac) = sign(key, &input, cpu_features);
+            let actual_mac = sign(key, &input, cpu_features);
             assert_eq!(expected_mac, actual_mac.as_ref());
 
             Ok(())
@@ -27,6 +27,8 @@ pub struct HeaderProtectionKey {
     algorithm: &'static Algorithm,
 }
 
+derive_debug_via_field!(HeaderProtectionKey, algorithm);
+
 #[allow(clippy::large_enum_variant, variant_size_differences)]
 enum KeyInner {
     Aes(aes::Key),
@@ -61,9 +63,20 @@ impl HeaderProtectionKey {
     ///
     /// `sample` must be exactly `self.algorithm().sample_len()` bytes long.
     pub fn new_mask(&self, sample: &[u8]) -> Result<[u8; 5], error::Unspecified> {
+        self.new_mask_with_cpu_features(sample, cpu::features())
+    }
+
+    /// Like `new_mask()`, but uses `cpu_features` instead of getting it
+    /// again; see `cpu::features()`.
+    #[inline]
+    pub fn new_mask_with_cpu_features(
+        &self,
+        sample: &[u8],
+        cpu_features: cpu::Features,
+    ) -> Result<[u8; 5], error::Unspecified> {
         let sample = <&[u8; SAMPLE_LEN]>::try_from(sample)?;
 
-        let out = (self.algorithm.new_mask)(&self.inner, *sample);
+        let out = (self.algorithm.new_mask)(&self.inner, *sample, cpu_features);
         Ok(out)
     }
 
@@ -83,7 +96,7 @@ pub type Sample = [u8; SAMPLE_LEN];
 pub struct Algorithm {
     init: fn(key: &[u8], cpu_features: cpu::Features) -> Result<KeyInner, error::Unspecified>,
 
-    new_mask: fn(key: &KeyInner, sample: Sample) -> [u8; 5],
+    new_mask: fn(key: &KeyInner, sample: Sample, cpu_features: cpu::Features) -> [u8; 5],
 
     key_len: usize,
     id: AlgorithmID,
@@ -155,13 +168,13 @@ fn aes_init_256(key: &[u8], cpu_features: cpu::Features) -> Result<KeyInner, err
     Ok(KeyInner::Aes(aes_key))
 }
 
-fn aes_new_mask(key: &KeyInner, sample: Sample) -> [u8; 5] {
+fn aes_new_mask(key: &KeyInner, sample: Sample, cpu_features: cpu::Features) -> [u8; 5] {
     let aes_key = match key {
         KeyInner::Aes(key) => key,
         _ => unreachable!(),
     };
 
-    aes_key.new_mask(sample)
+    aes_key.new_mask(sample, cpu_features)
 }
 
 /// ChaCha20.
@@ -177,7 +190,7 @@ fn chacha20_init(key: &[u8], _cpu_features: cpu::Features) -> Result<KeyInner, e
     Ok(KeyInner::ChaCha20(chacha::Key::new(chacha20_key)))
 }
 
-fn chacha20_new_mask(key: &KeyInner, sample: Sample) -> [u8; 5] {
+fn chacha20_new_mask(key: &KeyInner, sample: Sample, _cpu_features: cpu::Features) -> [u8; 5] {
     let chacha20_key = match key {
         KeyInner::ChaCha20(key) => key,
         _ => unreachable!(),
@@ -185,3 +198,40 @@ fn chacha20_new_mask(key: &KeyInner, sample: Sample) -> [u8; 5] {
 
     chacha20_key.new_mask(sample)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_mask_with_cpu_features_gets_features_once() {
+        const MASKS: usize = 1000;
+        let sample = [0x5a; SAMPLE_LEN];
+
+        for algorithm in [&AES_128, &AES_256, &CHACHA20] {
+            let key_bytes = [0x42; 32];
+            let key =
+                HeaderProtectionKey::new(algorithm, &key_bytes[..algorithm.key_len()]).unwrap();
+
+            let before = cpu::calls::get();
+            let cpu_features = cpu::features();
+            for _ in 0..MASKS {
+                let _: [u8; 5] = key
+                    .new_mask_with_cpu_features(&sample, cpu_features)
+                    .unwrap();
+            }
+            assert_eq!(cpu::calls::get() - before, 1);
+
+            let before = cpu::calls::get();
+            for _ in 0..MASKS {
+                let _: [u8; 5] = key.new_mask(&sample).unwrap();
+            }
+            assert_eq!(cpu::calls::get() - before, MASKS);
+
+            assert_eq!(
+                key.new_mask_with_cpu_features(&sample, cpu::features()),
+                key.new_mask(&sample)
+            );
+        }
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Chunked encryption of a long message.
+//!
+//! The message is split into chunks by the caller and each chunk is sealed
+//! separately, using the STREAM construction from [Online
+//! Authenticated-Encryption and its Nonce-Reuse Misuse-Resistance]. The nonce
+//! for chunk `i` (counting from zero) is the caller's `NONCE_PREFIX_LEN`-byte
+//! nonce prefix, followed by `i` as a 32-bit big-endian integer, followed by
+//! a byte that is 1 for the last chunk and 0 otherwise. Thus chunks can't be
+//! reordered, and since the last chunk is marked, a truncated stream is
+//! detected when the (apparent) last chunk is opened with
+//! [`OpeningStream::open_last_chunk`].
+//!
+//! `OpeningStream` never gives the caller any unauthenticated plaintext: each
+//! chunk is decrypted into a buffer owned by the `OpeningStream` and is only
+//! returned once that chunk's tag has been verified. The buffer holds one
+//! chunk, so the memory used is proportional to the chunk size, not the
+//! message size. Once a chunk fails to open, the `OpeningStream` rejects all
+//! further chunks.
+//!
+//! The nonce prefix must be unique for each stream sealed with a given key.
+//!
+//! A stream has at most 2<sup>32</sup> chunks, including the last one, so the
+//! chunk size limits the length of the message; e.g. with 64 KiB chunks, the
+//! chunk size that age uses, a stream can be up to 256 TiB long. Only one chunk needs to be in
+//! memory at a time, so large messages like multi-gigabyte files can be
+//! sealed and opened a chunk at a time without buffering the whole message.
+//!
+//! The nonces are laid out like those of Tink's streaming AEADs, but Tink
+//! also derives a key for each stream with HKDF and writes a header, which is
+//! left to the caller here. age's STREAM nonces have no prefix and an 11-byte
+//! counter, so age's payloads can't be opened with this module.
+//!
+//! With the `std` feature, [`SealingWriter`] and [`OpeningReader`] adapt
+//! streams to `std::io::Write` and `std::io::Read`.
+//!
+//! ```
+//! use ring::aead::{self, stream};
+//!
+//! let key = [0x42; 32];
+//! let nonce_prefix = [0; stream::NONCE_PREFIX_LEN];
+//!
+//! let mut sealer = stream::SealingStream::new(
+//!     aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key)?,
+//!     nonce_prefix,
+//! );
+//! let mut first = b"hello, ".to_vec();
+//! sealer.seal_chunk(aead::Aad::empty(), &mut first)?;
+//! let mut last = b"world".to_vec();
+//! sealer.seal_last_chunk(aead::Aad::empty(), &mut last)?;
+//!
+//! let mut opener = stream::OpeningStream::new(
+//!     aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key)?,
+//!     nonce_prefix,
+//! );
+//! assert_eq!(opener.open_chunk(aead::Aad::empty(), &first)?, b"hello, ");
+//! assert_eq!(opener.open_last_chunk(aead::Aad::empty(), &last)?, b"world");
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+//!
+//! [Online Authenticated-Encryption and its Nonce-Reuse Misuse-Resistance]:
+//!     https://eprint.iacr.org/2015/189.pdf
+
+use super::{Aad, Algorithm, LessSafeKey, Nonce, UnboundKey, NONCE_LEN};
+use crate::error;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+pub use io::{OpeningReader, SealingWriter};
+
+#[cfg(feature = "std")]
+mod io;
+
+/// The length of the nonce prefix of a stream.
+pub const NONCE_PREFIX_LEN: usize = NONCE_LEN - 4 - 1;
+
+/// Seals a stream chunk by chunk.
+pub struct SealingStream {
+    key: LessSafeKey,
+    nonces: Nonces,
+}
+
+impl SealingStream {
+    /// Constructs a new `SealingStream`.
+    ///
+    /// `nonce_prefix` must never be reused for another stream sealed with
+    /// `key`.
+    pub fn new(key: UnboundKey, nonce_prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            key: LessSafeKey::new(key),
+            nonces: Nonces::new(nonce_prefix),
+        }
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.key.algorithm()
+    }
+
+    /// Seals the next chunk, which isn't the last one, in place and appends
+    /// its tag.
+    ///
+    /// Fails if 2<sup>32</sup> - 1 chunks have already been sealed; the last
+    /// counter value is reserved for the last chunk.
+    pub fn seal_chunk<A, InOut>(
+        &mut self,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let nonce = self.nonces.next(false)?;
+        self.key.seal_in_place_append_tag(nonce, aad, in_out)
+    }
+
+    /// Seals the last chunk in place and appends its tag.
+    ///
+    /// The stream is finished after this; the last chunk may be empty.
+    pub fn seal_last_chunk<A, InOut>(
+        mut self,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let nonce = self.nonces.next(true)?;
+        self.key.seal_in_place_append_tag(nonce, aad, in_out)
+    }
+}
+
+impl core::fmt::Debug for SealingStream {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("SealingStream")
+            .field("algorithm", &self.algorithm())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Opens a stream chunk by chunk, releasing each chunk's plaintext only after
+/// the chunk has been authenticated.
+#[cfg(feature = "alloc")]
+pub struct OpeningStream {
+    key: LessSafeKey,
+    nonces: Nonces,
+    buffer: Vec<u8>,
+    failed: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl OpeningStream {
+    /// Constructs a new `OpeningStream`.
+    ///
+    /// `nonce_prefix` must be the nonce prefix the stream was sealed with.
+    pub fn new(key: UnboundKey, nonce_prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            key: LessSafeKey::new(key),
+            nonces: Nonces::new(nonce_prefix),
+            buffer: Vec::new(),
+            failed: false,
+        }
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.key.algorithm()
+    }
+
+    /// Opens the next chunk, which must not be the last one.
+    ///
+    /// `ciphertext_and_tag` is copied into the `OpeningStream`'s buffer and
+    /// decrypted there. The plaintext is returned only if the chunk's tag is
+    /// valid; otherwise nothing is returned and every later call on this
+    /// `OpeningStream` fails.
+    pub fn open_chunk<A>(
+        &mut self,
+        aad: Aad<A>,
+        ciphertext_and_tag: &[u8],
+    ) -> Result<&[u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open(aad, ciphertext_and_tag, false)?;
+        Ok(&self.buffer)
+    }
+
+    /// Opens the last chunk, returning its plaintext.
+    ///
+    /// The last chunk the caller has must always be opened with this, even if
+    /// the caller doesn't expect the stream to be finished; this fails if the
+    /// chunk wasn't sealed as the last one, i.e. if the stream was truncated.
+    pub fn open_last_chunk<A>(
+        mut self,
+        aad: Aad<A>,
+        ciphertext_and_tag: &[u8],
+    ) -> Result<Vec<u8>, error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open(aad, ciphertext_and_tag, true)?;
+        Ok(self.buffer)
+    }
+
+    fn open<A>(
+        &mut self,
+        aad: Aad<A>,
+        ciphertext_and_tag: &[u8],
+        last: bool,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        if self.failed {
+            return Err(error::Unspecified);
+        }
+        self.buffer.clear();
+        self.buffer.extend_from_slice(ciphertext_and_tag);
+        let plaintext_len = self
+            .nonces
+            .next(last)
+            .and_then(|nonce| self.key.open_in_place(nonce, aad, &mut self.buffer))
+            .map(|plaintext| plaintext.len());
+        match plaintext_len {
+            Ok(plaintext_len) => {
+                self.buffer.truncate(plaintext_len);
+                Ok(())
+            }
+            Err(error::Unspecified) => {
+                self.buffer.clear();
+                self.failed = true;
+                Err(error::Unspecified)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for OpeningStream {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("OpeningStream")
+            .field("algorithm", &self.algorithm())
+            .finish_non_exhaustive()
+    }
+}
+
+struct Nonces {
+    prefix: [u8; NONCE_PREFIX_LEN],
+    next_counter: u64,
+}
+
+impl Nonces {
+    fn new(prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            prefix,
+            next_counter: 0,
+        }
+    }
+
+    fn next(&mut self, last: bool) -> Result<Nonce, error::Unspecified> {
+        let counter = u32::try_from(self.next_counter).map_err(|_| error::Unspecified)?;
+        // The last counter value is reserved for the last chunk, so that a
+        // stream can always be finished.
+        if counter == u32::MAX && !last {
+            return Err(error::Unspecified);
+        }
+        self.next_counter += 1;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        let (prefix, rest) = nonce.split_at_mut(NONCE_PREFIX_LEN);
+        prefix.copy_from_slice(&self.prefix);
+        let (counter_bytes, last_flag) = rest.split_at_mut(4);
+        counter_bytes.copy_from_slice(&counter.to_be_bytes());
+        last_flag[0] = u8::from(last);
+        Ok(Nonce::assume_unique_for_key(nonce))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonces_limit() {
+        let mut nonces = Nonces::new([0; NONCE_PREFIX_LEN]);
+        nonces.next_counter = u64::from(u32::MAX) - 1;
+        assert!(nonces.next(false).is_ok());
+        // Only the last chunk may use the last counter value.
+        assert!(nonces.next(false).is_err());
+        let nonce = nonces.next(true).unwrap();
+        assert_eq!(
+            nonce.as_ref()[NONCE_PREFIX_LEN..],
+            [0xff, 0xff, 0xff, 0xff, 1]
+        );
+        assert!(nonces.next(false).is_err());
+        assert!(nonces.next(true).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! `std::io` adapters for streams.
+//!
+//! The plaintext is split into records of `record_len` bytes, except that the
+//! last record may be shorter, and may be empty. Each record is sealed as a
+//! chunk of the stream, with empty additional data, and the sealed records
+//! are concatenated. The reader must use the same `record_len` as the
+//! writer; it finds the last record by reaching the end of its input.
+
+extern crate std;
+
+use super::{OpeningStream, SealingStream, NONCE_PREFIX_LEN};
+use crate::{
+    aead::{Aad, Algorithm, UnboundKey},
+    error,
+};
+use std::{io, vec::Vec};
+
+/// Seals everything written to it as a stream, writing the sealed records to
+/// an underlying writer.
+///
+/// [`finish()`](Self::finish) must be called to seal and write the last
+/// record; otherwise the output will be rejected as truncated.
+pub struct SealingWriter<W: io::Write> {
+    sealer: SealingStream,
+    writer: W,
+    record_len: usize,
+    buffer: Vec<u8>,
+}
+
+impl<W: io::Write> SealingWriter<W> {
+    /// Constructs a new `SealingWriter` that writes records of `record_len`
+    /// bytes of plaintext to `writer`.
+    ///
+    /// `nonce_prefix` must never be reused for another stream sealed with
+    /// `key`. Fails if `record_len` is zero.
+    pub fn new(
+        key: UnboundKey,
+        nonce_prefix: [u8; NONCE_PREFIX_LEN],
+        record_len: usize,
+        writer: W,
+    ) -> Result<Self, error::Unspecified> {
+        if record_len == 0 {
+            return Err(error::Unspecified);
+        }
+        let sealer = SealingStream::new(key, nonce_prefix);
+        let buffer = Vec::with_capacity(record_len + sealer.algorithm().tag_len());
+        Ok(Self {
+            sealer,
+            writer,
+            record_len,
+            buffer,
+        })
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.sealer.algorithm()
+    }
+
+    /// Seals and writes the last record, which holds whatever has been
+    /// written since the last full record, and flushes the underlying writer.
+    ///
+    /// Returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.sealer
+            .seal_last_chunk(Aad::empty(), &mut self.buffer)
+            .map_err(sealing_failed)?;
+        self.writer.write_all(&self.buffer)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn seal_record(&mut self) -> io::Result<()> {
+        self.sealer
+            .seal_chunk(Aad::empty(), &mut self.buffer)
+            .map_err(sealing_failed)?;
+        let result = self.writer.write_all(&self.buffer);
+        self.buffer.clear();
+        result
+    }
+}
+
+impl<W: io::Write> io::Write for SealingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // A full record is only sealed once more data arrives, since it might
+        // turn out to be the last one.
+        if self.buffer.len() == self.record_len && !buf.is_empty() {
+            self.seal_record()?;
+        }
+        let len = core::cmp::min(buf.len(), self.record_len - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    /// Flushes the underlying writer. A partial record can't be written
+    /// before `finish()` is called, so it stays buffered.
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<W: io::Write> core::fmt::Debug for SealingWriter<W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("SealingWriter")
+            .field("algorithm", &self.algorithm())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads a stream written by a [`SealingWriter`] from an underlying reader,
+/// yielding its plaintext.
+///
+/// Plaintext is only yielded once its record has been authenticated. A
+/// record that fails to open, including a last record that is missing
+/// because the stream was truncated, is reported as an error of kind
+/// [`io::ErrorKind::InvalidData`], and every later read fails too.
+pub struct OpeningReader<R: io::Read> {
+    algorithm: &'static Algorithm,
+    opener: Option<OpeningStream>,
+    reader: R,
+    sealed_record_len: usize,
+    ciphertext: Vec<u8>,
+    plaintext: Vec<u8>,
+    plaintext_pos: usize,
+    failed: bool,
+}
+
+impl<R: io::Read> OpeningReader<R> {
+    /// Constructs a new `OpeningReader` that reads records of `record_len`
+    /// bytes of plaintext from `reader`.
+    ///
+    /// `nonce_prefix` and `record_len` must be the ones the stream was sealed
+    /// with. Fails if `record_len` is zero.
+    pub fn new(
+        key: UnboundKey,
+        nonce_prefix: [u8; NONCE_PREFIX_LEN],
+        record_len: usize,
+        reader: R,
+    ) -> Result<Self, error::Unspecified> {
+        if record_len == 0 {
+            return Err(error::Unspecified);
+        }
+        let opener = OpeningStream::new(key, nonce_prefix);
+        let sealed_record_len = record_len
+            .checked_add(opener.algorithm().tag_len())
+            .ok_or(error::Unspecified)?;
+        Ok(Self {
+            algorithm: opener.algorithm(),
+            opener: Some(opener),
+            reader,
+            sealed_record_len,
+            ciphertext: Vec::new(),
+            plaintext: Vec::new(),
+            plaintext_pos: 0,
+            failed: false,
+        })
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn open_record(&mut self) -> io::Result<()> {
+        // Read one byte past the record to find out whether it is the last.
+        let wanted = self.sealed_record_len + 1;
+        while self.ciphertext.len() < wanted {
+            let start = self.ciphertext.len();
+            self.ciphertext.resize(wanted, 0);
+            match self.reader.read(&mut self.ciphertext[start..]) {
+                Ok(0) => {
+                    self.ciphertext.truncate(start);
+                    break;
+                }
+                Ok(read) => self.ciphertext.truncate(start + read),
+                Err(e) => {
+                    self.ciphertext.truncate(start);
+                    if e.kind() != io::ErrorKind::Interrupted {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        self.plaintext.clear();
+        self.plaintext_pos = 0;
+        let opened = if self.ciphertext.len() == wanted {
+            let (record, next) = self.ciphertext.split_at(self.sealed_record_len);
+            let next = next[0];
+            let opened = match &mut self.opener {
+                Some(opener) => opener
+                    .open_chunk(Aad::empty(), record)
+                    .map(|plaintext| self.plaintext.extend_from_slice(plaintext)),
+                None => Err(error::Unspecified),
+            };
+            self.ciphertext.clear();
+            self.ciphertext.push(next);
+            opened
+        } else {
+            let opened = match self.opener.take() {
+                Some(opener) => opener
+                    .open_last_chunk(Aad::empty(), &self.ciphertext)
+                    .map(|plaintext| self.plaintext = plaintext),
+                None => Err(error::Unspecified),
+            };
+            self.ciphertext.clear();
+            opened
+        };
+        opened.map_err(|error::Unspecified| {
+            self.failed = true;
+            invalid_data()
+        })
+    }
+}
+
+impl<R: io::Read> io::Read for OpeningReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.plaintext_pos == self.plaintext.len() {
+            if self.failed {
+                return Err(invalid_data());
+            }
+            if self.opener.is_none() || buf.is_empty() {
+                return Ok(0);
+            }
+            self.open_record()?;
+        }
+        let available = &self.plaintext[self.plaintext_pos..];
+        let len = core::cmp::min(buf.len(), available.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.plaintext_pos += len;
+        Ok(len)
+    }
+}
+
+impl<R: io::Read> core::fmt::Debug for OpeningReader<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("OpeningReader")
+            .field("algorithm", &self.algorithm())
+            .finish_non_exhaustive()
+    }
+}
+
+fn sealing_failed(error: error::Unspecified) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
+
+fn invalid_data() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error::Unspecified)
+}
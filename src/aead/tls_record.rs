@@ -0,0 +1,52 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::Aad;
+
+// The `legacy_record_version` of TLS 1.3 records, which is also the version
+// of TLS 1.2 records.
+const TLS12_VERSION: [u8; 2] = [0x03, 0x03];
+
+/// Returns the additional data for protecting a TLS 1.3 record, as described
+/// in [RFC 8446 Section 5.2]: the 5-byte record header.
+///
+/// `content_type` is the record's `opaque_type`, which is always 23
+/// (`application_data`) for protected records, and `length` is the length
+/// of the `encrypted_record`, i.e. of the ciphertext including the tag.
+///
+/// [RFC 8446 Section 5.2]: https://tools.ietf.org/html/rfc8446#section-5.2
+#[inline]
+pub fn tls13_record_aad(content_type: u8, length: u16) -> Aad<[u8; 5]> {
+    let [length_hi, length_lo] = length.to_be_bytes();
+    let [version_hi, version_lo] = TLS12_VERSION;
+    Aad::from([content_type, version_hi, version_lo, length_hi, length_lo])
+}
+
+/// Returns the additional data for protecting a TLS 1.2 record with an
+/// AEAD, as described in [RFC 5246 Section 6.2.3.3].
+///
+/// Unlike in TLS 1.3, the additional data includes the record's sequence
+/// number, and `length` is the length of the plaintext, not the ciphertext.
+///
+/// [RFC 5246 Section 6.2.3.3]: https://tools.ietf.org/html/rfc5246#section-6.2.3.3
+#[inline]
+pub fn tls12_record_aad(sequence_number: u64, content_type: u8, length: u16) -> Aad<[u8; 13]> {
+    let mut aad = [0u8; 13];
+    let (seq, rest) = aad.split_at_mut(8);
+    seq.copy_from_slice(&sequence_number.to_be_bytes());
+    rest[0] = content_type;
+    rest[1..3].copy_from_slice(&TLS12_VERSION);
+    rest[3..].copy_from_slice(&length.to_be_bytes());
+    Aad::from(aad)
+}
@@ -0,0 +1,260 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! XChaCha20-Poly1305, ChaCha20-Poly1305 with 192-bit nonces.
+//!
+//! XChaCha20-Poly1305 is specified in [draft-irtf-cfrg-xchacha], and is
+//! compatible with libsodium's `crypto_aead_xchacha20poly1305_ietf_*`. For
+//! each message, HChaCha20 derives a subkey from the key and the first 16
+//! bytes of the nonce, and the message is sealed with ChaCha20-Poly1305 using
+//! the subkey and a 96-bit nonce made of four zero bytes followed by the
+//! last 8 bytes of the nonce.
+//!
+//! The nonces are long enough to be generated randomly, e.g. with
+//! [`crate::rand::SystemRandom`], without a practical risk of collisions.
+//! Since the nonces don't fit in a [`super::Nonce`], XChaCha20-Poly1305
+//! isn't an [`super::Algorithm`] and has its own [`Key`] and [`Nonce`] types.
+//!
+//! ```
+//! use ring::aead::{self, xchacha20_poly1305};
+//!
+//! let key = xchacha20_poly1305::Key::new(&aead::XCHACHA20_POLY1305, &[0x42; 32])?;
+//!
+//! let mut in_out = b"hello, world".to_vec();
+//! let nonce = xchacha20_poly1305::Nonce::assume_unique_for_key([0; 24]);
+//! key.seal_in_place_append_tag(nonce, aead::Aad::empty(), &mut in_out)?;
+//!
+//! let nonce = xchacha20_poly1305::Nonce::assume_unique_for_key([0; 24]);
+//! let plaintext = key.open_in_place(nonce, aead::Aad::empty(), &mut in_out)?;
+//! assert_eq!(plaintext, b"hello, world");
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+//!
+//! [draft-irtf-cfrg-xchacha]: https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03
+
+use super::{chacha, Aad, LessSafeKey, Tag, CHACHA20_POLY1305};
+use crate::{cpu, error};
+use core::ops::RangeFrom;
+
+/// The XChaCha20-Poly1305 algorithm.
+pub struct Algorithm {
+    aead: &'static super::Algorithm,
+}
+
+impl Algorithm {
+    /// The length of the key.
+    #[inline]
+    pub fn key_len(&self) -> usize {
+        KEY_LEN
+    }
+
+    /// The length of the nonces.
+    #[inline]
+    pub fn nonce_len(&self) -> usize {
+        NONCE_LEN
+    }
+
+    /// The length of a tag.
+    #[inline]
+    pub fn tag_len(&self) -> usize {
+        self.aead.tag_len()
+    }
+}
+
+impl core::fmt::Debug for Algorithm {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.write_str("XCHACHA20_POLY1305")
+    }
+}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Algorithm {}
+
+/// XChaCha20-Poly1305 as described in [draft-irtf-cfrg-xchacha].
+///
+/// The keys are 256 bits long and the nonces are 192 bits long. Messages are
+/// limited to the same length as `CHACHA20_POLY1305`'s.
+///
+/// [draft-irtf-cfrg-xchacha]: https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03
+pub static XCHACHA20_POLY1305: Algorithm = Algorithm {
+    aead: &CHACHA20_POLY1305,
+};
+
+/// The length of an XChaCha20-Poly1305 key.
+pub const KEY_LEN: usize = chacha::KEY_LEN;
+
+/// The length of an XChaCha20-Poly1305 nonce.
+pub const NONCE_LEN: usize = 192 / 8;
+
+const HCHACHA20_INPUT_LEN: usize = 16;
+
+/// A nonce for a single XChaCha20-Poly1305 opening or sealing operation.
+///
+/// The user must ensure, for a particular key, that each nonce is unique.
+///
+/// `Nonce` intentionally doesn't implement `Clone` to ensure that each one is
+/// consumed at most once.
+pub struct Nonce([u8; NONCE_LEN]);
+
+impl Nonce {
+    /// Constructs a `Nonce` with the given value, assuming that the value is
+    /// unique for the lifetime of the key it is being used with.
+    ///
+    /// Fails if `value` isn't `NONCE_LEN` bytes long.
+    #[inline]
+    pub fn try_assume_unique_for_key(value: &[u8]) -> Result<Self, error::Unspecified> {
+        let value: &[u8; NONCE_LEN] = value.try_into()?;
+        Ok(Self::assume_unique_for_key(*value))
+    }
+
+    /// Constructs a `Nonce` with the given value, assuming that the value is
+    /// unique for the lifetime of the key it is being used with.
+    #[inline]
+    pub fn assume_unique_for_key(value: [u8; NONCE_LEN]) -> Self {
+        Self(value)
+    }
+}
+
+impl AsRef<[u8; NONCE_LEN]> for Nonce {
+    fn as_ref(&self) -> &[u8; NONCE_LEN] {
+        &self.0
+    }
+}
+
+/// An XChaCha20-Poly1305 key.
+///
+/// Like `LessSafeKey`, the caller must ensure that each nonce is used at most
+/// once with a given key.
+#[derive(Clone)]
+pub struct Key {
+    key: chacha::Key,
+    algorithm: &'static Algorithm,
+}
+
+impl Key {
+    /// Constructs a key from its raw bytes.
+    ///
+    /// Fails if `key_bytes.len() != algorithm.key_len()`.
+    pub fn new(
+        algorithm: &'static Algorithm,
+        key_bytes: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let key_bytes: [u8; KEY_LEN] = key_bytes.try_into()?;
+        Ok(Self {
+            key: chacha::Key::new(key_bytes),
+            algorithm,
+        })
+    }
+
+    /// The key's algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    /// Like [`super::LessSafeKey::seal_in_place_append_tag()`].
+    ///
+    /// `nonce` must be unique for every use of the key to seal data.
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let (key, nonce) = self.subkey_and_nonce(nonce)?;
+        key.seal_in_place_append_tag(nonce, aad, in_out)
+    }
+
+    /// Like [`super::LessSafeKey::seal_in_place_separate_tag()`].
+    ///
+    /// `nonce` must be unique for every use of the key to seal data.
+    pub fn seal_in_place_separate_tag<A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut [u8],
+    ) -> Result<Tag, error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let (key, nonce) = self.subkey_and_nonce(nonce)?;
+        key.seal_in_place_separate_tag(nonce, aad, in_out)
+    }
+
+    /// Like [`super::LessSafeKey::open_in_place()`].
+    ///
+    /// When this returns `Err(..)`, `in_out` never contains any of the
+    /// plaintext.
+    pub fn open_in_place<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open_within(nonce, aad, in_out, 0..)
+    }
+
+    /// Like [`super::LessSafeKey::open_within()`].
+    ///
+    /// When this returns `Err(..)`, `in_out` never contains any of the
+    /// plaintext.
+    pub fn open_within<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+        ciphertext_and_tag: RangeFrom<usize>,
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let (key, nonce) = self.subkey_and_nonce(nonce)?;
+        key.open_within(nonce, aad, in_out, ciphertext_and_tag)
+    }
+
+    // draft-irtf-cfrg-xchacha Section 2.3.
+    fn subkey_and_nonce(
+        &self,
+        Nonce(nonce): Nonce,
+    ) -> Result<(LessSafeKey, super::Nonce), error::Unspecified> {
+        let (hchacha20_input, chacha20_nonce) = nonce.split_at(HCHACHA20_INPUT_LEN);
+        let subkey = self.key.hchacha20(hchacha20_input.try_into()?);
+        let key = LessSafeKey::new_(self.algorithm.aead, &subkey, cpu::features())?;
+
+        let mut nonce = [0u8; super::NONCE_LEN];
+        nonce[(super::NONCE_LEN - chacha20_nonce.len())..].copy_from_slice(chacha20_nonce);
+        Ok((key, super::Nonce::assume_unique_for_key(nonce)))
+    }
+}
+
+impl core::fmt::Debug for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Key")
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
@@ -76,6 +76,8 @@ pub struct Algorithm {
         peer_public_key: untrusted::Input,
         cpu: cpu::Features,
     ) -> Result<(), error::Unspecified>,
+    pub(crate) validate_public_key:
+        fn(public_key: untrusted::Input, cpu: cpu::Features) -> Result<(), error::Unspecified>,
 }
 
 derive_debug_via_field!(Algorithm, curve);
@@ -148,6 +150,96 @@ impl EphemeralPrivateKey {
     }
 }
 
+/// Derives an X25519 key pair deterministically from `seed`, without an RNG.
+///
+/// `seed` is used as the private key exactly as `EphemeralPrivateKey::generate`
+/// would use 32 random bytes: it is clamped as described in RFC 7748 when it
+/// is used as a scalar. The seed must be secret and must have as much entropy
+/// as a randomly-generated key; this is for reproducible key setup and
+/// testing, not for turning passwords or other low-entropy secrets into keys.
+pub fn x25519_keypair_from_seed(seed: &[u8; 32]) -> (EphemeralPrivateKey, PublicKey) {
+    let cpu_features = cpu::features();
+    // Neither step can fail: `seed` has the right length and every 32-byte
+    // string is a valid X25519 private key.
+    let private_key =
+        ec::Seed::from_bytes(X25519.curve, untrusted::Input::from(seed), cpu_features).unwrap();
+    let public_key = private_key.compute_public_key(cpu_features).unwrap();
+    (
+        EphemeralPrivateKey {
+            private_key,
+            algorithm: &X25519,
+        },
+        PublicKey {
+            algorithm: &X25519,
+            bytes: public_key,
+        },
+    )
+}
+
+/// A private key that, unlike `EphemeralPrivateKey`, can be used for any
+/// number of key agreements.
+///
+/// This is not exposed publicly; it is the building block for constructions
+/// where the recipient has a long-term key, which wrap it in their own types.
+pub(crate) struct StaticPrivateKey {
+    private_key: ec::Seed,
+    algorithm: &'static Algorithm,
+}
+
+derive_debug_via_field!(StaticPrivateKey, stringify!(StaticPrivateKey), algorithm);
+
+impl StaticPrivateKey {
+    pub(crate) fn generate(
+        alg: &'static Algorithm,
+        rng: &dyn rand::SecureRandom,
+        cpu: cpu::Features,
+    ) -> Result<Self, error::Unspecified> {
+        let private_key = ec::Seed::generate(alg.curve, rng, cpu)?;
+        Ok(Self {
+            private_key,
+            algorithm: alg,
+        })
+    }
+
+    pub(crate) fn from_bytes(
+        alg: &'static Algorithm,
+        bytes: untrusted::Input,
+        cpu: cpu::Features,
+    ) -> Result<Self, error::Unspecified> {
+        let private_key = ec::Seed::from_bytes(alg.curve, bytes, cpu)?;
+        Ok(Self {
+            private_key,
+            algorithm: alg,
+        })
+    }
+
+    pub(crate) fn compute_public_key(
+        &self,
+        cpu: cpu::Features,
+    ) -> Result<PublicKey, error::Unspecified> {
+        self.private_key
+            .compute_public_key(cpu)
+            .map(|public_key| PublicKey {
+                algorithm: self.algorithm,
+                bytes: public_key,
+            })
+    }
+
+    #[inline]
+    pub(crate) fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    pub(crate) fn agree<R>(
+        &self,
+        peer_public_key: UnparsedPublicKey<&[u8]>,
+        kdf: impl FnOnce(&[u8]) -> R,
+        cpu: cpu::Features,
+    ) -> Result<R, error::Unspecified> {
+        agree_(&self.private_key, self.algorithm, peer_public_key, kdf, cpu)
+    }
+}
+
 /// A public key for key agreement.
 #[derive(Clone)]
 pub struct PublicKey {
@@ -225,6 +317,27 @@ impl<B> UnparsedPublicKey<B> {
     }
 }
 
+/// Validates `public_key` as a peer public key for `algorithm`.
+///
+/// This is the validation that `agree_ephemeral` does on the peer's public
+/// key, so `Ok(())` is returned exactly when `agree_ephemeral` wouldn't reject
+/// `public_key`. See the algorithm's documentation for details on how keys are
+/// to be encoded and what constitutes a valid key for that algorithm.
+///
+/// For ECDH using P-256 and P-384, the key must be encoded in uncompressed
+/// form, its coordinates must be in the range [0, p), and it must be a point
+/// on the curve; the point at infinity has no valid encoding.
+///
+/// For X25519, the key must be 32 bytes and must not be a point of small
+/// order, including any non-canonical encoding of one. Other non-canonical
+/// encodings are accepted, as RFC 7748 requires.
+pub fn validate_public_key(
+    algorithm: &'static Algorithm,
+    public_key: &[u8],
+) -> Result<(), error::Unspecified> {
+    (algorithm.validate_public_key)(untrusted::Input::from(public_key), cpu::features())
+}
+
 /// Performs a key agreement with an ephemeral private key and the given public
 /// key.
 ///
@@ -260,18 +373,32 @@ fn agree_ephemeral_<R>(
     peer_public_key: UnparsedPublicKey<&[u8]>,
     kdf: impl FnOnce(&[u8]) -> R,
     cpu: cpu::Features,
+) -> Result<R, error::Unspecified> {
+    agree_(
+        &my_private_key.private_key,
+        my_private_key.algorithm,
+        peer_public_key,
+        kdf,
+        cpu,
+    )
+}
+
+fn agree_<R>(
+    my_private_key: &ec::Seed,
+    alg: &'static Algorithm,
+    peer_public_key: UnparsedPublicKey<&[u8]>,
+    kdf: impl FnOnce(&[u8]) -> R,
+    cpu: cpu::Features,
 ) -> Result<R, error::Unspecified> {
     // NSA Guide Prerequisite 1.
     //
     // The domain parameters are hard-coded. This check verifies that the
     // peer's public key's domain parameters match the domain parameters of
     // this private key.
-    if peer_public_key.algorithm != my_private_key.algorithm {
+    if peer_public_key.algorithm != alg {
         return Err(error::Unspecified);
     }
 
-    let alg = &my_private_key.algorithm;
-
     // NSA Guide Prerequisite 2, regarding which KDFs are allowed, is delegated
     // to the caller.
 
@@ -291,7 +418,7 @@ fn agree_ephemeral_<R>(
     // that doesn't meet the NSA requirement to "zeroize."
     (alg.ecdh)(
         shared_key,
-        &my_private_key.private_key,
+        my_private_key,
         untrusted::Input::from(peer_public_key.bytes),
         cpu,
     )?;
@@ -0,0 +1,122 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! bcrypt-pbkdf, the password-based key derivation function OpenSSH uses to
+//! encrypt private keys.
+//!
+//! bcrypt-pbkdf is PBKDF2 (see [`crate::pbkdf2`]) with a bcrypt-based hash
+//! in place of HMAC, with the output bytes interleaved across blocks so that
+//! every block has to be computed to get any prefix of the output. It isn't
+//! standardized; it is specified by the [OpenBSD implementation].
+//!
+//! This is only for interoperating with OpenSSH. bcrypt uses Blowfish, whose
+//! table lookups are indexed by secret data, so this isn't constant-time with
+//! respect to the password. New protocols should use PBKDF2 or a
+//! memory-hard function instead.
+//!
+//! [OpenBSD implementation]:
+//!     https://cvsweb.openbsd.org/src/lib/libutil/bcrypt_pbkdf.c?annotate=HEAD
+
+use crate::{digest, error, polyfill::ArraySplitMap};
+use core::num::NonZeroU32;
+
+mod blowfish;
+
+/// The maximum length of the output of `derive()`.
+pub const MAX_OUTPUT_LEN: usize = HASH_LEN * HASH_LEN;
+
+/// The maximum length of the salt.
+pub const MAX_SALT_LEN: usize = 1 << 20;
+
+/// Fills `out` with the key derived from `password` and `salt` using `rounds`
+/// rounds of bcrypt-pbkdf.
+///
+/// Fails if `salt` or `password` is empty, if `salt` is longer than
+/// `MAX_SALT_LEN`, or if `out` is empty or longer than `MAX_OUTPUT_LEN`; these
+/// are the same limits OpenSSH enforces. OpenSSH uses 16 rounds by default.
+pub fn derive(
+    rounds: NonZeroU32,
+    salt: &[u8],
+    password: &[u8],
+    out: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    if password.is_empty()
+        || salt.is_empty()
+        || salt.len() > MAX_SALT_LEN
+        || out.is_empty()
+        || out.len() > MAX_OUTPUT_LEN
+    {
+        return Err(error::Unspecified);
+    }
+
+    // Output byte `i` of block `b` (counting from zero) is output byte
+    // `i * stride + b`, so that every block is needed for any prefix of the
+    // output.
+    let stride = (out.len() + HASH_LEN - 1) / HASH_LEN;
+
+    let sha2_password = digest::sha512(password);
+    for (b, count) in (0..stride).zip(1u32..) {
+        let mut salt_ctx = digest::Context::new(&digest::SHA512);
+        salt_ctx.update(salt);
+        salt_ctx.update(&count.to_be_bytes());
+        let mut u = bcrypt_hash(&sha2_password, salt_ctx.finish().as_ref());
+        let mut block = u;
+        for _ in 1..rounds.get() {
+            u = bcrypt_hash(&sha2_password, &digest::sha512(&u));
+            block.iter_mut().zip(u.iter()).for_each(|(b, u)| *b ^= u);
+        }
+
+        out.iter_mut()
+            .skip(b)
+            .step_by(stride)
+            .zip(block.iter())
+            .for_each(|(out, b)| *out = *b);
+    }
+
+    Ok(())
+}
+
+// The bcrypt hash of the SHA-512 digests of the password and the salt, as
+// defined by OpenBSD. This isn't the same as the bcrypt password hash.
+fn bcrypt_hash(
+    sha2_password: &[u8; digest::SHA512_OUTPUT_LEN],
+    sha2_salt: &[u8],
+) -> [u8; HASH_LEN] {
+    const CIPHERTEXT: [u8; HASH_LEN] = *b"OxychromaticBlowfishSwatDynamite";
+
+    let mut state = blowfish::State::new();
+    state.expand_with_salt(sha2_salt, sha2_password);
+    for _ in 0..64 {
+        state.expand_without_salt(sha2_salt);
+        state.expand_without_salt(sha2_password);
+    }
+
+    let mut words: [u32; HASH_LEN / 4] = CIPHERTEXT.array_split_map(u32::from_be_bytes);
+    for _ in 0..64 {
+        for pair in words.chunks_exact_mut(2) {
+            let (l, r) = state.encrypt(pair[0], pair[1]);
+            pair[0] = l;
+            pair[1] = r;
+        }
+    }
+
+    // Unlike the input, the output is little-endian.
+    let mut out = [0u8; HASH_LEN];
+    out.chunks_exact_mut(4)
+        .zip(words.iter())
+        .for_each(|(out, w)| out.copy_from_slice(&w.to_le_bytes()));
+    out
+}
+
+const HASH_LEN: usize = 32;
@@ -0,0 +1,57 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Support for benchmarking ring's primitives without `std::time`.
+//!
+//! Nothing in this module is cryptographic. In particular, timer values must
+//! not be used as a source of randomness or as nonces.
+//!
+//! This module is only available with the `unstable-bench` feature; its API
+//! may change in any release.
+
+use core::arch::asm;
+
+/// Reads the AArch64 virtual counter, `CNTVCT_EL0`.
+///
+/// The counter ticks at the fixed frequency given by `CNTFRQ_EL0`, which is
+/// independent of the CPU's clock frequency. An `ISB` precedes the read so
+/// that the read isn't reordered before earlier instructions.
+#[inline]
+pub fn cntvct() -> u64 {
+    let value: u64;
+    // SAFETY: `CNTVCT_EL0` is readable at EL0 on every operating system that
+    // ring supports, and reading it has no side effects.
+    unsafe {
+        asm!(
+            "isb",
+            "mrs {value}, cntvct_el0",
+            value = out(reg) value,
+            options(nomem, nostack, preserves_flags),
+        );
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn cntvct_monotonic_test() {
+        let mut previous = super::cntvct();
+        for _ in 0..1000 {
+            let current = super::cntvct();
+            assert!(current >= previous);
+            previous = current;
+        }
+    }
+}
@@ -0,0 +1,508 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! BLAKE3.
+//!
+//! [BLAKE3] has three modes: a hash function (`hash`), a keyed hash that is a
+//! MAC and a PRF (`keyed_hash`), and a key derivation function
+//! (`derive_key`). Each mode can produce any amount of output using
+//! `Hasher::finalize_xof()`; shorter outputs are prefixes of longer ones.
+//!
+//! Where available, AVX2 (x86-64) or NEON (AArch64) is used to hash several
+//! 1 KiB chunks of a long input in parallel.
+//!
+//! ```
+//! use ring::blake3;
+//!
+//! let mut hasher = blake3::Hasher::new();
+//! hasher.update(b"hello, ");
+//! hasher.update(b"world");
+//! assert_eq!(hasher.finalize(), blake3::hash(b"hello, world"));
+//! ```
+//!
+//! [BLAKE3]: https://github.com/BLAKE3-team/BLAKE3-specs/blob/master/blake3.pdf
+
+use crate::{constant_time, cpu, error, polyfill::u64_from_usize};
+
+#[cfg(target_arch = "x86_64")]
+mod avx2;
+#[cfg(target_arch = "aarch64")]
+mod neon;
+
+/// The length of the output of `hash`, `keyed_hash`, and `derive_key`, and
+/// of `Hasher::finalize()`.
+pub const OUT_LEN: usize = 32;
+
+/// The length of a key for `keyed_hash`.
+pub const KEY_LEN: usize = 32;
+
+/// Returns the BLAKE3 hash of `data`.
+pub fn hash(data: &[u8]) -> [u8; OUT_LEN] {
+    let mut hasher = Hasher::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Returns the BLAKE3 keyed hash of `data` under `key`.
+///
+/// Use `verify_keyed_hash` to check a keyed hash used as a MAC.
+pub fn keyed_hash(key: &[u8; KEY_LEN], data: &[u8]) -> [u8; OUT_LEN] {
+    let mut hasher = Hasher::new_keyed(key);
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Calculates the BLAKE3 keyed hash of `data` under `key` and checks, in
+/// constant time, that it is equal to `expected`.
+pub fn verify_keyed_hash(
+    key: &[u8; KEY_LEN],
+    data: &[u8],
+    expected: &[u8],
+) -> Result<(), error::Unspecified> {
+    constant_time::verify_slices_are_equal(&keyed_hash(key, data), expected)
+}
+
+/// Derives a key from `key_material` for the purpose described by
+/// `context`.
+///
+/// `context` should be a hardcoded, globally unique, application-specific
+/// string, e.g. `"example.com 2024-01-01 session tokens v1"`. It must not
+/// contain secrets or any other variable data.
+pub fn derive_key(context: &str, key_material: &[u8]) -> [u8; OUT_LEN] {
+    let mut hasher = Hasher::new_derive_key(context);
+    hasher.update(key_material);
+    hasher.finalize()
+}
+
+/// An incremental BLAKE3 computation, in any of the three modes.
+#[derive(Clone)]
+pub struct Hasher {
+    key: ChainingValue,
+    chunk_state: ChunkState,
+    // The chaining values of the completed subtrees, from the largest to the
+    // smallest. The number of entries is the number of 1 bits in the number
+    // of completed chunks.
+    cv_stack: [ChainingValue; MAX_DEPTH],
+    cv_stack_len: usize,
+    flags: u32,
+}
+
+impl core::fmt::Debug for Hasher {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        // The key and the state are secret in the keyed modes.
+        f.debug_struct("Hasher").finish_non_exhaustive()
+    }
+}
+
+impl Hasher {
+    /// Starts computing a hash.
+    pub fn new() -> Self {
+        Self::new_(IV, 0)
+    }
+
+    /// Starts computing a keyed hash under `key`.
+    pub fn new_keyed(key: &[u8; KEY_LEN]) -> Self {
+        Self::new_(words_from_le_bytes(key), KEYED_HASH)
+    }
+
+    /// Starts deriving a key for the purpose described by `context`; see
+    /// `derive_key`. The key material is the input to `update()`.
+    pub fn new_derive_key(context: &str) -> Self {
+        let mut context_hasher = Self::new_(IV, DERIVE_KEY_CONTEXT);
+        context_hasher.update(context.as_bytes());
+        let context_key = context_hasher.finalize();
+        Self::new_(words_from_le_bytes(&context_key), DERIVE_KEY_MATERIAL)
+    }
+
+    fn new_(key: ChainingValue, flags: u32) -> Self {
+        Self {
+            key,
+            chunk_state: ChunkState::new(&key, 0, flags),
+            cv_stack: [[0; 8]; MAX_DEPTH],
+            cv_stack_len: 0,
+            flags,
+        }
+    }
+
+    /// Appends `data` to the input.
+    pub fn update(&mut self, mut data: &[u8]) {
+        let cpu_features = cpu::features();
+
+        while !data.is_empty() {
+            // A full chunk is only finished once we know more input follows
+            // it, since the last chunk is finished differently if it is the
+            // root.
+            if self.chunk_state.len() == CHUNK_LEN {
+                let cv = self.chunk_state.output().chaining_value();
+                let total_chunks = self.chunk_state.chunk_counter + 1;
+                self.add_chunk_chaining_value(cv, total_chunks);
+                self.chunk_state = ChunkState::new(&self.key, total_chunks, self.flags);
+            }
+
+            if self.chunk_state.len() == 0 {
+                let mut cvs = [[0; 8]; MAX_SIMD_DEGREE];
+                let chunk_counter = self.chunk_state.chunk_counter;
+                let n = hash_chunks_simd(
+                    &self.key,
+                    chunk_counter,
+                    self.flags,
+                    data,
+                    &mut cvs,
+                    cpu_features,
+                );
+                if n > 0 {
+                    cvs[..n].iter().zip(1..).for_each(|(cv, i)| {
+                        self.add_chunk_chaining_value(*cv, chunk_counter + i);
+                    });
+                    let next_counter = chunk_counter + u64_from_usize(n);
+                    self.chunk_state = ChunkState::new(&self.key, next_counter, self.flags);
+                    data = &data[(n * CHUNK_LEN)..];
+                    continue;
+                }
+            }
+
+            let todo = core::cmp::min(CHUNK_LEN - self.chunk_state.len(), data.len());
+            let (chunk_data, rest) = data.split_at(todo);
+            self.chunk_state.update(chunk_data);
+            data = rest;
+        }
+    }
+
+    /// Returns the first `OUT_LEN` bytes of the output.
+    pub fn finalize(&self) -> [u8; OUT_LEN] {
+        let mut out = [0; OUT_LEN];
+        self.finalize_xof(&mut out);
+        out
+    }
+
+    /// Fills `out` with output; the output can be any length.
+    ///
+    /// The first `OUT_LEN` bytes are the same as the result of `finalize()`.
+    pub fn finalize_xof(&self, out: &mut [u8]) {
+        let mut output = self.chunk_state.output();
+        for left in self.cv_stack[..self.cv_stack_len].iter().rev() {
+            output = parent_output(left, &output.chaining_value(), &self.key, self.flags);
+        }
+        output.root_output_bytes(out);
+    }
+
+    fn add_chunk_chaining_value(&mut self, mut new_cv: ChainingValue, mut total_chunks: u64) {
+        // Merge every completed subtree whose sibling is now complete; there
+        // is one for each trailing 0 bit in `total_chunks`.
+        while total_chunks & 1 == 0 {
+            self.cv_stack_len -= 1;
+            let left = &self.cv_stack[self.cv_stack_len];
+            new_cv = parent_output(left, &new_cv, &self.key, self.flags).chaining_value();
+            total_chunks >>= 1;
+        }
+        self.cv_stack[self.cv_stack_len] = new_cv;
+        self.cv_stack_len += 1;
+    }
+}
+
+#[derive(Clone)]
+struct ChunkState {
+    cv: ChainingValue,
+    chunk_counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    fn new(key: &ChainingValue, chunk_counter: u64, flags: u32) -> Self {
+        Self {
+            cv: *key,
+            chunk_counter,
+            block: [0; BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        (BLOCK_LEN * self.blocks_compressed) + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // As with chunks, a full block is only compressed once we know it
+            // isn't the last one.
+            if self.block_len == BLOCK_LEN {
+                let block_words = words_from_le_bytes(&self.block);
+                let out = compress(
+                    &self.cv,
+                    &block_words,
+                    self.chunk_counter,
+                    BLOCK_LEN_U32,
+                    self.flags | self.start_flag(),
+                );
+                self.cv = first_8_words(&out);
+                self.blocks_compressed += 1;
+                self.block = [0; BLOCK_LEN];
+                self.block_len = 0;
+            }
+
+            let todo = core::cmp::min(BLOCK_LEN - self.block_len, data.len());
+            let (block_data, rest) = data.split_at(todo);
+            self.block[self.block_len..][..todo].copy_from_slice(block_data);
+            self.block_len += todo;
+            data = rest;
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            input_cv: self.cv,
+            block_words: words_from_le_bytes(&self.block),
+            counter: self.chunk_counter,
+            #[allow(clippy::cast_possible_truncation)]
+            block_len: self.block_len as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+// The input to the last compression of a chunk or a parent node, which is
+// either finished as a chaining value or, for the root, extended to the
+// requested output length.
+struct Output {
+    input_cv: ChainingValue,
+    block_words: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> ChainingValue {
+        first_8_words(&compress(
+            &self.input_cv,
+            &self.block_words,
+            self.counter,
+            self.block_len,
+            self.flags,
+        ))
+    }
+
+    fn root_output_bytes(&self, out: &mut [u8]) {
+        for (out_block, counter) in out.chunks_mut(2 * OUT_LEN).zip(0..) {
+            let words = compress(
+                &self.input_cv,
+                &self.block_words,
+                counter,
+                self.block_len,
+                self.flags | ROOT,
+            );
+            out_block
+                .chunks_mut(4)
+                .zip(words.iter())
+                .for_each(|(out, word)| out.copy_from_slice(&word.to_le_bytes()[..out.len()]));
+        }
+    }
+}
+
+fn parent_output(
+    left: &ChainingValue,
+    right: &ChainingValue,
+    key: &ChainingValue,
+    flags: u32,
+) -> Output {
+    let mut block_words = [0; 16];
+    block_words[..8].copy_from_slice(left);
+    block_words[8..].copy_from_slice(right);
+    Output {
+        input_cv: *key,
+        block_words,
+        counter: 0,
+        block_len: BLOCK_LEN_U32,
+        flags: PARENT | flags,
+    }
+}
+
+// Hashes the longest run of whole chunks at the start of `data` that the
+// available SIMD implementation can hash in parallel, writing their chaining
+// values to the start of `out` and returning the number of chunks. At least
+// one byte of `data` is always left over, so none of the chunks is the last
+// one. Returns zero if no SIMD implementation is available.
+fn hash_chunks_simd(
+    key: &ChainingValue,
+    chunk_counter: u64,
+    flags: u32,
+    data: &[u8],
+    out: &mut [ChainingValue; MAX_SIMD_DEGREE],
+    cpu_features: cpu::Features,
+) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    if cpu::intel::AVX2.available(cpu_features) && data.len() > avx2::DEGREE * CHUNK_LEN {
+        let chunks = data[..(avx2::DEGREE * CHUNK_LEN)].try_into().unwrap();
+        // SAFETY: We just checked that AVX2 is available.
+        unsafe { avx2::hash_chunks(key, chunk_counter, flags, chunks, out) };
+        return avx2::DEGREE;
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    if cpu::arm::NEON.available(cpu_features) && data.len() > neon::DEGREE * CHUNK_LEN {
+        let chunks = data[..(neon::DEGREE * CHUNK_LEN)].try_into().unwrap();
+        let out = (&mut out[..neon::DEGREE]).try_into().unwrap();
+        // SAFETY: We just checked that NEON is available.
+        unsafe { neon::hash_chunks(key, chunk_counter, flags, chunks, out) };
+        return neon::DEGREE;
+    }
+
+    let _ = (key, chunk_counter, flags, data, out, cpu_features);
+    0
+}
+
+fn compress(
+    cv: &ChainingValue,
+    block_words: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let [counter_low, counter_high] = split_counter(counter);
+    #[rustfmt::skip]
+    let mut v = [
+        cv[0], cv[1], cv[2], cv[3],
+        cv[4], cv[5], cv[6], cv[7],
+        IV[0], IV[1], IV[2], IV[3],
+        counter_low, counter_high, block_len, flags,
+    ];
+    for s in MSG_SCHEDULE.iter() {
+        let m = |i: usize| block_words[s[i]];
+        // Mix the columns.
+        g(&mut v, 0, 4, 8, 12, m(0), m(1));
+        g(&mut v, 1, 5, 9, 13, m(2), m(3));
+        g(&mut v, 2, 6, 10, 14, m(4), m(5));
+        g(&mut v, 3, 7, 11, 15, m(6), m(7));
+        // Mix the diagonals.
+        g(&mut v, 0, 5, 10, 15, m(8), m(9));
+        g(&mut v, 1, 6, 11, 12, m(10), m(11));
+        g(&mut v, 2, 7, 8, 13, m(12), m(13));
+        g(&mut v, 3, 4, 9, 14, m(14), m(15));
+    }
+    for i in 0..8 {
+        v[i] ^= v[i + 8];
+        v[i + 8] ^= cv[i];
+    }
+    v
+}
+
+#[inline(always)]
+fn g(v: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(12);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(8);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(7);
+}
+
+fn split_counter(counter: u64) -> [u32; 2] {
+    #[allow(clippy::cast_possible_truncation)]
+    let words = [counter as u32, (counter >> 32) as u32];
+    words
+}
+
+fn words_from_le_bytes<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    let mut words = [0; N];
+    words
+        .iter_mut()
+        .zip(bytes.chunks_exact(4))
+        .for_each(|(word, bytes)| *word = u32::from_le_bytes(bytes.try_into().unwrap()));
+    words
+}
+
+fn first_8_words(words: &[u32; 16]) -> ChainingValue {
+    words[..8].try_into().unwrap()
+}
+
+type ChainingValue = [u32; 8];
+
+const BLOCK_LEN: usize = 64;
+#[allow(clippy::cast_possible_truncation)]
+const BLOCK_LEN_U32: u32 = BLOCK_LEN as u32;
+const CHUNK_LEN: usize = 1024;
+
+// Enough for 2**64 bytes of input.
+const MAX_DEPTH: usize = 54;
+
+const MAX_SIMD_DEGREE: usize = 8;
+
+// Domain separation flags.
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+const IV: ChainingValue = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+// The message word permutation applied 0..7 times; round `r` uses
+// `MSG_SCHEDULE[r]`.
+const MSG_SCHEDULE: [[usize; 16]; 7] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+    [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+    [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+    [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+    [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+    [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The SIMD implementations must agree with the portable one.
+    #[test]
+    fn test_hash_chunks_simd() {
+        let mut data = [0u8; (MAX_SIMD_DEGREE + 1) * CHUNK_LEN];
+        data.iter_mut()
+            .zip((0..251).cycle())
+            .for_each(|(b, i)| *b = i);
+        let key = words_from_le_bytes(b"whats the Elvish word for friend");
+        let cpu_features = cpu::features();
+
+        for &(key, flags) in &[(IV, 0), (key, KEYED_HASH)] {
+            for chunk_counter in [0, 1, u64::from(u32::MAX)] {
+                let mut simd = [[0; 8]; MAX_SIMD_DEGREE];
+                let n =
+                    hash_chunks_simd(&key, chunk_counter, flags, &data, &mut simd, cpu_features);
+                for ((chunk, cv), i) in data.chunks(CHUNK_LEN).zip(&simd[..n]).zip(0..) {
+                    let mut chunk_state = ChunkState::new(&key, chunk_counter + i, flags);
+                    chunk_state.update(chunk);
+                    assert_eq!(&chunk_state.output().chaining_value(), cv);
+                }
+            }
+        }
+    }
+}
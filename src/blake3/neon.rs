@@ -0,0 +1,139 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Hashing of four chunks in parallel using NEON. Lane `j` of each vector
+//! holds the corresponding state word of chunk `j`.
+
+use super::{
+    split_counter, ChainingValue, BLOCK_LEN, BLOCK_LEN_U32, CHUNK_END, CHUNK_LEN, CHUNK_START, IV,
+    MSG_SCHEDULE,
+};
+use core::arch::aarch64::*;
+
+pub(super) const DEGREE: usize = 4;
+
+/// Writes the chaining values of the `DEGREE` chunks in `chunks`, the first
+/// of which has index `chunk_counter`, to `out`.
+///
+/// # Safety
+///
+/// NEON must be available.
+#[target_feature(enable = "neon")]
+pub(super) unsafe fn hash_chunks(
+    key: &ChainingValue,
+    chunk_counter: u64,
+    flags: u32,
+    chunks: &[u8; DEGREE * CHUNK_LEN],
+    out: &mut [ChainingValue; DEGREE],
+) {
+    let mut counter_low = [0; DEGREE];
+    let mut counter_high = [0; DEGREE];
+    for (j, counter) in (chunk_counter..).take(DEGREE).enumerate() {
+        [counter_low[j], counter_high[j]] = split_counter(counter);
+    }
+    let counter_low = unsafe { load(&counter_low) };
+    let counter_high = unsafe { load(&counter_high) };
+
+    let mut cv = [vdupq_n_u32(0); 8];
+    for (cv, &k) in cv.iter_mut().zip(key) {
+        *cv = vdupq_n_u32(k);
+    }
+
+    for block in 0..(CHUNK_LEN / BLOCK_LEN) {
+        let mut block_flags = flags;
+        if block == 0 {
+            block_flags |= CHUNK_START;
+        }
+        if block == (CHUNK_LEN / BLOCK_LEN) - 1 {
+            block_flags |= CHUNK_END;
+        }
+
+        let mut m = [vdupq_n_u32(0); 16];
+        for (w, m) in m.iter_mut().enumerate() {
+            let mut words = [0; DEGREE];
+            for (j, word) in words.iter_mut().enumerate() {
+                let offset = (j * CHUNK_LEN) + (block * BLOCK_LEN) + (w * 4);
+                *word = u32::from_le_bytes(chunks[offset..][..4].try_into().unwrap());
+            }
+            *m = unsafe { load(&words) };
+        }
+
+        #[rustfmt::skip]
+        let mut v = [
+            cv[0], cv[1], cv[2], cv[3],
+            cv[4], cv[5], cv[6], cv[7],
+            vdupq_n_u32(IV[0]), vdupq_n_u32(IV[1]), vdupq_n_u32(IV[2]), vdupq_n_u32(IV[3]),
+            counter_low, counter_high, vdupq_n_u32(BLOCK_LEN_U32), vdupq_n_u32(block_flags),
+        ];
+        for s in MSG_SCHEDULE.iter() {
+            unsafe {
+                g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+                g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+                g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+                g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+                g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+                g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+                g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+                g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+            }
+        }
+        for i in 0..8 {
+            cv[i] = veorq_u32(v[i], v[i + 8]);
+        }
+    }
+
+    for (i, cv) in cv.iter().enumerate() {
+        let mut words = [0u32; DEGREE];
+        unsafe { vst1q_u32(words.as_mut_ptr(), *cv) };
+        for (out, &word) in out.iter_mut().zip(words.iter()) {
+            out[i] = word;
+        }
+    }
+}
+
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn g(
+    v: &mut [uint32x4_t; 16],
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    x: uint32x4_t,
+    y: uint32x4_t,
+) {
+    unsafe {
+        v[a] = vaddq_u32(vaddq_u32(v[a], v[b]), x);
+        v[d] = rotate_right::<16, 16>(veorq_u32(v[d], v[a]));
+        v[c] = vaddq_u32(v[c], v[d]);
+        v[b] = rotate_right::<12, 20>(veorq_u32(v[b], v[c]));
+        v[a] = vaddq_u32(vaddq_u32(v[a], v[b]), y);
+        v[d] = rotate_right::<8, 24>(veorq_u32(v[d], v[a]));
+        v[c] = vaddq_u32(v[c], v[d]);
+        v[b] = rotate_right::<7, 25>(veorq_u32(v[b], v[c]));
+    }
+}
+
+// `L` must be `32 - R`.
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn rotate_right<const R: i32, const L: i32>(x: uint32x4_t) -> uint32x4_t {
+    vorrq_u32(vshrq_n_u32::<R>(x), vshlq_n_u32::<L>(x))
+}
+
+#[target_feature(enable = "neon")]
+#[inline]
+unsafe fn load(words: &[u32; DEGREE]) -> uint32x4_t {
+    unsafe { vld1q_u32(words.as_ptr()) }
+}
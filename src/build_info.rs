@@ -0,0 +1,107 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use crate::cpu;
+
+/// Information about how *ring* was built, e.g. for checking in CI that an
+/// artifact was built with the intended optimizations.
+///
+/// This only describes what was compiled in; which implementation is used at
+/// runtime also depends on the features the CPU supports.
+pub struct BuildInfo {
+    _private: (),
+}
+
+/// Information about how this copy of *ring* was built.
+///
+/// ```
+/// for module in ring::BUILD_INFO.asm_modules() {
+///     println!("{}", module);
+/// }
+/// ```
+pub const BUILD_INFO: BuildInfo = BuildInfo { _private: () };
+
+impl BuildInfo {
+    /// The names of the assembly language modules that were compiled in, in
+    /// sorted order, e.g. `"aesni-gcm-x86_64"`. The names are the base names
+    /// of the source files, without the platform-specific suffix.
+    ///
+    /// There are none when *ring* was built for a target that it has no
+    /// assembly language code for.
+    pub fn asm_modules(&self) -> impl Iterator<Item = &'static str> {
+        // Keep in sync with `emit_asm_modules()` in build.rs.
+        env!("RING_ASM_MODULES")
+            .split(',')
+            .filter(|module| !module.is_empty())
+    }
+
+    /// The names of the CPU features that the code was compiled to require,
+    /// through the target's `target_feature`s, so that they are used without
+    /// runtime detection, e.g. `"NEON"` on AArch64.
+    ///
+    /// This is empty on x86 and x86-64, where *ring* detects all the CPU
+    /// features it uses at runtime.
+    pub fn static_cpu_features(&self) -> impl Iterator<Item = &'static str> {
+        cpu::static_feature_names()
+    }
+}
+
+impl core::fmt::Debug for BuildInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        struct List<F>(F);
+        impl<F, I> core::fmt::Debug for List<F>
+        where
+            F: Fn() -> I,
+            I: Iterator<Item = &'static str>,
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+                f.debug_list().entries((self.0)()).finish()
+            }
+        }
+        f.debug_struct("BuildInfo")
+            .field("asm_modules", &List(|| self.asm_modules()))
+            .field("static_cpu_features", &List(|| self.static_cpu_features()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asm_modules() {
+        let modules = BUILD_INFO.asm_modules().collect::<alloc::vec::Vec<_>>();
+        let mut sorted = modules.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(modules, sorted);
+
+        if cfg!(all(target_arch = "x86_64", target_os = "linux")) {
+            assert!(modules.contains(&"aesni-gcm-x86_64"));
+            assert!(modules.contains(&"sha256-x86_64"));
+            assert!(modules.contains(&"sha512-x86_64"));
+        }
+    }
+
+    #[test]
+    fn test_static_cpu_features() {
+        if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
+            assert_eq!(BUILD_INFO.static_cpu_features().count(), 0);
+        }
+        if cfg!(target_arch = "aarch64") {
+            assert!(BUILD_INFO.static_cpu_features().any(|f| f == "NEON"));
+        }
+    }
+}
@@ -41,6 +41,44 @@ prefixed_extern! {
     fn CRYPTO_memcmp(a: *const u8, b: *const u8, len: c::size_t) -> c::int;
 }
 
+/// Copies `table[index]` into `out`, in constant time with respect to
+/// `index`.
+///
+/// Every entry of `table` is read, and the wanted one is selected by masking,
+/// so that neither the memory access pattern nor the control flow depends on
+/// `index`. This makes it suitable for the windowed table lookups of scalar
+/// multiplication. The time taken does depend on `table.len()`.
+///
+/// Only the first 256 entries of `table` can be selected. If `index` is not
+/// less than `table.len()` then `out` is filled with zeros.
+pub fn table_lookup<const N: usize>(table: &[[u8; N]], index: u8, out: &mut [u8; N]) {
+    out.fill(0);
+    table.iter().zip(0..=u8::MAX).for_each(|(entry, i)| {
+        let mask = eq_mask(i, index);
+        out.iter_mut()
+            .zip(entry.iter())
+            .for_each(|(out, entry)| *out |= entry & mask);
+    });
+}
+
+// Returns 0xff if `a == b` and 0 otherwise, without branching.
+#[inline(always)]
+fn eq_mask(a: u8, b: u8) -> u8 {
+    let diff = u32::from(a ^ b);
+    // `diff - 1` only borrows into the upper bits when `diff` is zero.
+    #[allow(clippy::cast_possible_truncation)]
+    let mask = (diff.wrapping_sub(1) >> 8) as u8;
+    value_barrier(mask)
+}
+
+// Keeps the optimizer from reasoning about `value`, so it can't turn the
+// masking that uses it back into a branch.
+#[inline(always)]
+fn value_barrier(value: u8) -> u8 {
+    // SAFETY: `&value` is a valid, aligned pointer to an initialized `u8`.
+    unsafe { core::ptr::read_volatile(&value) }
+}
+
 pub(crate) fn xor_16(a: [u8; 16], b: [u8; 16]) -> [u8; 16] {
     let a = u128::from_ne_bytes(a);
     let b = u128::from_ne_bytes(b);
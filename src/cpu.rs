@@ -12,21 +12,105 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-pub(crate) use self::features::Features;
+//! CPU feature detection.
 
+pub use self::features::Features;
+
+/// Returns a token witnessing that CPU feature detection has been done.
+///
+/// Detection itself happens only once per process, but getting the token
+/// still checks that it has happened, which is a synchronized read. Most
+/// functions in *ring* do this on every call. For hot loops of many tiny
+/// operations, get the token once and pass it to the functions that accept
+/// one, such as `aead::quic::HeaderProtectionKey::new_mask_with_cpu_features`.
 #[inline(always)]
-pub(crate) fn features() -> Features {
+pub fn features() -> Features {
+    #[cfg(test)]
+    calls::increment();
     get_or_init_feature_flags()
 }
 
+/// Re-runs CPU feature detection, replacing the results that were cached the
+/// first time *ring* needed them.
+///
+/// The set of features supported by the CPU doesn't change during the
+/// lifetime of a process, with one narrow exception: a process that is
+/// checkpointed and restored on a different machine (e.g. using CRIU), or
+/// live-migrated between heterogeneous hosts, may resume on a CPU that
+/// supports a different set of features. In that case the cached results may
+/// cause *ring* to use instructions the new CPU doesn't support, or to miss
+/// out on instructions that it does. Call this function after such a
+/// migration. There is no reason to call it in any other situation; in
+/// particular, it is *not* needed after `fork()`.
+///
+/// # Safety
+///
+/// No other thread may be using *ring* while this function is executing.
+///
+/// Every key and other object created by *ring* before the call may have
+/// chosen, or may have been computed by, an implementation that is no longer
+/// selected (or no longer supported) afterwards; for example, the expanded
+/// form of an AES key depends on whether AES instructions were available. All
+/// such objects must be dropped before this function is called and must not
+/// be used afterwards. Internally, *ring* passes around tokens that witness
+/// that feature detection has completed; any such token that is outstanding
+/// when this function is called becomes stale.
+pub unsafe fn reinitialize_after_migration() {
+    // SAFETY: Delegated to the caller.
+    unsafe { reinitialize_feature_flags() }
+}
+
+/// Registers a function that detects the features of the CPU on targets for
+/// which *ring* has no way to detect them itself.
+///
+/// This only has an effect on AArch64 and 32-bit ARM targets for operating
+/// systems on which *ring* doesn't do dynamic feature detection, e.g. bare
+/// metal targets. It is ignored on all other targets, including those on
+/// which *ring* does its own detection.
+///
+/// `detector` returns a bitmask of features in the same layout as OpenSSL's
+/// `OPENSSL_armcap_P`, e.g. `1 << 0` for `ARMV7_NEON`, `1 << 2` for
+/// `ARMV8_AES`, and `1 << 5` for `ARMV8_PMULL`. Bits for features *ring*
+/// doesn't know about are ignored. Features that are enabled statically,
+/// e.g. with `-C target-feature=+aes`, are always used regardless of what
+/// `detector` returns.
+///
+/// Detection happens once, the first time *ring* needs it, so this must be
+/// called before *ring* is otherwise used; a detector registered afterwards
+/// is only used by [`reinitialize_after_migration()`]. Only the first
+/// registration takes effect.
+///
+/// # Safety
+///
+/// `detector` must only report features that the CPU supports, every time it
+/// is called. *ring* executes the instructions of every feature it reports,
+/// so reporting a feature that the CPU doesn't support is undefined behavior.
+///
+/// No other thread may be using *ring* while this function is executing.
+pub unsafe fn register_feature_detector(detector: fn() -> u32) {
+    #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+    arm::register_feature_detector(detector);
+
+    #[cfg(not(any(target_arch = "aarch64", target_arch = "arm")))]
+    let _ = detector;
+}
+
 mod features {
     use crate::polyfill::NotSend;
 
     /// A witness indicating that CPU features have been detected and cached.
     ///
     /// This is a zero-sized type so that it can be "stored" wherever convenient.
+    /// It is neither `Send` nor `Sync`, so each thread must get its own from
+    /// `cpu::features()`.
     #[derive(Copy, Clone)]
-    pub(crate) struct Features(NotSend);
+    pub struct Features(NotSend);
+
+    impl core::fmt::Debug for Features {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.write_str("Features")
+        }
+    }
 
     cfg_if::cfg_if! {
         if #[cfg(any(target_arch = "aarch64", target_arch = "arm",
@@ -52,14 +136,124 @@ const _: () = assert!(core::mem::size_of::<Features>() == 0);
 
 cfg_if::cfg_if! {
     if #[cfg(any(target_arch = "aarch64", target_arch = "arm"))] {
-        pub mod arm;
-        use arm::featureflags::get_or_init as get_or_init_feature_flags;
+        pub(crate) mod arm;
+        pub use arm::implementer_and_part;
+        use arm::featureflags::{
+            get_or_init as get_or_init_feature_flags,
+            reinitialize as reinitialize_feature_flags,
+        };
     } else if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
-        pub mod intel;
-        use intel::featureflags::get_or_init as get_or_init_feature_flags;
+        pub(crate) mod intel;
+        use intel::featureflags::{
+            get_or_init as get_or_init_feature_flags,
+            reinitialize as reinitialize_feature_flags,
+        };
     } else {
         pub(super) fn get_or_init_feature_flags() -> Features {
             Features::new_no_features_to_detect()
         }
+
+        unsafe fn reinitialize_feature_flags() {}
+    }
+}
+
+// The names of the CPU features that are enabled statically.
+pub(crate) fn static_feature_names() -> impl Iterator<Item = &'static str> {
+    #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+    {
+        arm::static_feature_names()
+    }
+
+    // All features are detected dynamically on other targets.
+    #[cfg(not(any(target_arch = "aarch64", target_arch = "arm")))]
+    {
+        core::iter::empty()
+    }
+}
+
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    any(
+        test,
+        all(feature = "std", any(target_arch = "aarch64", target_arch = "arm"))
+    )
+))]
+mod linux_cpuinfo;
+
+// Counts how many times `get_or_init_feature_flags()` has run feature
+// detection, so tests can verify that detection happens at most once.
+// `reinitialize_feature_flags()` isn't counted.
+#[cfg(all(
+    test,
+    any(
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    )
+))]
+mod detection_count {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    pub(super) fn increment() {
+        let _: usize = COUNT.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn get() -> usize {
+        COUNT.load(Ordering::Relaxed)
+    }
+}
+
+// Counts the calls to `features()` on the current thread, so tests can verify
+// that an operation that is given a `Features` doesn't get another one.
+#[cfg(test)]
+pub(crate) mod calls {
+    extern crate std;
+    use core::cell::Cell;
+
+    std::thread_local! {
+        static COUNT: Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub(super) fn increment() {
+        COUNT.with(|count| count.set(count.get() + 1));
+    }
+
+    pub(crate) fn get() -> usize {
+        COUNT.with(|count| count.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    ))]
+    #[test]
+    fn test_features_detected_once() {
+        extern crate std;
+        use std::{sync::Barrier, thread, vec::Vec};
+
+        const THREADS: usize = 32;
+
+        let barrier = Barrier::new(THREADS);
+        thread::scope(|s| {
+            let threads = (0..THREADS)
+                .map(|_| {
+                    s.spawn(|| {
+                        let _ = barrier.wait();
+                        let _: super::Features = super::features();
+                    })
+                })
+                .collect::<Vec<_>>();
+            threads.into_iter().for_each(|t| t.join().unwrap());
+        });
+
+        assert_eq!(super::detection_count::get(), 1);
     }
 }
@@ -12,6 +12,8 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
+//! ARM-specific CPU information.
+
 mod abi_assumptions {
     // TODO: Support ARM64_32; see
     // https://github.com/briansmith/ring/issues/1832#issuecomment-1892928147. This also requires
@@ -53,13 +55,80 @@ cfg_if::cfg_if! {
         mod windows;
         use windows as detect;
     } else {
+        // There is no dynamic detection on other operating systems, except
+        // by a detector that the application registered. This is
+        // conservative for CPUs that *ring* doesn't know about, but a target
+        // that is known to support a feature can still enable it statically,
+        // e.g. with `-C target-feature=+aes`; see `ARMCAP_STATIC`.
         mod detect {
+            use super::FEATURE_DETECTOR;
+
             pub const FORCE_DYNAMIC_DETECTION: u32 = 0;
-            pub fn detect_features() -> u32 { 0 }
+            pub fn detect_features() -> u32 {
+                FEATURE_DETECTOR.get().map_or(0, |detector| detector())
+            }
+
+            #[cfg(test)]
+            mod tests {
+                use super::super::{ALL_FEATURES, ARMCAP_STATIC};
+                use crate::cpu;
+
+                #[test]
+                fn test_only_static_features_available() {
+                    assert_eq!(super::detect_features(), 0);
+
+                    let cpu = cpu::features();
+                    ALL_FEATURES.iter().for_each(|feature| {
+                        let enabled_statically = (ARMCAP_STATIC & feature.mask) != 0;
+                        assert_eq!(feature.available(cpu), enabled_statically);
+                    });
+
+                    #[cfg(all(target_arch = "aarch64", target_feature = "aes"))]
+                    assert!(super::super::AES.available(cpu));
+                }
+
+                // This writes the feature flags, so it must not run
+                // concurrently with any other test. CI runs it by itself with
+                // `--ignored --test-threads=1`.
+                #[cfg(target_arch = "aarch64")]
+                #[test]
+                #[ignore = "writes the global feature flags"]
+                fn test_registered_feature_detector() {
+                    use super::super::{featureflags, SVE2};
+
+                    // SAFETY: No *ring* code uses SVE2, so reporting it is
+                    // harmless even if the CPU doesn't support it, and no
+                    // other test is running.
+                    unsafe { cpu::register_feature_detector(|| SVE2.mask) };
+                    assert_eq!(super::detect_features(), SVE2.mask);
+                    // SAFETY: No other test is running, and the flags are
+                    // only extended by a feature that isn't used.
+                    unsafe { featureflags::reinitialize() };
+
+                    let cpu = cpu::features();
+                    assert_eq!(
+                        SVE2.available(cpu),
+                        !cfg!(feature = "unstable-testing-arm-no-hw")
+                    );
+                    ALL_FEATURES.iter().for_each(|feature| {
+                        if (ARMCAP_STATIC & feature.mask) != 0 {
+                            assert!(feature.available(cpu));
+                        }
+                    });
+                }
+            }
         }
     }
 }
 
+// A detector registered by the application, which is only used when there is
+// no dynamic detection for the target.
+static FEATURE_DETECTOR: spin::Once<fn() -> u32> = spin::Once::new();
+
+pub(super) fn register_feature_detector(detector: fn() -> u32) {
+    let _: &fn() -> u32 = FEATURE_DETECTOR.call_once(|| detector);
+}
+
 macro_rules! features {
     {
         $(
@@ -95,9 +164,45 @@ macro_rules! features {
                 $name
             ),+
         ];
+
+        const ALL_FEATURE_NAMES: &[(&str, &Feature)] = &[
+            $(
+                (stringify!($name), &$name)
+            ),+
+        ];
     }
 }
 
+/// Returns the implementer code and primary part number from the CPU's Main
+/// ID Register (`MIDR` or `MIDR_EL1`), e.g. `(0x41, 0xd0b)` for an Arm
+/// Cortex-A76.
+///
+/// This is for diagnostics, such as logging which CPU a program is running
+/// on; *ring* doesn't use it. On Linux and Android, the register is read
+/// directly when the kernel allows it; otherwise, when the `std` feature is
+/// enabled, the values are read from `/proc/cpuinfo`. Returns `None` if the
+/// information isn't available, and always on other operating systems. On
+/// systems with cores of different types, the result may describe any one of
+/// them.
+pub fn implementer_and_part() -> Option<(u8, u16)> {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        detect::implementer_and_part()
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+pub(super) fn static_feature_names() -> impl Iterator<Item = &'static str> {
+    ALL_FEATURE_NAMES
+        .iter()
+        .filter(|(_, feature)| feature.mask == feature.mask & ARMCAP_STATIC)
+        .map(|&(name, _)| name)
+}
+
 pub(crate) struct Feature {
     mask: u32,
 }
@@ -131,12 +236,15 @@ features! {
 
     // Keep in sync with `ARMV8_PMULL`.
     //
-    // TODO(MSRV): There is no "pmull" feature listed from
-    // `rustc --print cfg --target=aarch64-apple-darwin`. Originally ARMv8 tied
-    // PMULL detection into AES detection, but later versions split it; see
+    // There is no "pmull" feature listed from
+    // `rustc --print cfg --target=aarch64-apple-darwin`; rustc's "aes" feature
+    // means FEAT_AES and FEAT_PMULL together. PMULL can't be present without
+    // AES: `ID_AA64ISAR0_EL1.AES` is 0b0001 for FEAT_AES and 0b0010 for
+    // FEAT_AES plus FEAT_PMULL, and the Linux kernel derives `HWCAP_AES` and
+    // `HWCAP_PMULL` from that one field. See
     // https://developer.arm.com/downloads/-/exploration-tools/feature-names-for-a-profile
-    // "Features introduced prior to 2020." Change this to use "pmull" when
-    // that is supported.
+    // "Features introduced prior to 2020." Dynamic detection still reports
+    // PMULL separately, since a core may have AES without PMULL.
     "aes" => PMULL {
         mask: 1 << 5,
     },
@@ -146,6 +254,23 @@ features! {
     "sha3" => SHA512 {
         mask: 1 << 6,
     },
+
+    // FEAT_RNG, i.e. the `RNDR` and `RNDRRS` registers. The mask matches
+    // OpenSSL's `ARMV8_RNG`.
+    "rand" => RNG {
+        mask: 1 << 8,
+    },
+
+    // SVE and SVE2 are detected so that future vectorized kernels can be
+    // gated on them; no *ring* kernel currently dispatches on either of them.
+    // The masks match OpenSSL's `ARMV8_SVE` and `ARMV8_SVE2`.
+    "sve" => SVE {
+        mask: 1 << 13,
+    },
+
+    "sve2" => SVE2 {
+        mask: 1 << 14,
+    },
 }
 
 #[cfg(target_arch = "arm")]
@@ -161,41 +286,57 @@ pub(super) mod featureflags {
     use crate::cpu;
     use core::ptr;
 
-    pub(in super::super) fn get_or_init() -> cpu::Features {
-        // SAFETY: `init` must be called only in `INIT.call_once(init)` below.
-        unsafe fn init() {
-            let detected = detect::detect_features();
-            let filtered = (if cfg!(feature = "unstable-testing-arm-no-hw") {
-                ALL_FEATURES
-                    .iter()
-                    .fold(0, |acc, feature| acc | feature.mask)
-                    & !NEON.mask
-            } else {
-                0
-            }) | (if cfg!(feature = "unstable-testing-arm-no-neon") {
-                NEON.mask
-            } else {
-                0
-            });
-            let detected = detected & !filtered;
-            let merged = ARMCAP_STATIC | detected;
-            // SAFETY: https://github.com/rust-lang/rust/issues/125833
-            let p = unsafe { ptr::addr_of_mut!(OPENSSL_armcap_P) };
-            // SAFETY: This is the only writer. Any concurrent reading doesn't
-            // affect the safety of this write.
-            unsafe {
-                p.write(merged);
-            }
+    // SAFETY: `init` must be called only in `INIT.call_once(init)` below or
+    // in `reinitialize()`.
+    unsafe fn init() {
+        let known = ALL_FEATURES
+            .iter()
+            .fold(0, |acc, feature| acc | feature.mask);
+        let detected = detect::detect_features() & known;
+        let filtered = (if cfg!(feature = "unstable-testing-arm-no-hw") {
+            known & !NEON.mask
+        } else {
+            0
+        }) | (if cfg!(feature = "unstable-testing-arm-no-neon") {
+            NEON.mask
+        } else {
+            0
+        });
+        let detected = detected & !filtered;
+        let merged = ARMCAP_STATIC | detected;
+        // SAFETY: https://github.com/rust-lang/rust/issues/125833
+        let p = unsafe { ptr::addr_of_mut!(OPENSSL_armcap_P) };
+        // SAFETY: This is the only writer. Any concurrent reading doesn't
+        // affect the safety of this write.
+        unsafe {
+            p.write(merged);
         }
+    }
+
+    pub(in super::super) fn get_or_init() -> cpu::Features {
         static INIT: spin::Once<()> = spin::Once::new();
         // SAFETY: This is the only caller. Any concurrent reading doesn't
         // affect the safety of the writing.
-        let () = INIT.call_once(|| unsafe { init() });
+        let () = INIT.call_once(|| {
+            #[cfg(test)]
+            cpu::detection_count::increment();
+            unsafe { init() }
+        });
         // SAFETY: We initialized the CPU features as required.
         // `INIT.call_once` has `happens-before` semantics.
         unsafe { cpu::Features::new_after_feature_flags_written_and_synced_unchecked() }
     }
 
+    // SAFETY: The caller must ensure that nothing reads `OPENSSL_armcap_P`
+    // concurrently.
+    pub(in super::super) unsafe fn reinitialize() {
+        // Ensure `INIT.call_once()` has completed so that it will never write
+        // `OPENSSL_armcap_P` after us.
+        let _: cpu::Features = get_or_init();
+        // SAFETY: Delegated to the caller.
+        unsafe { init() }
+    }
+
     pub(super) fn get(_cpu_features: cpu::Features) -> u32 {
         // SAFETY: https://github.com/rust-lang/rust/issues/125833
         let p = unsafe { ptr::addr_of!(OPENSSL_armcap_P) };
@@ -207,6 +348,14 @@ pub(super) mod featureflags {
         unsafe { ptr::read(p) }
     }
 
+    // SAFETY: The caller must ensure that nothing reads `OPENSSL_armcap_P`
+    // concurrently, and that `value` is a superset of `ARMCAP_STATIC`.
+    #[cfg(test)]
+    pub(super) unsafe fn set_for_testing(_cpu_features: cpu::Features, value: u32) {
+        // SAFETY: Delegated to the caller.
+        unsafe { ptr::addr_of_mut!(OPENSSL_armcap_P).write(value) }
+    }
+
     // Some non-Rust code still checks this even when it is statically known
     // the given feature is available, so we have to ensure that this is
     // initialized properly. Keep this in sync with the initialization in
@@ -228,6 +377,15 @@ pub(super) mod featureflags {
 const _AARCH64_HAS_NEON: () =
     assert!(((ARMCAP_STATIC & NEON.mask) == NEON.mask) || !cfg!(target_arch = "aarch64"));
 
+// On 32-bit ARM, NEON is only assumed when it is enabled statically, e.g. with
+// `-C target-feature=+neon`; otherwise it must be detected at runtime.
+#[allow(clippy::assertions_on_constants)]
+const _ARM_NEON_STATIC_ONLY_IF_ENABLED: () = assert!(
+    ((ARMCAP_STATIC & NEON.mask) == NEON.mask)
+        == cfg!(all(target_arch = "arm", target_feature = "neon"))
+        || !cfg!(target_arch = "arm")
+);
+
 #[allow(clippy::assertions_on_constants)]
 const _FORCE_DYNAMIC_DETECTION_HONORED: () =
     assert!((ARMCAP_STATIC & detect::FORCE_DYNAMIC_DETECTION) == 0);
@@ -237,6 +395,21 @@ mod tests {
     use super::*;
     use crate::cpu;
 
+    #[test]
+    fn test_static_feature_names() {
+        let reported = static_feature_names()
+            .map(|name| {
+                ALL_FEATURE_NAMES
+                    .iter()
+                    .find(|&&(n, _)| n == name)
+                    .unwrap()
+                    .1
+                    .mask
+            })
+            .fold(0, |acc, mask| acc | mask);
+        assert_eq!(reported, ARMCAP_STATIC);
+    }
+
     #[test]
     fn test_mask_abi() {
         assert_eq!(NEON.mask, 1);
@@ -249,6 +422,19 @@ mod tests {
         assert_eq!(SHA256.mask, 16);
         assert_eq!(PMULL.mask, 32);
         assert_eq!(SHA512.mask, 64);
+        assert_eq!(SVE.mask, 1 << 13);
+        assert_eq!(SVE2.mask, 1 << 14);
+        assert_eq!(RNG.mask, 1 << 8);
+    }
+
+    #[test]
+    fn test_masks_are_distinct() {
+        ALL_FEATURES.iter().enumerate().for_each(|(i, a)| {
+            assert_eq!(a.mask.count_ones(), 1);
+            ALL_FEATURES[(i + 1)..]
+                .iter()
+                .for_each(|b| assert_eq!(a.mask & b.mask, 0));
+        })
     }
 
     #[test]
@@ -263,4 +449,75 @@ mod tests {
             }
         })
     }
+
+    // Without static NEON, `NEON.available()` reflects what was detected,
+    // and the non-NEON implementations are used when it is cleared.
+    //
+    // This writes the feature flags, so it must not run concurrently with
+    // any other test. CI runs it by itself with `--ignored --test-threads=1`.
+    #[cfg(all(target_arch = "arm", not(target_feature = "neon")))]
+    #[test]
+    #[ignore = "writes the global feature flags"]
+    fn test_neon_detected_dynamically() {
+        use crate::aead;
+
+        // The implementation is chosen based on `cpu::features()`.
+        fn seal() -> ([u8; 1000], [u8; aead::MAX_TAG_LEN]) {
+            let key = aead::UnboundKey::new(&aead::AES_128_GCM, &[0x42; 16]).unwrap();
+            let key = aead::LessSafeKey::new(key);
+            let mut in_out = [0x5a; 1000];
+            let tag = key
+                .seal_in_place_separate_tag(
+                    aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]),
+                    aead::Aad::empty(),
+                    &mut in_out,
+                )
+                .unwrap();
+            (in_out, tag.as_ref().try_into().unwrap())
+        }
+
+        assert_eq!(ARMCAP_STATIC & NEON.mask, 0);
+
+        let cpu = cpu::features();
+        let original = featureflags::get(cpu);
+        assert_eq!(NEON.available(cpu), original & NEON.mask == NEON.mask);
+        let expected = seal();
+
+        // SAFETY: No other test is running, and clearing a feature never
+        // makes *ring* use an instruction that the CPU doesn't support.
+        unsafe { featureflags::set_for_testing(cpu, original & !NEON.mask) };
+        assert!(!NEON.available(cpu));
+        let fallback = seal();
+
+        // SAFETY: No other test is running, and these are the flags that
+        // were detected.
+        unsafe { featureflags::set_for_testing(cpu, original) };
+        assert_eq!(fallback, expected);
+    }
+
+    // This writes the feature flags, so it must not run concurrently with
+    // any other test. CI runs it by itself with `--ignored --test-threads=1`.
+    #[test]
+    #[ignore = "writes the global feature flags"]
+    fn test_reinitialize_after_migration() {
+        // No code uses this bit, so use it to simulate a feature appearing.
+        const SIMULATED: Feature = Feature { mask: 1 << 30 };
+        assert!(ALL_FEATURES.iter().all(|f| f.mask & SIMULATED.mask == 0));
+
+        let cpu = cpu::features();
+        let original = featureflags::get(cpu);
+        assert!(!SIMULATED.available(cpu));
+
+        // SAFETY: No other test is running, and the simulated feature isn't
+        // used by any code.
+        unsafe { featureflags::set_for_testing(cpu, original | SIMULATED.mask) };
+        assert!(SIMULATED.available(cpu));
+
+        // SAFETY: No other test is running, and no objects created by *ring*
+        // are alive.
+        unsafe { cpu::reinitialize_after_migration() };
+        let cpu = cpu::features();
+        assert!(!SIMULATED.available(cpu));
+        assert_eq!(featureflags::get(cpu), original);
+    }
 }
@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use super::{AES, ARMCAP_STATIC, NEON, PMULL, SHA256, SHA512};
+use super::{AES, ARMCAP_STATIC, NEON, PMULL, RNG, SHA256, SHA512, SVE, SVE2};
 use crate::polyfill::cstr;
 
 // ```
@@ -86,6 +86,25 @@ pub fn detect_features() -> u32 {
         features |= SHA512.mask;
     }
 
+    // No Apple CPU implements SVE yet, so these are expected to fail until
+    // one does.
+    const SVE_NAME: cstr::Ref =
+        cstr::unwrap_const_from_bytes_with_nul(b"hw.optional.arm.FEAT_SVE\0");
+    if detect_feature(SVE_NAME) {
+        features |= SVE.mask;
+    }
+    const SVE2_NAME: cstr::Ref =
+        cstr::unwrap_const_from_bytes_with_nul(b"hw.optional.arm.FEAT_SVE2\0");
+    if detect_feature(SVE2_NAME) {
+        features |= SVE2.mask;
+    }
+
+    const RNG_NAME: cstr::Ref =
+        cstr::unwrap_const_from_bytes_with_nul(b"hw.optional.arm.FEAT_RNG\0");
+    if detect_feature(RNG_NAME) {
+        features |= RNG.mask;
+    }
+
     features
 }
 
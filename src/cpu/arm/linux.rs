@@ -14,6 +14,9 @@
 
 use super::NEON;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 // Work around a bug in LLVM/rustc where `-C target_cpu=cortex-a72`--
 // and `-C target_cpu=native` on Cortex-A72 Raspberry PI devices in
 // particular--enables crypto features even though not all Cortex-A72
@@ -42,29 +45,60 @@ pub fn detect_features() -> u32 {
     0
 }
 
+// `getauxval` returns zero when the kernel didn't provide the requested
+// entry, e.g. in some sandboxes, in which case no features are detected.
 #[cfg(all(not(target_env = "uclibc"), target_arch = "aarch64"))]
 pub fn detect_features() -> u32 {
-    use super::{AES, ARMCAP_STATIC, PMULL, SHA256, SHA512};
-    use libc::{getauxval, AT_HWCAP, HWCAP_AES, HWCAP_PMULL, HWCAP_SHA2, HWCAP_SHA512};
+    use libc::{c_ulong, getauxval, AT_HWCAP};
 
-    let mut features = 0;
+    // The `libc` crate doesn't provide this for all aarch64 Linux targets.
+    const AT_HWCAP2: c_ulong = 26;
+
+    let caps = unsafe { getauxval(AT_HWCAP) };
+    let caps2 = unsafe { getauxval(AT_HWCAP2) };
+    features_from_hwcaps(caps, caps2)
+}
+
+// Some Android kernels report features inconsistently, e.g. `HWCAP_SHA512`
+// without `HWCAP_SHA2`. A feature is only enabled when the features it
+// extends are also reported, so that an unreliable bit can't enable code that
+// would raise `SIGILL`.
+#[cfg(all(not(target_env = "uclibc"), target_arch = "aarch64"))]
+fn features_from_hwcaps(caps: libc::c_ulong, caps2: libc::c_ulong) -> u32 {
+    use super::{AES, ARMCAP_STATIC, PMULL, RNG, SHA256, SHA512, SVE, SVE2};
+    use libc::{c_ulong, HWCAP_AES, HWCAP_PMULL, HWCAP_SHA2, HWCAP_SHA512, HWCAP_SVE};
+
+    // The `libc` crate doesn't provide these for all aarch64 Linux targets.
+    const HWCAP2_SVE2: c_ulong = 1 << 1;
+    const HWCAP2_RNG: c_ulong = 1 << 16;
 
     // We do not need to check for the presence of NEON, as Armv8-A always has it
     const _ASSERT_NEON_DETECTED: () = assert!((ARMCAP_STATIC & NEON.mask) == NEON.mask);
 
-    let caps = unsafe { getauxval(AT_HWCAP) };
+    let has = |caps: c_ulong, cap: c_ulong| caps & cap == cap;
+
+    let mut features = 0;
 
-    if caps & HWCAP_AES == HWCAP_AES {
+    if has(caps, HWCAP_AES) {
         features |= AES.mask;
+        if has(caps, HWCAP_PMULL) {
+            features |= PMULL.mask;
+        }
     }
-    if caps & HWCAP_PMULL == HWCAP_PMULL {
-        features |= PMULL.mask;
-    }
-    if caps & HWCAP_SHA2 == HWCAP_SHA2 {
+    if has(caps, HWCAP_SHA2) {
         features |= SHA256.mask;
+        if has(caps, HWCAP_SHA512) {
+            features |= SHA512.mask;
+        }
+    }
+    if has(caps, HWCAP_SVE) {
+        features |= SVE.mask;
+        if has(caps2, HWCAP2_SVE2) {
+            features |= SVE2.mask;
+        }
     }
-    if caps & HWCAP_SHA512 == HWCAP_SHA512 {
-        features |= SHA512.mask;
+    if has(caps2, HWCAP2_RNG) {
+        features |= RNG.mask;
     }
 
     features
@@ -83,19 +117,124 @@ pub fn detect_features() -> u32 {
         pub fn getauxval(type_: c_ulong) -> c_ulong;
     }
     const AT_HWCAP: c_ulong = 16;
-    const HWCAP_NEON: c_ulong = 1 << 12;
 
-    let mut features = 0;
+    // Unlike on aarch64, NEON is optional; many ARMv7 cores lack it. When it
+    // isn't enabled statically, it is only used if the kernel reports it.
+    if ARMCAP_STATIC & NEON.mask == NEON.mask {
+        return 0;
+    }
+    let caps = unsafe { getauxval(AT_HWCAP) };
+    features_from_hwcaps(caps)
+}
 
-    if ARMCAP_STATIC & NEON.mask != NEON.mask {
-        let caps = unsafe { getauxval(AT_HWCAP) };
+// OpenSSL and BoringSSL don't enable any other features if NEON isn't
+// available. We don't enable any hardware implementations for 32-bit ARM.
+#[cfg(all(not(target_env = "uclibc"), target_arch = "arm"))]
+fn features_from_hwcaps(caps: libc::c_ulong) -> u32 {
+    if caps & HWCAP_NEON == HWCAP_NEON {
+        NEON.mask
+    } else {
+        0
+    }
+}
 
-        // OpenSSL and BoringSSL don't enable any other features if NEON isn't
-        // available. We don't enable any hardware implementations for 32-bit ARM.
-        if caps & HWCAP_NEON == HWCAP_NEON {
-            features |= NEON.mask;
+#[cfg(all(not(target_env = "uclibc"), target_arch = "arm"))]
+const HWCAP_NEON: libc::c_ulong = 1 << 12;
+
+pub fn implementer_and_part() -> Option<(u8, u16)> {
+    #[cfg(all(not(target_env = "uclibc"), target_arch = "aarch64"))]
+    {
+        if let Some(midr) = midr_el1() {
+            // Implementer is bits [31:24]; PartNum is bits [15:4].
+            let [b0, b1, _, implementer, ..] = midr.to_le_bytes();
+            return Some((implementer, u16::from_le_bytes([b0, b1]) >> 4));
         }
     }
 
-    features
+    implementer_and_part_from_cpuinfo()
+}
+
+// Linux emulates reads of `MIDR_EL1` from userspace when it sets
+// `HWCAP_CPUID`; otherwise the read would raise `SIGILL`.
+#[cfg(all(not(target_env = "uclibc"), target_arch = "aarch64"))]
+fn midr_el1() -> Option<u64> {
+    use libc::{c_ulong, getauxval, AT_HWCAP};
+
+    // The `libc` crate doesn't provide this for all aarch64 Linux targets.
+    const HWCAP_CPUID: c_ulong = 1 << 11;
+
+    let caps = unsafe { getauxval(AT_HWCAP) };
+    if caps & HWCAP_CPUID != HWCAP_CPUID {
+        return None;
+    }
+
+    let midr: u64;
+    // SAFETY: The kernel handles the read, as `HWCAP_CPUID` indicates.
+    unsafe {
+        core::arch::asm!(
+            "mrs {}, midr_el1",
+            out(reg) midr,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+    Some(midr)
+}
+
+#[cfg(feature = "std")]
+fn implementer_and_part_from_cpuinfo() -> Option<(u8, u16)> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    crate::cpu::linux_cpuinfo::implementer_and_part(&cpuinfo)
+}
+
+#[cfg(not(feature = "std"))]
+fn implementer_and_part_from_cpuinfo() -> Option<(u8, u16)> {
+    None
+}
+
+#[cfg(all(test, not(target_env = "uclibc"), target_arch = "aarch64"))]
+mod tests {
+    #[test]
+    fn test_features_from_hwcaps() {
+        use super::{
+            super::{AES, PMULL, RNG, SHA256, SHA512, SVE, SVE2},
+            features_from_hwcaps,
+        };
+        use libc::{HWCAP_AES, HWCAP_PMULL, HWCAP_SHA2, HWCAP_SHA512, HWCAP_SVE};
+        const HWCAP2_SVE2: libc::c_ulong = 1 << 1;
+        const HWCAP2_RNG: libc::c_ulong = 1 << 16;
+
+        // A failed `getauxval` returns zero.
+        assert_eq!(features_from_hwcaps(0, 0), 0);
+
+        assert_eq!(
+            features_from_hwcaps(
+                HWCAP_AES | HWCAP_PMULL | HWCAP_SHA2 | HWCAP_SHA512 | HWCAP_SVE,
+                HWCAP2_SVE2 | HWCAP2_RNG
+            ),
+            AES.mask | PMULL.mask | SHA256.mask | SHA512.mask | SVE.mask | SVE2.mask | RNG.mask
+        );
+        assert_eq!(features_from_hwcaps(0, HWCAP2_RNG), RNG.mask);
+
+        // Inconsistent results are interpreted conservatively.
+        assert_eq!(features_from_hwcaps(HWCAP_SHA512, 0), 0);
+        assert_eq!(features_from_hwcaps(HWCAP_PMULL, 0), 0);
+        assert_eq!(features_from_hwcaps(0, HWCAP2_SVE2), 0);
+        assert_eq!(features_from_hwcaps(HWCAP_AES | HWCAP_SHA512, 0), AES.mask);
+    }
+}
+
+#[cfg(all(test, not(target_env = "uclibc"), target_arch = "arm"))]
+mod tests {
+    #[test]
+    fn test_features_from_hwcaps() {
+        use super::{super::NEON, features_from_hwcaps, HWCAP_NEON};
+
+        // A failed `getauxval` returns zero.
+        assert_eq!(features_from_hwcaps(0), 0);
+
+        assert_eq!(features_from_hwcaps(HWCAP_NEON), NEON.mask);
+
+        // Other capabilities, e.g. `HWCAP_VFPv4`, don't imply NEON.
+        assert_eq!(features_from_hwcaps(!HWCAP_NEON), 0);
+    }
 }
@@ -42,24 +42,30 @@ pub(super) mod featureflags {
     use core::ptr;
 
     pub(in super::super) fn get_or_init() -> cpu::Features {
-        // SAFETY: `OPENSSL_cpuid_setup` must be called only in
-        // `INIT.call_once()` below.
-        prefixed_extern! {
-            fn OPENSSL_cpuid_setup();
-        }
         static INIT: spin::Once<()> = spin::Once::new();
         // SAFETY: This is the only caller. Any concurrent reading doesn't
         // affect the safety of the writing.
-        let () = INIT.call_once(|| unsafe { OPENSSL_cpuid_setup() });
+        let () = INIT.call_once(|| {
+            #[cfg(test)]
+            cpu::detection_count::increment();
+            unsafe { OPENSSL_cpuid_setup() }
+        });
         // SAFETY: We initialized the CPU features as required.
         // `INIT.call_once` has `happens-before` semantics.
         unsafe { cpu::Features::new_after_feature_flags_written_and_synced_unchecked() }
     }
 
+    // SAFETY: The caller must ensure that nothing reads `OPENSSL_ia32cap_P`
+    // concurrently.
+    pub(in super::super) unsafe fn reinitialize() {
+        // Ensure `INIT.call_once()` has completed so that it will never write
+        // `OPENSSL_ia32cap_P` after us.
+        let _: cpu::Features = get_or_init();
+        // SAFETY: Delegated to the caller.
+        unsafe { OPENSSL_cpuid_setup() }
+    }
+
     pub(super) fn get(_cpu_features: cpu::Features) -> &'static [u32; 4] {
-        prefixed_extern! {
-            static mut OPENSSL_ia32cap_P: [u32; 4];
-        }
         // SAFETY: https://github.com/rust-lang/rust/issues/125833
         let p = unsafe { ptr::addr_of!(OPENSSL_ia32cap_P) };
         // SAFETY: Since only `get_or_init()` could have created
@@ -68,6 +74,24 @@ pub(super) mod featureflags {
         // `OPENSSL_ia32cap_P` without further synchronization.
         unsafe { &*p }
     }
+
+    // SAFETY: The caller must ensure that nothing reads `OPENSSL_ia32cap_P`
+    // concurrently.
+    #[cfg(test)]
+    pub(super) unsafe fn set_for_testing(_cpu_features: cpu::Features, word: usize, value: u32) {
+        // SAFETY: Delegated to the caller.
+        unsafe { (*ptr::addr_of_mut!(OPENSSL_ia32cap_P))[word] = value }
+    }
+
+    // SAFETY: `OPENSSL_cpuid_setup` must only be called in
+    // `INIT.call_once()` above or in `reinitialize()`.
+    prefixed_extern! {
+        fn OPENSSL_cpuid_setup();
+    }
+
+    prefixed_extern! {
+        static mut OPENSSL_ia32cap_P: [u32; 4];
+    }
 }
 
 impl Feature {
@@ -135,6 +159,49 @@ pub(crate) const AVX: Feature = Feature {
     mask: 1 << 28,
 };
 
+// `OPENSSL_cpuid_setup` clears this when the OS doesn't save the YMM
+// registers.
+#[cfg(target_arch = "x86_64")]
+pub(crate) const AVX2: Feature = Feature {
+    word: 2,
+    mask: 1 << 5,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu;
+
+    // This writes the feature flags, so it must not run concurrently with
+    // any other test. CI runs it by itself with `--ignored --test-threads=1`.
+    #[test]
+    #[ignore = "writes the global feature flags"]
+    fn test_reinitialize_after_migration() {
+        // `OPENSSL_cpuid_setup` always clears reserved bit #20 of word 0, so
+        // no code uses it; use it to simulate a feature appearing.
+        const SIMULATED: Feature = Feature {
+            word: 0,
+            mask: 1 << 20,
+        };
+
+        let cpu = cpu::features();
+        let original = *featureflags::get(cpu);
+        assert!(!SIMULATED.available(cpu));
+
+        // SAFETY: No other test is running, and the simulated feature isn't
+        // used by any code.
+        unsafe { featureflags::set_for_testing(cpu, 0, original[0] | SIMULATED.mask) };
+        assert!(SIMULATED.available(cpu));
+
+        // SAFETY: No other test is running, and no objects created by *ring*
+        // are alive.
+        unsafe { cpu::reinitialize_after_migration() };
+        let cpu = cpu::features();
+        assert!(!SIMULATED.available(cpu));
+        assert_eq!(*featureflags::get(cpu), original);
+    }
+}
+
 #[cfg(all(target_arch = "x86_64", test))]
 mod x86_64_tests {
     use super::*;
@@ -0,0 +1,129 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Parsing of the ARM fields of Linux's `/proc/cpuinfo`.
+//!
+//! This is compiled for all Linux targets when testing so that it is tested
+//! everywhere, not just on ARM.
+
+// Returns the first "CPU implementer" and "CPU part" values in `cpuinfo`,
+// i.e. the ones for the first processor listed.
+pub(super) fn implementer_and_part(cpuinfo: &str) -> Option<(u8, u16)> {
+    let mut implementer = None;
+    let mut part = None;
+    for line in cpuinfo.lines() {
+        let (key, value) = match line.split_once(':') {
+            Some(key_value) => key_value,
+            None => continue,
+        };
+        let value = value.trim().strip_prefix("0x");
+        match key.trim() {
+            "CPU implementer" if implementer.is_none() => {
+                implementer = Some(u8::from_str_radix(value?, 16).ok()?);
+            }
+            "CPU part" if part.is_none() => {
+                part = Some(u16::from_str_radix(value?, 16).ok()?);
+            }
+            _ => {}
+        }
+        if let (Some(implementer), Some(part)) = (implementer, part) {
+            return Some((implementer, part));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::implementer_and_part;
+
+    #[test]
+    fn test_implementer_and_part_aarch64() {
+        // Raspberry Pi 4 (Cortex-A72), abbreviated to two of its four cores.
+        const CPUINFO: &str = "\
+processor\t: 0
+BogoMIPS\t: 108.00
+Features\t: fp asimd evtstrm crc32 cpuid
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x0
+CPU part\t: 0xd08
+CPU revision\t: 3
+
+processor\t: 1
+BogoMIPS\t: 108.00
+Features\t: fp asimd evtstrm crc32 cpuid
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x0
+CPU part\t: 0xd08
+CPU revision\t: 3
+";
+        assert_eq!(implementer_and_part(CPUINFO), Some((0x41, 0xd08)));
+    }
+
+    #[test]
+    fn test_implementer_and_part_heterogeneous() {
+        // The first processor listed is reported.
+        const CPUINFO: &str = "\
+processor\t: 0
+CPU implementer\t: 0x41
+CPU part\t: 0xd05
+
+processor\t: 4
+CPU implementer\t: 0x41
+CPU part\t: 0xd0b
+";
+        assert_eq!(implementer_and_part(CPUINFO), Some((0x41, 0xd05)));
+    }
+
+    #[test]
+    fn test_implementer_and_part_arm() {
+        // 32-bit ARM kernels put the per-processor fields after a summary.
+        const CPUINFO: &str = "\
+processor\t: 0
+model name\t: ARMv7 Processor rev 4 (v7l)
+BogoMIPS\t: 38.40
+Features\t: half thumb fastmult vfp edsp neon vfpv3 tls vfpv4 idiva idivt vfpd32 lpae evtstrm crc32
+CPU implementer\t: 0x41
+CPU architecture: 7
+CPU variant\t: 0x0
+CPU part\t: 0xd03
+CPU revision\t: 4
+
+Hardware\t: BCM2835
+Revision\t: a02082
+";
+        assert_eq!(implementer_and_part(CPUINFO), Some((0x41, 0xd03)));
+    }
+
+    #[test]
+    fn test_implementer_and_part_missing_or_invalid() {
+        assert_eq!(implementer_and_part(""), None);
+        assert_eq!(
+            implementer_and_part("processor\t: 0\nvendor_id\t: GenuineIntel\n"),
+            None
+        );
+        assert_eq!(implementer_and_part("CPU implementer\t: 0x41\n"), None);
+        assert_eq!(implementer_and_part("CPU part\t: 0xd08\n"), None);
+        assert_eq!(
+            implementer_and_part("CPU implementer\t: 0x141\nCPU part\t: 0xd08\n"),
+            None
+        );
+        assert_eq!(
+            implementer_and_part("CPU implementer\t: 41\nCPU part\t: 0xd08\n"),
+            None
+        );
+    }
+}
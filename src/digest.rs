@@ -16,7 +16,8 @@
 //!
 //! If all the data is available in a single contiguous slice then the `digest`
 //! function should be used. Otherwise, the digest can be calculated in
-//! multiple steps using `Context`.
+//! multiple steps using `Context`. With the `std` feature, `DigestingReader`
+//! and `DigestingWriter` digest data as it is streamed through them.
 
 // Note on why are we doing things the hard way: It would be easy to implement
 // this using the C `EVP_MD`/`EVP_MD_CTX` interface. However, if we were to do
@@ -30,15 +31,24 @@ use self::{
 };
 use crate::{
     bits::{BitLength, FromByteLen as _},
-    cpu, debug,
+    constant_time, cpu, debug, error, hmac,
     polyfill::{self, slice, sliceutil},
 };
 use core::num::Wrapping;
 
 mod dynstate;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "std")]
+mod mmap;
+#[cfg(feature = "std")]
+pub mod parallel;
 mod sha1;
 mod sha2;
 
+#[cfg(feature = "std")]
+pub use self::io::{DigestingReader, DigestingWriter};
+
 #[derive(Clone)]
 pub(crate) struct BlockContext {
     state: DynState,
@@ -258,6 +268,98 @@ pub fn digest(algorithm: &'static Algorithm, data: &[u8]) -> Digest {
     ctx.finish()
 }
 
+/// Returns the SHA-256 digest of `data` as a fixed-size array.
+///
+/// This is equivalent to `digest(&SHA256, data)`, for when a `[u8; 32]` is
+/// more convenient than a `Digest`.
+///
+/// ```
+/// use ring::digest;
+///
+/// let fixed = digest::sha256(b"hello, world");
+/// assert_eq!(&fixed[..], digest::digest(&digest::SHA256, b"hello, world").as_ref());
+/// ```
+pub fn sha256(data: &[u8]) -> [u8; SHA256_OUTPUT_LEN] {
+    digest_fixed(&SHA256, data)
+}
+
+/// Returns the SHA-384 digest of `data` as a fixed-size array.
+///
+/// This is equivalent to `digest(&SHA384, data)`.
+pub fn sha384(data: &[u8]) -> [u8; SHA384_OUTPUT_LEN] {
+    digest_fixed(&SHA384, data)
+}
+
+/// Returns the SHA-512 digest of `data` as a fixed-size array.
+///
+/// This is equivalent to `digest(&SHA512, data)`.
+pub fn sha512(data: &[u8]) -> [u8; SHA512_OUTPUT_LEN] {
+    digest_fixed(&SHA512, data)
+}
+
+/// Writes the digest of `data` using the given digest algorithm to the start
+/// of `out`, returning the number of bytes written, which is
+/// `algorithm.output_len()`.
+///
+/// Fails if `out` is shorter than `algorithm.output_len()`. Any bytes of `out`
+/// after the digest are left unmodified.
+///
+/// ```
+/// use ring::digest;
+///
+/// let mut out = [0u8; digest::MAX_OUTPUT_LEN];
+/// let len = digest::digest_into(&digest::SHA256, b"hello, world", &mut out)?;
+/// assert_eq!(&out[..len], digest::sha256(b"hello, world"));
+/// # Ok::<(), ring::error::Unspecified>(())
+/// ```
+pub fn digest_into(
+    algorithm: &'static Algorithm,
+    data: &[u8],
+    out: &mut [u8],
+) -> Result<usize, error::Unspecified> {
+    let out = out
+        .get_mut(..algorithm.output_len())
+        .ok_or(error::Unspecified)?;
+    out.copy_from_slice(digest(algorithm, data).as_ref());
+    Ok(out.len())
+}
+
+fn digest_fixed<const N: usize>(algorithm: &'static Algorithm, data: &[u8]) -> [u8; N] {
+    let mut out = [0u8; N];
+    out.copy_from_slice(digest(algorithm, data).as_ref());
+    out
+}
+
+/// Returns the HMAC of `msg` under `key`.
+///
+/// Use this instead of `digest(algorithm, key || msg)`. For a hash function
+/// that is vulnerable to length extension (see [`is_length_extendable`]),
+/// anybody who knows `SHA256(key || msg)` can compute `SHA256(key || msg ||
+/// padding || suffix)` for any `suffix` without knowing `key`, so the
+/// "keyed prefix" construction isn't a secure MAC. HMAC doesn't have this
+/// problem.
+///
+/// This is equivalent to `hmac::sign(&hmac::Key::new(algorithm, key), msg)`;
+/// use `hmac::Key` directly to avoid recomputing the key for each message, and
+/// use `hmac::verify` to verify the result.
+pub fn keyed_prefix_mac(algorithm: hmac::Algorithm, key: &[u8], msg: &[u8]) -> hmac::Tag {
+    hmac::sign(&hmac::Key::new(algorithm, key), msg)
+}
+
+/// Returns `true` if an attacker who knows `digest(algorithm, m)` and the
+/// length of `m`, but not `m` itself, can compute the digest of `m` followed
+/// by the padding and any suffix.
+///
+/// This is true for the Merkle-Damgård hash functions whose output is the full
+/// chaining value: SHA-1, SHA-256, and SHA-512. It is false for SHA-384 and
+/// SHA-512/256, since their output is a truncated chaining value.
+pub fn is_length_extendable(algorithm: &'static Algorithm) -> bool {
+    match algorithm.id {
+        AlgorithmID::SHA1 | AlgorithmID::SHA256 | AlgorithmID::SHA512 => true,
+        AlgorithmID::SHA384 | AlgorithmID::SHA512_256 => false,
+    }
+}
+
 /// A calculated digest value.
 ///
 /// Use [`Self::as_ref`] to get the value as a `&[u8]`.
@@ -273,6 +375,17 @@ impl Digest {
     pub fn algorithm(&self) -> &'static Algorithm {
         self.algorithm
     }
+
+    /// Returns `Ok(())` if the digest value is equal to `expected`, and
+    /// `Err(error::Unspecified)` otherwise, including when `expected` has the
+    /// wrong length.
+    ///
+    /// The comparison is done in constant time with respect to the contents
+    /// of the values, so this should be used instead of comparing
+    /// `self.as_ref()` with `==`, e.g. when the digest is used like a MAC.
+    pub fn verify_eq(&self, expected: &[u8]) -> Result<(), error::Unspecified> {
+        constant_time::verify_slices_are_equal(self.as_ref(), expected)
+    }
 }
 
 impl AsRef<[u8]> for Digest {
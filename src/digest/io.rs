@@ -0,0 +1,175 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+extern crate std;
+
+use super::{Algorithm, Context, Digest};
+use std::io;
+
+/// A reader that digests all the data read through it.
+///
+/// This allows data to be hashed while it is being streamed elsewhere,
+/// without a second pass over it.
+///
+/// ```
+/// use ring::digest;
+/// use std::io::Read;
+///
+/// let data = b"hello, world";
+/// let mut reader = digest::DigestingReader::new(&digest::SHA256, &data[..]);
+/// let mut copy = Vec::new();
+/// reader.read_to_end(&mut copy).unwrap();
+///
+/// assert_eq!(&copy, data);
+/// assert_eq!(
+///     reader.into_digest().as_ref(),
+///     digest::digest(&digest::SHA256, data).as_ref()
+/// );
+/// ```
+pub struct DigestingReader<R> {
+    inner: R,
+    ctx: Context,
+}
+
+impl<R: io::Read> DigestingReader<R> {
+    /// Wraps `inner`, digesting everything read from it using `algorithm`.
+    pub fn new(algorithm: &'static Algorithm, inner: R) -> Self {
+        Self {
+            inner,
+            ctx: Context::new(algorithm),
+        }
+    }
+}
+
+impl<R> DigestingReader<R> {
+    /// The wrapped reader.
+    ///
+    /// Data read directly from the wrapped reader isn't digested.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// The algorithm that the reader is using.
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.ctx.algorithm()
+    }
+
+    /// Finishes the digest of all the data read so far, usually after the
+    /// wrapped reader has reached EOF.
+    pub fn into_digest(self) -> Digest {
+        self.ctx.finish()
+    }
+
+    /// Returns the wrapped reader and the digest of all the data read so far.
+    pub fn into_parts(self) -> (R, Digest) {
+        (self.inner, self.ctx.finish())
+    }
+}
+
+impl<R: io::Read> io::Read for DigestingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.ctx.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R> core::fmt::Debug for DigestingReader<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("DigestingReader")
+            .field("algorithm", self.algorithm())
+            .finish()
+    }
+}
+
+/// A writer that digests all the data written through it.
+///
+/// ```
+/// use ring::digest;
+/// use std::io::Write;
+///
+/// let data = b"hello, world";
+/// let mut writer = digest::DigestingWriter::new(&digest::SHA256, Vec::new());
+/// writer.write_all(data).unwrap();
+///
+/// let (copy, digest) = writer.into_parts();
+/// assert_eq!(&copy, data);
+/// assert_eq!(
+///     digest.as_ref(),
+///     digest::digest(&digest::SHA256, data).as_ref()
+/// );
+/// ```
+pub struct DigestingWriter<W> {
+    inner: W,
+    ctx: Context,
+}
+
+impl<W: io::Write> DigestingWriter<W> {
+    /// Wraps `inner`, digesting everything written to it using `algorithm`.
+    pub fn new(algorithm: &'static Algorithm, inner: W) -> Self {
+        Self {
+            inner,
+            ctx: Context::new(algorithm),
+        }
+    }
+}
+
+impl<W> DigestingWriter<W> {
+    /// The wrapped writer.
+    ///
+    /// Data written directly to the wrapped writer isn't digested.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// The algorithm that the writer is using.
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.ctx.algorithm()
+    }
+
+    /// Finishes the digest of all the data written so far.
+    ///
+    /// The wrapped writer is dropped without being flushed.
+    pub fn into_digest(self) -> Digest {
+        self.ctx.finish()
+    }
+
+    /// Returns the wrapped writer and the digest of all the data written so
+    /// far.
+    pub fn into_parts(self) -> (W, Digest) {
+        (self.inner, self.ctx.finish())
+    }
+}
+
+impl<W: io::Write> io::Write for DigestingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Only digest what the wrapped writer accepted; the caller will pass
+        // the rest again.
+        let n = self.inner.write(buf)?;
+        self.ctx.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W> core::fmt::Debug for DigestingWriter<W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("DigestingWriter")
+            .field("algorithm", self.algorithm())
+            .finish()
+    }
+}
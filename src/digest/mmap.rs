@@ -0,0 +1,126 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::Context;
+use crate::error;
+
+impl Context {
+    /// Updates the digest with all the data in `region`, which is usually a
+    /// memory-mapped file, in chunks of about `chunk_size` bytes.
+    ///
+    /// `chunk_size` is rounded up to a multiple of the page size, and the
+    /// chunk boundaries are page-aligned. Where the operating system supports
+    /// it, the kernel is advised to start reading each chunk in before the
+    /// previous one is digested. The advice is only a hint; the result is
+    /// always the same as `update(region)`.
+    ///
+    /// Fails if `chunk_size` is zero.
+    ///
+    /// ```
+    /// use ring::digest;
+    ///
+    /// let data = vec![0u8; 1 << 20];
+    /// let mut ctx = digest::Context::new(&digest::SHA256);
+    /// ctx.update_mmap(&data, 64 * 1024).unwrap();
+    /// assert_eq!(
+    ///     ctx.finish().as_ref(),
+    ///     digest::digest(&digest::SHA256, &data).as_ref()
+    /// );
+    /// ```
+    pub fn update_mmap(
+        &mut self,
+        region: &[u8],
+        chunk_size: usize,
+    ) -> Result<(), error::Unspecified> {
+        if chunk_size == 0 {
+            return Err(error::Unspecified);
+        }
+        let page_len = page_len();
+        let chunk_size = chunk_size
+            .checked_add(page_len - 1)
+            .ok_or(error::Unspecified)?
+            & !(page_len - 1);
+
+        // Make the first chunk end on a chunk boundary so that every later
+        // chunk starts on one.
+        let misalignment = (region.as_ptr() as usize) % chunk_size;
+        let first_len = core::cmp::min(region.len(), chunk_size - misalignment);
+        let (first, rest) = region.split_at(first_len);
+
+        let mut chunks = core::iter::once(first).chain(rest.chunks(chunk_size));
+        let mut next = chunks.next();
+        if let Some(chunk) = next {
+            will_need(chunk, page_len);
+        }
+        while let Some(chunk) = next {
+            next = chunks.next();
+            if let Some(next) = next {
+                will_need(next, page_len);
+            }
+            self.update(chunk);
+        }
+        Ok(())
+    }
+}
+
+// The size of a page, which is always a power of two.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn page_len() -> usize {
+    #[cfg(target_os = "android")]
+    const SC_PAGESIZE: i32 = 0x27;
+    #[cfg(target_os = "linux")]
+    const SC_PAGESIZE: i32 = 30;
+    // `long` is the size of a pointer on Android and Linux.
+    extern "C" {
+        fn sysconf(name: i32) -> isize;
+    }
+    // The page size varies, e.g. 4 KiB, 16 KiB, or 64 KiB on AArch64 Linux,
+    // so it must be queried; `madvise` fails for an unaligned address.
+    // SAFETY: `sysconf` has no preconditions.
+    let page_len = unsafe { sysconf(SC_PAGESIZE) };
+    match usize::try_from(page_len) {
+        Ok(page_len) if page_len.is_power_of_two() => page_len,
+        _ => FALLBACK_PAGE_LEN,
+    }
+}
+
+// Without `madvise`, the page size only affects the chunk boundaries.
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+fn page_len() -> usize {
+    FALLBACK_PAGE_LEN
+}
+
+const FALLBACK_PAGE_LEN: usize = 4096;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn will_need(chunk: &[u8], page_len: usize) {
+    // `madvise` requires a page-aligned address. Only the first chunk may be
+    // unaligned, and advising on the start of its page is harmless.
+    const MADV_WILLNEED: i32 = 3;
+    extern "C" {
+        fn madvise(addr: *mut core::ffi::c_void, len: usize, advice: i32) -> i32;
+    }
+    if chunk.is_empty() {
+        return;
+    }
+    let addr = chunk.as_ptr() as usize;
+    let page_start = addr & !(page_len - 1);
+    let len = chunk.len() + (addr - page_start);
+    // The advice doesn't modify the memory, and failure (e.g. because the
+    // region isn't mapped from a file) is ignored, since it is only a hint.
+    let _ = unsafe { madvise(page_start as *mut core::ffi::c_void, len, MADV_WILLNEED) };
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+fn will_need(_chunk: &[u8], _page_len: usize) {}
@@ -0,0 +1,150 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Tree hashing of large inputs using multiple threads.
+//!
+//! **The tree hash of an input is not the digest of the input.** It is a
+//! distinct construction built on top of a digest algorithm, and it is only
+//! useful when every party computes it the same way, with the same leaf
+//! size.
+//!
+//! The input is split into leaves of `leaf_size` bytes; the last leaf may be
+//! shorter, and empty input is a single empty leaf. With `H` being the
+//! digest algorithm and `u64be(x)` the 8-byte big-endian encoding of `x`:
+//!
+//! ```text
+//! leaf_i = H(0x00 || data_i)
+//! root   = H(0x01 || u64be(leaf_size) || u64be(len(data)) || leaf_0 || ... || leaf_k)
+//! ```
+//!
+//! The result is `root`. The prefix bytes separate leaves from the root, and
+//! the root commits to the leaf size and the input length, so the tree hash
+//! of an input never equals its digest or a tree hash with a different leaf
+//! size, except by a collision in `H`. The leaves are digested in parallel;
+//! the result doesn't depend on the number of threads.
+
+extern crate std;
+
+use super::{Algorithm, Context, Digest};
+use crate::{
+    error,
+    polyfill::{u64_from_usize, unwrap_const},
+};
+use core::num::NonZeroUsize;
+use std::{thread, vec::Vec};
+
+const LEAF_PREFIX: u8 = 0x00;
+const ROOT_PREFIX: u8 = 0x01;
+
+const ONE_THREAD: NonZeroUsize = unwrap_const(NonZeroUsize::new(1));
+
+/// Returns the tree hash of `data` with leaves of `leaf_size` bytes, using
+/// as many threads as the system reports are available.
+///
+/// Fails if `leaf_size` is zero, or if the total length of the leaf digests
+/// would overflow `usize`, which can only happen on 32-bit targets with a
+/// very small `leaf_size`.
+///
+/// ```
+/// use ring::digest::{self, parallel};
+///
+/// let data = vec![0u8; 1 << 20];
+/// let tree_hash = parallel::hash(&digest::SHA256, &data, 64 * 1024).unwrap();
+/// assert_ne!(
+///     tree_hash.as_ref(),
+///     digest::digest(&digest::SHA256, &data).as_ref()
+/// );
+/// ```
+pub fn hash(
+    algorithm: &'static Algorithm,
+    data: &[u8],
+    leaf_size: usize,
+) -> Result<Digest, error::Unspecified> {
+    let threads = thread::available_parallelism().unwrap_or(ONE_THREAD);
+    hash_with_threads(algorithm, data, leaf_size, threads)
+}
+
+/// Like `hash()`, but uses at most `threads` threads, including the calling
+/// thread.
+///
+/// The result is the same for any number of threads.
+pub fn hash_with_threads(
+    algorithm: &'static Algorithm,
+    data: &[u8],
+    leaf_size: usize,
+    threads: NonZeroUsize,
+) -> Result<Digest, error::Unspecified> {
+    if leaf_size == 0 {
+        return Err(error::Unspecified);
+    }
+
+    let output_len = algorithm.output_len();
+    let num_leaves = core::cmp::max(1, div_ceil(data.len(), leaf_size));
+    let leaf_digests_len = num_leaves
+        .checked_mul(output_len)
+        .ok_or(error::Unspecified)?;
+    let mut leaf_digests = std::vec![0u8; leaf_digests_len];
+
+    if data.is_empty() {
+        digest_leaf(algorithm, &[], &mut leaf_digests);
+    } else {
+        // Each thread digests a contiguous run of leaves. The calling thread
+        // takes the first run.
+        let leaves_per_thread = div_ceil(num_leaves, threads.get());
+        let run_digests_len = leaves_per_thread
+            .checked_mul(output_len)
+            .ok_or(error::Unspecified)?;
+        let mut runs = data
+            .chunks(leaves_per_thread.saturating_mul(leaf_size))
+            .zip(leaf_digests.chunks_mut(run_digests_len));
+        thread::scope(|s| {
+            let first = runs.next();
+            let others: Vec<_> = runs
+                .map(|(data, out)| s.spawn(move || digest_leaves(algorithm, data, leaf_size, out)))
+                .collect();
+            if let Some((data, out)) = first {
+                digest_leaves(algorithm, data, leaf_size, out);
+            }
+            others.into_iter().for_each(|t| {
+                if let Err(e) = t.join() {
+                    std::panic::resume_unwind(e)
+                }
+            });
+        });
+    }
+
+    let mut root = Context::new(algorithm);
+    root.update(&[ROOT_PREFIX]);
+    root.update(&u64_from_usize(leaf_size).to_be_bytes());
+    root.update(&u64_from_usize(data.len()).to_be_bytes());
+    root.update(&leaf_digests);
+    Ok(root.finish())
+}
+
+fn digest_leaves(algorithm: &'static Algorithm, data: &[u8], leaf_size: usize, out: &mut [u8]) {
+    data.chunks(leaf_size)
+        .zip(out.chunks_mut(algorithm.output_len()))
+        .for_each(|(leaf, out)| digest_leaf(algorithm, leaf, out));
+}
+
+fn digest_leaf(algorithm: &'static Algorithm, leaf: &[u8], out: &mut [u8]) {
+    let mut ctx = Context::new(algorithm);
+    ctx.update(&[LEAF_PREFIX]);
+    ctx.update(leaf);
+    out.copy_from_slice(ctx.finish().as_ref());
+}
+
+fn div_ceil(a: usize, b: usize) -> usize {
+    (a / b) + usize::from(a % b != 0)
+}
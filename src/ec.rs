@@ -14,7 +14,7 @@
 
 use crate::{cpu, error, rand};
 
-pub use self::keys::{KeyPair, PublicKey, Seed};
+pub use self::keys::{KeyPair, PublicKey, Seed, PUBLIC_KEY_MAX_LEN};
 
 pub struct Curve {
     pub public_key_len: usize,
@@ -61,5 +61,7 @@ const SEED_MAX_BYTES: usize = ELEM_MAX_BYTES;
 pub const PKCS8_DOCUMENT_MAX_LEN: usize = 40 + SCALAR_MAX_BYTES + keys::PUBLIC_KEY_MAX_LEN;
 
 pub mod curve25519;
+#[cfg(feature = "alloc")]
+pub mod jwk;
 mod keys;
 pub mod suite_b;
@@ -18,5 +18,7 @@ pub mod ed25519;
 
 pub mod x25519;
 
+pub mod xed25519;
+
 mod ops;
 mod scalar;
@@ -18,11 +18,17 @@ use super::{super::ops::*, eddsa_digest};
 use crate::{cpu, error, sealed, signature};
 
 /// Parameters for EdDSA signing and verification.
-pub struct EdDSAParameters;
+pub struct EdDSAParameters {
+    strict: bool,
+}
 
 impl core::fmt::Debug for EdDSAParameters {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
-        write!(f, "ring::signature::ED25519")
+        if self.strict {
+            write!(f, "ring::signature::ED25519_VERIFY_STRICT")
+        } else {
+            write!(f, "ring::signature::ED25519")
+        }
     }
 }
 
@@ -30,8 +36,34 @@ impl core::fmt::Debug for EdDSAParameters {
 ///
 /// Ed25519 uses SHA-512 as the digest algorithm.
 ///
+/// Verification uses the cofactorless equation `[S]B = R + [k]A` of
+/// [RFC 8032], checked by comparing the encoding of `[S]B - [k]A` with the
+/// encoding of `R`, and a signature whose `S` isn't fully reduced is
+/// rejected. Public keys and `R` values of small order are accepted, as are
+/// public keys whose encoding isn't canonical. A signature that only
+/// satisfies the cofactored equation `[8][S]B = [8]R + [8][k]A` is
+/// rejected, so some signatures that other implementations accept, e.g. ones
+/// following [ZIP 215], are rejected. Use [`ED25519_VERIFY_STRICT`] to also
+/// reject small-order and non-canonical public keys.
+///
 /// [Ed25519]: https://ed25519.cr.yp.to/
-pub static ED25519: EdDSAParameters = EdDSAParameters {};
+/// [RFC 8032]: https://tools.ietf.org/html/rfc8032#section-5.1.7
+/// [ZIP 215]: https://zips.z.cash/zip-0215
+pub static ED25519: EdDSAParameters = EdDSAParameters { strict: false };
+
+/// Strict verification of [Ed25519] signatures.
+///
+/// Like [`ED25519`], this uses the cofactorless equation and rejects a
+/// signature whose `S` isn't fully reduced, or whose `R` isn't canonically
+/// encoded. In addition, it rejects public keys that aren't canonically
+/// encoded, and public keys and `R` values of small order. With these
+/// checks, a valid signature can't be made for more than one message without
+/// the private key, and every conforming implementation that uses the
+/// cofactorless equation agrees on which signatures are valid. Public keys
+/// are validated the same way by [`parse_public_key()`](crate::signature::parse_public_key).
+///
+/// [Ed25519]: https://ed25519.cr.yp.to/
+pub static ED25519_VERIFY_STRICT: EdDSAParameters = EdDSAParameters { strict: true };
 
 impl signature::VerificationAlgorithm for EdDSAParameters {
     fn verify(
@@ -42,7 +74,10 @@ impl signature::VerificationAlgorithm for EdDSAParameters {
     ) -> Result<(), error::Unspecified> {
         let cpu_features = cpu::features();
 
-        let public_key: &[u8; ELEM_LEN] = public_key.as_slice_less_safe().try_into()?;
+        let public_key = public_key_bytes(public_key)?;
+        if self.strict {
+            check_strict(public_key)?;
+        }
         let (signature_r, signature_s) = signature.read_all(error::Unspecified, |input| {
             let signature_r: &[u8; ELEM_LEN] = input
                 .read_bytes(ELEM_LEN)?
@@ -57,6 +92,11 @@ impl signature::VerificationAlgorithm for EdDSAParameters {
 
         let signature_s = Scalar::from_bytes_checked(*signature_s)?;
 
+        // A non-canonical `R` is rejected by the comparison below.
+        if self.strict && is_small_order(signature_r) {
+            return Err(error::Unspecified);
+        }
+
         let mut a = ExtPoint::from_encoded_point_vartime(public_key)?;
         a.invert_vartime();
 
@@ -73,8 +113,105 @@ impl signature::VerificationAlgorithm for EdDSAParameters {
     }
 }
 
+/// Verifies a self-certifying Ed25519 signature of `msg`.
+///
+/// `data` is the 32-byte public key followed by the 64-byte signature, as
+/// used by some peer-to-peer protocols. On success the public key is
+/// returned so the caller can check it against a pinned key; a valid
+/// signature only proves that the signer holds the private key for the
+/// public key that was sent with it.
+pub fn ed25519_verify_selfcert<'a>(
+    msg: &[u8],
+    data: &'a [u8],
+) -> Result<signature::ParsedKey<&'a [u8]>, error::Unspecified> {
+    if data.len() != ELEM_LEN + ELEM_LEN + SCALAR_LEN {
+        return Err(error::Unspecified);
+    }
+    let (public_key, sig) = data.split_at(ELEM_LEN);
+    let public_key = signature::parse_public_key(&ED25519, public_key)?;
+    public_key.verify(msg, sig)?;
+    Ok(public_key)
+}
+
 impl sealed::Sealed for EdDSAParameters {}
 
+impl signature::public_key_matching::PublicKeyMatching for EdDSAParameters {}
+
+impl signature::public_key_validation::PublicKeyValidation for EdDSAParameters {
+    fn validate_public_key(&self, public_key: untrusted::Input) -> Result<(), error::Unspecified> {
+        let public_key = public_key_bytes(public_key)?;
+        let _ = ExtPoint::from_encoded_point_vartime(public_key)?;
+        if self.strict {
+            check_strict(public_key)?;
+        }
+        Ok(())
+    }
+}
+
+impl signature::public_key_weakness::PublicKeyWeakness for EdDSAParameters {
+    fn is_known_weak(&self, public_key: untrusted::Input) -> bool {
+        public_key_bytes(public_key).map_or(false, is_small_order)
+    }
+}
+
+// Returns `true` if `public_key` is a valid encoding of a point whose order
+// divides the cofactor 8, including the identity.
+pub(in super::super) fn is_small_order(public_key: &EncodedPoint) -> bool {
+    let a = match ExtPoint::from_encoded_point_vartime(public_key) {
+        Ok(a) => a,
+        Err(error::Unspecified) => return false,
+    };
+    let mut cofactor = [0; SCALAR_LEN];
+    cofactor[0] = 8;
+    let (cofactor, zero) = match (
+        Scalar::from_bytes_checked(cofactor),
+        Scalar::from_bytes_checked([0; SCALAR_LEN]),
+    ) {
+        (Ok(cofactor), Ok(zero)) => (cofactor, zero),
+        _ => unreachable!(),
+    };
+
+    // 8*A + 0*B.
+    let mut r = Point::new_at_infinity();
+    unsafe { x25519_ge_double_scalarmult_vartime(&mut r, &cofactor, &a, &zero) };
+    let mut identity = [0; ELEM_LEN];
+    identity[0] = 1;
+    r.into_encoded_point(cpu::features()) == identity
+}
+
+// Rejects public keys that `ED25519_VERIFY_STRICT` rejects but `ED25519`
+// doesn't.
+fn check_strict(public_key: &EncodedPoint) -> Result<(), error::Unspecified> {
+    if !is_canonical(public_key) || is_small_order(public_key) {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
+// Returns `false` if the y coordinate encoded in `encoded` isn't reduced
+// modulo q = 2**255 - 19. The encodings of the points with x = 0 and the sign
+// bit set also aren't canonical, but those points have small order.
+fn is_canonical(encoded: &EncodedPoint) -> bool {
+    // The little-endian encoding of q.
+    let mut q = [0xff; ELEM_LEN];
+    q[0] = 0xed;
+    q[ELEM_LEN - 1] = 0x7f;
+
+    let mut y = *encoded;
+    y[ELEM_LEN - 1] &= 0x7f;
+    // Compare from the most significant byte.
+    y.iter().rev().cmp(q.iter().rev()) == core::cmp::Ordering::Less
+}
+
+fn public_key_bytes<'a>(
+    public_key: untrusted::Input<'a>,
+) -> Result<&'a [u8; ELEM_LEN], error::Unspecified> {
+    public_key
+        .as_slice_less_safe()
+        .try_into()
+        .map_err(error::Unspecified::from)
+}
+
 prefixed_extern! {
     fn x25519_ge_double_scalarmult_vartime(
         r: &mut Point,
@@ -41,6 +41,11 @@ impl Scalar {
         Ok(Self(bytes))
     }
 
+    // XEdDSA hashes the private scalar to compute the nonce.
+    pub fn bytes_less_safe(&self) -> &[u8; SCALAR_LEN] {
+        &self.0
+    }
+
     // Constructs a `Scalar` from `digest` reduced modulo n.
     pub fn from_sha512_digest_reduced(digest: digest::Digest) -> Self {
         prefixed_extern! {
@@ -53,6 +58,19 @@ impl Scalar {
     }
 }
 
+// Sets `s` to `(a * b + c) mod n`.
+pub fn sc_muladd(s: &mut [u8; SCALAR_LEN], a: &Scalar, b: &Scalar, c: &Scalar) {
+    prefixed_extern! {
+        fn x25519_sc_muladd(
+            s: &mut [u8; SCALAR_LEN],
+            a: &Scalar,
+            b: &Scalar,
+            c: &Scalar,
+        );
+    }
+    unsafe { x25519_sc_muladd(s, a, b, c) }
+}
+
 #[repr(transparent)]
 pub struct MaskedScalar([u8; SCALAR_LEN]);
 
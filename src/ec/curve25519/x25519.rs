@@ -37,6 +37,7 @@ static CURVE25519: ec::Curve = ec::Curve {
 pub static X25519: agreement::Algorithm = agreement::Algorithm {
     curve: &CURVE25519,
     ecdh: x25519_ecdh,
+    validate_public_key: x25519_validate_public_key,
 };
 
 #[allow(clippy::unnecessary_wraps)]
@@ -61,7 +62,16 @@ fn x25519_public_from_private(
 
     let private_key: &[u8; SCALAR_LEN] = private_key.bytes_less_safe().try_into()?;
     let private_key = ops::MaskedScalar::from_bytes_masked(*private_key);
+    public_from_private_masked(public_out, &private_key, cpu_features);
+    Ok(())
+}
 
+// Also used by XEd25519, which uses X25519 key pairs for signing.
+pub(super) fn public_from_private_masked(
+    public_out: &mut PublicKey,
+    private_key: &PrivateKey,
+    cpu_features: cpu::Features,
+) {
     #[cfg(all(not(target_os = "ios"), target_arch = "arm"))]
     {
         if cpu::arm::NEON.available(cpu_features) {
@@ -69,8 +79,8 @@ fn x25519_public_from_private(
                 9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
                 0, 0, 0, 0,
             ];
-            x25519_neon(public_out, &private_key, &MONTGOMERY_BASE_POINT);
-            return Ok(());
+            x25519_neon(public_out, private_key, &MONTGOMERY_BASE_POINT);
+            return;
         }
     }
 
@@ -84,12 +94,10 @@ fn x25519_public_from_private(
     unsafe {
         x25519_public_from_private_generic_masked(
             public_out,
-            &private_key,
+            private_key,
             ops::has_fe25519_adx(cpu_features).into(),
         );
     }
-
-    Ok(())
 }
 
 fn x25519_ecdh(
@@ -102,45 +110,6 @@ fn x25519_ecdh(
     let my_private_key = ops::MaskedScalar::from_bytes_masked(*my_private_key);
     let peer_public_key: &[u8; PUBLIC_KEY_LEN] = peer_public_key.as_slice_less_safe().try_into()?;
 
-    fn scalar_mult(
-        out: &mut ops::EncodedPoint,
-        scalar: &ops::MaskedScalar,
-        point: &ops::EncodedPoint,
-        #[allow(unused_variables)] cpu_features: cpu::Features,
-    ) {
-        #[cfg(all(not(target_os = "ios"), target_arch = "arm"))]
-        {
-            if cpu::arm::NEON.available(cpu_features) {
-                return x25519_neon(out, scalar, point);
-            }
-        }
-
-        #[cfg(all(target_arch = "x86_64", not(target_os = "windows")))]
-        {
-            if ops::has_fe25519_adx(cpu_features) {
-                prefixed_extern! {
-                    fn x25519_scalar_mult_adx(
-                        out: &mut ops::EncodedPoint,
-                        scalar: &ops::MaskedScalar,
-                        point: &ops::EncodedPoint,
-                    );
-                }
-                return unsafe { x25519_scalar_mult_adx(out, scalar, point) };
-            }
-        }
-
-        prefixed_extern! {
-            fn x25519_scalar_mult_generic_masked(
-                out: &mut ops::EncodedPoint,
-                scalar: &ops::MaskedScalar,
-                point: &ops::EncodedPoint,
-            );
-        }
-        unsafe {
-            x25519_scalar_mult_generic_masked(out, scalar, point);
-        }
-    }
-
     scalar_mult(
         out.try_into()?,
         &my_private_key,
@@ -157,6 +126,67 @@ fn x25519_ecdh(
     Ok(())
 }
 
+fn x25519_validate_public_key(
+    public_key: untrusted::Input,
+    cpu_features: cpu::Features,
+) -> Result<(), error::Unspecified> {
+    let public_key: &[u8; PUBLIC_KEY_LEN] = public_key.as_slice_less_safe().try_into()?;
+
+    // Every clamped scalar is a multiple of the cofactor 8 (and of the twist's
+    // cofactor 4), and 2**254 isn't a multiple of either large prime order, so
+    // the result is zero exactly when the point has small order. This is the
+    // same condition under which `x25519_ecdh` fails.
+    let scalar = ops::MaskedScalar::from_bytes_masked([0; SCALAR_LEN]);
+    let mut out: SharedSecret = [0; SHARED_SECRET_LEN];
+    scalar_mult(&mut out, &scalar, public_key, cpu_features);
+
+    let zeros: SharedSecret = [0; SHARED_SECRET_LEN];
+    if constant_time::verify_slices_are_equal(&out, &zeros).is_ok() {
+        return Err(error::Unspecified);
+    }
+
+    Ok(())
+}
+
+fn scalar_mult(
+    out: &mut ops::EncodedPoint,
+    scalar: &ops::MaskedScalar,
+    point: &ops::EncodedPoint,
+    #[allow(unused_variables)] cpu_features: cpu::Features,
+) {
+    #[cfg(all(not(target_os = "ios"), target_arch = "arm"))]
+    {
+        if cpu::arm::NEON.available(cpu_features) {
+            return x25519_neon(out, scalar, point);
+        }
+    }
+
+    #[cfg(all(target_arch = "x86_64", not(target_os = "windows")))]
+    {
+        if ops::has_fe25519_adx(cpu_features) {
+            prefixed_extern! {
+                fn x25519_scalar_mult_adx(
+                    out: &mut ops::EncodedPoint,
+                    scalar: &ops::MaskedScalar,
+                    point: &ops::EncodedPoint,
+                );
+            }
+            return unsafe { x25519_scalar_mult_adx(out, scalar, point) };
+        }
+    }
+
+    prefixed_extern! {
+        fn x25519_scalar_mult_generic_masked(
+            out: &mut ops::EncodedPoint,
+            scalar: &ops::MaskedScalar,
+            point: &ops::EncodedPoint,
+        );
+    }
+    unsafe {
+        x25519_scalar_mult_generic_masked(out, scalar, point);
+    }
+}
+
 #[cfg(all(not(target_os = "ios"), target_arch = "arm"))]
 fn x25519_neon(out: &mut ops::EncodedPoint, scalar: &ops::MaskedScalar, point: &ops::EncodedPoint) {
     prefixed_extern! {
@@ -171,12 +201,12 @@ fn x25519_neon(out: &mut ops::EncodedPoint, scalar: &ops::MaskedScalar, point: &
 
 const ELEM_AND_SCALAR_LEN: usize = ops::ELEM_LEN;
 
-type PrivateKey = ops::MaskedScalar;
-const PRIVATE_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
+pub(super) type PrivateKey = ops::MaskedScalar;
+pub(super) const PRIVATE_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
 
 // An X25519 public key as an encoded Curve25519 point.
-type PublicKey = [u8; PUBLIC_KEY_LEN];
-const PUBLIC_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
+pub(super) type PublicKey = [u8; PUBLIC_KEY_LEN];
+pub(super) const PUBLIC_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
 
 // An X25519 shared secret as an encoded Curve25519 point.
 type SharedSecret = [u8; SHARED_SECRET_LEN];
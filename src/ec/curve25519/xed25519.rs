@@ -0,0 +1,251 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! XEd25519 signatures, using X25519 keys.
+//!
+//! See [The XEdDSA and VXEdDSA Signature Schemes].
+//!
+//! [The XEdDSA and VXEdDSA Signature Schemes]:
+//!     https://signal.org/docs/specifications/xeddsa/
+
+use super::{
+    ed25519::{
+        eddsa_digest,
+        verification::{is_small_order, ED25519},
+    },
+    ops::*,
+    x25519,
+};
+use crate::{cpu, digest, error, rand, sealed, signature};
+
+/// An X25519 key pair, for signing with XEd25519.
+pub struct XEd25519KeyPair {
+    // XEdDSA calls this *a*. It is the X25519 private key, negated if
+    // necessary so that the sign bit of `edwards_public_key` is zero.
+    private_scalar: Scalar,
+
+    // XEdDSA calls this *A*.
+    edwards_public_key: EncodedPoint,
+
+    // The X25519 public key.
+    public_key: PublicKey,
+}
+
+derive_debug_via_field!(XEd25519KeyPair, stringify!(XEd25519KeyPair), public_key);
+
+impl XEd25519KeyPair {
+    /// Constructs an XEd25519 key pair from an X25519 private key.
+    ///
+    /// `private_key` is the 32-byte X25519 private key, as described in
+    /// [RFC 7748 Section 5]; as in X25519, it is clamped before use.
+    ///
+    /// [RFC 7748 Section 5]: https://tools.ietf.org/html/rfc7748#section-5
+    pub fn from_x25519_private_key(private_key: &[u8]) -> Result<Self, error::KeyRejected> {
+        let private_key: &[u8; x25519::PRIVATE_KEY_LEN] = private_key
+            .try_into()
+            .map_err(|_| error::KeyRejected::invalid_encoding())?;
+        let cpu_features = cpu::features();
+
+        let mut public_key = [0; x25519::PUBLIC_KEY_LEN];
+        x25519::public_from_private_masked(
+            &mut public_key,
+            &MaskedScalar::from_bytes_masked(*private_key),
+            cpu_features,
+        );
+
+        // XEdDSA's calculate_key_pair.
+        let k = Scalar::from(MaskedScalar::from_bytes_masked(*private_key));
+        let mut edwards_public_key = ExtPoint::from_scalarmult_base_consttime(&k, cpu_features)
+            .into_encoded_point(cpu_features);
+        let sign_bit = edwards_public_key[ELEM_LEN - 1] >> 7;
+        edwards_public_key[ELEM_LEN - 1] &= 0x7f;
+
+        // a = k or -k (mod q), depending on `sign_bit`, in constant time.
+        let mask = 0u8.wrapping_sub(sign_bit);
+        let mut factor = [0; SCALAR_LEN];
+        for ((f, one), minus_one) in factor.iter_mut().zip(&ONE).zip(&MINUS_ONE) {
+            *f = (one & !mask) | (minus_one & mask);
+        }
+        let factor = Scalar::from_bytes_checked(factor)
+            .map_err(|_| error::KeyRejected::unexpected_error())?;
+        let zero = Scalar::from_bytes_checked([0; SCALAR_LEN])
+            .map_err(|_| error::KeyRejected::unexpected_error())?;
+        let mut private_scalar = [0; SCALAR_LEN];
+        sc_muladd(&mut private_scalar, &k, &factor, &zero);
+        let private_scalar = Scalar::from_bytes_checked(private_scalar)
+            .map_err(|_| error::KeyRejected::unexpected_error())?;
+
+        Ok(Self {
+            private_scalar,
+            edwards_public_key,
+            public_key: PublicKey(public_key),
+        })
+    }
+
+    /// Returns the signature of the message `msg`.
+    ///
+    /// XEd25519 signing is randomized; 64 bytes of `rng` output are used for
+    /// each signature.
+    pub fn sign(
+        &self,
+        rng: &dyn rand::SecureRandom,
+        msg: &[u8],
+    ) -> Result<signature::Signature, error::Unspecified> {
+        let cpu_features = cpu::features();
+        let z: [u8; RANDOM_LEN] = rand::generate(rng)?.expose();
+        Ok(signature::Signature::new(|signature_bytes| {
+            let (signature_bytes, _unused) = signature_bytes.split_at_mut(ELEM_LEN + SCALAR_LEN);
+            let (signature_r, signature_s) = signature_bytes.split_at_mut(ELEM_LEN);
+
+            // r = hash_1(a || M || Z) (mod q)
+            let nonce = {
+                let mut ctx = digest::Context::new(&digest::SHA512);
+                ctx.update(&HASH_1_PREFIX);
+                ctx.update(self.private_scalar.bytes_less_safe());
+                ctx.update(msg);
+                ctx.update(&z);
+                ctx.finish()
+            };
+            let nonce = Scalar::from_sha512_digest_reduced(nonce);
+
+            let r = ExtPoint::from_scalarmult_base_consttime(&nonce, cpu_features);
+            signature_r.copy_from_slice(&r.into_encoded_point(cpu_features));
+            let hram_digest = eddsa_digest(signature_r, &self.edwards_public_key, msg);
+            let hram = Scalar::from_sha512_digest_reduced(hram_digest);
+            sc_muladd(
+                signature_s.try_into().unwrap(),
+                &hram,
+                &self.private_scalar,
+                &nonce,
+            );
+
+            SIGNATURE_LEN
+        }))
+    }
+}
+
+impl signature::KeyPair for XEd25519KeyPair {
+    type PublicKey = PublicKey;
+
+    fn public_key(&self) -> &Self::PublicKey {
+        &self.public_key
+    }
+}
+
+/// An X25519 public key.
+#[derive(Clone, Copy)]
+pub struct PublicKey(x25519::PublicKey);
+
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+derive_debug_self_as_ref_hex_bytes!(PublicKey);
+
+/// Parameters for XEd25519 signature verification.
+pub struct XEdDSAParameters;
+
+impl core::fmt::Debug for XEdDSAParameters {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(f, "ring::signature::XED25519")
+    }
+}
+
+/// Verification of [XEd25519] signatures.
+///
+/// The public key is an X25519 public key. The signature is verified as an
+/// Ed25519 signature using the Edwards25519 public key with sign bit zero
+/// that corresponds to it, as XEdDSA specifies.
+///
+/// [XEd25519]: https://signal.org/docs/specifications/xeddsa/
+pub static XED25519: XEdDSAParameters = XEdDSAParameters {};
+
+impl signature::VerificationAlgorithm for XEdDSAParameters {
+    fn verify(
+        &self,
+        public_key: untrusted::Input,
+        msg: untrusted::Input,
+        signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        let edwards_public_key = edwards_public_key(public_key)?;
+        ED25519.verify(untrusted::Input::from(&edwards_public_key), msg, signature)
+    }
+}
+
+impl sealed::Sealed for XEdDSAParameters {}
+
+impl signature::public_key_matching::PublicKeyMatching for XEdDSAParameters {}
+
+impl signature::public_key_weakness::PublicKeyWeakness for XEdDSAParameters {
+    fn is_known_weak(&self, public_key: untrusted::Input) -> bool {
+        edwards_public_key(public_key).map_or(false, |a| is_small_order(&a))
+    }
+}
+
+impl signature::public_key_validation::PublicKeyValidation for XEdDSAParameters {
+    fn validate_public_key(&self, public_key: untrusted::Input) -> Result<(), error::Unspecified> {
+        let _ = ExtPoint::from_encoded_point_vartime(&edwards_public_key(public_key)?)?;
+        Ok(())
+    }
+}
+
+// XEdDSA's convert_mont.
+fn edwards_public_key(public_key: untrusted::Input) -> Result<EncodedPoint, error::Unspecified> {
+    let u: &[u8; x25519::PUBLIC_KEY_LEN] = public_key.as_slice_less_safe().try_into()?;
+
+    // Reject u >= p. The encoding is little-endian.
+    if u.iter().rev().cmp(FIELD_P.iter().rev()) != core::cmp::Ordering::Less {
+        return Err(error::Unspecified);
+    }
+
+    let mut edwards_public_key = [0; ELEM_LEN];
+    unsafe {
+        x25519_edwards_y_from_montgomery_u(&mut edwards_public_key, u);
+    }
+    Ok(edwards_public_key)
+}
+
+// 2**255 - 19, little-endian.
+const FIELD_P: [u8; ELEM_LEN] = [
+    0xed, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f,
+];
+
+// 1 and q - 1, little-endian.
+const ONE: [u8; SCALAR_LEN] = [
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+const MINUS_ONE: [u8; SCALAR_LEN] = [
+    0xec, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+// hash_1's prefix: 2**256 - 1 - 1, little-endian.
+const HASH_1_PREFIX: [u8; 32] = [
+    0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+];
+
+const RANDOM_LEN: usize = 64;
+
+const SIGNATURE_LEN: usize = ELEM_LEN + SCALAR_LEN;
+
+prefixed_extern! {
+    fn x25519_edwards_y_from_montgomery_u(
+        out_y: &mut EncodedPoint,
+        u: &[u8; x25519::PUBLIC_KEY_LEN],
+    );
+}
@@ -0,0 +1,85 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Parsing of elliptic curve public keys in the JSON Web Key (JWK) format.
+
+use crate::{
+    error,
+    jwk::{base64url_decode, parse_members},
+    signature::{self, ParsedKey, VerificationAlgorithm},
+};
+use alloc::vec::Vec;
+
+/// Parses an elliptic curve public key in the JSON Web Key (JWK) format of
+/// [RFC 7517].
+///
+/// Two forms are supported:
+///
+/// * `{"kty":"EC","crv":"P-256","x":"...","y":"..."}` (or `"P-384"`), as
+///   specified in [RFC 7518 Section 6.2.1]. `"x"` and `"y"` must each be the
+///   full length of a coordinate.
+/// * `{"kty":"OKP","crv":"Ed25519","x":"..."}`, as specified in
+///   [RFC 8037 Section 2].
+///
+/// The coordinates are encoded as unpadded base64url. The point is validated
+/// as [`signature::parse_public_key()`] does, e.g. a P-256 or P-384 point must
+/// be on the curve.
+///
+/// The algorithm of the returned key is the one that JWS uses for the curve:
+/// `ECDSA_P256_SHA256_FIXED` (ES256) for P-256, `ECDSA_P384_SHA384_FIXED`
+/// (ES384) for P-384, and `ED25519` (EdDSA) for Ed25519. Other members, such
+/// as `"alg"`, are ignored; it is up to the caller to check them. The JSON
+/// parsing is as strict as in [`signature::rsa_public_key_from_jwk()`].
+///
+/// [RFC 7517]: https://www.rfc-editor.org/rfc/rfc7517
+/// [RFC 7518 Section 6.2.1]: https://www.rfc-editor.org/rfc/rfc7518#section-6.2.1
+/// [RFC 8037 Section 2]: https://www.rfc-editor.org/rfc/rfc8037#section-2
+pub fn ec_public_key_from_jwk(jwk: &[u8]) -> Result<ParsedKey<Vec<u8>>, error::Unspecified> {
+    let [kty, crv, x, y] = parse_members(jwk, [b"kty", b"crv", b"x", b"y"])?;
+    let kty = kty.ok_or(error::Unspecified)?.as_slice_less_safe();
+    let crv = crv.ok_or(error::Unspecified)?.as_slice_less_safe();
+    let x = base64url_decode(x.ok_or(error::Unspecified)?)?;
+
+    let (algorithm, public_key): (&'static dyn VerificationAlgorithm, _) = match (kty, crv) {
+        (b"EC", b"P-256") => (
+            &signature::ECDSA_P256_SHA256_FIXED,
+            uncompressed_point(x, y, 32)?,
+        ),
+        (b"EC", b"P-384") => (
+            &signature::ECDSA_P384_SHA384_FIXED,
+            uncompressed_point(x, y, 48)?,
+        ),
+        (b"OKP", b"Ed25519") if y.is_none() => (&signature::ED25519, x),
+        _ => return Err(error::Unspecified),
+    };
+
+    signature::parse_public_key(algorithm, public_key)
+}
+
+// Returns the SEC 1 uncompressed encoding of the point (x, y).
+fn uncompressed_point(
+    x: Vec<u8>,
+    y: Option<untrusted::Input>,
+    coordinate_len: usize,
+) -> Result<Vec<u8>, error::Unspecified> {
+    let y = base64url_decode(y.ok_or(error::Unspecified)?)?;
+    if x.len() != coordinate_len || y.len() != coordinate_len {
+        return Err(error::Unspecified);
+    }
+    let mut point = Vec::with_capacity(1 + 2 * coordinate_len);
+    point.push(4);
+    point.extend_from_slice(&x);
+    point.extend_from_slice(&y);
+    Ok(point)
+}
@@ -233,6 +233,8 @@ pub mod ecdh;
 
 pub mod ecdsa;
 
+pub mod group;
+
 mod ops;
 
 mod private_key;
@@ -20,7 +20,7 @@ use crate::{agreement, cpu, ec, error};
 /// A key agreement algorithm.
 macro_rules! ecdh {
     ( $NAME:ident, $curve:expr, $name_str:expr, $private_key_ops:expr,
-      $public_key_ops:expr, $ecdh:ident ) => {
+      $public_key_ops:expr, $ecdh:ident, $validate_public_key:ident ) => {
         #[doc = "ECDH using the NSA Suite B"]
         #[doc=$name_str]
         #[doc = "curve."]
@@ -41,8 +41,16 @@ macro_rules! ecdh {
         pub static $NAME: agreement::Algorithm = agreement::Algorithm {
             curve: $curve,
             ecdh: $ecdh,
+            validate_public_key: $validate_public_key,
         };
 
+        fn $validate_public_key(
+            public_key: untrusted::Input,
+            cpu: cpu::Features,
+        ) -> Result<(), error::Unspecified> {
+            parse_uncompressed_point($public_key_ops, public_key, cpu).map(|_| ())
+        }
+
         fn $ecdh(
             out: &mut [u8],
             my_private_key: &ec::Seed,
@@ -67,7 +75,8 @@ ecdh!(
     "P-256 (secp256r1)",
     &p256::PRIVATE_KEY_OPS,
     &p256::PUBLIC_KEY_OPS,
-    p256_ecdh
+    p256_ecdh,
+    p256_validate_public_key
 );
 
 ecdh!(
@@ -76,7 +85,8 @@ ecdh!(
     "P-384 (secp384r1)",
     &p384::PRIVATE_KEY_OPS,
     &p384::PUBLIC_KEY_OPS,
-    p384_ecdh
+    p384_ecdh,
+    p384_validate_public_key
 );
 
 fn ecdh(
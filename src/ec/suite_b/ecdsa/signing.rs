@@ -57,7 +57,44 @@ impl Eq for EcdsaSigningAlgorithm {}
 
 impl sealed::Sealed for EcdsaSigningAlgorithm {}
 
+/// Wraps the private key `private_key`, which was generated outside of
+/// *ring*, as a PKCS#8 document for `alg`.
+///
+/// The private key must be encoded as a big-endian fixed-length integer, as
+/// in `EcdsaKeyPair::from_private_key_and_public_key()`, and it must be in
+/// the range [1, n), where n is the order of the curve. The public key is
+/// computed from it.
+///
+/// The result is in the same form as the result of
+/// `EcdsaKeyPair::generate_pkcs8()`, so it can be parsed with
+/// `EcdsaKeyPair::from_pkcs8()`.
+pub fn ecdsa_pkcs8_from_private_scalar(
+    alg: &'static EcdsaSigningAlgorithm,
+    private_key: &[u8],
+) -> Result<pkcs8::Document, error::Unspecified> {
+    let cpu = cpu::features();
+    let private_key = ec::Seed::from_bytes(alg.curve, untrusted::Input::from(private_key), cpu)?;
+    wrap_pkcs8(alg, &private_key, cpu)
+}
+
+fn wrap_pkcs8(
+    alg: &EcdsaSigningAlgorithm,
+    private_key: &ec::Seed,
+    cpu: cpu::Features,
+) -> Result<pkcs8::Document, error::Unspecified> {
+    let public_key = private_key.compute_public_key(cpu)?;
+    Ok(pkcs8::wrap_key(
+        alg.pkcs8_template,
+        private_key.bytes_less_safe(),
+        public_key.as_ref(),
+    ))
+}
+
 /// An ECDSA key pair, used for signing.
+///
+/// The public key, from `KeyPair::public_key()`, is encoded as an
+/// uncompressed point; its `as_compressed()` method returns the compressed
+/// encoding.
 pub struct EcdsaKeyPair {
     d: Scalar<R>,
     nonce_key: NonceRandomKey,
@@ -85,12 +122,7 @@ impl EcdsaKeyPair {
     ) -> Result<pkcs8::Document, error::Unspecified> {
         let cpu = cpu::features();
         let private_key = ec::Seed::generate(alg.curve, rng, cpu)?;
-        let public_key = private_key.compute_public_key(cpu)?;
-        Ok(pkcs8::wrap_key(
-            alg.pkcs8_template,
-            private_key.bytes_less_safe(),
-            public_key.as_ref(),
-        ))
+        wrap_pkcs8(alg, &private_key, cpu)
     }
 
     /// Constructs an ECDSA key pair by parsing an unencrypted PKCS#8 v1
@@ -117,6 +149,23 @@ impl EcdsaKeyPair {
         Self::new(alg, key_pair, rng)
     }
 
+    /// Constructs an ECDSA key pair by decrypting a PKCS#8
+    /// `EncryptedPrivateKeyInfo` with `passphrase` and then parsing the
+    /// decrypted key exactly like `EcdsaKeyPair::from_pkcs8()`.
+    ///
+    /// See the [`pkcs8`] module documentation for the supported encryption
+    /// schemes.
+    #[cfg(feature = "alloc")]
+    pub fn from_encrypted_pkcs8(
+        alg: &'static EcdsaSigningAlgorithm,
+        pkcs8: &[u8],
+        passphrase: &[u8],
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Self, error::KeyRejected> {
+        let pkcs8 = pkcs8::decrypt(untrusted::Input::from(pkcs8), passphrase)?;
+        Self::from_pkcs8(alg, &pkcs8, rng)
+    }
+
     /// Constructs an ECDSA key pair from the private key and public key bytes
     ///
     /// The private key must encoded as a big-endian fixed-length integer. For
@@ -148,6 +197,40 @@ impl EcdsaKeyPair {
         Self::new(alg, key_pair, rng)
     }
 
+    /// Deterministically derives a key pair for `alg` from `label`, for test
+    /// fixtures.
+    ///
+    /// **Insecure; for tests only.** Anybody who knows (or guesses) `label`
+    /// can compute the private key. The same label always gives the same key
+    /// pair for a given curve, in every version of *ring*. Signing is still
+    /// randomized.
+    #[cfg(feature = "test-util")]
+    pub fn from_test_label(alg: &'static EcdsaSigningAlgorithm, label: &str) -> Self {
+        let cpu = cpu::features();
+        let curve_name: &[u8] = match alg.curve.id {
+            ec::CurveID::P256 => b"P-256",
+            ec::CurveID::P384 => b"P-384",
+            ec::CurveID::Curve25519 => unreachable!(),
+        };
+        let mut bytes = [0u8; ec::SEED_MAX_BYTES];
+        let bytes = &mut bytes[..alg.curve.elem_scalar_seed_len];
+
+        // Retry in the astronomically unlikely case the bytes aren't a valid
+        // private key.
+        for counter in 0..=u8::MAX {
+            crate::test::expand_test_label(label, &[b"ECDSA", curve_name, &[counter]], bytes);
+            let seed = match ec::Seed::from_bytes(alg.curve, untrusted::Input::from(bytes), cpu) {
+                Ok(seed) => seed,
+                Err(error::Unspecified) => continue,
+            };
+            let key_pair = ec::KeyPair::derive(seed, cpu).unwrap();
+            crate::test::expand_test_label(label, &[b"ECDSA nonce key", curve_name], bytes);
+            let rng = crate::test::rand::FixedSliceRandom { bytes };
+            return Self::new(alg, key_pair, &rng).unwrap();
+        }
+        unreachable!()
+    }
+
     fn new(
         alg: &'static EcdsaSigningAlgorithm,
         key_pair: ec::KeyPair,
@@ -371,11 +454,42 @@ impl signature::KeyPair for EcdsaKeyPair {
     }
 }
 
+/// The public key of an [`EcdsaKeyPair`].
+///
+/// `as_ref()` returns the public key encoded as an uncompressed point, as
+/// described in [SEC 1: Elliptic Curve Cryptography, Version 2.0] Section
+/// 2.3.3, i.e. `0x04` followed by the big-endian x and y coordinates. This is
+/// 65 bytes for P-256 and 97 bytes for P-384.
+///
+/// [SEC 1: Elliptic Curve Cryptography, Version 2.0]:
+///     http://www.secg.org/sec1-v2.pdf
 #[derive(Clone, Copy)]
 pub struct PublicKey(ec::PublicKey);
 
 derive_debug_self_as_ref_hex_bytes!(PublicKey);
 
+impl PublicKey {
+    /// Returns the public key encoded as a compressed point, as described in
+    /// SEC 1 Section 2.3.3, i.e. `0x02` if y is even or `0x03` if y is odd,
+    /// followed by the big-endian x coordinate. This is 33 bytes for P-256
+    /// and 49 bytes for P-384.
+    #[cfg(feature = "alloc")]
+    pub fn as_compressed(&self) -> alloc::vec::Vec<u8> {
+        let (x, y) = match self.as_ref().split_first() {
+            Some((_, coordinates)) => coordinates.split_at(coordinates.len() / 2),
+            None => unreachable!(),
+        };
+        let y_is_odd = match y.last() {
+            Some(last) => last & 1,
+            None => unreachable!(),
+        };
+        let mut compressed = alloc::vec::Vec::with_capacity(1 + x.len());
+        compressed.push(0x02 | y_is_odd);
+        compressed.extend_from_slice(x);
+        compressed
+    }
+}
+
 impl AsRef<[u8]> for PublicKey {
     fn as_ref(&self) -> &[u8] {
         self.0.as_ref()
@@ -520,10 +634,92 @@ static EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
     private_key_index: 0x23,
 };
 
+// The first P-256/SHA-256 test vector from the NIST CAVP 186-4 ECDSA2VS
+// Signature Generation tests; see `ecdsa_sign_fixed_tests.txt`. This is for
+// `fips::run_self_tests()`.
+pub(crate) mod p256_sha256_known_answer_test {
+    use super::EcdsaKeyPair;
+    use crate::{digest, error, rand, signature, test};
+
+    const PRIVATE_KEY: [u8; 32] = [
+        0x51, 0x9b, 0x42, 0x3d, 0x71, 0x5f, 0x8b, 0x58, 0x1f, 0x4f, 0xa8, 0xee, 0x59, 0xf4, 0x77,
+        0x1a, 0x5b, 0x44, 0xc8, 0x13, 0x0b, 0x4e, 0x3e, 0xac, 0xca, 0x54, 0xa5, 0x6d, 0xda, 0x72,
+        0xb4, 0x64,
+    ];
+    pub(crate) const PUBLIC_KEY: [u8; 65] = [
+        0x04, 0x1c, 0xcb, 0xe9, 0x1c, 0x07, 0x5f, 0xc7, 0xf4, 0xf0, 0x33, 0xbf, 0xa2, 0x48, 0xdb,
+        0x8f, 0xcc, 0xd3, 0x56, 0x5d, 0xe9, 0x4b, 0xbf, 0xb1, 0x2f, 0x3c, 0x59, 0xff, 0x46, 0xc2,
+        0x71, 0xbf, 0x83, 0xce, 0x40, 0x14, 0xc6, 0x88, 0x11, 0xf9, 0xa2, 0x1a, 0x1f, 0xdb, 0x2c,
+        0x0e, 0x61, 0x13, 0xe0, 0x6d, 0xb7, 0xca, 0x93, 0xb7, 0x40, 0x4e, 0x78, 0xdc, 0x7c, 0xcd,
+        0x5c, 0xa8, 0x9a, 0x4c, 0xa9,
+    ];
+    const NONCE: [u8; 32] = [
+        0x94, 0xa1, 0xbb, 0xb1, 0x4b, 0x90, 0x6a, 0x61, 0xa2, 0x80, 0xf2, 0x45, 0xf9, 0xe9, 0x3c,
+        0x7f, 0x3b, 0x4a, 0x62, 0x47, 0x82, 0x4f, 0x5d, 0x33, 0xb9, 0x67, 0x07, 0x87, 0x64, 0x2a,
+        0x68, 0xde,
+    ];
+    pub(crate) const MESSAGE: [u8; 128] = [
+        0x59, 0x05, 0x23, 0x88, 0x77, 0xc7, 0x74, 0x21, 0xf7, 0x3e, 0x43, 0xee, 0x3d, 0xa6, 0xf2,
+        0xd9, 0xe2, 0xcc, 0xad, 0x5f, 0xc9, 0x42, 0xdc, 0xec, 0x0c, 0xbd, 0x25, 0x48, 0x29, 0x35,
+        0xfa, 0xaf, 0x41, 0x69, 0x83, 0xfe, 0x16, 0x5b, 0x1a, 0x04, 0x5e, 0xe2, 0xbc, 0xd2, 0xe6,
+        0xdc, 0xa3, 0xbd, 0xf4, 0x6c, 0x43, 0x10, 0xa7, 0x46, 0x1f, 0x9a, 0x37, 0x96, 0x0c, 0xa6,
+        0x72, 0xd3, 0xfe, 0xb5, 0x47, 0x3e, 0x25, 0x36, 0x05, 0xfb, 0x1d, 0xdf, 0xd2, 0x80, 0x65,
+        0xb5, 0x3c, 0xb5, 0x85, 0x8a, 0x8a, 0xd2, 0x81, 0x75, 0xbf, 0x9b, 0xd3, 0x86, 0xa5, 0xe4,
+        0x71, 0xea, 0x7a, 0x65, 0xc1, 0x7c, 0xc9, 0x34, 0xa9, 0xd7, 0x91, 0xe9, 0x14, 0x91, 0xeb,
+        0x37, 0x54, 0xd0, 0x37, 0x99, 0x79, 0x0f, 0xe2, 0xd3, 0x08, 0xd1, 0x61, 0x46, 0xd5, 0xc9,
+        0xb0, 0xd0, 0xde, 0xbd, 0x97, 0xd7, 0x9c, 0xe8,
+    ];
+    pub(crate) const SIGNATURE: [u8; 64] = [
+        0xf3, 0xac, 0x80, 0x61, 0xb5, 0x14, 0x79, 0x5b, 0x88, 0x43, 0xe3, 0xd6, 0x62, 0x95, 0x27,
+        0xed, 0x2a, 0xfd, 0x6b, 0x1f, 0x6a, 0x55, 0x5a, 0x7a, 0xca, 0xbb, 0x5e, 0x6f, 0x79, 0xc8,
+        0xc2, 0xac, 0x8b, 0xf7, 0x78, 0x19, 0xca, 0x05, 0xa6, 0xb2, 0x78, 0x6c, 0x76, 0x26, 0x2b,
+        0xf7, 0x37, 0x1c, 0xef, 0x97, 0xb2, 0x18, 0xe9, 0x6f, 0x17, 0x5a, 0x3c, 0xcd, 0xda, 0x2a,
+        0xcc, 0x05, 0x89, 0x03,
+    ];
+
+    /// Signs `MESSAGE` with the test vector's private key and nonce. The
+    /// nonce never leaves this module, so nothing else can be signed with it.
+    pub(crate) fn sign() -> Result<signature::Signature, error::Unspecified> {
+        let key_pair = EcdsaKeyPair::from_private_key_and_public_key(
+            &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+            &PRIVATE_KEY,
+            &PUBLIC_KEY,
+            &rand::SystemRandom::new(),
+        )?;
+        let h = digest::digest(key_pair.alg.digest_alg, &MESSAGE);
+        key_pair.sign_digest(
+            h,
+            &test::rand::FixedSliceRandom { bytes: &NONCE },
+            crate::cpu::features(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{rand, signature, test};
 
+    #[test]
+    fn signature_ecdsa_sign_invalid_nonce_test() {
+        let rng = rand::SystemRandom::new();
+        for alg in [
+            &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+            &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+        ] {
+            let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(alg, &rng).unwrap();
+            let key_pair = signature::EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng).unwrap();
+
+            // A nonce of zero, or one that isn't less than the group order,
+            // is rejected instead of being used.
+            for byte in [0x00, 0xff] {
+                let rng = test::rand::FixedByteRandom { byte };
+                assert!(key_pair
+                    .sign_with_fixed_nonce_during_test(&rng, b"hello")
+                    .is_err());
+            }
+        }
+    }
+
     #[test]
     fn signature_ecdsa_sign_fixed_test() {
         let rng = rand::SystemRandom::new();
@@ -17,7 +17,7 @@
 use super::digest_scalar::digest_scalar;
 use crate::{
     arithmetic::montgomery::*,
-    cpu, digest,
+    constant_time, cpu, digest, ec,
     ec::suite_b::{ops::*, public_key::*, verify_jacobian_point_is_on_the_curve},
     error,
     io::der,
@@ -70,6 +70,30 @@ impl signature::VerificationAlgorithm for EcdsaVerificationAlgorithm {
     }
 }
 
+/// Verifies an ECDSA signature given as separate big-endian `r` and `s`
+/// components, e.g. the two halves of a JWS `ES256` signature.
+///
+/// `r` and `s` may have leading zeros, but must not be longer than the
+/// curve's scalar length; both must be in the range [1, n - 1], where n is
+/// the order of the curve. The components are checked the same way regardless
+/// of whether `algorithm` is an ASN.1 or a fixed-length one.
+pub fn ecdsa_verify_components(
+    algorithm: &EcdsaVerificationAlgorithm,
+    public_key: &[u8],
+    msg: &[u8],
+    r: &[u8],
+    s: &[u8],
+) -> Result<(), error::Unspecified> {
+    let h = digest::digest(algorithm.digest_alg, msg);
+    let e = digest_scalar(algorithm.ops.scalar_ops, h);
+    algorithm.verify_digest_components(
+        untrusted::Input::from(public_key),
+        e,
+        untrusted::Input::from(r),
+        untrusted::Input::from(s),
+    )
+}
+
 impl EcdsaVerificationAlgorithm {
     /// This is intentionally not public.
     fn verify_digest(
@@ -77,6 +101,19 @@ impl EcdsaVerificationAlgorithm {
         public_key: untrusted::Input,
         e: Scalar,
         signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        let (r, s) = signature.read_all(error::Unspecified, |input| {
+            (self.split_rs)(self.ops.scalar_ops, input)
+        })?;
+        self.verify_digest_components(public_key, e, r, s)
+    }
+
+    fn verify_digest_components(
+        &self,
+        public_key: untrusted::Input,
+        e: Scalar,
+        r: untrusted::Input,
+        s: untrusted::Input,
     ) -> Result<(), error::Unspecified> {
         let cpu = cpu::features();
 
@@ -104,10 +141,6 @@ impl EcdsaVerificationAlgorithm {
         // handled by `parse_uncompressed_point`.
         let peer_pub_key = parse_uncompressed_point(public_key_ops, public_key, cpu)?;
 
-        let (r, s) = signature.read_all(error::Unspecified, |input| {
-            (self.split_rs)(scalar_ops, input)
-        })?;
-
         // NSA Guide Step 1: "If r and s are not both integers in the interval
         // [1, n − 1], output INVALID."
         let r = scalar_parse_big_endian_variable(public_key_ops.common, limb::AllowZero::No, r)?;
@@ -171,6 +204,35 @@ impl EcdsaVerificationAlgorithm {
 
 impl sealed::Sealed for EcdsaVerificationAlgorithm {}
 
+impl signature::public_key_matching::PublicKeyMatching for EcdsaVerificationAlgorithm {
+    fn public_keys_match(
+        &self,
+        a: untrusted::Input,
+        b: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        let cpu = cpu::features();
+        let ops = self.ops.public_key_ops;
+
+        let mut a_uncompressed = [0u8; ec::PUBLIC_KEY_MAX_LEN];
+        let a_len = parse_point_to_uncompressed(ops, a, &mut a_uncompressed, cpu)?;
+        let mut b_uncompressed = [0u8; ec::PUBLIC_KEY_MAX_LEN];
+        let b_len = parse_point_to_uncompressed(ops, b, &mut b_uncompressed, cpu)?;
+
+        constant_time::verify_slices_are_equal(&a_uncompressed[..a_len], &b_uncompressed[..b_len])
+    }
+}
+
+// The groups have prime order, so there are no small-order points, and the
+// point at infinity has no valid encoding.
+impl signature::public_key_weakness::PublicKeyWeakness for EcdsaVerificationAlgorithm {}
+
+impl signature::public_key_validation::PublicKeyValidation for EcdsaVerificationAlgorithm {
+    fn validate_public_key(&self, public_key: untrusted::Input) -> Result<(), error::Unspecified> {
+        let _ = parse_uncompressed_point(self.ops.public_key_ops, public_key, cpu::features())?;
+        Ok(())
+    }
+}
+
 fn split_rs_fixed<'a>(
     ops: &'static ScalarOps,
     input: &mut untrusted::Reader<'a>,
@@ -0,0 +1,293 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{
+    ops::{self, p256, p384},
+    private_key::{affine_from_jacobian, check_scalar_big_endian_bytes},
+    public_key::parse_uncompressed_point,
+};
+use crate::{cpu, error, limb};
+
+/// A prime-order elliptic curve group.
+pub struct Curve {
+    id: CurveID,
+    private_key_ops: &'static ops::PrivateKeyOps,
+    public_key_ops: &'static ops::PublicKeyOps,
+    private_scalar_ops: &'static ops::PrivateScalarOps,
+}
+
+derive_debug_via_id!(Curve);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CurveID {
+    P256,
+    P384,
+}
+
+impl PartialEq for Curve {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Curve {}
+
+/// NIST P-256.
+pub static P256: Curve = Curve {
+    id: CurveID::P256,
+    private_key_ops: &p256::PRIVATE_KEY_OPS,
+    public_key_ops: &p256::PUBLIC_KEY_OPS,
+    private_scalar_ops: &p256::PRIVATE_SCALAR_OPS,
+};
+
+/// NIST P-384.
+pub static P384: Curve = Curve {
+    id: CurveID::P384,
+    private_key_ops: &p384::PRIVATE_KEY_OPS,
+    public_key_ops: &p384::PUBLIC_KEY_OPS,
+    private_scalar_ops: &p384::PRIVATE_SCALAR_OPS,
+};
+
+impl Curve {
+    /// The length of an encoded scalar, in bytes.
+    #[inline]
+    pub fn scalar_len(&self) -> usize {
+        self.common().len()
+    }
+
+    /// The length of an uncompressed encoded point, in bytes.
+    #[inline]
+    pub fn uncompressed_point_len(&self) -> usize {
+        1 + (2 * self.common().len())
+    }
+
+    fn common(&self) -> &'static ops::CommonOps {
+        self.private_key_ops.common
+    }
+}
+
+/// Checks that `bytes` is a valid private key for `curve` without
+/// constructing a key.
+///
+/// `bytes` must be the fixed-length big-endian encoding of an integer in the
+/// range [1, n), where n is the order of the curve's group; that is, it must
+/// be exactly `curve.scalar_len()` bytes, and neither zero nor n or larger.
+/// This is the same check that `agreement` and `signature` do when they
+/// import a private key. It is constant-time with respect to valid values,
+/// but it may leak which constraint an invalid value failed.
+pub fn validate_private_scalar(
+    curve: &'static Curve,
+    bytes: &[u8],
+) -> Result<(), error::Unspecified> {
+    if bytes.len() != curve.scalar_len() {
+        return Err(error::Unspecified);
+    }
+    check_scalar_big_endian_bytes(curve.private_key_ops, bytes)
+}
+
+/// A scalar, i.e. an integer in the range [0, n) where n is the order of the
+/// curve's group.
+pub struct Scalar {
+    curve: &'static Curve,
+    value: ops::Scalar,
+}
+
+impl Scalar {
+    /// Parses a scalar from its fixed-length big-endian encoding.
+    ///
+    /// `bytes` must be exactly `curve.scalar_len()` bytes and the value must
+    /// be less than the group order; zero is allowed. This is constant-time
+    /// with respect to valid values.
+    pub fn from_big_endian_bytes(
+        curve: &'static Curve,
+        bytes: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let value = ops::scalar_parse_big_endian_fixed_consttime_allow_zero(
+            curve.common(),
+            untrusted::Input::from(bytes),
+        )?;
+        Ok(Self { curve, value })
+    }
+
+    /// The scalar's curve.
+    #[inline]
+    pub fn curve(&self) -> &'static Curve {
+        self.curve
+    }
+
+    /// Writes the fixed-length big-endian encoding of the scalar into `out`.
+    ///
+    /// Fails if `out` isn't exactly `self.curve().scalar_len()` bytes.
+    pub fn write_big_endian_bytes(&self, out: &mut [u8]) -> Result<(), error::Unspecified> {
+        if out.len() != self.curve.scalar_len() {
+            return Err(error::Unspecified);
+        }
+        let scalar_ops = self.curve.private_scalar_ops.scalar_ops;
+        limb::big_endian_from_limbs(scalar_ops.leak_limbs(&self.value), out);
+        Ok(())
+    }
+
+    /// Returns `self + other` (mod n).
+    ///
+    /// Fails if the scalars are for different curves.
+    pub fn add(&self, other: &Self) -> Result<Self, error::Unspecified> {
+        self.check_same_curve(other.curve)?;
+        Ok(Self {
+            curve: self.curve,
+            value: ops::scalar_sum(self.curve.common(), &self.value, other.value),
+        })
+    }
+
+    /// Returns `self * other` (mod n).
+    ///
+    /// Fails if the scalars are for different curves.
+    pub fn mul(&self, other: &Self) -> Result<Self, error::Unspecified> {
+        self.check_same_curve(other.curve)?;
+        let cpu = cpu::features();
+        let ops = self.curve.private_scalar_ops;
+        let a = ops.to_mont(&self.value, cpu);
+        Ok(Self {
+            curve: self.curve,
+            value: ops.scalar_ops.scalar_product(&a, &other.value, cpu),
+        })
+    }
+
+    fn check_same_curve(&self, curve: &Curve) -> Result<(), error::Unspecified> {
+        if self.curve != curve {
+            return Err(error::Unspecified);
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for Scalar {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Scalar")
+            .field("curve", &self.curve)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A point on the curve, other than the point at infinity.
+pub struct Point {
+    curve: &'static Curve,
+    value: ops::Point, // Jacobian coordinates, with a non-zero Z.
+}
+
+impl Point {
+    /// Parses and validates a point in the uncompressed form
+    /// `0x04 || x || y`.
+    ///
+    /// The point is validated as for ECDH peer public keys: its coordinates
+    /// must be in range and it must be on the curve.
+    pub fn from_uncompressed(
+        curve: &'static Curve,
+        bytes: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let common = curve.common();
+        let xy = parse_uncompressed_point(
+            curve.public_key_ops,
+            untrusted::Input::from(bytes),
+            cpu::features(),
+        )?;
+        Ok(Self {
+            curve,
+            value: common.point_from_affine(&xy),
+        })
+    }
+
+    /// Returns `scalar * G`, where `G` is the curve's generator.
+    ///
+    /// This is constant-time with respect to `scalar`. Fails if `scalar` is
+    /// zero.
+    pub fn base_mul(scalar: &Scalar) -> Result<Self, error::Unspecified> {
+        let curve = scalar.curve;
+        let value = curve
+            .private_key_ops
+            .point_mul_base(&scalar.value, cpu::features());
+        Self::new(curve, value)
+    }
+
+    /// The point's curve.
+    #[inline]
+    pub fn curve(&self) -> &'static Curve {
+        self.curve
+    }
+
+    /// Returns `scalar * self`.
+    ///
+    /// This is constant-time with respect to `scalar` and `self`. Fails if
+    /// `scalar` is zero or is for a different curve.
+    pub fn mul(&self, scalar: &Scalar) -> Result<Self, error::Unspecified> {
+        if scalar.curve != self.curve {
+            return Err(error::Unspecified);
+        }
+        let cpu = cpu::features();
+        let ops = self.curve.private_key_ops;
+        let xy = affine_from_jacobian(ops, &self.value, cpu)?;
+        Self::new(self.curve, ops.point_mul(&scalar.value, &xy, cpu))
+    }
+
+    /// Returns `self + other`.
+    ///
+    /// Fails if the sum is the point at infinity, i.e. if `other` is the
+    /// negation of `self`, or if the points are for different curves. This
+    /// isn't constant-time when `other` is equal to `self` or its negation,
+    /// so it shouldn't be used when that is secret.
+    pub fn add(&self, other: &Self) -> Result<Self, error::Unspecified> {
+        if other.curve != self.curve {
+            return Err(error::Unspecified);
+        }
+        let value = self
+            .curve
+            .common()
+            .point_sum(&self.value, &other.value, cpu::features());
+        Self::new(self.curve, value)
+    }
+
+    /// Writes the uncompressed encoding `0x04 || x || y` of the point into
+    /// `out`.
+    ///
+    /// Fails if `out` isn't exactly `self.curve().uncompressed_point_len()`
+    /// bytes.
+    pub fn write_uncompressed(&self, out: &mut [u8]) -> Result<(), error::Unspecified> {
+        if out.len() != self.curve.uncompressed_point_len() {
+            return Err(error::Unspecified);
+        }
+        let ops = self.curve.private_key_ops;
+        let (x, y) = affine_from_jacobian(ops, &self.value, cpu::features())?;
+        let (x, y) = (ops.common.elem_unencoded(&x), ops.common.elem_unencoded(&y));
+        let (encoding, xy_out) = out.split_first_mut().ok_or(error::Unspecified)?;
+        *encoding = 4;
+        let (x_out, y_out) = xy_out.split_at_mut(ops.common.len());
+        limb::big_endian_from_limbs(ops.leak_limbs(&x), x_out);
+        limb::big_endian_from_limbs(ops.leak_limbs(&y), y_out);
+        Ok(())
+    }
+
+    // Rejects the point at infinity, which is the only point with Z == 0.
+    fn new(curve: &'static Curve, value: ops::Point) -> Result<Self, error::Unspecified> {
+        let common = curve.common();
+        common.elem_verify_is_not_zero(&common.point_z(&value))?;
+        Ok(Self { curve, value })
+    }
+}
+
+impl core::fmt::Debug for Point {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Point")
+            .field("curve", &self.curve)
+            .finish_non_exhaustive()
+    }
+}
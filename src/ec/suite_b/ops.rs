@@ -139,6 +139,104 @@ impl CommonOps {
         }
     }
 
+    pub fn leak_limbs<'a>(&self, a: &'a Elem<Unencoded>) -> &'a [Limb] {
+        &a.limbs[..self.num_limbs]
+    }
+
+    // Returns q - a, or zero if `a` is zero.
+    pub fn elem_negated(&self, a: &Elem<Unencoded>) -> Elem<Unencoded> {
+        let num_limbs = self.num_limbs;
+        let mut r = Elem::zero();
+        let mut borrow: Limb = 0;
+        r.limbs[..num_limbs]
+            .iter_mut()
+            .zip(
+                self.q.p[..num_limbs]
+                    .iter()
+                    .zip(a.limbs[..num_limbs].iter()),
+            )
+            .for_each(|(r, (&q, &a))| {
+                let (d, b1) = q.overflowing_sub(a);
+                let (d, b2) = d.overflowing_sub(borrow);
+                *r = d;
+                borrow = Limb::from(b1 | b2);
+            });
+        debug_assert_eq!(borrow, 0);
+        limbs_reduce_once_constant_time(&mut r.limbs[..num_limbs], &self.q.p[..num_limbs]);
+        r
+    }
+
+    // Returns a square root of `a`, or an error if `a` is not a square.
+    //
+    // q ≡ 3 (mod 4) for both P-256 and P-384, so a square root of `a`, if one
+    // exists, is a**((q + 1) / 4) (mod q). The exponent is public so this is
+    // constant-time with respect to `a`.
+    pub fn elem_sqrt(&self, a: &Elem<R>) -> Result<Elem<R>, error::Unspecified> {
+        let num_limbs = self.num_limbs;
+        let q = &self.q.p[..num_limbs];
+        debug_assert_eq!(q[0] & 3, 3);
+
+        // Since q ≡ 3 (mod 4), (q + 1) / 4 == (q >> 2) + 1.
+        let mut exponent = [0; MAX_LIMBS];
+        let exponent = &mut exponent[..num_limbs];
+        for i in 0..num_limbs {
+            let hi = q.get(i + 1).map_or(0, |&hi| hi << (LIMB_BITS - 2));
+            exponent[i] = (q[i] >> 2) | hi;
+        }
+        for limb in exponent.iter_mut() {
+            let (sum, carry) = limb.overflowing_add(1);
+            *limb = sum;
+            if !carry {
+                break;
+            }
+        }
+
+        // Left-to-right binary exponentiation, starting after the most
+        // significant set bit of the exponent.
+        let mut acc: Option<Elem<R>> = None;
+        for &limb in exponent.iter().rev() {
+            for bit in (0..LIMB_BITS).rev() {
+                let bit_is_set = (limb >> bit) & 1 == 1;
+                acc = match (acc, bit_is_set) {
+                    (None, false) => None,
+                    (None, true) => Some(*a),
+                    (Some(mut acc), bit_is_set) => {
+                        self.elem_square(&mut acc);
+                        if bit_is_set {
+                            self.elem_mul(&mut acc, a);
+                        }
+                        Some(acc)
+                    }
+                };
+            }
+        }
+        let root = acc.ok_or(error::Unspecified)?;
+
+        if self.elems_are_equal(&self.elem_squared(&root), a) != LimbMask::True {
+            return Err(error::Unspecified);
+        }
+        Ok(root)
+    }
+
+    /// Returns the Jacobian point (x, y, 1) for the affine point (x, y).
+    pub(super) fn point_from_affine(&self, (x, y): &(Elem<R>, Elem<R>)) -> Point {
+        const ONE: Elem<Unencoded> = Elem::from_hex("1");
+        let mut z: Elem<R> = Elem::zero();
+        // Montgomery encode (elem_to_mont).
+        unsafe {
+            (self.elem_mul_mont)(z.limbs.as_mut_ptr(), ONE.limbs.as_ptr(), self.q.rr.as_ptr())
+        }
+
+        let num_limbs = self.num_limbs;
+        let mut r = Point::new_at_infinity();
+        let (r_x, r_yz) = r.xyz.split_at_mut(num_limbs);
+        let (r_y, r_z) = r_yz.split_at_mut(num_limbs);
+        r_x.copy_from_slice(&x.limbs[..num_limbs]);
+        r_y.copy_from_slice(&y.limbs[..num_limbs]);
+        r_z[..num_limbs].copy_from_slice(&z.limbs[..num_limbs]);
+        r
+    }
+
     pub(super) fn point_sum(&self, a: &Point, b: &Point, _cpu: cpu::Features) -> Point {
         let mut r = Point::new_at_infinity();
         unsafe {
@@ -425,6 +523,14 @@ pub fn scalar_parse_big_endian_fixed_consttime(
     parse_big_endian_fixed_consttime(ops, bytes, AllowZero::No, &ops.n.limbs[..ops.num_limbs])
 }
 
+#[inline]
+pub fn scalar_parse_big_endian_fixed_consttime_allow_zero(
+    ops: &CommonOps,
+    bytes: untrusted::Input,
+) -> Result<Scalar, error::Unspecified> {
+    parse_big_endian_fixed_consttime(ops, bytes, AllowZero::Yes, &ops.n.limbs[..ops.num_limbs])
+}
+
 #[inline]
 pub fn scalar_parse_big_endian_variable(
     ops: &CommonOps,
@@ -16,7 +16,7 @@
 //! ECDH agreement).
 
 use super::{ops::*, verify_affine_point_is_on_the_curve};
-use crate::{arithmetic::montgomery::*, cpu, error};
+use crate::{arithmetic::montgomery::*, cpu, ec, error, limb};
 
 /// Parses a public key encoded in uncompressed form. The key is validated
 /// using the ECC Partial Public-Key Validation Routine from
@@ -65,6 +65,69 @@ pub(super) fn parse_uncompressed_point(
     Ok((x, y))
 }
 
+/// Parses a public key encoded in either compressed or uncompressed form,
+/// validates it as `parse_uncompressed_point` does, and writes its
+/// uncompressed encoding into `out`, returning the length of the encoding.
+///
+/// Compressed points are decompressed as described in [SEC 1] Section 2.3.4.
+///
+/// [SEC 1]: https://www.secg.org/sec1-v2.pdf
+pub(super) fn parse_point_to_uncompressed(
+    ops: &PublicKeyOps,
+    input: untrusted::Input,
+    out: &mut [u8; ec::PUBLIC_KEY_MAX_LEN],
+    cpu: cpu::Features,
+) -> Result<usize, error::Unspecified> {
+    let common = ops.common;
+    let elem_len = common.len();
+    let len = 1 + (2 * elem_len);
+
+    if input.as_slice_less_safe().first() == Some(&4) {
+        let _ = parse_uncompressed_point(ops, input, cpu)?;
+        out[..len].copy_from_slice(input.as_slice_less_safe());
+        return Ok(len);
+    }
+
+    let (y_is_odd, x) = input.read_all(error::Unspecified, |input| {
+        let y_is_odd = match input.read_byte()? {
+            2 => false,
+            3 => true,
+            _ => return Err(error::Unspecified),
+        };
+        let x = ops.elem_parse(input, cpu)?;
+        Ok((y_is_odd, x))
+    })?;
+
+    // y**2 == (x**2 + a)*x + b
+    let mut y_squared = common.elem_squared(&x);
+    common.elem_add(&mut y_squared, &common.a);
+    common.elem_mul(&mut y_squared, &x);
+    common.elem_add(&mut y_squared, &common.b);
+
+    let y = common.elem_sqrt(&y_squared)?;
+    let mut y = common.elem_unencoded(&y);
+    let parity = common.leak_limbs(&y)[0] & 1;
+    if (parity == 1) != y_is_odd {
+        // When y is zero, -y is also zero, which has the wrong parity.
+        if common.is_zero(&y) {
+            return Err(error::Unspecified);
+        }
+        y = common.elem_negated(&y);
+    }
+
+    let x = common.elem_unencoded(&x);
+    out[0] = 4;
+    let (x_out, y_out) = out[1..len].split_at_mut(elem_len);
+    limb::big_endian_from_limbs(common.leak_limbs(&x), x_out);
+    limb::big_endian_from_limbs(common.leak_limbs(&y), y_out);
+
+    // The decompressed point is on the curve by construction, but re-validate
+    // it anyway since that is cheap in comparison to the square root.
+    let _ = parse_uncompressed_point(ops, untrusted::Input::from(&out[..len]), cpu)?;
+
+    Ok(len)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
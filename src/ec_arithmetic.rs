@@ -0,0 +1,50 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Scalar and point arithmetic on the NIST P-256 and P-384 curves.
+//!
+//! This is a low-level interface for implementing protocols, e.g. Schnorr
+//! signatures or threshold schemes, that *ring* doesn't provide. Prefer the
+//! higher-level `agreement` and `signature` APIs whenever possible; they
+//! handle the many subtle details that these building blocks leave to the
+//! caller.
+//!
+//! The operations use the same constant-time implementations that ECDH and
+//! ECDSA use, so the only thing that is leaked about a secret scalar is
+//! whether an operation failed because the result would have been the point
+//! at infinity, which can't be represented as a `Point`.
+//!
+//! ```
+//! use ring::ec_arithmetic::{Point, Scalar, P256};
+//!
+//! let mut two = [0u8; 32];
+//! two[31] = 2;
+//! let two = Scalar::from_big_endian_bytes(&P256, &two)?;
+//! let mut one = [0u8; 32];
+//! one[31] = 1;
+//! let one = Scalar::from_big_endian_bytes(&P256, &one)?;
+//!
+//! let g = Point::base_mul(&one)?;
+//! let two_g = Point::base_mul(&two)?;
+//!
+//! let mut a = [0u8; 65];
+//! let mut b = [0u8; 65];
+//! g.mul(&two)?.write_uncompressed(&mut a)?;
+//! two_g.write_uncompressed(&mut b)?;
+//! assert_eq!(a, b);
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+
+pub use crate::ec::suite_b::group::{validate_private_scalar, Curve, Point, Scalar, P256, P384};
@@ -70,6 +70,12 @@ extern crate std;
 /// cause of a failure. Users of *ring* are encouraged to report such cases so
 /// that they can be addressed individually.
 ///
+/// In particular, there is no Cargo feature that replaces `Unspecified` with a
+/// more detailed error type. Cargo features are unified across the whole
+/// dependency graph, so such a feature enabled by any one crate would change
+/// the signatures of *ring*'s functions, and break the code, of every other
+/// crate that uses *ring* in the same build.
+///
 /// [`std::error::Error`]: https://doc.rust-lang.org/std/error/trait.Error.html
 /// [“Error Handling” in the Rust Book]:
 ///     https://doc.rust-lang.org/book/first-edition/error-handling.html#the-from-trait
@@ -127,6 +133,9 @@ impl From<core::array::TryFromSliceError> for Unspecified {
 ///  * Wrong algorithm: The key is not valid for the algorithm in which it was
 ///    being used.
 ///
+///  * Encrypted keys: The key is encrypted in a way that isn't supported, or
+///    it couldn't be decrypted, e.g. because the passphrase is wrong.
+///
 ///  * Unexpected errors: Report this as a bug.
 #[derive(Copy, Clone, Debug)]
 pub struct KeyRejected(&'static str);
@@ -136,6 +145,11 @@ impl KeyRejected {
         Self("InconsistentComponents")
     }
 
+    #[cfg(feature = "alloc")]
+    pub(crate) fn inconsistent_crt_components() -> Self {
+        Self("InconsistentCrtComponents")
+    }
+
     pub(crate) fn invalid_component() -> Self {
         Self("InvalidComponent")
     }
@@ -145,6 +159,15 @@ impl KeyRejected {
         Self("InvalidEncoding")
     }
 
+    pub(crate) fn encrypted_keys_not_supported() -> Self {
+        Self("EncryptedKeysNotSupported")
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn decryption_failed() -> Self {
+        Self("DecryptionFailed")
+    }
+
     // XXX: See the comment at the call site.
     pub(crate) fn rng_failed() -> Self {
         Self("RNG failed")
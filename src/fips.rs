@@ -0,0 +1,352 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Known-answer self-tests that can be run on demand.
+//!
+//! FIPS 140-3 requires a cryptographic module to test its algorithms before
+//! they are used. `run_self_tests()` runs known-answer tests for the
+//! algorithms that such operational environments most commonly require, so
+//! that an application can refuse to start if any of them fail. These tests
+//! are not a replacement for *ring*'s test suite; they only check that the
+//! implementations selected for the current CPU produce the expected results
+//! for a few fixed inputs.
+//!
+//! *ring* is not FIPS validated; running these tests doesn't change that.
+
+#[cfg(feature = "std")]
+extern crate std;
+
+use crate::{aead, digest, error, hmac, rand, signature};
+
+/// A self-test failed.
+///
+/// The `Display` implementation returns the name of the test that failed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelfTestError(&'static str);
+
+impl SelfTestError {
+    /// The name of the test that failed.
+    pub fn test_name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl core::fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SelfTestError {}
+
+impl From<SelfTestError> for error::Unspecified {
+    fn from(_: SelfTestError) -> Self {
+        Self
+    }
+}
+
+/// Runs all the self-tests, stopping at the first failure.
+///
+/// The tests are:
+///
+/// * AES-128-GCM and AES-256-GCM sealing and opening, including the
+///   rejection of a tampered tag.
+/// * SHA-256, SHA-384, and SHA-512.
+/// * HMAC-SHA-256, HMAC-SHA-384, and HMAC-SHA-512.
+/// * ECDSA P-256 with SHA-256 signing, with a fixed nonce, and verification,
+///   including the rejection of a tampered signature.
+/// * A health test of `rand::SystemRandom`. *ring* doesn't implement a DRBG
+///   itself; it uses the operating system's random number generator, which
+///   can't be tested against known answers. Instead, this test fails if two
+///   consecutive outputs are equal.
+pub fn run_self_tests() -> Result<(), SelfTestError> {
+    aes_gcm_self_tests()?;
+    digest_self_tests()?;
+    hmac_self_tests()?;
+    ecdsa_self_tests()?;
+    rng_self_tests()
+}
+
+fn check(name: &'static str, passed: bool) -> Result<(), SelfTestError> {
+    if passed {
+        Ok(())
+    } else {
+        Err(SelfTestError(name))
+    }
+}
+
+fn aes_gcm_self_tests() -> Result<(), SelfTestError> {
+    {
+        const KEY: [u8; 16] = [
+            0xcd, 0xbc, 0x90, 0xe6, 0x0a, 0xab, 0x79, 0x05, 0xbd, 0xff, 0xdf, 0xd8, 0xd1, 0x3c,
+            0x01, 0x38,
+        ];
+        const NONCE: [u8; 12] = [
+            0x9d, 0x98, 0x71, 0x84, 0xc4, 0xb4, 0xe8, 0x73, 0xd4, 0x77, 0x49, 0x31,
+        ];
+        const AD: [u8; 15] = [
+            0x7d, 0xc7, 0x9f, 0x38, 0xe1, 0xdf, 0x93, 0x83, 0xe5, 0xd3, 0xa1, 0x37, 0x8b, 0x56,
+            0xef,
+        ];
+        const PLAINTEXT: [u8; 15] = [
+            0xcb, 0x75, 0xa0, 0xf9, 0x13, 0x4c, 0x57, 0x9b, 0xeb, 0xbd, 0x27, 0xfe, 0x4a, 0x30,
+            0x11,
+        ];
+        const CIPHERTEXT: [u8; 15] = [
+            0xc6, 0xa8, 0x99, 0x75, 0x8b, 0x6c, 0x11, 0x20, 0x82, 0x41, 0x62, 0x7c, 0x8a, 0x00,
+            0x96,
+        ];
+        const TAG: [u8; 16] = [
+            0x75, 0x25, 0x12, 0x5e, 0x65, 0x0d, 0x39, 0x7d, 0x0e, 0x17, 0x6f, 0xa2, 0x13, 0x15,
+            0xf0, 0x9a,
+        ];
+
+        aead_self_test(
+            "AES-128-GCM",
+            &aead::AES_128_GCM,
+            &KEY,
+            NONCE,
+            &AD,
+            &PLAINTEXT,
+            &CIPHERTEXT,
+            &TAG,
+        )?;
+    }
+
+    {
+        const KEY: [u8; 32] = [
+            0x80, 0xe2, 0xe5, 0x61, 0x88, 0x6e, 0xb2, 0xa9, 0x53, 0xcf, 0x92, 0x3a, 0xaa, 0xc1,
+            0x65, 0x3e, 0xd2, 0xdb, 0x01, 0x11, 0xee, 0x62, 0xe0, 0x9c, 0xb2, 0x0d, 0x9e, 0x26,
+            0x52, 0xbd, 0x34, 0x76,
+        ];
+        const NONCE: [u8; 12] = [
+            0x5d, 0xaf, 0x20, 0x15, 0x89, 0x65, 0x4d, 0xa8, 0x88, 0x4c, 0x3c, 0x68,
+        ];
+        const AD: [u8; 10] = [0xe5, 0x1e, 0x5b, 0xce, 0x7c, 0xbc, 0xeb, 0x66, 0x03, 0x99];
+        const PLAINTEXT: [u8; 10] = [0x96, 0x66, 0x9d, 0x2d, 0x35, 0x42, 0xa4, 0xd4, 0x9c, 0x7c];
+        const CIPHERTEXT: [u8; 10] = [0x45, 0x21, 0x95, 0x3e, 0x7d, 0x39, 0x49, 0x7e, 0x45, 0x63];
+        const TAG: [u8; 16] = [
+            0x20, 0x83, 0xe3, 0xc0, 0xd8, 0x4d, 0x66, 0x30, 0x66, 0xbb, 0xe2, 0x96, 0x1b, 0x08,
+            0xdc, 0xf7,
+        ];
+
+        aead_self_test(
+            "AES-256-GCM",
+            &aead::AES_256_GCM,
+            &KEY,
+            NONCE,
+            &AD,
+            &PLAINTEXT,
+            &CIPHERTEXT,
+            &TAG,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn aead_self_test(
+    name: &'static str,
+    algorithm: &'static aead::Algorithm,
+    key: &[u8],
+    nonce: [u8; aead::NONCE_LEN],
+    ad: &[u8],
+    plaintext: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> Result<(), SelfTestError> {
+    const MAX_LEN: usize = 16;
+
+    let key = aead::UnboundKey::new(algorithm, key).map_err(|_| SelfTestError(name))?;
+    let key = aead::LessSafeKey::new(key);
+
+    let mut buf = [0u8; MAX_LEN + aead::MAX_TAG_LEN];
+    let in_out = &mut buf[..plaintext.len()];
+    in_out.copy_from_slice(plaintext);
+    let actual_tag = key
+        .seal_in_place_separate_tag(
+            aead::Nonce::assume_unique_for_key(nonce),
+            aead::Aad::from(ad),
+            in_out,
+        )
+        .map_err(|_| SelfTestError(name))?;
+    check(name, in_out == ciphertext && actual_tag.as_ref() == tag)?;
+
+    let mut buf = [0u8; MAX_LEN + aead::MAX_TAG_LEN];
+    let in_out = &mut buf[..(ciphertext.len() + tag.len())];
+    in_out[..ciphertext.len()].copy_from_slice(ciphertext);
+    in_out[ciphertext.len()..].copy_from_slice(tag);
+    let opened = key
+        .open_in_place(
+            aead::Nonce::assume_unique_for_key(nonce),
+            aead::Aad::from(ad),
+            in_out,
+        )
+        .map_err(|_| SelfTestError(name))?;
+    check(name, opened == plaintext)?;
+
+    in_out[ciphertext.len()..].copy_from_slice(tag);
+    in_out[..ciphertext.len()].copy_from_slice(ciphertext);
+    in_out[ciphertext.len()] ^= 1;
+    let tampered = key.open_in_place(
+        aead::Nonce::assume_unique_for_key(nonce),
+        aead::Aad::from(ad),
+        in_out,
+    );
+    check(name, tampered.is_err())
+}
+
+fn digest_self_tests() -> Result<(), SelfTestError> {
+    const INPUT: &[u8] = b"abc";
+
+    {
+        const EXPECTED: [u8; 32] = [
+            0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae,
+            0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61,
+            0xf2, 0x00, 0x15, 0xad,
+        ];
+        check(
+            "SHA-256",
+            digest::digest(&digest::SHA256, INPUT).as_ref() == EXPECTED,
+        )?;
+    }
+
+    {
+        const EXPECTED: [u8; 48] = [
+            0xcb, 0x00, 0x75, 0x3f, 0x45, 0xa3, 0x5e, 0x8b, 0xb5, 0xa0, 0x3d, 0x69, 0x9a, 0xc6,
+            0x50, 0x07, 0x27, 0x2c, 0x32, 0xab, 0x0e, 0xde, 0xd1, 0x63, 0x1a, 0x8b, 0x60, 0x5a,
+            0x43, 0xff, 0x5b, 0xed, 0x80, 0x86, 0x07, 0x2b, 0xa1, 0xe7, 0xcc, 0x23, 0x58, 0xba,
+            0xec, 0xa1, 0x34, 0xc8, 0x25, 0xa7,
+        ];
+        check(
+            "SHA-384",
+            digest::digest(&digest::SHA384, INPUT).as_ref() == EXPECTED,
+        )?;
+    }
+
+    {
+        const EXPECTED: [u8; 64] = [
+            0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba, 0xcc, 0x41, 0x73, 0x49, 0xae, 0x20,
+            0x41, 0x31, 0x12, 0xe6, 0xfa, 0x4e, 0x89, 0xa9, 0x7e, 0xa2, 0x0a, 0x9e, 0xee, 0xe6,
+            0x4b, 0x55, 0xd3, 0x9a, 0x21, 0x92, 0x99, 0x2a, 0x27, 0x4f, 0xc1, 0xa8, 0x36, 0xba,
+            0x3c, 0x23, 0xa3, 0xfe, 0xeb, 0xbd, 0x45, 0x4d, 0x44, 0x23, 0x64, 0x3c, 0xe8, 0x0e,
+            0x2a, 0x9a, 0xc9, 0x4f, 0xa5, 0x4c, 0xa4, 0x9f,
+        ];
+        check(
+            "SHA-512",
+            digest::digest(&digest::SHA512, INPUT).as_ref() == EXPECTED,
+        )?;
+    }
+
+    Ok(())
+}
+
+// RFC 4231 Test Case 2.
+fn hmac_self_tests() -> Result<(), SelfTestError> {
+    const KEY: &[u8] = b"Jefe";
+    const INPUT: &[u8] = b"what do ya want for nothing?";
+
+    fn hmac_self_test(
+        name: &'static str,
+        algorithm: hmac::Algorithm,
+        expected: &[u8],
+    ) -> Result<(), SelfTestError> {
+        let key = hmac::Key::new(algorithm, KEY);
+        check(name, hmac::sign(&key, INPUT).as_ref() == expected)?;
+        check(name, hmac::verify(&key, INPUT, expected).is_ok())
+    }
+
+    {
+        const EXPECTED: [u8; 32] = [
+            0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+            0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+            0x64, 0xec, 0x38, 0x43,
+        ];
+        hmac_self_test("HMAC-SHA-256", hmac::HMAC_SHA256, &EXPECTED)?;
+    }
+
+    {
+        const EXPECTED: [u8; 48] = [
+            0xaf, 0x45, 0xd2, 0xe3, 0x76, 0x48, 0x40, 0x31, 0x61, 0x7f, 0x78, 0xd2, 0xb5, 0x8a,
+            0x6b, 0x1b, 0x9c, 0x7e, 0xf4, 0x64, 0xf5, 0xa0, 0x1b, 0x47, 0xe4, 0x2e, 0xc3, 0x73,
+            0x63, 0x22, 0x44, 0x5e, 0x8e, 0x22, 0x40, 0xca, 0x5e, 0x69, 0xe2, 0xc7, 0x8b, 0x32,
+            0x39, 0xec, 0xfa, 0xb2, 0x16, 0x49,
+        ];
+        hmac_self_test("HMAC-SHA-384", hmac::HMAC_SHA384, &EXPECTED)?;
+    }
+
+    {
+        const EXPECTED: [u8; 64] = [
+            0x16, 0x4b, 0x7a, 0x7b, 0xfc, 0xf8, 0x19, 0xe2, 0xe3, 0x95, 0xfb, 0xe7, 0x3b, 0x56,
+            0xe0, 0xa3, 0x87, 0xbd, 0x64, 0x22, 0x2e, 0x83, 0x1f, 0xd6, 0x10, 0x27, 0x0c, 0xd7,
+            0xea, 0x25, 0x05, 0x54, 0x97, 0x58, 0xbf, 0x75, 0xc0, 0x5a, 0x99, 0x4a, 0x6d, 0x03,
+            0x4f, 0x65, 0xf8, 0xf0, 0xe6, 0xfd, 0xca, 0xea, 0xb1, 0xa3, 0x4d, 0x4a, 0x6b, 0x4b,
+            0x63, 0x6e, 0x07, 0x0a, 0x38, 0xbc, 0xe7, 0x37,
+        ];
+        hmac_self_test("HMAC-SHA-512", hmac::HMAC_SHA512, &EXPECTED)?;
+    }
+
+    Ok(())
+}
+
+fn ecdsa_self_tests() -> Result<(), SelfTestError> {
+    use crate::ec::suite_b::ecdsa::signing::p256_sha256_known_answer_test::{
+        self, MESSAGE, PUBLIC_KEY, SIGNATURE,
+    };
+
+    const SIGN: &str = "ECDSA-P256-SHA256 sign";
+    const VERIFY: &str = "ECDSA-P256-SHA256 verify";
+
+    let actual = p256_sha256_known_answer_test::sign().map_err(|_| SelfTestError(SIGN))?;
+    check(SIGN, actual.as_ref() == SIGNATURE)?;
+
+    let public_key =
+        signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, &PUBLIC_KEY);
+    check(VERIFY, public_key.verify(&MESSAGE, &SIGNATURE).is_ok())?;
+
+    let mut tampered = SIGNATURE;
+    tampered[tampered.len() - 1] ^= 1;
+    check(VERIFY, public_key.verify(&MESSAGE, &tampered).is_err())
+}
+
+fn rng_self_tests() -> Result<(), SelfTestError> {
+    const NAME: &str = "RNG";
+
+    let rng = rand::SystemRandom::new();
+    let a: [u8; 32] = rand::generate(&rng)
+        .map_err(|error::Unspecified| SelfTestError(NAME))?
+        .expose();
+    let b: [u8; 32] = rand::generate(&rng)
+        .map_err(|error::Unspecified| SelfTestError(NAME))?
+        .expose();
+    check(NAME, a != b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_self_tests_test() {
+        assert_eq!(run_self_tests(), Ok(()));
+    }
+
+    #[test]
+    fn self_test_error_test() {
+        let err = SelfTestError("SHA-256");
+        assert_eq!(err.test_name(), "SHA-256");
+        assert_eq!(alloc::format!("{}", err), "SHA-256");
+    }
+}
@@ -80,6 +80,29 @@ impl Salt {
         Prk(hmac::Key::new(salt.algorithm(), prk.as_ref()))
     }
 
+    /// The [HKDF-Extract] operation, where the input keying material is the
+    /// concatenation of `secrets`.
+    ///
+    /// This combines several secrets into one PRK, as in hybrid key
+    /// exchanges that combine a classical and a post-quantum shared secret.
+    /// For example, the TLS 1.3 hybrid design concatenates the shared secrets
+    /// in the order of the key shares and uses the result as the IKM of
+    /// HKDF-Extract; that is `salt.extract_concatenated(&[ecdhe, pq])`.
+    ///
+    /// The result is the same as `self.extract(&[secrets...].concat())`, but
+    /// no copy of the secrets is made. Since the secrets are concatenated
+    /// without any framing, the order of the secrets matters, and callers
+    /// must ensure that the lengths of the secrets are fixed by context.
+    ///
+    /// [HKDF-Extract]: https://tools.ietf.org/html/rfc5869#section-2.2
+    pub fn extract_concatenated(&self, secrets: &[&[u8]]) -> Prk {
+        let salt = &self.0;
+        let mut ctx = hmac::Context::with_key(salt);
+        secrets.iter().for_each(|secret| ctx.update(secret));
+        let prk = ctx.sign();
+        Prk(hmac::Key::new(salt.algorithm(), prk.as_ref()))
+    }
+
     /// The algorithm used to derive this salt.
     #[inline]
     pub fn algorithm(&self) -> Algorithm {
@@ -87,6 +110,19 @@ impl Salt {
     }
 }
 
+/// Uses an HMAC key as the key for the HKDF-Extract step.
+///
+/// HKDF-Extract is HMAC keyed with the salt, so the resulting `Salt` is
+/// equivalent to `Salt::new(algorithm, key_value)`, where `algorithm` is the
+/// HKDF algorithm for `key.algorithm()` and `key_value` is the value that
+/// `key` was constructed from. This allows a key schedule to feed an HMAC key
+/// into HKDF without the caller keeping a separate copy of the raw key value.
+impl From<hmac::Key> for Salt {
+    fn from(key: hmac::Key) -> Self {
+        Self(key)
+    }
+}
+
 impl From<Okm<'_, Algorithm>> for Salt {
     fn from(okm: Okm<'_, Algorithm>) -> Self {
         Self(hmac::Key::from(Okm {
@@ -130,9 +166,7 @@ impl Prk {
         len: L,
     ) -> Result<Okm<'a, L>, error::Unspecified> {
         let len_cached = len.len();
-        if len_cached > 255 * self.0.algorithm().digest_algorithm().output_len() {
-            return Err(error::Unspecified);
-        }
+        self.check_len(len_cached)?;
         Ok(Okm {
             prk: self,
             info,
@@ -140,6 +174,17 @@ impl Prk {
             len_cached,
         })
     }
+
+    pub(crate) fn algorithm(&self) -> Algorithm {
+        Algorithm(self.0.algorithm())
+    }
+
+    fn check_len(&self, len: usize) -> Result<(), error::Unspecified> {
+        if len > 255 * self.0.algorithm().digest_algorithm().output_len() {
+            return Err(error::Unspecified);
+        }
+        Ok(())
+    }
 }
 
 impl From<Okm<'_, Algorithm>> for Prk {
@@ -153,6 +198,79 @@ impl From<Okm<'_, Algorithm>> for Prk {
     }
 }
 
+/// Derives subkeys from a `Prk` and remembers them, so that looking up the
+/// same subkey again doesn't repeat the HKDF-Expand operation.
+///
+/// Lookups take `&mut self`, so a `DerivedKeyCache` can't be shared between
+/// threads without external synchronization, e.g. a `Mutex`.
+///
+/// The cached subkeys are kept in memory until the cache is dropped and
+/// aren't zeroized.
+///
+/// ```
+/// use ring::hkdf;
+///
+/// let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, b"salt").extract(b"secret");
+/// let mut cache = hkdf::DerivedKeyCache::new(prk);
+/// let key = cache.get(&[b"key"], 16)?.to_vec();
+/// let iv = cache.get(&[b"iv"], 12)?.to_vec();
+/// assert_eq!(cache.get(&[b"key"], 16)?, &key[..]);
+/// # Ok::<(), ring::error::Unspecified>(())
+/// ```
+#[cfg(feature = "alloc")]
+pub struct DerivedKeyCache {
+    prk: Prk,
+    // Keyed by the concatenated `info` and the length.
+    cache: alloc::collections::BTreeMap<(alloc::vec::Vec<u8>, usize), alloc::boxed::Box<[u8]>>,
+}
+
+#[cfg(feature = "alloc")]
+derive_debug_via_field!(DerivedKeyCache, prk);
+
+#[cfg(feature = "alloc")]
+impl DerivedKeyCache {
+    /// Constructs an empty cache of subkeys of `prk`.
+    pub fn new(prk: Prk) -> Self {
+        Self {
+            prk,
+            cache: alloc::collections::BTreeMap::new(),
+        }
+    }
+
+    /// Returns the output of `prk.expand(info, len)`, computing it only if it
+    /// hasn't been computed already.
+    ///
+    /// As in HKDF, the elements of `info` are concatenated, so `&[b"a", b"b"]`
+    /// and `&[b"ab"]` give the same subkey.
+    ///
+    /// Fails if (and only if) `len` is too large.
+    pub fn get(&mut self, info: &[&[u8]], len: usize) -> Result<&[u8], error::Unspecified> {
+        use alloc::collections::btree_map::Entry;
+
+        let cache_key = (info.concat(), len);
+        let okm = match self.cache.entry(cache_key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                // Check `len` before allocating the output.
+                self.prk.check_len(len)?;
+                let mut okm = alloc::vec![0u8; len].into_boxed_slice();
+                self.prk.expand(info, Len(len))?.fill(&mut okm)?;
+                entry.insert(okm)
+            }
+        };
+        Ok(okm)
+    }
+}
+
+/// A `KeyType` that is just a length, for expanding to a buffer.
+pub(crate) struct Len(pub(crate) usize);
+
+impl KeyType for Len {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
 /// An HKDF OKM (Output Keying Material)
 ///
 /// Intentionally not `Clone` or `Copy` as an OKM is generally only safe to
@@ -185,6 +303,41 @@ impl<L: KeyType> Okm<'_, L> {
     }
 }
 
+/// Fills each of the slices in `outs`, in order, from a single HKDF-Expand
+/// operation.
+///
+/// The result is the same as expanding to the total length of `outs` and then
+/// splitting the output; e.g. an encryption key, an IV, and a MAC key can be
+/// derived from one PRK with the boundaries between them explicit:
+///
+/// ```
+/// use ring::hkdf;
+///
+/// let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, b"salt").extract(b"secret");
+/// let mut enc_key = [0u8; 32];
+/// let mut iv = [0u8; 12];
+/// let mut mac_key = [0u8; 32];
+/// hkdf::expand_into(&prk, &[b"info"], &mut [&mut enc_key, &mut iv, &mut mac_key])?;
+/// # Ok::<(), ring::error::Unspecified>(())
+/// ```
+///
+/// Fails if (and only if) the total length of `outs` is larger than 255 times
+/// the size of the digest algorithm's output.
+pub fn expand_into(
+    prk: &Prk,
+    info: &[&[u8]],
+    outs: &mut [&mut [u8]],
+) -> Result<(), error::Unspecified> {
+    let len = outs
+        .iter()
+        .try_fold(0usize, |acc, out| acc.checked_add(out.len()))
+        .ok_or(error::Unspecified)?;
+    if len > 255 * prk.0.algorithm().digest_algorithm().output_len() {
+        return Err(error::Unspecified);
+    }
+    fill_okm_split(prk, info, outs, len)
+}
+
 fn fill_okm(
     prk: &Prk,
     info: &[&[u8]],
@@ -194,14 +347,25 @@ fn fill_okm(
     if out.len() != len {
         return Err(error::Unspecified);
     }
+    fill_okm_split(prk, info, &mut [out], len)
+}
 
+// `len` must be the total length of `outs`.
+fn fill_okm_split(
+    prk: &Prk,
+    info: &[&[u8]],
+    outs: &mut [&mut [u8]],
+    len: usize,
+) -> Result<(), error::Unspecified> {
     let digest_alg = prk.0.algorithm().digest_algorithm();
     assert!(digest_alg.block_len() >= digest_alg.output_len());
 
+    let mut out = outs.iter_mut().flat_map(|out| out.iter_mut());
+    let mut remaining = len;
+
     let mut ctx = hmac::Context::with_key(&prk.0);
 
     let mut n = 1u8;
-    let mut out = out;
     loop {
         for info in info {
             ctx.update(info);
@@ -211,18 +375,16 @@ fn fill_okm(
         let t = ctx.sign();
         let t = t.as_ref();
 
-        // Append `t` to the output.
-        out = if out.len() < digest_alg.output_len() {
-            let len = out.len();
-            out.copy_from_slice(&t[..len]);
-            &mut []
-        } else {
-            let (this_chunk, rest) = out.split_at_mut(digest_alg.output_len());
-            this_chunk.copy_from_slice(t);
-            rest
-        };
+        // Append `t` to the output. `t` is zipped first so that no output
+        // byte is skipped when `t` is exhausted.
+        let chunk_len = core::cmp::min(remaining, t.len());
+        t[..chunk_len]
+            .iter()
+            .zip(out.by_ref())
+            .for_each(|(t, out)| *out = *t);
+        remaining -= chunk_len;
 
-        if out.is_empty() {
+        if remaining == 0 {
             return Ok(());
         }
 
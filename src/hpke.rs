@@ -0,0 +1,740 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Hybrid Public Key Encryption (HPKE), as specified in [RFC 9180].
+//!
+//! Only the base mode (`mode_base`) is supported; the PSK and authenticated
+//! modes aren't. The supported KEMs are DHKEM(X25519, HKDF-SHA256) and
+//! DHKEM(P-256, HKDF-SHA256).
+//!
+//! ```
+//! use ring::{aead, hpke, rand};
+//!
+//! let rng = rand::SystemRandom::new();
+//! let suite = hpke::Suite::new(
+//!     &hpke::DHKEM_X25519_HKDF_SHA256,
+//!     &hpke::HKDF_SHA256,
+//!     &hpke::AES_256_GCM,
+//! );
+//! let info = b"example";
+//!
+//! let recipient_key = hpke::PrivateKey::generate(suite.kem(), &rng)?;
+//!
+//! // The sender.
+//! let (enc, mut sender) =
+//!     hpke::setup_sender(&suite, recipient_key.public_key().as_ref(), info, &rng)?;
+//! let mut in_out = b"hello, world".to_vec();
+//! sender.seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)?;
+//!
+//! // The recipient.
+//! let mut receiver = hpke::setup_receiver(&suite, enc.as_ref(), &recipient_key, info)?;
+//! let plaintext = receiver.open_in_place(aead::Aad::empty(), &mut in_out)?;
+//! assert_eq!(plaintext, b"hello, world");
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+//!
+//! [RFC 9180]: https://www.rfc-editor.org/rfc/rfc9180
+
+use crate::{aead, agreement, cpu, debug, digest, ec, error, hkdf, hmac, rand};
+
+/// A key encapsulation mechanism (KEM).
+pub struct Kem {
+    id: u16,
+    agreement: &'static agreement::Algorithm,
+    derive_private_key:
+        fn(kem: &Kem, dkp_prk: &hkdf::Prk, out: &mut [u8]) -> Result<(), error::Unspecified>,
+}
+
+derive_debug_via_id!(Kem);
+
+impl Kem {
+    /// The key agreement algorithm of the KEM's keys.
+    #[inline]
+    pub fn agreement_algorithm(&self) -> &'static agreement::Algorithm {
+        self.agreement
+    }
+
+    fn suite_id(&self) -> [u8; 5] {
+        let [id_hi, id_lo] = self.id.to_be_bytes();
+        [b'K', b'E', b'M', id_hi, id_lo]
+    }
+}
+
+impl PartialEq for Kem {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Kem {}
+
+/// DHKEM(X25519, HKDF-SHA256).
+pub static DHKEM_X25519_HKDF_SHA256: Kem = Kem {
+    id: 0x0020,
+    agreement: &agreement::X25519,
+    derive_private_key: x25519_derive_private_key,
+};
+
+/// DHKEM(P-256, HKDF-SHA256).
+pub static DHKEM_P256_HKDF_SHA256: Kem = Kem {
+    id: 0x0010,
+    agreement: &agreement::ECDH_P256,
+    derive_private_key: p256_derive_private_key,
+};
+
+/// The KDF that the DHKEMs use internally; this is fixed by the KEM, and is
+/// independent of the suite's `Kdf`.
+static KEM_HKDF: hkdf::Algorithm = hkdf::HKDF_SHA256;
+
+/// The length of the shared secret of each KEM, `Nsecret`.
+const KEM_SHARED_SECRET_LEN: usize = 32;
+
+/// A key derivation function (KDF).
+pub struct Kdf {
+    id: u16,
+    hkdf: hkdf::Algorithm,
+}
+
+derive_debug_via_id!(Kdf);
+
+impl PartialEq for Kdf {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Kdf {}
+
+/// HKDF-SHA256.
+pub static HKDF_SHA256: Kdf = Kdf {
+    id: 0x0001,
+    hkdf: hkdf::HKDF_SHA256,
+};
+
+/// An authenticated encryption with associated data (AEAD) algorithm.
+pub struct Aead {
+    id: u16,
+    aead: &'static aead::Algorithm,
+}
+
+derive_debug_via_id!(Aead);
+
+impl Aead {
+    /// The underlying AEAD algorithm.
+    #[inline]
+    pub fn aead_algorithm(&self) -> &'static aead::Algorithm {
+        self.aead
+    }
+}
+
+impl PartialEq for Aead {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Aead {}
+
+/// AES-128-GCM.
+pub static AES_128_GCM: Aead = Aead {
+    id: 0x0001,
+    aead: &aead::AES_128_GCM,
+};
+
+/// AES-256-GCM.
+pub static AES_256_GCM: Aead = Aead {
+    id: 0x0002,
+    aead: &aead::AES_256_GCM,
+};
+
+/// ChaCha20Poly1305.
+pub static CHACHA20_POLY1305: Aead = Aead {
+    id: 0x0003,
+    aead: &aead::CHACHA20_POLY1305,
+};
+
+/// An HPKE ciphersuite: a combination of a KEM, a KDF, and an AEAD.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Suite {
+    kem: &'static Kem,
+    kdf: &'static Kdf,
+    aead: &'static Aead,
+}
+
+impl Suite {
+    /// Constructs a new ciphersuite.
+    pub fn new(kem: &'static Kem, kdf: &'static Kdf, aead: &'static Aead) -> Self {
+        Self { kem, kdf, aead }
+    }
+
+    /// The suite's KEM.
+    #[inline]
+    pub fn kem(&self) -> &'static Kem {
+        self.kem
+    }
+
+    /// The suite's KDF.
+    #[inline]
+    pub fn kdf(&self) -> &'static Kdf {
+        self.kdf
+    }
+
+    /// The suite's AEAD.
+    #[inline]
+    pub fn aead(&self) -> &'static Aead {
+        self.aead
+    }
+
+    fn suite_id(&self) -> [u8; 10] {
+        let [kem_hi, kem_lo] = self.kem.id.to_be_bytes();
+        let [kdf_hi, kdf_lo] = self.kdf.id.to_be_bytes();
+        let [aead_hi, aead_lo] = self.aead.id.to_be_bytes();
+        [
+            b'H', b'P', b'K', b'E', kem_hi, kem_lo, kdf_hi, kdf_lo, aead_hi, aead_lo,
+        ]
+    }
+}
+
+/// A recipient's private key.
+pub struct PrivateKey {
+    kem: &'static Kem,
+    private_key: agreement::StaticPrivateKey,
+    public_key: agreement::PublicKey,
+}
+
+derive_debug_via_field!(PrivateKey, stringify!(PrivateKey), public_key);
+
+impl PrivateKey {
+    /// Generates a new private key for `kem`.
+    pub fn generate(
+        kem: &'static Kem,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Self, error::Unspecified> {
+        let cpu = cpu::features();
+        let private_key = agreement::StaticPrivateKey::generate(kem.agreement, rng, cpu)?;
+        Self::new(kem, private_key, cpu)
+    }
+
+    /// Derives a private key from the input keying material `ikm`, as in
+    /// `DeriveKeyPair()`.
+    ///
+    /// `ikm` must have at least as much entropy as the private key.
+    pub fn derive(kem: &'static Kem, ikm: &[u8]) -> Result<Self, error::Unspecified> {
+        let dkp_prk = labeled_extract(KEM_HKDF, &kem.suite_id(), b"", b"dkp_prk", &[ikm]);
+        let dkp_prk = hkdf::Prk::new_less_safe(KEM_HKDF, dkp_prk.as_ref());
+        let mut private_key = [0u8; ec::SCALAR_MAX_BYTES];
+        let private_key = &mut private_key[..kem.agreement.curve.elem_scalar_seed_len];
+        (kem.derive_private_key)(kem, &dkp_prk, private_key)?;
+        let cpu = cpu::features();
+        let private_key = agreement::StaticPrivateKey::from_bytes(
+            kem.agreement,
+            untrusted::Input::from(private_key),
+            cpu,
+        )?;
+        Self::new(kem, private_key, cpu)
+    }
+
+    /// Constructs a private key from its serialization, as in
+    /// `DeserializePrivateKey()`.
+    pub fn from_bytes(kem: &'static Kem, bytes: &[u8]) -> Result<Self, error::KeyRejected> {
+        let cpu = cpu::features();
+        let private_key = agreement::StaticPrivateKey::from_bytes(
+            kem.agreement,
+            untrusted::Input::from(bytes),
+            cpu,
+        )
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_component())?;
+        Self::new(kem, private_key, cpu)
+            .map_err(|error::Unspecified| error::KeyRejected::unexpected_error())
+    }
+
+    fn new(
+        kem: &'static Kem,
+        private_key: agreement::StaticPrivateKey,
+        cpu: cpu::Features,
+    ) -> Result<Self, error::Unspecified> {
+        let public_key = private_key.compute_public_key(cpu)?;
+        Ok(Self {
+            kem,
+            private_key,
+            public_key,
+        })
+    }
+
+    /// The KEM that the key is for.
+    #[inline]
+    pub fn kem(&self) -> &'static Kem {
+        self.kem
+    }
+
+    /// The public key, serialized as in `SerializePublicKey()`.
+    #[inline]
+    pub fn public_key(&self) -> &agreement::PublicKey {
+        &self.public_key
+    }
+}
+
+/// The encapsulated key `enc` that the sender sends to the recipient.
+#[derive(Clone)]
+pub struct EncapsulatedKey {
+    bytes: [u8; ec::PUBLIC_KEY_MAX_LEN],
+    len: usize,
+}
+
+impl AsRef<[u8]> for EncapsulatedKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl core::fmt::Debug for EncapsulatedKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_tuple("EncapsulatedKey")
+            .field(&debug::HexStr(self.as_ref()))
+            .finish()
+    }
+}
+
+/// Sets up a context for sending messages to the recipient with public key
+/// `recipient_public_key`, as in `SetupBaseS()`.
+///
+/// Returns the encapsulated key that must be sent to the recipient along with
+/// the messages.
+pub fn setup_sender(
+    suite: &Suite,
+    recipient_public_key: &[u8],
+    info: &[u8],
+    rng: &dyn rand::SecureRandom,
+) -> Result<(EncapsulatedKey, SenderContext), error::Unspecified> {
+    let kem = suite.kem;
+
+    // Encap()
+    let ephemeral_private_key = agreement::EphemeralPrivateKey::generate(kem.agreement, rng)?;
+    let ephemeral_public_key = ephemeral_private_key.compute_public_key()?;
+    let mut enc = EncapsulatedKey {
+        bytes: [0; ec::PUBLIC_KEY_MAX_LEN],
+        len: ephemeral_public_key.as_ref().len(),
+    };
+    enc.bytes[..enc.len].copy_from_slice(ephemeral_public_key.as_ref());
+    let shared_secret = agreement::agree_ephemeral(
+        ephemeral_private_key,
+        &agreement::UnparsedPublicKey::new(kem.agreement, recipient_public_key),
+        |dh| extract_and_expand(kem, dh, enc.as_ref(), recipient_public_key),
+    )??;
+
+    let context = Context::new(suite, &shared_secret, info)?;
+    Ok((enc, SenderContext { context }))
+}
+
+/// Sets up a context for receiving messages from the sender that sent `enc`,
+/// as in `SetupBaseR()`.
+pub fn setup_receiver(
+    suite: &Suite,
+    enc: &[u8],
+    recipient_private_key: &PrivateKey,
+    info: &[u8],
+) -> Result<ReceiverContext, error::Unspecified> {
+    let kem = suite.kem;
+    if recipient_private_key.kem != kem {
+        return Err(error::Unspecified);
+    }
+
+    // Decap()
+    let recipient_public_key = recipient_private_key.public_key.as_ref();
+    let shared_secret = recipient_private_key.private_key.agree(
+        agreement::UnparsedPublicKey::new(kem.agreement, enc),
+        |dh| extract_and_expand(kem, dh, enc, recipient_public_key),
+        cpu::features(),
+    )??;
+
+    let context = Context::new(suite, &shared_secret, info)?;
+    Ok(ReceiverContext { context })
+}
+
+/// The sender's encryption context.
+pub struct SenderContext {
+    context: Context,
+}
+
+derive_debug_via_field!(SenderContext, context);
+
+impl SenderContext {
+    /// Encrypts and authenticates `in_out` with the next nonce in the
+    /// sequence, appending the tag, as in `ContextS.Seal()`.
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &mut self,
+        aad: aead::Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let nonce = self.context.compute_nonce()?;
+        self.context
+            .key
+            .seal_in_place_append_tag(nonce, aad, in_out)?;
+        self.context.increment_seq()
+    }
+
+    /// Exports a secret of length `out.len()` for `exporter_context`, as in
+    /// `Context.Export()`.
+    pub fn export(
+        &self,
+        exporter_context: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        self.context.export(exporter_context, out)
+    }
+}
+
+/// The recipient's decryption context.
+pub struct ReceiverContext {
+    context: Context,
+}
+
+derive_debug_via_field!(ReceiverContext, context);
+
+impl ReceiverContext {
+    /// Authenticates and decrypts `in_out` with the next nonce in the
+    /// sequence, as in `ContextR.Open()`, returning the plaintext as a prefix
+    /// of `in_out`.
+    ///
+    /// The sequence is only advanced if the message is authentic, so the
+    /// messages must be opened in the order they were sealed.
+    pub fn open_in_place<'in_out, A: AsRef<[u8]>>(
+        &mut self,
+        aad: aead::Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified> {
+        let nonce = self.context.compute_nonce()?;
+        let plaintext = self.context.key.open_in_place(nonce, aad, in_out)?;
+        self.context.increment_seq()?;
+        Ok(plaintext)
+    }
+
+    /// Exports a secret of length `out.len()` for `exporter_context`, as in
+    /// `Context.Export()`.
+    pub fn export(
+        &self,
+        exporter_context: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        self.context.export(exporter_context, out)
+    }
+}
+
+struct Context {
+    suite_id: [u8; 10],
+    key: aead::LessSafeKey,
+    base_nonce: [u8; aead::NONCE_LEN],
+    seq: u64,
+    exporter_secret: hkdf::Prk,
+}
+
+impl core::fmt::Debug for Context {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("Context")
+            .field("algorithm", self.key.algorithm())
+            .finish()
+    }
+}
+
+const MODE_BASE: u8 = 0x00;
+
+impl Context {
+    // KeySchedule()
+    fn new(suite: &Suite, shared_secret: &[u8], info: &[u8]) -> Result<Self, error::Unspecified> {
+        let suite_id = suite.suite_id();
+        let kdf = suite.kdf.hkdf;
+
+        // `psk` and `psk_id` are empty in base mode.
+        let psk_id_hash = labeled_extract(kdf, &suite_id, b"", b"psk_id_hash", &[]);
+        let info_hash = labeled_extract(kdf, &suite_id, b"", b"info_hash", &[info]);
+        let key_schedule_context: &[&[u8]] =
+            &[&[MODE_BASE], psk_id_hash.as_ref(), info_hash.as_ref()];
+
+        let secret = labeled_extract(kdf, &suite_id, shared_secret, b"secret", &[]);
+        let secret = hkdf::Prk::new_less_safe(kdf, secret.as_ref());
+
+        let aead_algorithm = suite.aead.aead;
+        let mut key = [0u8; 32];
+        let key = &mut key[..aead_algorithm.key_len()];
+        labeled_expand(&secret, &suite_id, b"key", key_schedule_context, key)?;
+        let key = aead::LessSafeKey::new(aead::UnboundKey::new(aead_algorithm, key)?);
+
+        let mut base_nonce = [0u8; aead::NONCE_LEN];
+        labeled_expand(
+            &secret,
+            &suite_id,
+            b"base_nonce",
+            key_schedule_context,
+            &mut base_nonce,
+        )?;
+
+        let mut exporter_secret = [0u8; digest::MAX_OUTPUT_LEN];
+        let exporter_secret =
+            &mut exporter_secret[..kdf.hmac_algorithm().digest_algorithm().output_len()];
+        labeled_expand(
+            &secret,
+            &suite_id,
+            b"exp",
+            key_schedule_context,
+            exporter_secret,
+        )?;
+        let exporter_secret = hkdf::Prk::new_less_safe(kdf, exporter_secret);
+
+        Ok(Self {
+            suite_id,
+            key,
+            base_nonce,
+            seq: 0,
+            exporter_secret,
+        })
+    }
+
+    // ComputeNonce()
+    fn compute_nonce(&self) -> Result<aead::Nonce, error::Unspecified> {
+        let mut nonce = self.base_nonce;
+        let seq = self.seq.to_be_bytes();
+        nonce[(aead::NONCE_LEN - seq.len())..]
+            .iter_mut()
+            .zip(seq.iter())
+            .for_each(|(n, s)| *n ^= *s);
+        Ok(aead::Nonce::assume_unique_for_key(nonce))
+    }
+
+    // IncrementSeq()
+    //
+    // The sequence number is limited to 2**64 - 1 instead of 2**96 - 1.
+    fn increment_seq(&mut self) -> Result<(), error::Unspecified> {
+        self.seq = self.seq.checked_add(1).ok_or(error::Unspecified)?;
+        Ok(())
+    }
+
+    // Context.Export()
+    fn export(&self, exporter_context: &[u8], out: &mut [u8]) -> Result<(), error::Unspecified> {
+        labeled_expand(
+            &self.exporter_secret,
+            &self.suite_id,
+            b"sec",
+            &[exporter_context],
+            out,
+        )
+    }
+}
+
+// ExtractAndExpand()
+fn extract_and_expand(
+    kem: &Kem,
+    dh: &[u8],
+    enc: &[u8],
+    recipient_public_key: &[u8],
+) -> Result<[u8; KEM_SHARED_SECRET_LEN], error::Unspecified> {
+    let suite_id = kem.suite_id();
+    let eae_prk = labeled_extract(KEM_HKDF, &suite_id, b"", b"eae_prk", &[dh]);
+    let eae_prk = hkdf::Prk::new_less_safe(KEM_HKDF, eae_prk.as_ref());
+    let mut shared_secret = [0u8; KEM_SHARED_SECRET_LEN];
+    labeled_expand(
+        &eae_prk,
+        &suite_id,
+        b"shared_secret",
+        &[enc, recipient_public_key],
+        &mut shared_secret,
+    )?;
+    Ok(shared_secret)
+}
+
+fn x25519_derive_private_key(
+    kem: &Kem,
+    dkp_prk: &hkdf::Prk,
+    out: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    labeled_expand(dkp_prk, &kem.suite_id(), b"sk", &[], out)
+}
+
+// The rejection sampling of `DeriveKeyPair()` for the NIST curves. The
+// bitmask is 0xff for P-256, so no bits of the candidate are masked.
+fn p256_derive_private_key(
+    kem: &Kem,
+    dkp_prk: &hkdf::Prk,
+    out: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    let suite_id = kem.suite_id();
+    let cpu = cpu::features();
+    for counter in 0..=u8::MAX {
+        labeled_expand(dkp_prk, &suite_id, b"candidate", &[&[counter]], out)?;
+        if ec::Seed::from_bytes(kem.agreement.curve, untrusted::Input::from(out), cpu).is_ok() {
+            return Ok(());
+        }
+    }
+    Err(error::Unspecified)
+}
+
+const HPKE_VERSION_LABEL: &[u8] = b"HPKE-v1";
+
+// The maximum number of slices that `labeled_expand`'s `info` may be split
+// into; the key schedule context has three parts.
+const MAX_INFO_PARTS: usize = 3;
+
+// LabeledExtract(), where `ikm` is the concatenation of the slices in `ikm`.
+fn labeled_extract(
+    kdf: hkdf::Algorithm,
+    suite_id: &[u8],
+    salt: &[u8],
+    label: &[u8],
+    ikm: &[&[u8]],
+) -> hmac::Tag {
+    // HKDF-Extract is HMAC with the salt as the key. An empty salt is
+    // equivalent to the default all-zero salt since HMAC pads keys with zeros.
+    let salt = hmac::Key::new(kdf.hmac_algorithm(), salt);
+    let mut ctx = hmac::Context::with_key(&salt);
+    ctx.update(HPKE_VERSION_LABEL);
+    ctx.update(suite_id);
+    ctx.update(label);
+    ikm.iter().for_each(|ikm| ctx.update(ikm));
+    ctx.sign()
+}
+
+// LabeledExpand(), where `info` is the concatenation of the slices in
+// `info` and `L` is `out.len()`.
+fn labeled_expand(
+    prk: &hkdf::Prk,
+    suite_id: &[u8],
+    label: &[u8],
+    info: &[&[u8]],
+    out: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    let len = u16::try_from(out.len()).map_err(|_| error::Unspecified)?;
+    let len = len.to_be_bytes();
+    let mut labeled_info: [&[u8]; 4 + MAX_INFO_PARTS] = [&[]; 4 + MAX_INFO_PARTS];
+    labeled_info[..4].copy_from_slice(&[&len, HPKE_VERSION_LABEL, suite_id, label]);
+    let labeled_info = labeled_info
+        .get_mut(..(4 + info.len()))
+        .ok_or(error::Unspecified)?;
+    labeled_info[4..].copy_from_slice(info);
+    prk.expand(labeled_info, hkdf::Len(out.len()))?.fill(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    // Checks the intermediate values of the key schedule, which aren't
+    // observable through the public API, against the test vectors.
+    #[test]
+    fn hpke_key_schedule_test() {
+        test::run(
+            test_file!("../tests/hpke_key_schedule_tests.txt"),
+            |section, test_case| {
+                assert_eq!(section, "");
+
+                let suite = suite_from_names(
+                    &test_case.consume_string("KEM"),
+                    &test_case.consume_string("KDF"),
+                    &test_case.consume_string("AEAD"),
+                );
+                let info = test_case.consume_bytes("Info");
+                let sk_e = test_case.consume_bytes("SkEm");
+                let sk_r = test_case.consume_bytes("SkRm");
+                let pk_r = test_case.consume_bytes("PkRm");
+                let enc = test_case.consume_bytes("Enc");
+                let expected_shared_secret = test_case.consume_bytes("SharedSecret");
+                let expected_key = test_case.consume_bytes("Key");
+                let expected_base_nonce = test_case.consume_bytes("BaseNonce");
+                let expected_exporter_secret = test_case.consume_bytes("ExporterSecret");
+
+                let recipient = PrivateKey::from_bytes(suite.kem, &sk_r).unwrap();
+                assert_eq!(recipient.public_key().as_ref(), &pk_r[..]);
+
+                let shared_secret = recipient
+                    .private_key
+                    .agree(
+                        agreement::UnparsedPublicKey::new(suite.kem.agreement, &enc),
+                        |dh| extract_and_expand(suite.kem, dh, &enc, &pk_r),
+                        cpu::features(),
+                    )
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(&shared_secret[..], &expected_shared_secret[..]);
+
+                let (_, sender) = setup_sender(
+                    &suite,
+                    &pk_r,
+                    &info,
+                    &test::rand::FixedSliceRandom { bytes: &sk_e },
+                )
+                .unwrap();
+                let context = Context::new(&suite, &shared_secret, &info).unwrap();
+                assert_eq!(&context.base_nonce[..], &expected_base_nonce[..]);
+                assert_eq!(&sender.context.base_nonce[..], &expected_base_nonce[..]);
+
+                // The key isn't observable directly, so check that it seals the
+                // same way as a key constructed from the expected value.
+                let expected_key = aead::LessSafeKey::new(
+                    aead::UnboundKey::new(suite.aead.aead, &expected_key).unwrap(),
+                );
+                let nonce = || aead::Nonce::assume_unique_for_key(context.base_nonce);
+                let tag = context
+                    .key
+                    .seal_in_place_separate_tag(nonce(), aead::Aad::empty(), &mut [0u8; 0])
+                    .unwrap();
+                let expected_tag = expected_key
+                    .seal_in_place_separate_tag(nonce(), aead::Aad::empty(), &mut [0u8; 0])
+                    .unwrap();
+                assert_eq!(tag.as_ref(), expected_tag.as_ref());
+
+                // Likewise, check the exporter secret by exporting with it.
+                let expected_exporter_secret =
+                    hkdf::Prk::new_less_safe(suite.kdf.hkdf, &expected_exporter_secret);
+                let mut exported = [0u8; 32];
+                let mut expected_exported = [0u8; 32];
+                context.export(b"", &mut exported).unwrap();
+                labeled_expand(
+                    &expected_exporter_secret,
+                    &context.suite_id,
+                    b"sec",
+                    &[b""],
+                    &mut expected_exported,
+                )
+                .unwrap();
+                assert_eq!(exported, expected_exported);
+
+                Ok(())
+            },
+        );
+    }
+
+    fn suite_from_names(kem: &str, kdf: &str, aead: &str) -> Suite {
+        let kem = match kem {
+            "DHKEM(X25519, HKDF-SHA256)" => &DHKEM_X25519_HKDF_SHA256,
+            "DHKEM(P-256, HKDF-SHA256)" => &DHKEM_P256_HKDF_SHA256,
+            _ => unreachable!(),
+        };
+        let kdf = match kdf {
+            "HKDF-SHA256" => &HKDF_SHA256,
+            _ => unreachable!(),
+        };
+        let aead = match aead {
+            "AES-128-GCM" => &AES_128_GCM,
+            "AES-256-GCM" => &AES_256_GCM,
+            "ChaCha20Poly1305" => &CHACHA20_POLY1305,
+            _ => unreachable!(),
+        };
+        Suite::new(kem, kdf, aead)
+    }
+}
@@ -0,0 +1,314 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Parsing of JSON Web Keys (JWK), as specified in [RFC 7517].
+//!
+//! Only what's needed to extract the members of a public key is implemented.
+//! The parsing is strict: the input must be a single JSON object, encoded as
+//! UTF-8 (RFC 8259), with no duplicate member names and no escape sequences
+//! in member names. The members that are looked up must have string values;
+//! the values of other members are validated as JSON and then ignored.
+//!
+//! [RFC 7517]: https://www.rfc-editor.org/rfc/rfc7517
+
+use crate::error;
+use alloc::vec::Vec;
+
+/// Parses the JWK `jwk`, returning the (still base64url-encoded) values of
+/// the members named in `names`, in the same order, or `None` for those that
+/// are missing. Fails if any of them isn't a string.
+pub(crate) fn parse_members<'a, const N: usize>(
+    jwk: &'a [u8],
+    names: [&[u8]; N],
+) -> Result<[Option<untrusted::Input<'a>>; N], error::Unspecified> {
+    // JSON text must be UTF-8 (RFC 8259 Section 8.1).
+    let _: &str = core::str::from_utf8(jwk).map_err(|_| error::Unspecified)?;
+
+    untrusted::Input::from(jwk).read_all(error::Unspecified, |input| {
+        skip_whitespace(input);
+        let values = parse_object(input, &names)?;
+        skip_whitespace(input);
+        Ok(values)
+    })
+}
+
+fn parse_object<'a, const N: usize>(
+    input: &mut untrusted::Reader<'a>,
+    names: &[&[u8]; N],
+) -> Result<[Option<untrusted::Input<'a>>; N], error::Unspecified> {
+    let mut values = [None; N];
+    let mut seen = Vec::new();
+
+    expect(input, b'{')?;
+    skip_whitespace(input);
+    if !input.peek(b'}') {
+        loop {
+            skip_whitespace(input);
+            let (name, escaped) = parse_string(input)?;
+            if escaped {
+                return Err(error::Unspecified);
+            }
+            let name = name.as_slice_less_safe();
+            if seen.contains(&name) {
+                return Err(error::Unspecified);
+            }
+            seen.push(name);
+
+            skip_whitespace(input);
+            expect(input, b':')?;
+            skip_whitespace(input);
+
+            match names.iter().position(|&n| n == name) {
+                Some(i) => {
+                    // Escape sequences can't occur in base64url or in any of
+                    // the registered values we look for, so any escaped value
+                    // will be rejected by the caller.
+                    let (value, _) = parse_string(input)?;
+                    values[i] = Some(value);
+                }
+                None => skip_value(input, MAX_DEPTH)?,
+            }
+
+            skip_whitespace(input);
+            if !input.peek(b',') {
+                break;
+            }
+            expect(input, b',')?;
+        }
+    }
+    expect(input, b'}')?;
+
+    Ok(values)
+}
+
+// The maximum nesting depth of the values of ignored members, e.g. the
+// arrays of `"key_ops"` or `"x5c"`.
+const MAX_DEPTH: usize = 8;
+
+fn skip_value(input: &mut untrusted::Reader, depth: usize) -> Result<(), error::Unspecified> {
+    let depth = depth.checked_sub(1).ok_or(error::Unspecified)?;
+    if input.peek(b'"') {
+        let _ = parse_string(input)?;
+    } else if input.peek(b'{') {
+        expect(input, b'{')?;
+        skip_whitespace(input);
+        if !input.peek(b'}') {
+            loop {
+                skip_whitespace(input);
+                let _ = parse_string(input)?;
+                skip_whitespace(input);
+                expect(input, b':')?;
+                skip_whitespace(input);
+                skip_value(input, depth)?;
+                skip_whitespace(input);
+                if !input.peek(b',') {
+                    break;
+                }
+                expect(input, b',')?;
+            }
+        }
+        expect(input, b'}')?;
+    } else if input.peek(b'[') {
+        expect(input, b'[')?;
+        skip_whitespace(input);
+        if !input.peek(b']') {
+            loop {
+                skip_whitespace(input);
+                skip_value(input, depth)?;
+                skip_whitespace(input);
+                if !input.peek(b',') {
+                    break;
+                }
+                expect(input, b',')?;
+            }
+        }
+        expect(input, b']')?;
+    } else if input.peek(b't') {
+        expect_all(input, b"true")?;
+    } else if input.peek(b'f') {
+        expect_all(input, b"false")?;
+    } else if input.peek(b'n') {
+        expect_all(input, b"null")?;
+    } else {
+        skip_number(input)?;
+    }
+    Ok(())
+}
+
+// Parses a string, returning its contents without the quotes, and whether it
+// contains any escape sequences.
+fn parse_string<'a>(
+    input: &mut untrusted::Reader<'a>,
+) -> Result<(untrusted::Input<'a>, bool), error::Unspecified> {
+    expect(input, b'"')?;
+    let mut escaped = false;
+    let (contents, ()) = input.read_partial(|input| loop {
+        if input.peek(b'"') {
+            return Ok(());
+        }
+        match input.read_byte()? {
+            b'\\' => {
+                escaped = true;
+                match input.read_byte()? {
+                    b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => {}
+                    b'u' => {
+                        for _ in 0..4 {
+                            if !input.read_byte()?.is_ascii_hexdigit() {
+                                return Err(error::Unspecified);
+                            }
+                        }
+                    }
+                    _ => return Err(error::Unspecified),
+                }
+            }
+            0x00..=0x1f => return Err(error::Unspecified),
+            _ => {}
+        }
+    })?;
+    expect(input, b'"')?;
+    Ok((contents, escaped))
+}
+
+// -?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?
+fn skip_number(input: &mut untrusted::Reader) -> Result<(), error::Unspecified> {
+    if input.peek(b'-') {
+        expect(input, b'-')?;
+    }
+    if input.peek(b'0') {
+        expect(input, b'0')?;
+    } else {
+        skip_digits(input)?;
+    }
+    if input.peek(b'.') {
+        expect(input, b'.')?;
+        skip_digits(input)?;
+    }
+    if input.peek(b'e') || input.peek(b'E') {
+        let _: u8 = input.read_byte()?;
+        if input.peek(b'+') || input.peek(b'-') {
+            let _: u8 = input.read_byte()?;
+        }
+        skip_digits(input)?;
+    }
+    Ok(())
+}
+
+// Skips one or more digits.
+fn skip_digits(input: &mut untrusted::Reader) -> Result<(), error::Unspecified> {
+    if !input.read_byte()?.is_ascii_digit() {
+        return Err(error::Unspecified);
+    }
+    while (b'0'..=b'9').any(|digit| input.peek(digit)) {
+        let _: u8 = input.read_byte()?;
+    }
+    Ok(())
+}
+
+fn skip_whitespace(input: &mut untrusted::Reader) {
+    while [b' ', b'\t', b'\n', b'\r'].iter().any(|&b| input.peek(b)) {
+        let _ = input.read_byte();
+    }
+}
+
+fn expect(input: &mut untrusted::Reader, expected: u8) -> Result<(), error::Unspecified> {
+    if input.read_byte()? != expected {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
+fn expect_all(input: &mut untrusted::Reader, expected: &[u8]) -> Result<(), error::Unspecified> {
+    expected.iter().try_for_each(|&b| expect(input, b))
+}
+
+// Decodes unpadded base64url (RFC 4648 Section 5), as JWK requires (RFC 7515
+// Section 2). Non-canonical encodings, i.e. those with nonzero trailing bits,
+// are rejected.
+pub(crate) fn base64url_decode(input: untrusted::Input) -> Result<Vec<u8>, error::Unspecified> {
+    fn value(c: u8) -> Result<u32, error::Unspecified> {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'-' => 62,
+            b'_' => 63,
+            _ => return Err(error::Unspecified),
+        };
+        Ok(u32::from(v))
+    }
+
+    let input = input.as_slice_less_safe();
+    let mut out = Vec::with_capacity(input.len() / 4 * 3 + 2);
+    for chunk in input.chunks(4) {
+        let mut acc = 0u32;
+        for &c in chunk {
+            acc = (acc << 6) | value(c)?;
+        }
+        let bytes = match chunk.len() {
+            4 => acc.to_be_bytes(),
+            3 => {
+                if acc & 0b11 != 0 {
+                    return Err(error::Unspecified);
+                }
+                (acc << 6).to_be_bytes()
+            }
+            2 => {
+                if acc & 0b1111 != 0 {
+                    return Err(error::Unspecified);
+                }
+                (acc << 12).to_be_bytes()
+            }
+            _ => return Err(error::Unspecified),
+        };
+        out.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64url_decode() {
+        // RFC 4648 Section 10, with the padding removed.
+        for &(encoded, decoded) in &[
+            (&b""[..], &b""[..]),
+            (b"Zg", b"f"),
+            (b"Zm8", b"fo"),
+            (b"Zm9v", b"foo"),
+            (b"Zm9vYg", b"foob"),
+            (b"Zm9vYmE", b"fooba"),
+            (b"Zm9vYmFy", b"foobar"),
+            (b"-_-_", &[0xfb, 0xff, 0xbf]),
+        ] {
+            assert_eq!(
+                base64url_decode(untrusted::Input::from(encoded)).as_deref(),
+                Ok(decoded)
+            );
+        }
+
+        for &encoded in &[
+            &b"Z"[..],
+            b"Zg==",
+            b"Zh",
+            b"Zm9",
+            b"Zm9v+",
+            b"Zm9v/A",
+            b"Zm9 v",
+        ] {
+            assert!(base64url_decode(untrusted::Input::from(encoded)).is_err());
+        }
+    }
+}
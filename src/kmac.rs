@@ -0,0 +1,212 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! KMAC, the Keccak-based message authentication code.
+//!
+//! KMAC128 and KMAC256 are specified in [NIST SP 800-185] Section 4. The
+//! output length is an input to the computation, so a tag of one length
+//! isn't a prefix of the tag of another length.
+//!
+//! [NIST SP 800-185]: https://doi.org/10.6028/NIST.SP.800-185
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::kmac;
+//!
+//! let key = [0x40; 32];
+//! let mut ctx = kmac::Context::new(&kmac::KMAC256, &key, b"My Application");
+//! ctx.update(b"hello, ");
+//! ctx.update(b"world");
+//! let mut tag = [0u8; 32];
+//! ctx.finish(&mut tag)?;
+//!
+//! let mut ctx = kmac::Context::new(&kmac::KMAC256, &key, b"My Application");
+//! ctx.update(b"hello, world");
+//! ctx.verify(&tag)?;
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+
+use crate::{constant_time, error, polyfill::u64_from_usize};
+
+mod keccak;
+
+/// A KMAC algorithm.
+pub struct Algorithm {
+    // The cSHAKE rate, in bytes.
+    rate: usize,
+    id: AlgorithmID,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum AlgorithmID {
+    KMAC128,
+    KMAC256,
+}
+
+derive_debug_via_id!(Algorithm);
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Algorithm {}
+
+/// KMAC128, based on cSHAKE128.
+pub static KMAC128: Algorithm = Algorithm {
+    rate: 168,
+    id: AlgorithmID::KMAC128,
+};
+
+/// KMAC256, based on cSHAKE256.
+pub static KMAC256: Algorithm = Algorithm {
+    rate: 136,
+    id: AlgorithmID::KMAC256,
+};
+
+/// The minimum length of a tag, in bytes.
+///
+/// [NIST SP 800-185] Section 8.4.2 prohibits tags shorter than 32 bits.
+///
+/// [NIST SP 800-185]: https://doi.org/10.6028/NIST.SP.800-185
+pub const MIN_TAG_LEN: usize = 32 / 8;
+
+/// A context for multi-step (Init-Update-Finish) KMAC computation.
+#[derive(Clone)]
+pub struct Context {
+    sponge: keccak::Sponge,
+    algorithm: &'static Algorithm,
+}
+
+impl core::fmt::Debug for Context {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Context")
+            .field("algorithm", self.algorithm)
+            .finish()
+    }
+}
+
+impl Context {
+    /// Constructs a new KMAC context with the given key and customization
+    /// string, which may be empty.
+    pub fn new(algorithm: &'static Algorithm, key: &[u8], customization: &[u8]) -> Self {
+        let mut sponge = keccak::Sponge::new(algorithm.rate);
+
+        // cSHAKE's bytepad(encode_string(N) || encode_string(S), rate), with
+        // the function name N = "KMAC".
+        absorb_left_encoded(&mut sponge, u64_from_usize(algorithm.rate).into());
+        absorb_encoded_string(&mut sponge, b"KMAC");
+        absorb_encoded_string(&mut sponge, customization);
+        sponge.absorb_zero_padding();
+
+        // KMAC's bytepad(encode_string(K), rate).
+        absorb_left_encoded(&mut sponge, u64_from_usize(algorithm.rate).into());
+        absorb_encoded_string(&mut sponge, key);
+        sponge.absorb_zero_padding();
+
+        Self { sponge, algorithm }
+    }
+
+    /// Updates the KMAC computation with all the data in `data`. `update`
+    /// may be called zero or more times before `finish` or `verify`.
+    pub fn update(&mut self, data: &[u8]) {
+        self.sponge.absorb(data);
+    }
+
+    /// Finalizes the KMAC computation, filling `tag` with a tag of length
+    /// `tag.len()`.
+    ///
+    /// Fails if `tag` is shorter than `MIN_TAG_LEN`. Use `verify`, not a
+    /// comparison with the output of `finish`, to verify tags.
+    pub fn finish(self, tag: &mut [u8]) -> Result<(), error::Unspecified> {
+        if tag.len() < MIN_TAG_LEN {
+            return Err(error::Unspecified);
+        }
+        let mut sponge = self.into_squeezing(tag.len());
+        sponge.squeeze(tag);
+        Ok(())
+    }
+
+    /// Finalizes the KMAC computation and verifies, in constant time, that
+    /// `tag` is the tag of length `tag.len()`.
+    ///
+    /// Fails if `tag` is shorter than `MIN_TAG_LEN`.
+    pub fn verify(self, tag: &[u8]) -> Result<(), error::Unspecified> {
+        if tag.len() < MIN_TAG_LEN {
+            return Err(error::Unspecified);
+        }
+        let mut sponge = self.into_squeezing(tag.len());
+
+        // Every chunk is compared, even after a mismatch.
+        let mut result = Ok(());
+        let mut calculated = [0u8; 64];
+        for received in tag.chunks(calculated.len()) {
+            let calculated = &mut calculated[..received.len()];
+            sponge.squeeze(calculated);
+            result = result.and(constant_time::verify_slices_are_equal(calculated, received));
+        }
+        result
+    }
+
+    /// The algorithm for this context.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+
+    fn into_squeezing(self, tag_len: usize) -> keccak::Sponge {
+        let mut sponge = self.sponge;
+        absorb_right_encoded(&mut sponge, bit_len(tag_len));
+        sponge.finish_absorbing(CSHAKE_DOMAIN_SUFFIX);
+        sponge
+    }
+}
+
+// The "00" bits that cSHAKE appends, followed by the first bit of pad10*1.
+const CSHAKE_DOMAIN_SUFFIX: u8 = 0x04;
+
+fn bit_len(byte_len: usize) -> u128 {
+    u128::from(u64_from_usize(byte_len)) * 8
+}
+
+// The minimal big-endian encoding of `value`, which is at least one byte.
+fn encode_integer(value: &[u8; 16]) -> &[u8] {
+    let leading_zeros = value.iter().take_while(|&&b| b == 0).count();
+    &value[core::cmp::min(leading_zeros, value.len() - 1)..]
+}
+
+// SP 800-185's left_encode.
+fn absorb_left_encoded(sponge: &mut keccak::Sponge, value: u128) {
+    let value = value.to_be_bytes();
+    let encoded = encode_integer(&value);
+    sponge.absorb(&[u8::try_from(encoded.len()).unwrap()]);
+    sponge.absorb(encoded);
+}
+
+// SP 800-185's right_encode.
+fn absorb_right_encoded(sponge: &mut keccak::Sponge, value: u128) {
+    let value = value.to_be_bytes();
+    let encoded = encode_integer(&value);
+    sponge.absorb(encoded);
+    sponge.absorb(&[u8::try_from(encoded.len()).unwrap()]);
+}
+
+// SP 800-185's encode_string.
+fn absorb_encoded_string(sponge: &mut keccak::Sponge, s: &[u8]) {
+    absorb_left_encoded(sponge, bit_len(s.len()));
+    sponge.absorb(s);
+}
@@ -0,0 +1,156 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The sponge construction over Keccak-p[1600, 24], as specified in
+//! [FIPS 202], as needed by cSHAKE.
+//!
+//! [FIPS 202]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+
+#[derive(Clone)]
+pub(super) struct Sponge {
+    state: [u64; LANES],
+
+    // The rate, in bytes.
+    rate: usize,
+
+    // The number of bytes of the current block that have been absorbed or
+    // squeezed.
+    pos: usize,
+}
+
+impl Sponge {
+    pub(super) fn new(rate: usize) -> Self {
+        debug_assert!(rate % 8 == 0 && rate < LANES * 8);
+        Self {
+            state: [0; LANES],
+            rate,
+            pos: 0,
+        }
+    }
+
+    pub(super) fn absorb(&mut self, input: &[u8]) {
+        for &b in input {
+            self.xor_byte_at(self.pos, b);
+            self.pos += 1;
+            if self.pos == self.rate {
+                keccak_p1600(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    /// Absorbs zeros up to the next block boundary, if not already at one.
+    pub(super) fn absorb_zero_padding(&mut self) {
+        if self.pos != 0 {
+            keccak_p1600(&mut self.state);
+            self.pos = 0;
+        }
+    }
+
+    /// Pads the input with `domain_suffix` followed by pad10*1 and switches
+    /// to squeezing. For cSHAKE, `domain_suffix` is 0x04, i.e. the bits 00.
+    pub(super) fn finish_absorbing(&mut self, domain_suffix: u8) {
+        self.xor_byte_at(self.pos, domain_suffix);
+        self.xor_byte_at(self.rate - 1, 0x80);
+        keccak_p1600(&mut self.state);
+        self.pos = 0;
+    }
+
+    pub(super) fn squeeze(&mut self, out: &mut [u8]) {
+        for out in out {
+            if self.pos == self.rate {
+                keccak_p1600(&mut self.state);
+                self.pos = 0;
+            }
+            *out = self.state[self.pos / 8].to_le_bytes()[self.pos % 8];
+            self.pos += 1;
+        }
+    }
+
+    fn xor_byte_at(&mut self, pos: usize, b: u8) {
+        self.state[pos / 8] ^= u64::from(b) << (8 * (pos % 8));
+    }
+}
+
+// Lane (x, y) is `state[x + 5 * y]`.
+fn keccak_p1600(a: &mut [u64; LANES]) {
+    for rc in ROUND_CONSTANTS.iter() {
+        // θ
+        let mut c = [0u64; 5];
+        for (x, c) in c.iter_mut().enumerate() {
+            *c = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for (i, lane) in a.iter_mut().enumerate() {
+            let x = i % 5;
+            *lane ^= c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        }
+
+        // ρ and π
+        let mut current = a[1];
+        for (&pi, &rho) in PI.iter().zip(RHO.iter()) {
+            let next = a[pi];
+            a[pi] = current.rotate_left(rho);
+            current = next;
+        }
+
+        // χ
+        for row in a.chunks_exact_mut(5) {
+            let r = [row[0], row[1], row[2], row[3], row[4]];
+            for (x, lane) in row.iter_mut().enumerate() {
+                *lane = r[x] ^ (!r[(x + 1) % 5] & r[(x + 2) % 5]);
+            }
+        }
+
+        // ι
+        a[0] ^= rc;
+    }
+}
+
+const LANES: usize = 25;
+
+// The order in which π visits the lanes, starting from lane 1, and the ρ
+// rotation of each lane as it is visited.
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
@@ -22,11 +22,23 @@
 //!     <th>Description
 //! <tr><td><code>alloc (default)</code>
 //!     <td>Enable features that require use of the heap, RSA in particular.
+//! <tr><td><code>force-constant-time-software-aes</code>
+//!     <td>On CPUs without AES instructions, always use the bitsliced
+//!         software AES implementation instead of the faster vector
+//!         permutation (vpaes) and bitsliced SIMD (bsaes) implementations.
+//!         All of *ring*'s AES implementations are constant-time, and none
+//!         of them uses lookup tables in memory, so this is only useful to
+//!         rule out the SIMD implementations entirely. AES instructions are
+//!         still used when available.
+//! <tr><td><code>legacy-cbc</code>
+//!     <td>Enable <code>aead::cbc_hmac</code>, AES-CBC with HMAC for
+//!         interoperability with legacy protocols. Implies `alloc`.
 //! <tr><td><code>less-safe-getrandom-custom-or-rdrand</code>
 //!     <td>Treat user-provided ("custom") and RDRAND-based <code>getrandom</code>
 //!         implementations as secure random number generators (see
 //!         <code>SecureRandom</code>). This feature only works with
-//!         <code>os = "none"</code> targets. See
+//!         <code>os = "none"</code> targets, and it enables
+//!         <code>getrandom</code>'s <code>custom</code> feature. See
 //!         <a href="https://docs.rs/getrandom/0.2.10/getrandom/macro.register_custom_getrandom.html">
 //!             <code>register_custom_getrandom</code>
 //!         </a> and <a href="https://docs.rs/getrandom/0.2.10/getrandom/#rdrand-on-x86">
@@ -40,9 +52,18 @@
 //!         on esp-idf despite the likelihood that RNG is not secure.
 //!         This feature only works with <code>os = espidf</code> targets.
 //!         See <a href="https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/random.html">
+//! <tr><td><code>rand_core</code>
+//!     <td>Implement <code>rand_core::RngCore</code> and
+//!         <code>rand_core::CryptoRng</code> for <code>SystemRandom</code>,
+//!         using <code>rand_core</code> 0.6.
 //! <tr><td><code>std</code>
 //!     <td>Enable features that use libstd, in particular
 //!         <code>std::error::Error</code> integration. Implies `alloc`.
+//! <tr><td><code>test-util</code>
+//!     <td>Enable insecure functions for deriving key pairs from labels for
+//!         use as test fixtures, e.g. <code>Ed25519KeyPair::from_test_label</code>,
+//!         and <code>rand::FaultyRandom</code> for simulating a broken random
+//!         number generator. Never enable this outside of tests.
 //! <tr><td><code>wasm32_unknown_unknown_js</code>
 //!     <td>When this feature is enabled, for the wasm32-unknown-unknown target,
 //!         Web APIs will be used to implement features like `ring::rand` that
@@ -108,21 +129,35 @@ mod polyfill;
 pub mod aead;
 
 pub mod agreement;
+pub mod bcrypt_pbkdf;
+
+#[cfg(all(feature = "unstable-bench", target_arch = "aarch64"))]
+pub mod bench;
 
 mod bits;
+pub mod blake3;
+mod build_info;
 
 pub(crate) mod c;
 pub mod constant_time;
 
 pub mod io;
 
-mod cpu;
+pub mod cpu;
 pub mod digest;
 mod ec;
+pub mod ec_arithmetic;
 pub mod error;
+pub mod fips;
 pub mod hkdf;
 pub mod hmac;
+pub mod hpke;
+#[cfg(feature = "alloc")]
+mod jwk;
+pub mod kmac;
 mod limb;
+pub mod merkle;
+pub mod noise_primitives;
 pub mod pbkdf2;
 pub mod pkcs8;
 pub mod rand;
@@ -132,6 +167,14 @@ pub mod rsa;
 
 pub mod signature;
 
+pub use build_info::{BuildInfo, BUILD_INFO};
+
+mod supported;
+pub use supported::{
+    supported_algorithms, AeadInfo, AgreementInfo, DigestInfo, SignatureVerificationInfo,
+    SupportedAlgorithms,
+};
+
 #[cfg(test)]
 mod tests;
 
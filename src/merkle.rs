@@ -0,0 +1,155 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Merkle tree inclusion proofs and signed tree heads, as used by
+//! Certificate Transparency.
+//!
+//! The tree is the one specified in [RFC 6962 Section 2.1], using SHA-256:
+//! a leaf is hashed as `SHA-256(0x00 || leaf)` and an interior node as
+//! `SHA-256(0x01 || left || right)`, so a leaf can't be confused with an
+//! interior node.
+//!
+//! A log publishes a signed tree head, i.e. its signature over the size and
+//! the root hash of the tree; [`verify_signed_tree_head()`] checks that
+//! signature. [`verify_inclusion()`] then checks that a leaf is in the tree
+//! with that root, given an audit path from the log.
+//!
+//! ```
+//! use ring::{digest, merkle};
+//!
+//! // A tree with two leaves.
+//! let a = merkle::leaf_hash(b"a");
+//! let b = merkle::leaf_hash(b"b");
+//! let mut root = [0u8; merkle::HASH_LEN];
+//! let mut ctx = digest::Context::new(&digest::SHA256);
+//! ctx.update(&[0x01]);
+//! ctx.update(&a);
+//! ctx.update(&b);
+//! root.copy_from_slice(ctx.finish().as_ref());
+//!
+//! assert!(merkle::verify_inclusion(b"a", &[b], 0, 2, &root));
+//! assert!(merkle::verify_inclusion(b"b", &[a], 1, 2, &root));
+//! assert!(!merkle::verify_inclusion(b"b", &[a], 0, 2, &root));
+//! ```
+//!
+//! [RFC 6962 Section 2.1]: https://www.rfc-editor.org/rfc/rfc6962#section-2.1
+
+use crate::{digest, error, signature};
+
+/// The length of a [`Hash`].
+pub const HASH_LEN: usize = digest::SHA256_OUTPUT_LEN;
+
+/// The SHA-256 hash of a leaf or an interior node of a tree.
+pub type Hash = [u8; HASH_LEN];
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Returns the hash of the leaf with data `leaf`, `SHA-256(0x00 || leaf)`.
+pub fn leaf_hash(leaf: &[u8]) -> Hash {
+    hash(&[&[LEAF_PREFIX], leaf])
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    hash(&[&[NODE_PREFIX], left, right])
+}
+
+fn hash(parts: &[&[u8]]) -> Hash {
+    let mut ctx = digest::Context::new(&digest::SHA256);
+    parts.iter().for_each(|part| ctx.update(part));
+    let mut hash = [0; HASH_LEN];
+    hash.copy_from_slice(ctx.finish().as_ref());
+    hash
+}
+
+/// Returns `true` if `proof` proves that the leaf with data `leaf` is at
+/// position `index` in the tree of `tree_size` leaves whose root hash is
+/// `root`.
+///
+/// `proof` is the audit path of [RFC 6962 Section 2.1.1], ordered from the
+/// leaf towards the root, as returned by a log's `get-proof-by-hash`. The
+/// proof is checked using the algorithm of [RFC 9162 Section 2.1.3.2]. A proof
+/// that is longer or shorter than the audit path for `index` and `tree_size`
+/// is rejected.
+///
+/// `root` must be authenticated separately, e.g. with
+/// [`verify_signed_tree_head()`].
+///
+/// [RFC 6962 Section 2.1.1]: https://www.rfc-editor.org/rfc/rfc6962#section-2.1.1
+/// [RFC 9162 Section 2.1.3.2]: https://www.rfc-editor.org/rfc/rfc9162#section-2.1.3.2
+pub fn verify_inclusion(
+    leaf: &[u8],
+    proof: &[Hash],
+    index: u64,
+    tree_size: u64,
+    root: &Hash,
+) -> bool {
+    if index >= tree_size {
+        return false;
+    }
+
+    let mut f_n = index;
+    let mut s_n = tree_size - 1;
+    let mut r = leaf_hash(leaf);
+    for p in proof {
+        if s_n == 0 {
+            return false;
+        }
+        if (f_n & 1 == 1) || (f_n == s_n) {
+            r = node_hash(p, &r);
+            while (f_n & 1 == 0) && (f_n != 0) {
+                f_n >>= 1;
+                s_n >>= 1;
+            }
+        } else {
+            r = node_hash(&r, p);
+        }
+        f_n >>= 1;
+        s_n >>= 1;
+    }
+
+    s_n == 0 && r == *root
+}
+
+/// Verifies the signature of a log over a tree head.
+///
+/// `signature` is the signature in the `tree_head_signature` field of the
+/// log's `get-sth` response, without the hash and signature algorithm
+/// identifiers and the length that precede it in the TLS `digitally-signed`
+/// encoding. It is verified with `log_key` over the `TreeHeadSignature`
+/// structure of [RFC 6962 Section 3.5] for version 1. Logs usually use
+/// ECDSA P-256 with SHA-256, i.e. `signature::ECDSA_P256_SHA256_ASN1`.
+///
+/// [RFC 6962 Section 3.5]: https://www.rfc-editor.org/rfc/rfc6962#section-3.5
+pub fn verify_signed_tree_head<B: AsRef<[u8]>>(
+    log_key: &signature::UnparsedPublicKey<B>,
+    timestamp: u64,
+    tree_size: u64,
+    root: &Hash,
+    signature: &[u8],
+) -> Result<(), error::Unspecified> {
+    const V1: u8 = 0;
+    const TREE_HASH: u8 = 1;
+
+    let mut message = [0u8; 2 + 8 + 8 + HASH_LEN];
+    let (header, rest) = message.split_at_mut(2);
+    header.copy_from_slice(&[V1, TREE_HASH]);
+    let (timestamp_out, rest) = rest.split_at_mut(8);
+    timestamp_out.copy_from_slice(&timestamp.to_be_bytes());
+    let (tree_size_out, root_out) = rest.split_at_mut(8);
+    tree_size_out.copy_from_slice(&tree_size.to_be_bytes());
+    root_out.copy_from_slice(root);
+
+    log_key.verify(&message, signature)
+}
@@ -0,0 +1,457 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The DH, cipher, and hash functions of the [Noise Protocol Framework].
+//!
+//! This isn't an implementation of Noise. It exposes *ring*'s primitives
+//! through the three interfaces that the Noise specification defines in its
+//! Section 4, so that a Noise implementation can be built on *ring*:
+//!
+//! * [`Dh`]: `25519` ([`X25519`]).
+//! * [`Cipher`]: `ChaChaPoly` ([`ChaChaPoly`]) and `AESGCM` ([`AesGcm`]).
+//! * [`Hash`]: `SHA256` ([`Sha256`]), `SHA512` ([`Sha512`]), and `BLAKE2s`
+//!   ([`Blake2s`]).
+//!
+//! Each is usable as a trait object, e.g. `&dyn Hash`, so a protocol name
+//! like `Noise_XX_25519_ChaChaPoly_BLAKE2s` can be mapped to its primitives
+//! at runtime.
+//!
+//! ```
+//! use ring::noise_primitives::{Cipher, ChaChaPoly, Dh, Hash, Sha256, X25519};
+//! use ring::rand;
+//!
+//! let rng = rand::SystemRandom::new();
+//! let (dh, cipher, hash): (&dyn Dh, &dyn Cipher, &dyn Hash) = (&X25519, &ChaChaPoly, &Sha256);
+//!
+//! let initiator = dh.generate_keypair(&rng)?;
+//! let responder = dh.generate_keypair(&rng)?;
+//! let mut shared = [0u8; 32];
+//! dh.dh(&initiator, responder.public_key(), &mut shared)?;
+//!
+//! let (_ck, k) = hash.hkdf2(hash.hash(&[b"chaining key"]).as_ref(), &shared);
+//! let k = cipher.key(k.as_ref()[..32].try_into().unwrap())?;
+//!
+//! let mut in_out = b"hello, world".to_vec();
+//! k.seal_in_place_append_tag(0, b"", &mut in_out)?;
+//! let plaintext = k.open_in_place(0, b"", &mut in_out)?;
+//! assert_eq!(plaintext, b"hello, world");
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+//!
+//! [Noise Protocol Framework]: https://noiseprotocol.org/noise.html
+
+use crate::{aead, agreement, cpu, digest, error, hmac, rand, sealed};
+
+mod blake2s;
+
+/// A Noise DH function.
+pub trait Dh: 'static + Sync + sealed::Sealed + core::fmt::Debug {
+    /// The name of the DH function in Noise protocol names.
+    fn name(&self) -> &'static str;
+
+    /// `DHLEN`: the length of public keys and of DH outputs.
+    fn dh_len(&self) -> usize;
+
+    /// `GENERATE_KEYPAIR()`.
+    fn generate_keypair(
+        &self,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<DhKeyPair, error::Unspecified>;
+
+    /// Constructs a key pair from an existing private key, e.g. a static key
+    /// that was generated earlier.
+    fn keypair_from_private_key(&self, private_key: &[u8])
+        -> Result<DhKeyPair, error::Unspecified>;
+
+    /// `DH(key_pair, public_key)`: writes the DH output, which is `dh_len()`
+    /// bytes, to `out`.
+    ///
+    /// Fails if `public_key` is invalid, including when the output would be
+    /// all zeros, which the Noise specification allows.
+    fn dh(
+        &self,
+        key_pair: &DhKeyPair,
+        public_key: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), error::Unspecified>;
+}
+
+/// A key pair for a [`Dh`] function.
+pub struct DhKeyPair {
+    private_key: agreement::StaticPrivateKey,
+    public_key: agreement::PublicKey,
+}
+
+derive_debug_via_field!(DhKeyPair, stringify!(DhKeyPair), public_key);
+
+impl DhKeyPair {
+    fn new(private_key: agreement::StaticPrivateKey) -> Result<Self, error::Unspecified> {
+        let public_key = private_key.compute_public_key(cpu::features())?;
+        Ok(Self {
+            private_key,
+            public_key,
+        })
+    }
+
+    /// The encoded public key.
+    #[inline]
+    pub fn public_key(&self) -> &[u8] {
+        self.public_key.as_ref()
+    }
+}
+
+/// The `25519` DH function: X25519.
+#[derive(Clone, Copy, Debug)]
+pub struct X25519;
+
+impl sealed::Sealed for X25519 {}
+
+impl Dh for X25519 {
+    fn name(&self) -> &'static str {
+        "25519"
+    }
+
+    fn dh_len(&self) -> usize {
+        32
+    }
+
+    fn generate_keypair(
+        &self,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<DhKeyPair, error::Unspecified> {
+        let private_key =
+            agreement::StaticPrivateKey::generate(&agreement::X25519, rng, cpu::features())?;
+        DhKeyPair::new(private_key)
+    }
+
+    fn keypair_from_private_key(
+        &self,
+        private_key: &[u8],
+    ) -> Result<DhKeyPair, error::Unspecified> {
+        let private_key = agreement::StaticPrivateKey::from_bytes(
+            &agreement::X25519,
+            untrusted::Input::from(private_key),
+            cpu::features(),
+        )?;
+        DhKeyPair::new(private_key)
+    }
+
+    fn dh(
+        &self,
+        key_pair: &DhKeyPair,
+        public_key: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        if out.len() != self.dh_len() {
+            return Err(error::Unspecified);
+        }
+        key_pair.private_key.agree(
+            agreement::UnparsedPublicKey::new(&agreement::X25519, public_key),
+            |shared| out.copy_from_slice(shared),
+            cpu::features(),
+        )
+    }
+}
+
+/// The length of a [`Cipher`] key.
+pub const CIPHER_KEY_LEN: usize = 32;
+
+/// A Noise cipher function.
+pub trait Cipher: 'static + Sync + sealed::Sealed + core::fmt::Debug {
+    /// The name of the cipher function in Noise protocol names.
+    fn name(&self) -> &'static str;
+
+    /// Constructs the key `k` that `ENCRYPT`, `DECRYPT`, and `REKEY` take.
+    fn key(&self, k: &[u8; CIPHER_KEY_LEN]) -> Result<CipherKey, error::Unspecified>;
+}
+
+/// A key for a [`Cipher`].
+///
+/// The operations take the 64-bit Noise nonce `n` and encode it into the
+/// AEAD's 96-bit nonce as the cipher function specifies. The maximum nonce,
+/// 2**64 - 1, is reserved for `rekey()`, so `seal_in_place_append_tag()` and
+/// `open_in_place()` fail when given it.
+pub struct CipherKey {
+    key: aead::LessSafeKey,
+    nonce_encoding: NonceEncoding,
+}
+
+derive_debug_via_field!(CipherKey, stringify!(CipherKey), key);
+
+#[derive(Clone, Copy)]
+enum NonceEncoding {
+    LittleEndian,
+    BigEndian,
+}
+
+impl CipherKey {
+    fn new(
+        algorithm: &'static aead::Algorithm,
+        nonce_encoding: NonceEncoding,
+        k: &[u8; CIPHER_KEY_LEN],
+    ) -> Result<Self, error::Unspecified> {
+        Ok(Self {
+            key: aead::LessSafeKey::new(aead::UnboundKey::new(algorithm, k)?),
+            nonce_encoding,
+        })
+    }
+
+    /// `ENCRYPT(k, n, ad, plaintext)`: encrypts `in_out` in place and
+    /// appends the tag.
+    pub fn seal_in_place_append_tag<InOut>(
+        &self,
+        n: u64,
+        ad: &[u8],
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let nonce = self.nonce(n)?;
+        self.key
+            .seal_in_place_append_tag(nonce, aead::Aad::from(ad), in_out)
+    }
+
+    /// `DECRYPT(k, n, ad, ciphertext)`: authenticates and decrypts `in_out`,
+    /// which is the ciphertext followed by the tag, in place, returning the
+    /// plaintext.
+    pub fn open_in_place<'in_out>(
+        &self,
+        n: u64,
+        ad: &[u8],
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified> {
+        let nonce = self.nonce(n)?;
+        self.key.open_in_place(nonce, aead::Aad::from(ad), in_out)
+    }
+
+    /// `REKEY(k)`: replaces the key with the first 32 bytes of the
+    /// encryption of 32 zero bytes under the maximum nonce.
+    pub fn rekey(&mut self) -> Result<(), error::Unspecified> {
+        let mut k = [0u8; CIPHER_KEY_LEN];
+        let nonce = self.encode_nonce(u64::MAX);
+        let _: aead::Tag =
+            self.key
+                .seal_in_place_separate_tag(nonce, aead::Aad::empty(), &mut k)?;
+        *self = Self::new(self.key.algorithm(), self.nonce_encoding, &k)?;
+        Ok(())
+    }
+
+    fn nonce(&self, n: u64) -> Result<aead::Nonce, error::Unspecified> {
+        if n == u64::MAX {
+            return Err(error::Unspecified);
+        }
+        Ok(self.encode_nonce(n))
+    }
+
+    // 32 bits of zeros followed by `n`.
+    fn encode_nonce(&self, n: u64) -> aead::Nonce {
+        let n = match self.nonce_encoding {
+            NonceEncoding::LittleEndian => n.to_le_bytes(),
+            NonceEncoding::BigEndian => n.to_be_bytes(),
+        };
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        nonce[4..].copy_from_slice(&n);
+        aead::Nonce::assume_unique_for_key(nonce)
+    }
+}
+
+/// The `ChaChaPoly` cipher function: ChaCha20-Poly1305 with the nonce
+/// encoded in little-endian form.
+#[derive(Clone, Copy, Debug)]
+pub struct ChaChaPoly;
+
+impl sealed::Sealed for ChaChaPoly {}
+
+impl Cipher for ChaChaPoly {
+    fn name(&self) -> &'static str {
+        "ChaChaPoly"
+    }
+
+    fn key(&self, k: &[u8; CIPHER_KEY_LEN]) -> Result<CipherKey, error::Unspecified> {
+        CipherKey::new(&aead::CHACHA20_POLY1305, NonceEncoding::LittleEndian, k)
+    }
+}
+
+/// The `AESGCM` cipher function: AES-256-GCM with the nonce encoded in
+/// big-endian form.
+#[derive(Clone, Copy, Debug)]
+pub struct AesGcm;
+
+impl sealed::Sealed for AesGcm {}
+
+impl Cipher for AesGcm {
+    fn name(&self) -> &'static str {
+        "AESGCM"
+    }
+
+    fn key(&self, k: &[u8; CIPHER_KEY_LEN]) -> Result<CipherKey, error::Unspecified> {
+        CipherKey::new(&aead::AES_256_GCM, NonceEncoding::BigEndian, k)
+    }
+}
+
+/// The maximum `HASHLEN` of the hash functions in this module.
+pub const MAX_HASH_LEN: usize = digest::MAX_OUTPUT_LEN;
+
+/// A Noise hash function.
+///
+/// Besides `HASH()`, this provides `HMAC-HASH()` and the two- and
+/// three-output forms of `HKDF()` that the Noise specification defines on top
+/// of it.
+pub trait Hash: 'static + Sync + sealed::Sealed + core::fmt::Debug {
+    /// The name of the hash function in Noise protocol names.
+    fn name(&self) -> &'static str;
+
+    /// `HASHLEN`.
+    fn hash_len(&self) -> usize;
+
+    /// `BLOCKLEN`.
+    fn block_len(&self) -> usize;
+
+    /// `HASH(data)`, where `data` is the concatenation of `parts`.
+    fn hash(&self, parts: &[&[u8]]) -> HashOutput;
+
+    /// `HMAC-HASH(key, data)`, where `data` is the concatenation of `parts`.
+    fn hmac(&self, key: &[u8], parts: &[&[u8]]) -> HashOutput;
+
+    /// `HKDF(chaining_key, input_key_material, 2)`.
+    fn hkdf2(&self, chaining_key: &[u8], input_key_material: &[u8]) -> (HashOutput, HashOutput) {
+        let temp_key = self.hmac(chaining_key, &[input_key_material]);
+        let output1 = self.hmac(temp_key.as_ref(), &[&[0x01]]);
+        let output2 = self.hmac(temp_key.as_ref(), &[output1.as_ref(), &[0x02]]);
+        (output1, output2)
+    }
+
+    /// `HKDF(chaining_key, input_key_material, 3)`.
+    fn hkdf3(
+        &self,
+        chaining_key: &[u8],
+        input_key_material: &[u8],
+    ) -> (HashOutput, HashOutput, HashOutput) {
+        let temp_key = self.hmac(chaining_key, &[input_key_material]);
+        let output1 = self.hmac(temp_key.as_ref(), &[&[0x01]]);
+        let output2 = self.hmac(temp_key.as_ref(), &[output1.as_ref(), &[0x02]]);
+        let output3 = self.hmac(temp_key.as_ref(), &[output2.as_ref(), &[0x03]]);
+        (output1, output2, output3)
+    }
+}
+
+/// The output of a [`Hash`] function, `HASHLEN` bytes long.
+///
+/// The outputs of `HMAC-HASH()` and `HKDF()` are often keys, so the `Debug`
+/// implementation doesn't show the value.
+#[derive(Clone, Copy)]
+pub struct HashOutput {
+    value: [u8; MAX_HASH_LEN],
+    len: usize,
+}
+
+impl HashOutput {
+    fn new(value: &[u8]) -> Self {
+        let mut r = Self {
+            value: [0; MAX_HASH_LEN],
+            len: value.len(),
+        };
+        r.value[..value.len()].copy_from_slice(value);
+        r
+    }
+}
+
+impl AsRef<[u8]> for HashOutput {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.value[..self.len]
+    }
+}
+
+impl core::fmt::Debug for HashOutput {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("HashOutput").finish_non_exhaustive()
+    }
+}
+
+/// The `SHA256` hash function.
+#[derive(Clone, Copy, Debug)]
+pub struct Sha256;
+
+/// The `SHA512` hash function.
+#[derive(Clone, Copy, Debug)]
+pub struct Sha512;
+
+macro_rules! sha_hash {
+    ( $hash:ident, $name:expr, $digest:expr, $hmac:expr ) => {
+        impl sealed::Sealed for $hash {}
+
+        impl Hash for $hash {
+            fn name(&self) -> &'static str {
+                $name
+            }
+
+            fn hash_len(&self) -> usize {
+                $digest.output_len()
+            }
+
+            fn block_len(&self) -> usize {
+                $digest.block_len()
+            }
+
+            fn hash(&self, parts: &[&[u8]]) -> HashOutput {
+                let mut ctx = digest::Context::new(&$digest);
+                parts.iter().for_each(|part| ctx.update(part));
+                HashOutput::new(ctx.finish().as_ref())
+            }
+
+            fn hmac(&self, key: &[u8], parts: &[&[u8]]) -> HashOutput {
+                let key = hmac::Key::new($hmac, key);
+                let mut ctx = hmac::Context::with_key(&key);
+                parts.iter().for_each(|part| ctx.update(part));
+                HashOutput::new(ctx.sign().as_ref())
+            }
+        }
+    };
+}
+
+sha_hash!(Sha256, "SHA256", digest::SHA256, hmac::HMAC_SHA256);
+sha_hash!(Sha512, "SHA512", digest::SHA512, hmac::HMAC_SHA512);
+
+/// The `BLAKE2s` hash function: BLAKE2s-256.
+#[derive(Clone, Copy, Debug)]
+pub struct Blake2s;
+
+impl sealed::Sealed for Blake2s {}
+
+impl Hash for Blake2s {
+    fn name(&self) -> &'static str {
+        "BLAKE2s"
+    }
+
+    fn hash_len(&self) -> usize {
+        blake2s::OUTPUT_LEN
+    }
+
+    fn block_len(&self) -> usize {
+        blake2s::BLOCK_LEN
+    }
+
+    fn hash(&self, parts: &[&[u8]]) -> HashOutput {
+        let mut ctx = blake2s::Context::new();
+        parts.iter().for_each(|part| ctx.update(part));
+        HashOutput::new(&ctx.finish())
+    }
+
+    fn hmac(&self, key: &[u8], parts: &[&[u8]]) -> HashOutput {
+        HashOutput::new(&blake2s::hmac(key, parts))
+    }
+}
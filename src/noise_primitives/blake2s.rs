@@ -0,0 +1,160 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! BLAKE2s-256, as specified in [RFC 7693], and HMAC using it.
+//!
+//! [RFC 7693]: https://www.rfc-editor.org/rfc/rfc7693
+
+use crate::polyfill::u64_from_usize;
+
+pub(super) const OUTPUT_LEN: usize = 32;
+pub(super) const BLOCK_LEN: usize = 64;
+
+#[derive(Clone)]
+pub(super) struct Context {
+    h: [u32; 8],
+    // The number of bytes compressed so far.
+    t: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+}
+
+impl Context {
+    pub(super) fn new() -> Self {
+        let mut h = IV;
+        // The parameter block: no key, and `OUTPUT_LEN` bytes of output.
+        #[allow(clippy::cast_possible_truncation)]
+        let output_len = OUTPUT_LEN as u32;
+        h[0] ^= 0x0101_0000 | output_len;
+        Self {
+            h,
+            t: 0,
+            block: [0; BLOCK_LEN],
+            block_len: 0,
+        }
+    }
+
+    pub(super) fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // The last block is compressed differently, so a full block is
+            // only compressed once we know more input follows it.
+            if self.block_len == BLOCK_LEN {
+                self.t = self.t.wrapping_add(u64_from_usize(BLOCK_LEN));
+                compress(&mut self.h, &self.block, self.t, false);
+                self.block_len = 0;
+            }
+            let todo = core::cmp::min(BLOCK_LEN - self.block_len, data.len());
+            let (block_data, rest) = data.split_at(todo);
+            self.block[self.block_len..][..todo].copy_from_slice(block_data);
+            self.block_len += todo;
+            data = rest;
+        }
+    }
+
+    pub(super) fn finish(mut self) -> [u8; OUTPUT_LEN] {
+        self.t = self.t.wrapping_add(u64_from_usize(self.block_len));
+        self.block[self.block_len..].fill(0);
+        compress(&mut self.h, &self.block, self.t, true);
+
+        let mut out = [0; OUTPUT_LEN];
+        out.chunks_exact_mut(4)
+            .zip(self.h.iter())
+            .for_each(|(out, h)| out.copy_from_slice(&h.to_le_bytes()));
+        out
+    }
+}
+
+// HMAC as specified in RFC 2104, with `BLOCK_LEN`-byte blocks.
+pub(super) fn hmac(key: &[u8], parts: &[&[u8]]) -> [u8; OUTPUT_LEN] {
+    let mut padded_key = [0u8; BLOCK_LEN];
+    if key.len() > BLOCK_LEN {
+        let mut ctx = Context::new();
+        ctx.update(key);
+        padded_key[..OUTPUT_LEN].copy_from_slice(&ctx.finish());
+    } else {
+        padded_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Context::new();
+    let mut outer = Context::new();
+    inner.update(&padded_key.map(|b| b ^ 0x36));
+    outer.update(&padded_key.map(|b| b ^ 0x5c));
+
+    parts.iter().for_each(|part| inner.update(part));
+    outer.update(&inner.finish());
+    outer.finish()
+}
+
+fn compress(h: &mut [u32; 8], block: &[u8; BLOCK_LEN], t: u64, last: bool) {
+    let mut m = [0u32; 16];
+    m.iter_mut()
+        .zip(block.chunks_exact(4))
+        .for_each(|(m, b)| *m = u32::from_le_bytes(b.try_into().unwrap()));
+
+    #[allow(clippy::cast_possible_truncation)]
+    let (t_low, t_high) = (t as u32, (t >> 32) as u32);
+
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= t_low;
+    v[13] ^= t_high;
+    if last {
+        v[14] = !v[14];
+    }
+
+    for s in SIGMA.iter() {
+        g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+#[inline(always)]
+fn g(v: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(12);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(8);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(7);
+}
+
+const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
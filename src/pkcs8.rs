@@ -14,10 +14,53 @@
 
 //! PKCS#8 is specified in [RFC 5958].
 //!
+//! # Encrypted keys
+//!
+//! The `from_encrypted_pkcs8()` constructors of the key pair types accept an
+//! `EncryptedPrivateKeyInfo` encrypted with PBES2 ([RFC 8018 Section 6.2]),
+//! using PBKDF2 with HMAC-SHA256 and either AES-256-CBC or AES-256-GCM
+//! ([RFC 5084]). Such keys can be produced using the OpenSSL command line
+//! tool like this:
+//!
+//! ```sh
+//! openssl pkcs8 -topk8 -v2 aes-256-cbc -v2prf hmacWithSHA256 -outform der \
+//!     -in private-key.pem > private-key-encrypted.pk8
+//! ```
+//!
+//! The PBKDF2 iteration count is read from the key. Keys with more than
+//! [`MAX_ITERATIONS`] iterations are rejected, which bounds the time it takes
+//! to decrypt a key from an untrusted source.
+//!
+//! [`encrypt()`] produces such keys, using AES-256-GCM.
+//!
 //! [RFC 5958]: https://tools.ietf.org/html/rfc5958
+//! [RFC 8018 Section 6.2]: https://tools.ietf.org/html/rfc8018#section-6.2
+//! [RFC 5084]: https://tools.ietf.org/html/rfc5084
 
 use crate::{ec, error, io::der};
 
+#[cfg(feature = "alloc")]
+use crate::rand;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+#[cfg(feature = "alloc")]
+use core::num::NonZeroU32;
+
+#[cfg(feature = "alloc")]
+pub(crate) use pbes2::decrypt;
+
+#[cfg(feature = "alloc")]
+mod pbes2;
+
+/// The maximum PBKDF2 iteration count of an encrypted key.
+///
+/// Encrypted keys with a larger count are rejected with `TooLarge` before
+/// the key is derived, and [`encrypt()`] won't produce them.
+#[cfg(feature = "alloc")]
+pub const MAX_ITERATIONS: u32 = 10_000_000;
+
 pub(crate) struct PublicKeyOptions {
     /// Should the wrong public key ASN.1 tagging used by early implementations
     /// of PKCS#8 v2 (including earlier versions of *ring*) be accepted?
@@ -181,6 +224,13 @@ pub struct Document {
     len: usize,
 }
 
+// The document contains the private key, so don't print its contents.
+impl core::fmt::Debug for Document {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Document").finish_non_exhaustive()
+    }
+}
+
 impl AsRef<[u8]> for Document {
     #[inline]
     fn as_ref(&self) -> &[u8] {
@@ -214,3 +264,73 @@ fn wrap_key_(template: &Template, private_key: &[u8], public_key: &[u8], bytes:
         .copy_from_slice(after_private_key);
     bytes[(private_key_end_index + after_private_key.len())..].copy_from_slice(public_key);
 }
+
+/// An encrypted PKCS#8 document, generated by [`encrypt()`].
+#[cfg(feature = "alloc")]
+pub struct EncryptedDocument {
+    bytes: Box<[u8]>,
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for EncryptedDocument {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("EncryptedDocument").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for EncryptedDocument {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Encrypts the unencrypted PKCS#8 document `pkcs8` using `passphrase`.
+///
+/// The result is a PKCS#8 `EncryptedPrivateKeyInfo` encrypted with PBES2,
+/// using PBKDF2 with HMAC-SHA256, `iterations` iterations, and a random salt,
+/// and AES-256-GCM with a random nonce. It can be parsed by the
+/// `from_encrypted_pkcs8()` constructors of the key pair types.
+///
+/// `pkcs8` would usually come from a `generate_pkcs8()` function. It is only
+/// checked to be a DER `SEQUENCE`; it isn't otherwise parsed.
+///
+/// Fails if `iterations` is larger than [`MAX_ITERATIONS`].
+///
+/// ```
+/// use core::num::NonZeroU32;
+/// use ring::{pkcs8, rand, signature::{self, KeyPair}};
+///
+/// let rng = rand::SystemRandom::new();
+/// let document = signature::Ed25519KeyPair::generate_pkcs8(&rng)?;
+/// let encrypted = pkcs8::encrypt(
+///     document.as_ref(),
+///     b"passphrase",
+///     NonZeroU32::new(100_000).unwrap(),
+///     &rng,
+/// )?;
+///
+/// let key_pair =
+///     signature::Ed25519KeyPair::from_encrypted_pkcs8(encrypted.as_ref(), b"passphrase")?;
+/// assert_eq!(
+///     key_pair.public_key().as_ref(),
+///     signature::Ed25519KeyPair::from_pkcs8(document.as_ref())?
+///         .public_key()
+///         .as_ref()
+/// );
+/// # Ok::<(), ring::error::Unspecified>(())
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encrypt(
+    pkcs8: &[u8],
+    passphrase: &[u8],
+    iterations: NonZeroU32,
+    rng: &dyn rand::SecureRandom,
+) -> Result<EncryptedDocument, error::Unspecified> {
+    let _ = untrusted::Input::from(pkcs8).read_all(error::Unspecified, |input| {
+        der::expect_tag_and_get_value(input, der::Tag::Sequence)
+    })?;
+    let bytes = pbes2::encrypt(pkcs8, passphrase, iterations, rng)?;
+    Ok(EncryptedDocument { bytes })
+}
@@ -0,0 +1,435 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Encryption and decryption of `EncryptedPrivateKeyInfo` documents using
+//! PBES2 ([RFC 8018 Section 6.2]).
+//!
+//! [RFC 8018 Section 6.2]: https://tools.ietf.org/html/rfc8018#section-6.2
+
+use crate::{
+    aead::{self, aes},
+    constant_time, error,
+    error::KeyRejected,
+    io::{der, der_writer, Positive},
+    pbkdf2, rand,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::num::NonZeroU32;
+
+// 1.2.840.113549.1.5.13
+const PBES2: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0d];
+
+// 1.2.840.113549.1.5.12
+const PBKDF2: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0c];
+
+// 1.2.840.113549.2.9
+const HMAC_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x09];
+
+// 2.16.840.1.101.3.4.1.42
+const AES_256_CBC: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2a];
+
+// 2.16.840.1.101.3.4.1.46
+const AES_256_GCM: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2e];
+
+const KEY_LEN: usize = aes::AES_256_KEY_LEN;
+
+const SALT_LEN: usize = 16;
+
+// The ICVlen of AES-256-GCM, which must be encoded as it isn't the default.
+const GCM_TAG_LEN: u8 = 16;
+
+struct Parameters<'a> {
+    salt: untrusted::Input<'a>,
+    iterations: NonZeroU32,
+    scheme: EncryptionScheme,
+}
+
+enum EncryptionScheme {
+    Aes256Cbc {
+        iv: aes::Block,
+    },
+    Aes256Gcm {
+        algorithm: &'static aead::Algorithm,
+        nonce: [u8; aead::NONCE_LEN],
+    },
+}
+
+/// Decrypts the `EncryptedPrivateKeyInfo` in `input` using `passphrase`,
+/// returning the encoded private key it contains.
+///
+/// A wrong passphrase is reported as `DecryptionFailed`, unless it happens
+/// to produce valid CBC padding, in which case parsing the garbled key will
+/// fail instead.
+pub(crate) fn decrypt(input: untrusted::Input, passphrase: &[u8]) -> Result<Vec<u8>, KeyRejected> {
+    let (parameters, ciphertext) = input.read_all(KeyRejected::invalid_encoding(), |input| {
+        der::nested(
+            input,
+            der::Tag::Sequence,
+            KeyRejected::invalid_encoding(),
+            |input| {
+                let parameters = der::nested(
+                    input,
+                    der::Tag::Sequence,
+                    KeyRejected::invalid_encoding(),
+                    parse_encryption_algorithm,
+                )?;
+                let ciphertext = der::expect_tag_and_get_value(input, der::Tag::OctetString)
+                    .map_err(|error::Unspecified| KeyRejected::invalid_encoding())?;
+                Ok((parameters, ciphertext))
+            },
+        )
+    })?;
+
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        parameters.iterations,
+        parameters.salt.as_slice_less_safe(),
+        passphrase,
+        &mut key,
+    );
+
+    let mut in_out = ciphertext.as_slice_less_safe().to_vec();
+    let plaintext_len = match parameters.scheme {
+        EncryptionScheme::Aes256Cbc { iv } => cbc_decrypt_in_place(&key, iv, &mut in_out)?,
+        EncryptionScheme::Aes256Gcm { algorithm, nonce } => {
+            let key = aead::UnboundKey::new(algorithm, &key)
+                .map_err(|error::Unspecified| KeyRejected::unexpected_error())?;
+            aead::LessSafeKey::new(key)
+                .open_in_place(
+                    aead::Nonce::assume_unique_for_key(nonce),
+                    aead::Aad::empty(),
+                    &mut in_out,
+                )
+                .map_err(|error::Unspecified| KeyRejected::decryption_failed())?
+                .len()
+        }
+    };
+    in_out.truncate(plaintext_len);
+    Ok(in_out)
+}
+
+/// Encrypts the encoded private key `pkcs8` with AES-256-GCM under a key
+/// derived from `passphrase` using PBKDF2-HMAC-SHA256, returning the
+/// `EncryptedPrivateKeyInfo`.
+pub(crate) fn encrypt(
+    pkcs8: &[u8],
+    passphrase: &[u8],
+    iterations: NonZeroU32,
+    rng: &dyn rand::SecureRandom,
+) -> Result<Box<[u8]>, error::Unspecified> {
+    if iterations.get() > super::MAX_ITERATIONS {
+        return Err(error::Unspecified);
+    }
+
+    let salt: [u8; SALT_LEN] = rand::generate(rng)?.expose();
+    let nonce: [u8; aead::NONCE_LEN] = rand::generate(rng)?.expose();
+
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        &salt,
+        passphrase,
+        &mut key,
+    );
+    let key = aead::LessSafeKey::new(aead::UnboundKey::new(&aead::AES_256_GCM, &key)?);
+    let mut ciphertext = pkcs8.to_vec();
+    key.seal_in_place_append_tag(
+        aead::Nonce::assume_unique_for_key(nonce),
+        aead::Aad::empty(),
+        &mut ciphertext,
+    )?;
+
+    let iterations = iterations.get().to_be_bytes();
+    let leading_zeros = iterations.iter().take_while(|&&b| b == 0).count();
+    let iterations = Positive::from_be_bytes(untrusted::Input::from(&iterations[leading_zeros..]))?;
+
+    der_writer::write_all(der::Tag::Sequence, &|output| {
+        der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+            der_writer::write_tlv(output, der::Tag::OID, |output| output.write_bytes(PBES2))?;
+            der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                    der_writer::write_tlv(output, der::Tag::OID, |output| {
+                        output.write_bytes(PBKDF2)
+                    })?;
+                    der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                        der_writer::write_tlv(output, der::Tag::OctetString, |output| {
+                            output.write_bytes(&salt)
+                        })?;
+                        der_writer::write_positive_integer(output, &iterations)?;
+                        der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                            der_writer::write_tlv(output, der::Tag::OID, |output| {
+                                output.write_bytes(HMAC_WITH_SHA256)
+                            })?;
+                            der_writer::write_tlv(output, der::Tag::Null, |_| Ok(()))
+                        })
+                    })
+                })?;
+                der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                    der_writer::write_tlv(output, der::Tag::OID, |output| {
+                        output.write_bytes(AES_256_GCM)
+                    })?;
+                    der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                        der_writer::write_tlv(output, der::Tag::OctetString, |output| {
+                            output.write_bytes(&nonce)
+                        })?;
+                        der_writer::write_tlv(output, der::Tag::Integer, |output| {
+                            output.write_byte(GCM_TAG_LEN)
+                        })
+                    })
+                })
+            })
+        })?;
+        der_writer::write_tlv(output, der::Tag::OctetString, |output| {
+            output.write_bytes(&ciphertext)
+        })
+    })
+    .map_err(|_| error::Unspecified)
+}
+
+fn parse_encryption_algorithm<'a>(
+    input: &mut untrusted::Reader<'a>,
+) -> Result<Parameters<'a>, KeyRejected> {
+    expect_oid(input, PBES2)?;
+    der::nested(
+        input,
+        der::Tag::Sequence,
+        KeyRejected::invalid_encoding(),
+        |input| {
+            let (salt, iterations) = der::nested(
+                input,
+                der::Tag::Sequence,
+                KeyRejected::invalid_encoding(),
+                parse_key_derivation_function,
+            )?;
+            let scheme = der::nested(
+                input,
+                der::Tag::Sequence,
+                KeyRejected::invalid_encoding(),
+                parse_encryption_scheme,
+            )?;
+            Ok(Parameters {
+                salt,
+                iterations,
+                scheme,
+            })
+        },
+    )
+}
+
+// RFC 8018 Appendix A.2.
+fn parse_key_derivation_function<'a>(
+    input: &mut untrusted::Reader<'a>,
+) -> Result<(untrusted::Input<'a>, NonZeroU32), KeyRejected> {
+    expect_oid(input, PBKDF2)?;
+    der::nested(
+        input,
+        der::Tag::Sequence,
+        KeyRejected::invalid_encoding(),
+        |input| {
+            let salt = der::expect_tag_and_get_value(input, der::Tag::OctetString)
+                .map_err(|error::Unspecified| KeyRejected::invalid_encoding())?;
+            let iterations = nonzero_u32(input)?;
+            if iterations.get() > super::MAX_ITERATIONS {
+                return Err(KeyRejected::too_large());
+            }
+
+            if input.peek(der::Tag::Integer.into()) {
+                let key_len = der::small_nonnegative_integer(input)
+                    .map_err(|error::Unspecified| KeyRejected::invalid_encoding())?;
+                if usize::from(key_len) != KEY_LEN {
+                    return Err(KeyRejected::invalid_encoding());
+                }
+            }
+
+            // The default PRF is HMAC-SHA1, which isn't supported.
+            if input.at_end() {
+                return Err(KeyRejected::encrypted_keys_not_supported());
+            }
+            der::nested(
+                input,
+                der::Tag::Sequence,
+                KeyRejected::invalid_encoding(),
+                |input| {
+                    expect_oid(input, HMAC_WITH_SHA256)?;
+                    skip_null_parameters(input)
+                },
+            )?;
+
+            Ok((salt, iterations))
+        },
+    )
+}
+
+fn parse_encryption_scheme(input: &mut untrusted::Reader) -> Result<EncryptionScheme, KeyRejected> {
+    let oid = der::expect_tag_and_get_value(input, der::Tag::OID)
+        .map_err(|error::Unspecified| KeyRejected::invalid_encoding())?;
+    match oid.as_slice_less_safe() {
+        AES_256_CBC => {
+            let iv = fixed_len_octet_string(input)?;
+            Ok(EncryptionScheme::Aes256Cbc { iv })
+        }
+
+        // RFC 5084 Section 3.2.
+        AES_256_GCM => der::nested(
+            input,
+            der::Tag::Sequence,
+            KeyRejected::invalid_encoding(),
+            |input| {
+                let nonce = fixed_len_octet_string(input)?;
+                let tag_len = if input.at_end() {
+                    12
+                } else {
+                    der::small_nonnegative_integer(input)
+                        .map_err(|error::Unspecified| KeyRejected::invalid_encoding())?
+                };
+                let algorithm = match tag_len {
+                    GCM_TAG_LEN => &aead::AES_256_GCM,
+                    12 => &aead::AES_256_GCM_TAG96,
+                    8 => &aead::AES_256_GCM_TAG64,
+                    _ => {
+                        return Err(KeyRejected::encrypted_keys_not_supported());
+                    }
+                };
+                Ok(EncryptionScheme::Aes256Gcm { algorithm, nonce })
+            },
+        ),
+
+        _ => Err(KeyRejected::encrypted_keys_not_supported()),
+    }
+}
+
+// Decrypts `in_out` with AES-256-CBC and removes the padding described in
+// RFC 8018 Section 6.1.1, returning the length of the plaintext.
+//
+// Unlike in `aead::cbc_hmac`, the padding isn't checked in constant time;
+// the only secret it could reveal is whether a guessed passphrase is
+// correct, which the attacker can already check offline.
+fn cbc_decrypt_in_place(
+    key: &[u8; KEY_LEN],
+    iv: aes::Block,
+    in_out: &mut [u8],
+) -> Result<usize, KeyRejected> {
+    if in_out.is_empty() || in_out.len() % aes::BLOCK_LEN != 0 {
+        return Err(KeyRejected::invalid_encoding());
+    }
+
+    let key = aes::DecryptKey::new(aes::KeyBytes::AES_256(key));
+    let mut chaining = iv;
+    for block in in_out.chunks_exact_mut(aes::BLOCK_LEN) {
+        let mut ciphertext = [0u8; aes::BLOCK_LEN];
+        ciphertext.copy_from_slice(block);
+        let plaintext = constant_time::xor_16(chaining, key.decrypt_block(ciphertext));
+        block.copy_from_slice(&plaintext);
+        chaining = ciphertext;
+    }
+
+    let padding_len = in_out.last().map_or(0, |&b| usize::from(b));
+    if padding_len == 0 || padding_len > aes::BLOCK_LEN {
+        return Err(KeyRejected::decryption_failed());
+    }
+    let plaintext_len = in_out.len() - padding_len;
+    if in_out[plaintext_len..]
+        .iter()
+        .any(|&b| usize::from(b) != padding_len)
+    {
+        return Err(KeyRejected::decryption_failed());
+    }
+    Ok(plaintext_len)
+}
+
+fn expect_oid(input: &mut untrusted::Reader, expected: &[u8]) -> Result<(), KeyRejected> {
+    let oid = der::expect_tag_and_get_value(input, der::Tag::OID)
+        .map_err(|error::Unspecified| KeyRejected::invalid_encoding())?;
+    if oid.as_slice_less_safe() != expected {
+        return Err(KeyRejected::encrypted_keys_not_supported());
+    }
+    Ok(())
+}
+
+// The parameters of an `AlgorithmIdentifier` that takes none may be absent
+// or NULL.
+fn skip_null_parameters(input: &mut untrusted::Reader) -> Result<(), KeyRejected> {
+    if input.at_end() {
+        return Ok(());
+    }
+    let null = der::expect_tag_and_get_value(input, der::Tag::Null)
+        .map_err(|error::Unspecified| KeyRejected::invalid_encoding())?;
+    if !null.is_empty() {
+        return Err(KeyRejected::invalid_encoding());
+    }
+    Ok(())
+}
+
+fn fixed_len_octet_string<const N: usize>(
+    input: &mut untrusted::Reader,
+) -> Result<[u8; N], KeyRejected> {
+    let value = der::expect_tag_and_get_value(input, der::Tag::OctetString)
+        .map_err(|error::Unspecified| KeyRejected::invalid_encoding())?;
+    value
+        .as_slice_less_safe()
+        .try_into()
+        .map_err(|_| KeyRejected::invalid_encoding())
+}
+
+// Values of 2^31 or more are encoded with a leading zero byte, which
+// `der::nonnegative_integer()` removes.
+fn nonzero_u32(input: &mut untrusted::Reader) -> Result<NonZeroU32, KeyRejected> {
+    let value = der::nonnegative_integer(input)
+        .map_err(|error::Unspecified| KeyRejected::invalid_encoding())?;
+    let value = value.as_slice_less_safe();
+    if value.len() > 4 {
+        return Err(KeyRejected::invalid_encoding());
+    }
+    let value = value.iter().fold(0u32, |acc, &b| (acc << 8) | u32::from(b));
+    NonZeroU32::new(value).ok_or_else(KeyRejected::invalid_encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonzero_u32() {
+        for (encoded, expected) in [
+            (&[0x02, 0x01, 0x01][..], Some(1)),
+            (&[0x02, 0x02, 0x00, 0x80][..], Some(0x80)),
+            (&[0x02, 0x04, 0x7f, 0xff, 0xff, 0xff][..], Some(0x7fff_ffff)),
+            (
+                &[0x02, 0x05, 0x00, 0x80, 0x00, 0x00, 0x00][..],
+                Some(0x8000_0000),
+            ),
+            (
+                &[0x02, 0x05, 0x00, 0xff, 0xff, 0xff, 0xff][..],
+                Some(u32::MAX),
+            ),
+            // Zero.
+            (&[0x02, 0x01, 0x00][..], None),
+            // Negative.
+            (&[0x02, 0x04, 0x80, 0x00, 0x00, 0x00][..], None),
+            // Unnecessary leading zero.
+            (&[0x02, 0x02, 0x00, 0x01][..], None),
+            // Too large.
+            (&[0x02, 0x05, 0x01, 0x00, 0x00, 0x00, 0x00][..], None),
+            (&[0x02, 0x06, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00][..], None),
+        ] {
+            let actual = untrusted::Input::from(encoded)
+                .read_all(KeyRejected::invalid_encoding(), nonzero_u32)
+                .ok()
+                .map(NonZeroU32::get);
+            assert_eq!(actual, expected, "{encoded:02x?}");
+        }
+    }
+}
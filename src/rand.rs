@@ -105,6 +105,25 @@ impl<T> RandomlyConstructable for T where T: sealed::RandomlyConstructable {}
 /// initialization is deferred to it. Therefore, it may be a good idea to call
 /// `fill()` once at a non-latency-sensitive time to minimize latency for
 /// future calls.
+///
+/// On `target_os = "none"` targets, which have no operating system RNG,
+/// `SystemRandom` is only available with the
+/// `less-safe-getrandom-custom-or-rdrand` feature. That feature enables
+/// `getrandom`'s `custom` backend, so the application can provide its own
+/// entropy source by registering it with `getrandom`:
+///
+/// ```ignore
+/// // In the application, which depends on `getrandom` 0.2 directly.
+/// fn hardware_rng_fill(dest: &mut [u8]) -> Result<(), getrandom::Error> {
+///     // Fill `dest` from the hardware RNG.
+/// }
+/// getrandom::register_custom_getrandom!(hardware_rng_fill);
+/// ```
+///
+/// `SystemRandom::fill()` then uses `hardware_rng_fill`. On x86 targets,
+/// RDRAND is used instead if the application enables `getrandom`'s `rdrand`
+/// feature. Registering a custom function has no effect on targets that have
+/// an operating system RNG.
 #[derive(Clone, Debug)]
 pub struct SystemRandom(());
 
@@ -118,42 +137,243 @@ impl SystemRandom {
 
 impl crate::sealed::Sealed for SystemRandom {}
 
-// Use the `getrandom` crate whenever it is using the environment's (operating
-// system's) CSPRNG. Avoid using it on targets where it uses the `rdrand`
-// implementation.
-#[cfg(any(
-    all(feature = "less-safe-getrandom-custom-or-rdrand", target_os = "none"),
-    all(feature = "less-safe-getrandom-espidf", target_os = "espidf"),
-    target_os = "aix",
-    target_os = "android",
-    target_os = "dragonfly",
-    target_os = "freebsd",
-    target_os = "haiku",
-    target_os = "hermit",
-    target_os = "hurd",
-    target_os = "illumos",
-    target_os = "ios",
-    target_os = "linux",
-    target_os = "macos",
-    target_os = "netbsd",
-    target_os = "openbsd",
-    target_os = "redox",
-    target_os = "solaris",
-    target_os = "tvos",
-    target_os = "vita",
-    target_os = "windows",
-    target_os = "espidf",
-    all(
-        target_arch = "wasm32",
-        any(
-            target_os = "wasi",
-            all(target_os = "unknown", feature = "wasm32_unknown_unknown_js")
-        )
-    ),
-))]
-impl sealed::SecureRandom for SystemRandom {
-    #[inline(always)]
+// Applies to each item the `cfg` of the targets on which `SystemRandom` is a
+// `SecureRandom`. Use the `getrandom` crate whenever it is using the
+// environment's (operating system's) CSPRNG. Avoid using it on targets where
+// it uses the `rdrand` implementation.
+//
+// `SupplementedRandom` is built on `SystemRandom`, so it exists only on these
+// targets too.
+macro_rules! if_system_random {
+    ( $( $item:item )* ) => {
+        $(
+            #[cfg(any(
+                all(feature = "less-safe-getrandom-custom-or-rdrand", target_os = "none"),
+                all(feature = "less-safe-getrandom-espidf", target_os = "espidf"),
+                target_os = "aix",
+                target_os = "android",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "haiku",
+                target_os = "hermit",
+                target_os = "hurd",
+                target_os = "illumos",
+                target_os = "ios",
+                target_os = "linux",
+                target_os = "macos",
+                target_os = "netbsd",
+                target_os = "openbsd",
+                target_os = "redox",
+                target_os = "solaris",
+                target_os = "tvos",
+                target_os = "vita",
+                target_os = "windows",
+                target_os = "espidf",
+                all(
+                    target_arch = "wasm32",
+                    any(
+                        target_os = "wasi",
+                        all(target_os = "unknown", feature = "wasm32_unknown_unknown_js")
+                    )
+                ),
+            ))]
+            $item
+        )*
+    };
+}
+
+if_system_random! {
+    use crate::hkdf;
+
+    impl SystemRandom {
+        /// Constructs a `SecureRandom` that mixes `seed` into the output of the
+        /// operating system's random number generator.
+        ///
+        /// This is for applications that have an additional source of entropy,
+        /// such as a hardware TRNG, that they want to use without relying on it
+        /// exclusively.
+        ///
+        /// Every `fill()` XORs fresh system randomness with an HKDF-SHA256 stream
+        /// that is keyed by `seed` and bound to another fresh system random
+        /// value. The output is thus never weaker than `SystemRandom`'s output
+        /// alone, and it remains unpredictable to anybody who doesn't know `seed`
+        /// even if the system's output is predictable.
+        ///
+        /// ```
+        /// use ring::rand::{self, SecureRandom};
+        ///
+        /// let trng_output = [0x42; 32]; // Read from the hardware TRNG.
+        /// let rng = rand::SystemRandom::with_additional_entropy(&trng_output);
+        /// let mut key = [0u8; 32];
+        /// rng.fill(&mut key)?;
+        /// # Ok::<(), ring::error::Unspecified>(())
+        /// ```
+        pub fn with_additional_entropy(seed: &[u8]) -> SupplementedRandom {
+            SupplementedRandom {
+                prk: hkdf::Salt::new(hkdf::HKDF_SHA256, SUPPLEMENTED_SALT).extract(seed),
+            }
+        }
+    }
+
+    /// A `SecureRandom` that mixes additional entropy into the output of
+    /// `SystemRandom`.
+    ///
+    /// See [`SystemRandom::with_additional_entropy()`].
+    #[derive(Clone)]
+    pub struct SupplementedRandom {
+        prk: hkdf::Prk,
+    }
+
+    impl crate::sealed::Sealed for SupplementedRandom {}
+
+    impl core::fmt::Debug for SupplementedRandom {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.debug_struct("SupplementedRandom").finish()
+        }
+    }
+
+    const SUPPLEMENTED_SALT: &[u8] = b"ring SupplementedRandom";
+
+    // Bounds the stack usage of `fill()`. This must not exceed 255 * 32, the
+    // largest output of a single HKDF-SHA256 expansion.
+    const SUPPLEMENTED_CHUNK_LEN: usize = 1024;
+
+    impl SupplementedRandom {
+        fn fill_(&self, system: &dyn SecureRandom, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            let mut stream = [0u8; SUPPLEMENTED_CHUNK_LEN];
+            for chunk in dest.chunks_mut(SUPPLEMENTED_CHUNK_LEN) {
+                system.fill(chunk)?;
+
+                // `nonce` is independent of `chunk`, so the XOR below can't
+                // cancel out the system's randomness.
+                let mut nonce = [0u8; 32];
+                system.fill(&mut nonce)?;
+                let stream = &mut stream[..chunk.len()];
+                self.prk
+                    .expand(&[&nonce], hkdf::Len(stream.len()))?
+                    .fill(stream)?;
+                chunk
+                    .iter_mut()
+                    .zip(stream.iter())
+                    .for_each(|(c, s)| *c ^= *s);
+            }
+            Ok(())
+        }
+    }
+
+    impl sealed::SecureRandom for SupplementedRandom {
+        fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            self.fill_(&SystemRandom::new(), dest)
+        }
+    }
+}
+
+/// A `SecureRandom` that simulates a faulty random number generator.
+///
+/// This is for testing that protocols built on *ring* fail safely when the
+/// random number generator is broken. *ring*'s own operations are hedged
+/// against such failures: for example, ECDSA signing mixes the private key
+/// and the message into each nonce, so signing with an RNG that only returns
+/// zeros still never reuses a nonce for different messages, and a nonce that
+/// isn't a valid scalar, e.g. zero, is rejected. (Ed25519 signing doesn't use
+/// an RNG at all.)
+///
+/// Only available with the `test-util` feature. Never use this outside of
+/// tests.
+///
+/// ```
+/// use ring::rand::{self, SecureRandom};
+///
+/// let rng = rand::FaultyRandom::new(rand::Fault::Constant(0));
+/// let mut bytes = [0xff; 4];
+/// rng.fill(&mut bytes)?;
+/// assert_eq!(bytes, [0; 4]);
+///
+/// let rng = rand::FaultyRandom::new(rand::Fault::Fail);
+/// assert!(rng.fill(&mut bytes).is_err());
+/// # Ok::<(), ring::error::Unspecified>(())
+/// ```
+#[cfg(feature = "test-util")]
+#[derive(Clone, Debug)]
+pub struct FaultyRandom {
+    fault: Fault,
+}
+
+/// How a [`FaultyRandom`] misbehaves.
+#[cfg(feature = "test-util")]
+#[derive(Clone, Copy, Debug)]
+pub enum Fault {
+    /// Every byte of the output is the given byte, e.g. zero, so every
+    /// `fill()` repeats the output of the previous one.
+    Constant(u8),
+
+    /// Every `fill()` fails.
+    Fail,
+}
+
+#[cfg(feature = "test-util")]
+impl FaultyRandom {
+    /// Constructs a new `FaultyRandom` that misbehaves according to `fault`.
+    pub fn new(fault: Fault) -> Self {
+        Self { fault }
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl crate::sealed::Sealed for FaultyRandom {}
+
+#[cfg(feature = "test-util")]
+impl sealed::SecureRandom for FaultyRandom {
     fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
-        getrandom::getrandom(dest).map_err(|_| error::Unspecified)
+        match self.fault {
+            Fault::Constant(byte) => {
+                dest.fill(byte);
+                Ok(())
+            }
+            Fault::Fail => Err(error::Unspecified),
+        }
+    }
+}
+
+if_system_random! {
+    mod os {
+        use super::{sealed, SystemRandom};
+        use crate::error;
+
+        impl sealed::SecureRandom for SystemRandom {
+            #[inline(always)]
+            fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+                getrandom::getrandom(dest).map_err(|_| error::Unspecified)
+            }
+        }
+
+        /// Only available with the `rand_core` feature.
+        #[cfg(feature = "rand_core")]
+        impl rand_core::RngCore for SystemRandom {
+            fn next_u32(&mut self) -> u32 {
+                rand_core::impls::next_u32_via_fill(self)
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                rand_core::impls::next_u64_via_fill(self)
+            }
+
+            /// Panics if the operating system's random number generator fails, as
+            /// `rand_core` specifies; use `try_fill_bytes()` to handle that.
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                if let Err(e) = self.try_fill_bytes(dest) {
+                    panic!("SystemRandom failed: {}", e);
+                }
+            }
+
+            /// The `rand_core::Error` has the error code reported by `getrandom`.
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+                getrandom::getrandom(dest).map_err(|e| rand_core::Error::from(e.code()))
+            }
+        }
+
+        /// Only available with the `rand_core` feature.
+        #[cfg(feature = "rand_core")]
+        impl rand_core::CryptoRng for SystemRandom {}
     }
 }
@@ -59,6 +59,7 @@ enum N {}
 
 impl bigint::PublicModulus for N {}
 
+pub(crate) mod jwk;
 mod keypair;
 mod keypair_components;
 mod public_exponent;
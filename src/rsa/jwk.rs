@@ -0,0 +1,66 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Parsing of RSA public keys in the JSON Web Key (JWK) format.
+
+use super::{PublicExponent, PublicKey, PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN};
+use crate::{
+    bits::{self, FromByteLen as _},
+    cpu, error, io,
+    jwk::{base64url_decode, parse_members},
+};
+
+/// Parses an RSA public key in the JSON Web Key (JWK) format of [RFC 7517],
+/// with the members specified in [RFC 7518 Section 6.3.1].
+///
+/// `jwk` must be a JSON object with a `"kty"` member with the value `"RSA"`
+/// and `"n"` and `"e"` members whose values are the modulus and the public
+/// exponent, each encoded as the base64url encoding, without padding, of its
+/// big-endian representation with no leading zeros. Other members, such as
+/// `"alg"`, `"kid"`, or `"use"`, are ignored; it is up to the caller to check
+/// them. Duplicate member names, member names containing escape sequences,
+/// and anything other than strict JSON are rejected.
+///
+/// The modulus must be between 1024 and 8192 bits, and the public exponent
+/// must be at least 3. The `RsaParameters` that the key is used with may
+/// impose stricter limits.
+///
+/// The result can be used with `signature::UnparsedPublicKey`, since its
+/// `as_ref()` is the key's DER-encoded `RSAPublicKey`.
+///
+/// [RFC 7517]: https://www.rfc-editor.org/rfc/rfc7517
+/// [RFC 7518 Section 6.3.1]: https://www.rfc-editor.org/rfc/rfc7518#section-6.3.1
+pub fn rsa_public_key_from_jwk(jwk: &[u8]) -> Result<PublicKey, error::Unspecified> {
+    let (n, e) = match parse_members(jwk, [b"kty", b"n", b"e"])? {
+        [Some(kty), Some(n), Some(e)] if kty.as_slice_less_safe() == b"RSA" => (n, e),
+        _ => return Err(error::Unspecified),
+    };
+
+    let n = base64url_decode(n)?;
+    let e = base64url_decode(e)?;
+
+    // Reject zero and leading zeros, as RFC 7518 requires.
+    let _: io::Positive = io::Positive::from_be_bytes(untrusted::Input::from(&n))?;
+    let _: io::Positive = io::Positive::from_be_bytes(untrusted::Input::from(&e))?;
+
+    PublicKey::from_modulus_and_exponent(
+        untrusted::Input::from(&n),
+        untrusted::Input::from(&e),
+        bits::BitLength::from_bits(1024),
+        bits::BitLength::<usize>::from_byte_len(PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN)?,
+        PublicExponent::_3,
+        cpu::features(),
+    )
+    .map_err(error::Unspecified::from)
+}
@@ -19,7 +19,10 @@ mod pss;
 
 pub use self::{
     pkcs1::{RSA_PKCS1_SHA256, RSA_PKCS1_SHA384, RSA_PKCS1_SHA512},
-    pss::{RSA_PSS_SHA256, RSA_PSS_SHA384, RSA_PSS_SHA512},
+    pss::{
+        RSA_PSS_SHA256, RSA_PSS_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY, RSA_PSS_SHA384,
+        RSA_PSS_SHA512,
+    },
 };
 pub(super) use pkcs1::RSA_PKCS1_SHA1_FOR_LEGACY_USE_ONLY;
 
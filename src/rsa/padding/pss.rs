@@ -25,6 +25,7 @@ use crate::{bits, constant_time, digest, error, rand};
 #[derive(Debug)]
 pub struct PSS {
     digest_alg: &'static digest::Algorithm,
+    mgf1_digest_alg: &'static digest::Algorithm,
 }
 
 impl crate::sealed::Sealed for PSS {}
@@ -86,7 +87,7 @@ impl RsaEncoding for PSS {
         db[separator_pos] = 0x01;
 
         // Steps 9 and 10.
-        mgf1(self.digest_alg, h.as_ref(), db);
+        mgf1(self.mgf1_digest_alg, h.as_ref(), db);
 
         // Step 11.
         db[0] &= metrics.top_byte_mask;
@@ -146,7 +147,7 @@ impl Verification for PSS {
         let mut db = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
         let db = &mut db[..metrics.db_len];
 
-        mgf1(self.digest_alg, h_hash.as_slice_less_safe(), db);
+        mgf1(self.mgf1_digest_alg, h_hash.as_slice_less_safe(), db);
 
         masked_db.read_all(error::Unspecified, |masked_bytes| {
             // Step 6. Check the top bits of first byte are zero.
@@ -211,6 +212,8 @@ impl PSSMetrics {
         debug_assert!(leading_zero_bits < 8);
         let top_byte_mask = 0xffu8 >> leading_zero_bits;
 
+        // The MGF1 digest algorithm doesn't affect the lengths, since MGF1
+        // output can be of any length.
         let h_len = digest_alg.output_len();
 
         // We require the salt length to be equal to the digest length.
@@ -257,9 +260,14 @@ fn pss_digest(
 
 macro_rules! rsa_pss_padding {
     ( $vis:vis $PADDING_ALGORITHM:ident, $digest_alg:expr, $doc_str:expr ) => {
+        rsa_pss_padding!($vis $PADDING_ALGORITHM, $digest_alg, $digest_alg, $doc_str);
+    };
+    ( $vis:vis $PADDING_ALGORITHM:ident, $digest_alg:expr, $mgf1_digest_alg:expr,
+      $doc_str:expr ) => {
         #[doc=$doc_str]
         $vis static $PADDING_ALGORITHM: PSS = PSS {
             digest_alg: $digest_alg,
+            mgf1_digest_alg: $mgf1_digest_alg,
         };
     };
 }
@@ -287,3 +295,14 @@ rsa_pss_padding!(
                  \"`RSA_PSS_*` Details\" in `ring::signature`'s module-level
                  documentation for more details."
 );
+
+rsa_pss_padding!(
+    pub RSA_PSS_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY,
+    &digest::SHA256,
+    &digest::SHA1_FOR_LEGACY_USE_ONLY,
+    "RSA PSS padding using SHA-256 for the message digest and SHA-1 for MGF1,
+                 for RSA signatures.\n\nThis is only for interoperating with legacy
+                 systems that require this combination.\n\nSee
+                 \"`RSA_PSS_*` Details\" in `ring::signature`'s module-level
+                 documentation for more details."
+);
@@ -45,6 +45,25 @@ impl signature::VerificationAlgorithm for RsaParameters {
 
 impl sealed::Sealed for RsaParameters {}
 
+impl signature::public_key_matching::PublicKeyMatching for RsaParameters {}
+
+impl signature::public_key_weakness::PublicKeyWeakness for RsaParameters {}
+
+impl signature::public_key_validation::PublicKeyValidation for RsaParameters {
+    fn validate_public_key(&self, public_key: untrusted::Input) -> Result<(), error::Unspecified> {
+        let (n, e) = parse_public_key(public_key)?;
+        let _ = public_key_inner(
+            self,
+            (
+                n.big_endian_without_leading_zero_as_input(),
+                e.big_endian_without_leading_zero_as_input(),
+            ),
+            cpu::features(),
+        )?;
+        Ok(())
+    }
+}
+
 macro_rules! rsa_params {
     ( $VERIFY_ALGORITHM:ident, $min_bits:expr, $PADDING_ALGORITHM:expr,
       $doc_str:expr ) => {
@@ -63,7 +82,10 @@ rsa_params!(
     1024,
     &super::padding::RSA_PKCS1_SHA1_FOR_LEGACY_USE_ONLY,
     "Verification of signatures using RSA keys of 1024-8192 bits,
-             PKCS#1.5 padding, and SHA-1.\n\nSee \"`RSA_PKCS1_*` Details\" in
+             PKCS#1.5 padding, and SHA-1.\n\nThis is only for validating
+             legacy signatures, e.g. on archived documents and old
+             certificates; *ring* doesn't support creating SHA-1
+             signatures.\n\nSee \"`RSA_PKCS1_*` Details\" in
              `ring::signature`'s module-level documentation for more details."
 );
 rsa_params!(
@@ -71,7 +93,10 @@ rsa_params!(
     2048,
     &super::padding::RSA_PKCS1_SHA1_FOR_LEGACY_USE_ONLY,
     "Verification of signatures using RSA keys of 2048-8192 bits,
-             PKCS#1.5 padding, and SHA-1.\n\nSee \"`RSA_PKCS1_*` Details\" in
+             PKCS#1.5 padding, and SHA-1.\n\nThis is only for validating
+             legacy signatures, e.g. on archived documents and old
+             certificates; *ring* doesn't support creating SHA-1
+             signatures.\n\nSee \"`RSA_PKCS1_*` Details\" in
              `ring::signature`'s module-level documentation for more details."
 );
 rsa_params!(
@@ -147,6 +172,16 @@ rsa_params!(
              PSS padding, and SHA-512.\n\nSee \"`RSA_PSS_*` Details\" in
              `ring::signature`'s module-level documentation for more details."
 );
+rsa_params!(
+    RSA_PSS_2048_8192_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY,
+    2048,
+    &super::padding::RSA_PSS_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY,
+    "Verification of signatures using RSA keys of 2048-8192 bits,
+             PSS padding, SHA-256 for the message digest, and SHA-1 for
+             MGF1.\n\nThis is only for interoperating with legacy systems that
+             use this combination.\n\nSee \"`RSA_PSS_*` Details\" in
+             `ring::signature`'s module-level documentation for more details."
+);
 
 pub use super::PublicKeyComponents as RsaPublicKeyComponents;
 
@@ -200,6 +235,24 @@ pub(crate) fn verify_rsa_(
     signature: untrusted::Input,
     cpu_features: cpu::Features,
 ) -> Result<(), error::Unspecified> {
+    let key = public_key_inner(params, (n, e), cpu_features)?;
+
+    // RFC 8017 Section 5.2.2: RSAVP1.
+    let mut decoded = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
+    let decoded = key.exponentiate(signature, &mut decoded, cpu_features)?;
+
+    // Verify the padded message is correct.
+    let m_hash = digest::digest(params.padding_alg.digest_alg(), msg.as_slice_less_safe());
+    untrusted::Input::from(decoded).read_all(error::Unspecified, |m| {
+        params.padding_alg.verify(m_hash, m, key.n().len_bits())
+    })
+}
+
+fn public_key_inner(
+    params: &RsaParameters,
+    (n, e): (untrusted::Input, untrusted::Input),
+    cpu_features: cpu::Features,
+) -> Result<public_key::Inner, error::Unspecified> {
     let max_bits: bits::BitLength =
         bits::BitLength::from_byte_len(PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN)?;
 
@@ -207,22 +260,13 @@ pub(crate) fn verify_rsa_(
     // exponent value is 2**16 + 1, but it isn't clear if this is just for
     // signing or also for verification. We support exponents of 3 and larger
     // for compatibility with other commonly-used crypto libraries.
-    let key = public_key::Inner::from_modulus_and_exponent(
+    public_key::Inner::from_modulus_and_exponent(
         n,
         e,
         params.min_bits,
         max_bits,
         PublicExponent::_3,
         cpu_features,
-    )?;
-
-    // RFC 8017 Section 5.2.2: RSAVP1.
-    let mut decoded = [0u8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN];
-    let decoded = key.exponentiate(signature, &mut decoded, cpu_features)?;
-
-    // Verify the padded message is correct.
-    let m_hash = digest::digest(params.padding_alg.digest_alg(), msg.as_slice_less_safe());
-    untrusted::Input::from(decoded).read_all(error::Unspecified, |m| {
-        params.padding_alg.verify(m_hash, m, key.n().len_bits())
-    })
+    )
+    .map_err(error::Unspecified::from)
 }
@@ -102,6 +102,10 @@
 //! During signing, the message digest algorithm will be used as the MGF1
 //! digest algorithm. The salt will be the same length as the message digest.
 //! This matches the requirements in TLS 1.3 and other recent specifications.
+//!
+//! The exceptions are the `*_MGF1_SHA1_FOR_LEGACY_USE_ONLY` algorithms, which
+//! use SHA-1 as the MGF1 digest algorithm instead, for interoperability with
+//! legacy systems. The salt is still the same length as the message digest.
 //! Additionally, the entire salt is randomly generated separately for each
 //! signature using the secure random number generator passed to `sign()`.
 //!
@@ -259,29 +263,34 @@ use crate::{cpu, debug, ec, error, sealed};
 
 pub use crate::ec::{
     curve25519::ed25519::{
-        signing::Ed25519KeyPair,
-        verification::{EdDSAParameters, ED25519},
+        signing::{Ed25519KeyPair, Ed25519StreamSigner},
+        verification::{ed25519_verify_selfcert, EdDSAParameters, ED25519, ED25519_VERIFY_STRICT},
         ED25519_PUBLIC_KEY_LEN,
     },
+    curve25519::xed25519::{XEd25519KeyPair, XEdDSAParameters, XED25519},
     suite_b::ecdsa::{
         signing::{
-            EcdsaKeyPair, EcdsaSigningAlgorithm, ECDSA_P256_SHA256_ASN1_SIGNING,
-            ECDSA_P256_SHA256_FIXED_SIGNING, ECDSA_P384_SHA384_ASN1_SIGNING,
-            ECDSA_P384_SHA384_FIXED_SIGNING,
+            ecdsa_pkcs8_from_private_scalar, EcdsaKeyPair, EcdsaSigningAlgorithm,
+            ECDSA_P256_SHA256_ASN1_SIGNING, ECDSA_P256_SHA256_FIXED_SIGNING,
+            ECDSA_P384_SHA384_ASN1_SIGNING, ECDSA_P384_SHA384_FIXED_SIGNING,
         },
         verification::{
-            EcdsaVerificationAlgorithm, ECDSA_P256_SHA256_ASN1, ECDSA_P256_SHA256_FIXED,
-            ECDSA_P256_SHA384_ASN1, ECDSA_P384_SHA256_ASN1, ECDSA_P384_SHA384_ASN1,
-            ECDSA_P384_SHA384_FIXED,
+            ecdsa_verify_components, EcdsaVerificationAlgorithm, ECDSA_P256_SHA256_ASN1,
+            ECDSA_P256_SHA256_FIXED, ECDSA_P256_SHA384_ASN1, ECDSA_P384_SHA256_ASN1,
+            ECDSA_P384_SHA384_ASN1, ECDSA_P384_SHA384_FIXED,
         },
     },
 };
 
+#[cfg(feature = "alloc")]
+pub use crate::ec::jwk::ec_public_key_from_jwk;
+
 #[cfg(feature = "alloc")]
 pub use crate::rsa::{
+    jwk::rsa_public_key_from_jwk,
     padding::{
         RsaEncoding, RSA_PKCS1_SHA256, RSA_PKCS1_SHA384, RSA_PKCS1_SHA512, RSA_PSS_SHA256,
-        RSA_PSS_SHA384, RSA_PSS_SHA512,
+        RSA_PSS_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY, RSA_PSS_SHA384, RSA_PSS_SHA512,
     },
     verification::{
         RsaPublicKeyComponents, RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY,
@@ -289,7 +298,8 @@ pub use crate::rsa::{
         RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY,
         RSA_PKCS1_2048_8192_SHA1_FOR_LEGACY_USE_ONLY, RSA_PKCS1_2048_8192_SHA256,
         RSA_PKCS1_2048_8192_SHA384, RSA_PKCS1_2048_8192_SHA512, RSA_PKCS1_3072_8192_SHA384,
-        RSA_PSS_2048_8192_SHA256, RSA_PSS_2048_8192_SHA384, RSA_PSS_2048_8192_SHA512,
+        RSA_PSS_2048_8192_SHA256, RSA_PSS_2048_8192_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY,
+        RSA_PSS_2048_8192_SHA384, RSA_PSS_2048_8192_SHA512,
     },
     RsaParameters,
 };
@@ -343,7 +353,14 @@ pub(crate) const MAX_LEN: usize = 1/*tag:SEQUENCE*/ + 2/*len*/ +
     (2 * (1/*tag:INTEGER*/ + 1/*len*/ + 1/*zero*/ + ec::SCALAR_MAX_BYTES));
 
 /// A signature verification algorithm.
-pub trait VerificationAlgorithm: core::fmt::Debug + Sync + sealed::Sealed {
+pub trait VerificationAlgorithm:
+    core::fmt::Debug
+    + Sync
+    + sealed::Sealed
+    + public_key_matching::PublicKeyMatching
+    + public_key_validation::PublicKeyValidation
+    + public_key_weakness::PublicKeyWeakness
+{
     /// Verify the signature `signature` of message `msg` with the public key
     /// `public_key`.
     fn verify(
@@ -406,4 +423,300 @@ impl<B> UnparsedPublicKey<B> {
             untrusted::Input::from(signature),
         )
     }
+
+    /// Returns `true` if `self` and `other` are for the same algorithm and
+    /// encode the same public key, and `false` otherwise.
+    ///
+    /// The encodings are canonicalized before being compared, so that, for
+    /// example, the compressed and uncompressed encodings of the same ECDSA
+    /// public key match; an ECDSA public key that isn't a valid point never
+    /// matches anything. The canonicalized keys are compared in constant time,
+    /// so this is suitable for comparing a received public key against a
+    /// pinned one.
+    pub fn matches<B2>(&self, other: &UnparsedPublicKey<B2>) -> bool
+    where
+        B: AsRef<[u8]>,
+        B2: AsRef<[u8]>,
+    {
+        // Compare only the addresses of the algorithms. The vtable pointers
+        // of the same algorithm may differ across codegen units.
+        let addr = |algorithm: &'static dyn VerificationAlgorithm| {
+            (algorithm as *const dyn VerificationAlgorithm).cast::<u8>()
+        };
+        let same_algorithm = core::ptr::eq(addr(self.algorithm), addr(other.algorithm));
+        if !same_algorithm {
+            return false;
+        }
+        self.algorithm
+            .public_keys_match(
+                untrusted::Input::from(self.bytes.as_ref()),
+                untrusted::Input::from(other.bytes.as_ref()),
+            )
+            .is_ok()
+    }
+}
+
+/// Parses and validates `bytes` as a public key for `algorithm`, without
+/// verifying any signature.
+///
+/// This does all the validation of the public key that
+/// [`UnparsedPublicKey::verify`] would do, e.g. for ECDSA it checks that the
+/// point is encoded correctly and is on the curve, so it is suitable for
+/// checking public keys when they are received, or for testing a parser of
+/// public keys independently of signature verification.
+///
+/// `ParsedKey::verify` validates the key again; this doesn't make
+/// verification any faster.
+pub fn parse_public_key<B>(
+    algorithm: &'static dyn VerificationAlgorithm,
+    bytes: B,
+) -> Result<ParsedKey<B>, error::Unspecified>
+where
+    B: AsRef<[u8]>,
+{
+    let _ = cpu::features();
+    algorithm.validate_public_key(untrusted::Input::from(bytes.as_ref()))?;
+    Ok(ParsedKey { algorithm, bytes })
+}
+
+/// A public key that has been validated by [`parse_public_key()`].
+#[derive(Clone, Copy)]
+pub struct ParsedKey<B> {
+    algorithm: &'static dyn VerificationAlgorithm,
+    bytes: B,
+}
+
+impl<B> AsRef<[u8]> for ParsedKey<B>
+where
+    B: AsRef<[u8]>,
+{
+    fn as_ref(&self) -> &[u8] {
+        self.bytes.as_ref()
+    }
+}
+
+impl<B: core::fmt::Debug> core::fmt::Debug for ParsedKey<B>
+where
+    B: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("ParsedKey")
+            .field("algorithm", &self.algorithm)
+            .field("bytes", &debug::HexStr(self.bytes.as_ref()))
+            .finish()
+    }
+}
+
+impl<B> ParsedKey<B> {
+    /// The key's algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static dyn VerificationAlgorithm {
+        self.algorithm
+    }
+
+    /// Verifies `signature` is a valid signature of `message` using the
+    /// public key.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), error::Unspecified>
+    where
+        B: AsRef<[u8]>,
+    {
+        UnparsedPublicKey::new(self.algorithm, self.bytes.as_ref()).verify(message, signature)
+    }
+}
+
+/// A signature to verify with [`verify_batch()`].
+#[derive(Clone, Copy, Debug)]
+pub struct VerifyItem<'a> {
+    public_key: UnparsedPublicKey<&'a [u8]>,
+    message: &'a [u8],
+    signature: &'a [u8],
+}
+
+impl<'a> VerifyItem<'a> {
+    /// Constructs a `VerifyItem` for verifying that `signature` is a valid
+    /// signature of `message` using `public_key`.
+    #[inline]
+    pub fn new(
+        public_key: UnparsedPublicKey<&'a [u8]>,
+        message: &'a [u8],
+        signature: &'a [u8],
+    ) -> Self {
+        Self {
+            public_key,
+            message,
+            signature,
+        }
+    }
+}
+
+/// Verifies each of `items`, which may use different algorithms, returning
+/// the result for each item, in the same order.
+///
+/// The result for each item is the same as that of
+/// [`UnparsedPublicKey::verify`]; one item failing doesn't affect the others.
+///
+/// With the `std` feature, the items are divided among as many threads as
+/// the system reports are available, including the calling thread; see
+/// [`verify_batch_with_threads()`]. Without it, they are verified
+/// sequentially on the calling thread.
+#[cfg(feature = "alloc")]
+pub fn verify_batch(items: &[VerifyItem]) -> alloc::vec::Vec<Result<(), error::Unspecified>> {
+    #[cfg(feature = "std")]
+    {
+        batch::verify_parallel(items)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        let mut results = alloc::vec![Err(error::Unspecified); items.len()];
+        batch::verify_items(items, &mut results);
+        results
+    }
+}
+
+/// Like [`verify_batch()`], but uses at most `threads` threads, including the
+/// calling thread.
+///
+/// The results are the same for any number of threads.
+#[cfg(feature = "std")]
+pub fn verify_batch_with_threads(
+    items: &[VerifyItem],
+    threads: core::num::NonZeroUsize,
+) -> alloc::vec::Vec<Result<(), error::Unspecified>> {
+    batch::verify_with_threads(items, threads)
+}
+
+#[cfg(feature = "alloc")]
+mod batch {
+    use super::VerifyItem;
+    use crate::error;
+
+    pub(super) fn verify_items(items: &[VerifyItem], out: &mut [Result<(), error::Unspecified>]) {
+        items.iter().zip(out).for_each(|(item, out)| {
+            *out = item.public_key.verify(item.message, item.signature);
+        });
+    }
+
+    #[cfg(feature = "std")]
+    pub(super) use parallel::{verify_parallel, verify_with_threads};
+
+    #[cfg(feature = "std")]
+    mod parallel {
+        extern crate std;
+
+        use super::{verify_items, VerifyItem};
+        use crate::{error, polyfill::unwrap_const};
+        use core::num::NonZeroUsize;
+        use std::{thread, vec::Vec};
+
+        const ONE_THREAD: NonZeroUsize = unwrap_const(NonZeroUsize::new(1));
+
+        pub fn verify_parallel(items: &[VerifyItem]) -> Vec<Result<(), error::Unspecified>> {
+            let threads = thread::available_parallelism().unwrap_or(ONE_THREAD);
+            verify_with_threads(items, threads)
+        }
+
+        pub fn verify_with_threads(
+            items: &[VerifyItem],
+            threads: NonZeroUsize,
+        ) -> Vec<Result<(), error::Unspecified>> {
+            let mut results = std::vec![Err(error::Unspecified); items.len()];
+            if items.is_empty() {
+                return results;
+            }
+
+            // Each thread verifies a contiguous run of items. The calling
+            // thread takes the first run.
+            let items_per_thread = div_ceil(items.len(), threads.get());
+            let mut runs = items
+                .chunks(items_per_thread)
+                .zip(results.chunks_mut(items_per_thread));
+            thread::scope(|s| {
+                let first = runs.next();
+                let others: Vec<_> = runs
+                    .map(|(items, out)| s.spawn(move || verify_items(items, out)))
+                    .collect();
+                if let Some((items, out)) = first {
+                    verify_items(items, out);
+                }
+                others.into_iter().for_each(|t| {
+                    if let Err(e) = t.join() {
+                        std::panic::resume_unwind(e)
+                    }
+                });
+            });
+            results
+        }
+
+        fn div_ceil(a: usize, b: usize) -> usize {
+            (a / b) + usize::from(a % b != 0)
+        }
+    }
+}
+
+pub(crate) mod public_key_validation {
+    use crate::error;
+
+    /// Validation of a public key without verifying a signature. This is a
+    /// supertrait of `VerificationAlgorithm` that isn't nameable outside of
+    /// *ring*.
+    pub trait PublicKeyValidation {
+        /// Returns `Ok(())` if `public_key` would be accepted by `verify()`.
+        fn validate_public_key(
+            &self,
+            public_key: untrusted::Input,
+        ) -> Result<(), error::Unspecified>;
+    }
+}
+
+/// Returns `true` if `public_key` is a known-weak public key for `algorithm`.
+///
+/// For Ed25519 and XEd25519, the public keys of small order are known to be
+/// weak; they include the identity point. A signature that verifies with
+/// such a key doesn't show that the signer knows a private key.
+///
+/// There are no known-weak ECDSA public keys that `UnparsedPublicKey::verify`
+/// would accept; the P-256 and P-384 groups have prime order and the point at
+/// infinity has no valid encoding. No blocklist of RSA public keys, e.g. of
+/// those generated by Debian's broken OpenSSL package in 2006-2008, is
+/// included, so `false` is always returned for RSA keys.
+///
+/// This doesn't otherwise validate `public_key`; use [`parse_public_key()`]
+/// for that. `false` is returned if `public_key` isn't a valid encoding of a
+/// public key.
+pub fn is_known_weak(algorithm: &'static dyn VerificationAlgorithm, public_key: &[u8]) -> bool {
+    let _ = cpu::features();
+    algorithm.is_known_weak(untrusted::Input::from(public_key))
+}
+
+pub(crate) mod public_key_weakness {
+    /// Detection of known-weak public keys. This is a supertrait of
+    /// `VerificationAlgorithm` that isn't nameable outside of *ring*.
+    pub trait PublicKeyWeakness {
+        /// Returns `true` if `public_key` is a valid encoding of a known-weak
+        /// public key. The default implementation is for algorithms without
+        /// any known-weak keys.
+        fn is_known_weak(&self, _public_key: untrusted::Input) -> bool {
+            false
+        }
+    }
+}
+
+pub(crate) mod public_key_matching {
+    use crate::{constant_time, error};
+
+    /// Comparison of public keys after canonicalizing their encodings. This
+    /// is a supertrait of `VerificationAlgorithm` that isn't nameable outside
+    /// of *ring*.
+    pub trait PublicKeyMatching {
+        /// Returns `Ok(())` if `a` and `b` are both valid encodings of the same
+        /// public key. The default implementation is suitable for algorithms
+        /// with only one valid encoding of each public key.
+        fn public_keys_match(
+            &self,
+            a: untrusted::Input,
+            b: untrusted::Input,
+        ) -> Result<(), error::Unspecified> {
+            constant_time::verify_slices_are_equal(a.as_slice_less_safe(), b.as_slice_less_safe())
+        }
+    }
 }
@@ -0,0 +1,263 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use crate::{aead, agreement, digest, signature};
+
+/// The algorithms that this build of *ring* supports.
+///
+/// Each algorithm is listed with the name of the *ring* item that refers to
+/// it, e.g. `"AES_256_GCM"` for `ring::aead::AES_256_GCM`. Legacy algorithms
+/// like `SHA1_FOR_LEGACY_USE_ONLY` are included, so servers that advertise
+/// these lists should filter out whatever they don't want to offer. The order
+/// of each list is unspecified.
+///
+/// ```
+/// let supported = ring::supported_algorithms();
+/// assert!(supported.aeads().iter().any(|aead| aead.name() == "AES_256_GCM"));
+/// ```
+#[inline]
+pub fn supported_algorithms() -> &'static SupportedAlgorithms {
+    &SUPPORTED_ALGORITHMS
+}
+
+/// The algorithms that this build of *ring* supports.
+///
+/// See [`supported_algorithms()`].
+#[derive(Debug)]
+pub struct SupportedAlgorithms {
+    aeads: &'static [AeadInfo],
+    digests: &'static [DigestInfo],
+    signature_verification: &'static [SignatureVerificationInfo],
+    agreements: &'static [AgreementInfo],
+}
+
+impl SupportedAlgorithms {
+    /// The AEAD algorithms.
+    #[inline]
+    pub fn aeads(&self) -> &'static [AeadInfo] {
+        self.aeads
+    }
+
+    /// The digest algorithms.
+    #[inline]
+    pub fn digests(&self) -> &'static [DigestInfo] {
+        self.digests
+    }
+
+    /// The signature verification algorithms.
+    #[inline]
+    pub fn signature_verification(&self) -> &'static [SignatureVerificationInfo] {
+        self.signature_verification
+    }
+
+    /// The key agreement algorithms.
+    #[inline]
+    pub fn agreements(&self) -> &'static [AgreementInfo] {
+        self.agreements
+    }
+}
+
+/// An AEAD algorithm and its parameters.
+#[derive(Debug)]
+pub struct AeadInfo {
+    name: &'static str,
+    algorithm: &'static aead::Algorithm,
+}
+
+impl AeadInfo {
+    /// The name of the algorithm, e.g. `"AES_256_GCM"`.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static aead::Algorithm {
+        self.algorithm
+    }
+
+    /// The length of the key, in bytes.
+    #[inline]
+    pub fn key_len(&self) -> usize {
+        self.algorithm.key_len()
+    }
+
+    /// The length of the nonces, in bytes.
+    #[inline]
+    pub fn nonce_len(&self) -> usize {
+        self.algorithm.nonce_len()
+    }
+
+    /// The length of the tag, in bytes.
+    #[inline]
+    pub fn tag_len(&self) -> usize {
+        self.algorithm.tag_len()
+    }
+}
+
+/// A digest algorithm and its parameters.
+#[derive(Debug)]
+pub struct DigestInfo {
+    name: &'static str,
+    algorithm: &'static digest::Algorithm,
+}
+
+impl DigestInfo {
+    /// The name of the algorithm, e.g. `"SHA256"`.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static digest::Algorithm {
+        self.algorithm
+    }
+
+    /// The length of the digest, in bytes.
+    #[inline]
+    pub fn output_len(&self) -> usize {
+        self.algorithm.output_len()
+    }
+
+    /// The internal block length, in bytes.
+    #[inline]
+    pub fn block_len(&self) -> usize {
+        self.algorithm.block_len()
+    }
+}
+
+/// A signature verification algorithm.
+#[derive(Debug)]
+pub struct SignatureVerificationInfo {
+    name: &'static str,
+    algorithm: &'static dyn signature::VerificationAlgorithm,
+}
+
+impl SignatureVerificationInfo {
+    /// The name of the algorithm, e.g. `"ECDSA_P256_SHA256_ASN1"`.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static dyn signature::VerificationAlgorithm {
+        self.algorithm
+    }
+}
+
+/// A key agreement algorithm and its parameters.
+#[derive(Debug)]
+pub struct AgreementInfo {
+    name: &'static str,
+    algorithm: &'static agreement::Algorithm,
+}
+
+impl AgreementInfo {
+    /// The name of the algorithm, e.g. `"X25519"`.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static agreement::Algorithm {
+        self.algorithm
+    }
+
+    /// The length of the encoded public keys, in bytes.
+    #[inline]
+    pub fn public_key_len(&self) -> usize {
+        self.algorithm.curve.public_key_len
+    }
+}
+
+macro_rules! infos {
+    ( $info:ident, $module:ident, [ $( $( #[$attr:meta] )* $name:ident ),+ $(,)? ] ) => {
+        &[
+            $(
+                $( #[$attr] )*
+                $info {
+                    name: stringify!($name),
+                    algorithm: &$module::$name,
+                }
+            ),+
+        ]
+    };
+}
+
+static SUPPORTED_ALGORITHMS: SupportedAlgorithms = SupportedAlgorithms {
+    aeads: infos!(
+        AeadInfo,
+        aead,
+        [
+            AES_128_GCM,
+            AES_256_GCM,
+            AES_256_GCM_TAG96,
+            AES_256_GCM_TAG64,
+            AES_128_GCM_SIV,
+            AES_256_GCM_SIV,
+            CHACHA20_POLY1305
+        ]
+    ),
+    digests: infos!(
+        DigestInfo,
+        digest,
+        [SHA1_FOR_LEGACY_USE_ONLY, SHA256, SHA384, SHA512, SHA512_256]
+    ),
+    signature_verification: infos!(
+        SignatureVerificationInfo,
+        signature,
+        [
+            ECDSA_P256_SHA256_ASN1,
+            ECDSA_P256_SHA256_FIXED,
+            ECDSA_P256_SHA384_ASN1,
+            ECDSA_P384_SHA256_ASN1,
+            ECDSA_P384_SHA384_ASN1,
+            ECDSA_P384_SHA384_FIXED,
+            ED25519,
+            XED25519,
+            #[cfg(feature = "alloc")]
+            RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY,
+            #[cfg(feature = "alloc")]
+            RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY,
+            #[cfg(feature = "alloc")]
+            RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY,
+            #[cfg(feature = "alloc")]
+            RSA_PKCS1_2048_8192_SHA1_FOR_LEGACY_USE_ONLY,
+            #[cfg(feature = "alloc")]
+            RSA_PKCS1_2048_8192_SHA256,
+            #[cfg(feature = "alloc")]
+            RSA_PKCS1_2048_8192_SHA384,
+            #[cfg(feature = "alloc")]
+            RSA_PKCS1_2048_8192_SHA512,
+            #[cfg(feature = "alloc")]
+            RSA_PKCS1_3072_8192_SHA384,
+            #[cfg(feature = "alloc")]
+            RSA_PSS_2048_8192_SHA256,
+            #[cfg(feature = "alloc")]
+            RSA_PSS_2048_8192_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY,
+            #[cfg(feature = "alloc")]
+            RSA_PSS_2048_8192_SHA384,
+            #[cfg(feature = "alloc")]
+            RSA_PSS_2048_8192_SHA512,
+        ]
+    ),
+    agreements: infos!(AgreementInfo, agreement, [X25519, ECDH_P256, ECDH_P384]),
+};
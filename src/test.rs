@@ -461,6 +461,19 @@ fn parse_test_case(
     }
 }
 
+/// Fills `out` with bytes derived from `label` and `info`, for deriving test
+/// keys from a label. Insecure; the output is entirely determined by `label`.
+#[cfg(feature = "test-util")]
+pub(crate) fn expand_test_label(label: &str, info: &[&[u8]], out: &mut [u8]) {
+    use crate::hkdf;
+
+    hkdf::Salt::new(hkdf::HKDF_SHA256, b"ring test key")
+        .extract(label.as_bytes())
+        .expand(info, hkdf::Len(out.len()))
+        .and_then(|okm| okm.fill(out))
+        .unwrap()
+}
+
 /// Deterministic implementations of `ring::rand::SecureRandom`.
 ///
 /// These implementations are particularly useful for testing implementations
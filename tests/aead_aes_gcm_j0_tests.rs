@@ -0,0 +1,105 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{aead, aead::aes_gcm, test, test_file};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn key(key_bytes: &[u8]) -> aead::LessSafeKey {
+    let algorithm = match key_bytes.len() {
+        16 => &aead::AES_128_GCM,
+        32 => &aead::AES_256_GCM,
+        _ => unreachable!(),
+    };
+    aead::LessSafeKey::new(aead::UnboundKey::new(algorithm, key_bytes).unwrap())
+}
+
+#[test]
+fn aes_gcm_seal_with_j0_tests() {
+    test::run(
+        test_file!("aead_aes_gcm_j0_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let key = key(&test_case.consume_bytes("KEY"));
+            let j0: [u8; 16] = test_case.consume_bytes("J0").try_into().unwrap();
+            let aad = test_case.consume_bytes("AD");
+            let mut in_out = test_case.consume_bytes("IN");
+            let ct = test_case.consume_bytes("CT");
+            let tag = test_case.consume_bytes("TAG");
+
+            let actual_tag =
+                aes_gcm::seal_with_j0(&key, j0, aead::Aad::from(&aad[..]), &mut in_out)?;
+            assert_eq!(in_out, ct);
+            assert_eq!(actual_tag.as_ref(), &tag[..]);
+
+            // Any more input would make the counter wrap in the last test
+            // case; check that it is rejected rather than wrapping.
+            let [.., c0, c1, c2, c3] = j0;
+            if u32::from_be_bytes([c0, c1, c2, c3]) == 0xffff_fffa {
+                let mut in_out = vec![0u8; ct.len() + 16];
+                assert!(aes_gcm::seal_with_j0(&key, j0, aead::Aad::empty(), &mut in_out).is_err());
+            }
+
+            Ok(())
+        },
+    );
+}
+
+// `seal_with_j0` with J0 = nonce || 00000001 is the normal 96-bit-nonce path.
+#[test]
+fn aes_gcm_seal_with_j0_matches_nonce() {
+    const NONCE: [u8; aead::NONCE_LEN] = [
+        0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad, 0xde, 0xca, 0xf8, 0x88,
+    ];
+    let mut j0 = [0u8; 16];
+    j0[..aead::NONCE_LEN].copy_from_slice(&NONCE);
+    j0[15] = 1;
+
+    for algorithm in [
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::AES_256_GCM_TAG96,
+        &aead::AES_256_GCM_TAG64,
+    ] {
+        let key_bytes = vec![0x42; algorithm.key_len()];
+        let key = aead::LessSafeKey::new(aead::UnboundKey::new(algorithm, &key_bytes).unwrap());
+        for len in [0, 1, 15, 16, 17, 64, 1000] {
+            let mut expected = vec![0x5a; len];
+            let expected_tag = key
+                .seal_in_place_separate_tag(
+                    aead::Nonce::assume_unique_for_key(NONCE),
+                    aead::Aad::from(b"aad"),
+                    &mut expected,
+                )
+                .unwrap();
+
+            let mut actual = vec![0x5a; len];
+            let actual_tag =
+                aes_gcm::seal_with_j0(&key, j0, aead::Aad::from(b"aad"), &mut actual).unwrap();
+            assert_eq!(actual, expected);
+            assert_eq!(actual_tag.as_ref(), expected_tag.as_ref());
+        }
+    }
+}
+
+#[test]
+fn aes_gcm_seal_with_j0_rejects_chacha20_poly1305() {
+    let key =
+        aead::LessSafeKey::new(aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &[0; 32]).unwrap());
+    assert!(aes_gcm::seal_with_j0(&key, [0; 16], aead::Aad::empty(), &mut []).is_err());
+}
@@ -0,0 +1,127 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![cfg(feature = "legacy-cbc")]
+
+use ring::{
+    aead::{self, cbc_hmac},
+    test, test_file,
+};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn cbc_hmac_tests() {
+    test::run(
+        test_file!("aead_cbc_hmac_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let key_bytes = test_case.consume_bytes("KEY");
+            let iv = test_case.consume_bytes("IV");
+            let ad = test_case.consume_bytes("AD");
+            let plaintext = test_case.consume_bytes("IN");
+            let ct = test_case.consume_bytes("CT");
+            let tag = test_case.consume_bytes("TAG");
+
+            let key = cbc_hmac::Aes128CbcHmacSha256::new(key_bytes.as_slice().try_into()?)?;
+
+            let expected = [&iv[..], &ct[..], &tag[..]].concat();
+
+            let rng = test::rand::FixedSliceRandom { bytes: &iv };
+            let sealed = key.seal(&rng, aead::Aad::from(&ad), &plaintext)?;
+            assert_eq!(sealed, expected);
+
+            let mut in_out = expected.clone();
+            let opened = key.open_in_place(aead::Aad::from(&ad), &mut in_out)?;
+            assert_eq!(opened, &plaintext[..]);
+
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn cbc_hmac_open_rejects_modified_input() {
+    test::run(
+        test_file!("aead_cbc_hmac_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let key_bytes = test_case.consume_bytes("KEY");
+            let iv = test_case.consume_bytes("IV");
+            let ad = test_case.consume_bytes("AD");
+            let _ = test_case.consume_bytes("IN");
+            let ct = test_case.consume_bytes("CT");
+            let tag = test_case.consume_bytes("TAG");
+
+            let key = cbc_hmac::Aes128CbcHmacSha256::new(key_bytes.as_slice().try_into()?)?;
+
+            let sealed = [&iv[..], &ct[..], &tag[..]].concat();
+
+            // Flipping any bit of the IV, the ciphertext, or the tag is detected.
+            for i in 0..sealed.len() {
+                let mut in_out = sealed.clone();
+                in_out[i] ^= 0x01;
+                assert!(key
+                    .open_in_place(aead::Aad::from(&ad), &mut in_out)
+                    .is_err());
+            }
+
+            // So is a change to the associated data, including its length.
+            let mut modified_ad = ad.clone();
+            modified_ad.push(0);
+            let mut in_out = sealed.clone();
+            assert!(key
+                .open_in_place(aead::Aad::from(&modified_ad), &mut in_out)
+                .is_err());
+
+            // Truncated inputs are rejected.
+            for len in [
+                0,
+                cbc_hmac::IV_LEN + cbc_hmac::TAG_LEN,
+                sealed.len() - 1,
+                sealed.len() - cbc_hmac::TAG_LEN,
+            ] {
+                let mut in_out = sealed[..len].to_vec();
+                assert!(key
+                    .open_in_place(aead::Aad::from(&ad), &mut in_out)
+                    .is_err());
+            }
+
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn cbc_hmac_round_trip() {
+    let rng = ring::rand::SystemRandom::new();
+    let key = cbc_hmac::Aes128CbcHmacSha256::new(&[0x5a; cbc_hmac::KEY_LEN]).unwrap();
+    let plaintext = [0x42; 100];
+    for len in 0..plaintext.len() {
+        let mut in_out = key
+            .seal(&rng, aead::Aad::empty(), &plaintext[..len])
+            .unwrap();
+        let padded_len = (len / 16 + 1) * 16;
+        assert_eq!(
+            in_out.len(),
+            cbc_hmac::IV_LEN + padded_len + cbc_hmac::TAG_LEN
+        );
+        let opened = key.open_in_place(aead::Aad::empty(), &mut in_out).unwrap();
+        assert_eq!(opened, &plaintext[..len]);
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::aead::{self, chacha20_poly1305_seek::KeystreamKey};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+const KEY: [u8; 32] = [0x42; 32];
+const NONCE: [u8; aead::NONCE_LEN] = [7; aead::NONCE_LEN];
+
+fn nonce() -> aead::Nonce {
+    aead::Nonce::assume_unique_for_key(NONCE)
+}
+
+#[test]
+fn test_chacha20_poly1305_seek_matches_sealing() {
+    const LEN: usize = 300;
+    let plaintext: Vec<u8> = (0..LEN).map(|i| i as u8).collect();
+
+    let key =
+        aead::LessSafeKey::new(aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &KEY).unwrap());
+    let mut ciphertext = plaintext.clone();
+    let _tag = key
+        .seal_in_place_separate_tag(nonce(), aead::Aad::empty(), &mut ciphertext)
+        .unwrap();
+
+    let keystream = KeystreamKey::new(&KEY);
+
+    // Processing from 0 matches the sealed ciphertext.
+    let mut in_out = plaintext.clone();
+    keystream
+        .apply_keystream_at(nonce(), 0, &mut in_out)
+        .unwrap();
+    assert_eq!(in_out, ciphertext);
+
+    // Seeking to any offset matches processing from 0, for slices that start
+    // and end inside and on the boundaries of blocks.
+    for &offset in &[0, 1, 63, 64, 65, 127, 128, 200] {
+        for &len in &[0, 1, 63, 64, 65, 100] {
+            let end = offset + len;
+            if end > LEN {
+                continue;
+            }
+            let mut in_out = plaintext[offset..end].to_vec();
+            keystream
+                .apply_keystream_at(nonce(), offset as u64, &mut in_out)
+                .unwrap();
+            assert_eq!(&in_out[..], &ciphertext[offset..end], "{} {}", offset, len);
+
+            // Decryption is the same operation.
+            keystream
+                .apply_keystream_at(nonce(), offset as u64, &mut in_out)
+                .unwrap();
+            assert_eq!(&in_out[..], &plaintext[offset..end]);
+        }
+    }
+}
+
+#[test]
+fn test_chacha20_poly1305_seek_limits() {
+    const MAX_RECORD_LEN: u64 = 274_877_906_880;
+    let keystream = KeystreamKey::new(&KEY);
+
+    let mut in_out = [0u8; 65];
+    assert!(keystream
+        .apply_keystream_at(nonce(), MAX_RECORD_LEN - 65, &mut in_out)
+        .is_ok());
+    assert!(keystream
+        .apply_keystream_at(nonce(), MAX_RECORD_LEN - 64, &mut in_out)
+        .is_err());
+    assert!(keystream
+        .apply_keystream_at(nonce(), MAX_RECORD_LEN, &mut [])
+        .is_ok());
+    assert!(keystream
+        .apply_keystream_at(nonce(), MAX_RECORD_LEN + 1, &mut [])
+        .is_err());
+    assert!(keystream
+        .apply_keystream_at(nonce(), u64::MAX, &mut in_out)
+        .is_err());
+}
@@ -0,0 +1,145 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+use ring::{
+    aead::{self, committing},
+    test,
+};
+
+const KEY1: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const KEY2: &str = "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f";
+const NONCE: &str = "000102030405060708090a0b";
+
+fn nonce() -> aead::Nonce {
+    aead::Nonce::try_assume_unique_for_key(&test::from_hex(NONCE).unwrap()).unwrap()
+}
+
+fn aes_256_gcm_opens(key: &str, in_out: &str) -> bool {
+    let key = aead::UnboundKey::new(&aead::AES_256_GCM, &test::from_hex(key).unwrap()).unwrap();
+    let key = aead::LessSafeKey::new(key);
+    let mut in_out = test::from_hex(in_out).unwrap();
+    key.open_in_place(nonce(), aead::Aad::empty(), &mut in_out)
+        .is_ok()
+}
+
+fn committing_opens(key: &str, in_out: &[u8]) -> bool {
+    let key =
+        committing::Key::new(&committing::AES_256_GCM, &test::from_hex(key).unwrap()).unwrap();
+    let mut in_out = in_out.to_vec();
+    key.open_in_place(nonce(), aead::Aad::empty(), &mut in_out)
+        .is_ok()
+}
+
+#[test]
+fn committing_rejects_multi_key_ciphertext_test() {
+    // A 32-byte ciphertext and its tag that are valid AES-256-GCM under both
+    // `KEY1` and `KEY2`, constructed by solving the GHASH equations for one
+    // ciphertext block.
+    const RAW: &str = "9fc5cddd92bae1720be83fdd2659d2af2a8c08accc4998a18e73a79c34da479d\
+                       72e6b98e9738a1955320a4a072961b17";
+    assert!(aes_256_gcm_opens(KEY1, RAW));
+    assert!(aes_256_gcm_opens(KEY2, RAW));
+
+    // The same attack on the underlying AEAD of the committing AEAD. These are
+    // the AES-256-GCM keys that `KEY1` and `KEY2` derive for `NONCE`.
+    const DERIVED_KEY1: &str = "d7ac0b42ba93c6009778f1ea79cd4d221ed8505a7f4de0d64edc4a750b638343";
+    const DERIVED_KEY2: &str = "63bb61b09a1d175b0c452b64fbc8dc997cd64dc0e6838225dc71e69f8269a39a";
+    const INNER: &str = "548f1e46161e661f1d9e13d8a29a35aeec52cd7ca3f52e62937bbe692d897808\
+                         5a93dd0c6558b51551ad81a643796b8e";
+    assert!(aes_256_gcm_opens(DERIVED_KEY1, INNER));
+    assert!(aes_256_gcm_opens(DERIVED_KEY2, INNER));
+
+    // Whichever key's commitment is attached, the ciphertext only opens under
+    // that key.
+    const COMMITMENT1: &str = "9150cf5c83144f4efec93673c20742156b1f3223a24da3ebcfdf808d4d000fa5";
+    const COMMITMENT2: &str = "a401876f6ded2d037585c44b8bc13da91aae421501a3fa6acef76bba6f24391a";
+    for (commitment, key, other_key) in &[(COMMITMENT1, KEY1, KEY2), (COMMITMENT2, KEY2, KEY1)] {
+        let mut in_out = test::from_hex(INNER).unwrap();
+        in_out.extend_from_slice(&test::from_hex(commitment).unwrap());
+        assert!(committing_opens(key, &in_out));
+        assert!(!committing_opens(other_key, &in_out));
+    }
+
+    // The commitment is computed as documented.
+    let key =
+        committing::Key::new(&committing::AES_256_GCM, &test::from_hex(KEY1).unwrap()).unwrap();
+    let mut in_out = vec![];
+    key.seal_in_place_append_tag(nonce(), aead::Aad::empty(), &mut in_out)
+        .unwrap();
+    assert_eq!(
+        &in_out[(in_out.len() - committing::COMMITMENT_LEN)..],
+        &test::from_hex(COMMITMENT1).unwrap()[..]
+    );
+}
+
+#[test]
+fn committing_round_trip_test() {
+    const PLAINTEXT: &[u8] = b"hello, world";
+    const AAD: &[u8] = b"header";
+
+    for &algorithm in &[&committing::AES_256_GCM, &committing::CHACHA20_POLY1305] {
+        let key = committing::Key::new(algorithm, &test::from_hex(KEY1).unwrap()).unwrap();
+        let other_key = committing::Key::new(algorithm, &test::from_hex(KEY2).unwrap()).unwrap();
+        assert_eq!(key.algorithm(), algorithm);
+
+        let mut sealed = PLAINTEXT.to_vec();
+        key.seal_in_place_append_tag(nonce(), aead::Aad::from(AAD), &mut sealed)
+            .unwrap();
+        assert_eq!(sealed.len(), PLAINTEXT.len() + algorithm.tag_len());
+
+        let mut in_out = sealed.clone();
+        let opened = key
+            .open_in_place(nonce(), aead::Aad::from(AAD), &mut in_out)
+            .unwrap();
+        assert_eq!(opened, PLAINTEXT);
+
+        // The wrong key, AAD, or nonce, and any tampering, are detected.
+        let mut in_out = sealed.clone();
+        assert!(other_key
+            .open_in_place(nonce(), aead::Aad::from(AAD), &mut in_out)
+            .is_err());
+        let mut in_out = sealed.clone();
+        assert!(key
+            .open_in_place(nonce(), aead::Aad::empty(), &mut in_out)
+            .is_err());
+        let mut in_out = sealed.clone();
+        assert!(key
+            .open_in_place(
+                aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]),
+                aead::Aad::from(AAD),
+                &mut in_out
+            )
+            .is_err());
+        for i in 0..sealed.len() {
+            let mut in_out = sealed.clone();
+            in_out[i] ^= 1;
+            assert!(key
+                .open_in_place(nonce(), aead::Aad::from(AAD), &mut in_out)
+                .is_err());
+        }
+        let mut in_out = sealed[..(committing::COMMITMENT_LEN - 1)].to_vec();
+        assert!(key
+            .open_in_place(nonce(), aead::Aad::from(AAD), &mut in_out)
+            .is_err());
+
+        // Keys must be 32 bytes.
+        assert!(committing::Key::new(algorithm, &[0; 16]).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{aead, aead::gmac, test, test_file};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn algorithm_for_key(key_bytes: &[u8]) -> &'static aead::Algorithm {
+    match key_bytes.len() {
+        16 => &aead::AES_128_GCM,
+        32 => &aead::AES_256_GCM,
+        _ => unreachable!(),
+    }
+}
+
+fn nonce(nonce_bytes: &[u8]) -> aead::Nonce {
+    aead::Nonce::try_assume_unique_for_key(nonce_bytes).unwrap()
+}
+
+#[test]
+fn gmac_tests() {
+    test::run(test_file!("aead_gmac_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let key_bytes = test_case.consume_bytes("KEY");
+        let nonce_bytes = test_case.consume_bytes("NONCE");
+        let data = test_case.consume_bytes("AD");
+        let expected_tag = test_case.consume_bytes("TAG");
+
+        let algorithm = algorithm_for_key(&key_bytes);
+        let key = gmac::Key::new(algorithm, &key_bytes).unwrap();
+
+        let tag = key.sign(nonce(&nonce_bytes), &data).unwrap();
+        assert_eq!(tag.as_ref(), &expected_tag[..]);
+        assert!(key
+            .verify(nonce(&nonce_bytes), &data, &expected_tag)
+            .is_ok());
+
+        // GMAC is AES-GCM with an empty plaintext.
+        let aead_key =
+            aead::LessSafeKey::new(aead::UnboundKey::new(algorithm, &key_bytes).unwrap());
+        let aead_tag = aead_key
+            .seal_in_place_separate_tag(nonce(&nonce_bytes), aead::Aad::from(&data), &mut [])
+            .unwrap();
+        assert_eq!(aead_tag.as_ref(), tag.as_ref());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn gmac_verify_rejects_bad_tags() {
+    test::run(test_file!("aead_gmac_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let key_bytes = test_case.consume_bytes("KEY");
+        let nonce_bytes = test_case.consume_bytes("NONCE");
+        let data = test_case.consume_bytes("AD");
+        let tag = test_case.consume_bytes("TAG");
+
+        let key = gmac::Key::new(algorithm_for_key(&key_bytes), &key_bytes).unwrap();
+
+        // Every single-bit change to the tag is rejected.
+        for i in 0..(tag.len() * 8) {
+            let mut bad_tag = tag.clone();
+            bad_tag[i / 8] ^= 1 << (i % 8);
+            assert!(key.verify(nonce(&nonce_bytes), &data, &bad_tag).is_err());
+        }
+
+        // Truncated and extended tags are rejected.
+        assert!(key
+            .verify(nonce(&nonce_bytes), &data, &tag[..(tag.len() - 1)])
+            .is_err());
+        let mut long_tag = tag.clone();
+        long_tag.push(0);
+        assert!(key.verify(nonce(&nonce_bytes), &data, &long_tag).is_err());
+
+        // A different nonce or different data gives a different tag.
+        let mut other_nonce = nonce_bytes.clone();
+        other_nonce[0] ^= 1;
+        assert!(key.verify(nonce(&other_nonce), &data, &tag).is_err());
+        let mut other_data = data.clone();
+        other_data.push(0);
+        assert!(key.verify(nonce(&nonce_bytes), &other_data, &tag).is_err());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn gmac_truncated_tags() {
+    let key_bytes = [0x42; 32];
+    let full = gmac::Key::new(&aead::AES_256_GCM, &key_bytes).unwrap();
+    let full_tag = full.sign(nonce(&[1; aead::NONCE_LEN]), b"data").unwrap();
+
+    for &(algorithm, tag_len) in &[
+        (&aead::AES_256_GCM_TAG96, 12),
+        (&aead::AES_256_GCM_TAG64, 8),
+    ] {
+        let key = gmac::Key::new(algorithm, &key_bytes).unwrap();
+        let tag = key.sign(nonce(&[1; aead::NONCE_LEN]), b"data").unwrap();
+        assert_eq!(tag.as_ref(), &full_tag.as_ref()[..tag_len]);
+        assert!(key
+            .verify(nonce(&[1; aead::NONCE_LEN]), b"data", tag.as_ref())
+            .is_ok());
+        assert!(key
+            .verify(nonce(&[1; aead::NONCE_LEN]), b"data", full_tag.as_ref())
+            .is_err());
+    }
+}
+
+#[test]
+fn gmac_key_new() {
+    // Wrong key lengths.
+    assert!(gmac::Key::new(&aead::AES_128_GCM, &[0; 32]).is_err());
+    assert!(gmac::Key::new(&aead::AES_256_GCM, &[0; 16]).is_err());
+
+    // Not an AES-GCM algorithm.
+    assert!(gmac::Key::new(&aead::CHACHA20_POLY1305, &[0; 32]).is_err());
+    assert!(gmac::Key::new(&aead::AES_256_GCM_SIV, &[0; 32]).is_err());
+
+    let key = gmac::Key::new(&aead::AES_128_GCM, &[0; 16]).unwrap();
+    assert_eq!(key.algorithm(), &aead::AES_128_GCM);
+    assert_eq!(format!("{:?}", key), "Key { algorithm: AES_128_GCM }");
+}
@@ -0,0 +1,246 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![cfg(feature = "alloc")]
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+use ring::aead::{self, stream};
+
+const KEY: [u8; 32] = [0x42; 32];
+const NONCE_PREFIX: [u8; stream::NONCE_PREFIX_LEN] = [7; stream::NONCE_PREFIX_LEN];
+
+static ALGORITHMS: [&aead::Algorithm; 3] = [
+    &aead::AES_128_GCM,
+    &aead::AES_256_GCM,
+    &aead::CHACHA20_POLY1305,
+];
+
+fn sealer(alg: &'static aead::Algorithm) -> stream::SealingStream {
+    let key = aead::UnboundKey::new(alg, &KEY[..alg.key_len()]).unwrap();
+    stream::SealingStream::new(key, NONCE_PREFIX)
+}
+
+fn opener(alg: &'static aead::Algorithm) -> stream::OpeningStream {
+    let key = aead::UnboundKey::new(alg, &KEY[..alg.key_len()]).unwrap();
+    stream::OpeningStream::new(key, NONCE_PREFIX)
+}
+
+fn seal(alg: &'static aead::Algorithm, chunks: &[&[u8]]) -> Vec<Vec<u8>> {
+    let mut sealer = sealer(alg);
+    let (last, rest) = chunks.split_last().unwrap();
+    let mut sealed: Vec<Vec<u8>> = rest
+        .iter()
+        .map(|chunk| {
+            let mut in_out = chunk.to_vec();
+            sealer.seal_chunk(aead::Aad::empty(), &mut in_out).unwrap();
+            in_out
+        })
+        .collect();
+    let mut in_out = last.to_vec();
+    sealer
+        .seal_last_chunk(aead::Aad::empty(), &mut in_out)
+        .unwrap();
+    sealed.push(in_out);
+    sealed
+}
+
+#[test]
+fn aead_stream_round_trip() {
+    let chunks: [&[u8]; 4] = [b"one", b"", b"three", b"four"];
+    for &alg in ALGORITHMS.iter() {
+        let sealed = seal(alg, &chunks);
+
+        let mut opener = opener(alg);
+        let (last, rest) = sealed.split_last().unwrap();
+        for (sealed_chunk, chunk) in rest.iter().zip(chunks.iter()) {
+            let opened = opener.open_chunk(aead::Aad::empty(), sealed_chunk).unwrap();
+            assert_eq!(opened, *chunk);
+        }
+        let opened = opener.open_last_chunk(aead::Aad::empty(), last).unwrap();
+        assert_eq!(opened, chunks[3]);
+    }
+}
+
+#[test]
+fn aead_stream_bad_tag() {
+    for &alg in ALGORITHMS.iter() {
+        let mut sealed = seal(alg, &[b"one", b"two", b"three"]);
+        let tampered = sealed[1].len() - 1;
+        sealed[1][tampered] ^= 1;
+
+        let mut opener = opener(alg);
+        assert_eq!(
+            opener.open_chunk(aead::Aad::empty(), &sealed[0]).unwrap(),
+            b"one"
+        );
+        // The chunk with the bad tag yields no plaintext.
+        assert!(opener.open_chunk(aead::Aad::empty(), &sealed[1]).is_err());
+        // The stream can't be resumed after a failure.
+        assert!(opener
+            .open_last_chunk(aead::Aad::empty(), &sealed[2])
+            .is_err());
+    }
+}
+
+#[test]
+fn aead_stream_truncation_and_reordering() {
+    for &alg in ALGORITHMS.iter() {
+        let sealed = seal(alg, &[b"one", b"two", b"three"]);
+
+        // Truncated: a chunk that isn't the last one can't be opened as the
+        // last one.
+        let mut opener = opener(alg);
+        assert!(opener.open_chunk(aead::Aad::empty(), &sealed[0]).is_ok());
+        assert!(opener
+            .open_last_chunk(aead::Aad::empty(), &sealed[1])
+            .is_err());
+
+        // Extended: the last chunk can't be opened as a non-last one.
+        let mut opener = self::opener(alg);
+        assert!(opener.open_chunk(aead::Aad::empty(), &sealed[0]).is_ok());
+        assert!(opener.open_chunk(aead::Aad::empty(), &sealed[1]).is_ok());
+        assert!(opener.open_chunk(aead::Aad::empty(), &sealed[2]).is_err());
+
+        // Reordered.
+        let mut opener = self::opener(alg);
+        assert!(opener.open_chunk(aead::Aad::empty(), &sealed[1]).is_err());
+    }
+}
+
+#[test]
+fn aead_stream_debug() {
+    assert_eq!(
+        format!("{:?}", sealer(&aead::AES_128_GCM)),
+        "SealingStream { algorithm: AES_128_GCM, .. }"
+    );
+    assert_eq!(
+        format!("{:?}", opener(&aead::CHACHA20_POLY1305)),
+        "OpeningStream { algorithm: CHACHA20_POLY1305, .. }"
+    );
+}
+
+#[cfg(feature = "std")]
+mod io {
+    use super::{ALGORITHMS, KEY, NONCE_PREFIX};
+    use ring::aead;
+    use std::io::{Read, Write};
+
+    const RECORD_LEN: usize = 16;
+
+    fn seal(alg: &'static aead::Algorithm, plaintext: &[u8], write_len: usize) -> Vec<u8> {
+        let key = aead::UnboundKey::new(alg, &KEY[..alg.key_len()]).unwrap();
+        let mut writer =
+            aead::SealingWriter::new(key, NONCE_PREFIX, RECORD_LEN, Vec::new()).unwrap();
+        for piece in plaintext.chunks(write_len) {
+            writer.write_all(piece).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn open(
+        alg: &'static aead::Algorithm,
+        sealed: &[u8],
+        read_len: usize,
+    ) -> std::io::Result<Vec<u8>> {
+        let key = aead::UnboundKey::new(alg, &KEY[..alg.key_len()]).unwrap();
+        let mut reader = aead::OpeningReader::new(key, NONCE_PREFIX, RECORD_LEN, sealed).unwrap();
+        let mut plaintext = Vec::new();
+        let mut buf = vec![0; read_len];
+        loop {
+            let read = reader.read(&mut buf)?;
+            if read == 0 {
+                return Ok(plaintext);
+            }
+            plaintext.extend_from_slice(&buf[..read]);
+        }
+    }
+
+    #[test]
+    fn aead_stream_io_round_trip() {
+        let data: Vec<u8> = (0..=255).collect();
+        for &alg in ALGORITHMS.iter() {
+            for &len in &[
+                0,
+                1,
+                RECORD_LEN - 1,
+                RECORD_LEN,
+                RECORD_LEN + 1,
+                3 * RECORD_LEN,
+                255,
+            ] {
+                let plaintext = &data[..len];
+                for &piece_len in &[1, 7, RECORD_LEN, 100] {
+                    let sealed = seal(alg, plaintext, piece_len);
+
+                    // Every record is full except the last, which is never
+                    // absent.
+                    let records = core::cmp::max(1, (len + RECORD_LEN - 1) / RECORD_LEN);
+                    assert_eq!(sealed.len(), len + (records * alg.tag_len()));
+
+                    assert_eq!(open(alg, &sealed, piece_len).unwrap(), plaintext);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn aead_stream_io_truncation() {
+        let plaintext = [0x5a; 3 * RECORD_LEN + 5];
+        for &alg in ALGORITHMS.iter() {
+            let sealed = seal(alg, &plaintext, 10);
+            let sealed_record_len = RECORD_LEN + alg.tag_len();
+
+            // Truncated at a record boundary, or anywhere else.
+            for len in [
+                0,
+                sealed_record_len,
+                2 * sealed_record_len,
+                sealed.len() - 1,
+            ] {
+                let err = open(alg, &sealed[..len], 100).unwrap_err();
+                assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            }
+
+            // Extended.
+            let mut extended = sealed.clone();
+            extended.push(0);
+            assert!(open(alg, &extended, 100).is_err());
+
+            // The stream can't be resumed after a failure, and no plaintext
+            // from the bad record is released.
+            let mut tampered = sealed.clone();
+            tampered[sealed_record_len] ^= 1;
+            let key = aead::UnboundKey::new(alg, &KEY[..alg.key_len()]).unwrap();
+            let mut reader =
+                aead::OpeningReader::new(key, NONCE_PREFIX, RECORD_LEN, &tampered[..]).unwrap();
+            let mut buf = [0; RECORD_LEN];
+            assert_eq!(reader.read(&mut buf).unwrap(), RECORD_LEN);
+            assert!(reader.read(&mut buf).is_err());
+            assert!(reader.read(&mut buf).is_err());
+        }
+    }
+
+    #[test]
+    fn aead_stream_io_zero_record_len() {
+        let key = aead::UnboundKey::new(&aead::AES_128_GCM, &KEY[..16]).unwrap();
+        assert!(aead::SealingWriter::new(key, NONCE_PREFIX, 0, Vec::new()).is_err());
+        let key = aead::UnboundKey::new(&aead::AES_128_GCM, &KEY[..16]).unwrap();
+        assert!(aead::OpeningReader::new(key, NONCE_PREFIX, 0, &[][..]).is_err());
+    }
+}
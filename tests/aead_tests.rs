@@ -19,7 +19,7 @@ use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
 wasm_bindgen_test_configure!(run_in_browser);
 
 use core::ops::RangeFrom;
-use ring::{aead, error, test, test_file};
+use ring::{aead, error, hkdf, test, test_file};
 
 /// Generate the known answer test functions for the given algorithm and test
 /// case input file, where each test is implemented by a test in `$test`.
@@ -61,7 +61,9 @@ macro_rules! test_aead {
                             less_safe_key_open_within,
                             less_safe_key_seal_in_place_append_tag,
                             less_safe_key_seal_in_place_separate_tag,
+                            less_safe_key_seal_gather_separate_tag,
                             opening_key_open_in_place,
+                            opening_key_open_in_place_separate_tag,
                             opening_key_open_within,
                             sealing_key_seal_in_place_append_tag,
                             sealing_key_seal_in_place_separate_tag,
@@ -81,6 +83,10 @@ macro_rules! test_aead {
 test_aead! {
     { AES_128_GCM, "aead_aes_128_gcm_tests.txt" },
     { AES_256_GCM, "aead_aes_256_gcm_tests.txt" },
+    { AES_256_GCM_TAG96, "aead_aes_256_gcm_tag96_tests.txt" },
+    { AES_256_GCM_TAG64, "aead_aes_256_gcm_tag64_tests.txt" },
+    { AES_128_GCM_SIV, "aead_aes_128_gcm_siv_tests.txt" },
+    { AES_256_GCM_SIV, "aead_aes_256_gcm_siv_tests.txt" },
     { CHACHA20_POLY1305, "aead_chacha20_poly1305_tests.txt" },
 }
 
@@ -193,15 +199,31 @@ fn test_open_in_place_seperate_tag(
     tc: KnownAnswerTestCase,
 ) -> Result<(), error::Unspecified> {
     let key = make_less_safe_key(alg, tc.key);
+    test_open_separate_tag(alg, &tc, |nonce, tag, in_out, ciphertext| {
+        key.open_in_place_separate_tag(nonce, tc.aad, tag, in_out, ciphertext)
+    })
+}
 
+fn test_open_separate_tag<OpenSeparateTag>(
+    alg: &'static aead::Algorithm,
+    tc: &KnownAnswerTestCase<'_>,
+    open_separate_tag: OpenSeparateTag,
+) -> Result<(), error::Unspecified>
+where
+    OpenSeparateTag: for<'a> Fn(
+        aead::Nonce,
+        aead::Tag,
+        &'a mut [u8],
+        RangeFrom<usize>,
+    ) -> Result<&'a mut [u8], error::Unspecified>,
+{
     let mut in_out = Vec::from(tc.ciphertext);
-    let tag = tc.tag.try_into().unwrap();
+    let tag = aead::Tag::try_for_algorithm(alg, tc.tag).unwrap();
 
     // Test the simplest behavior.
     {
         let nonce = aead::Nonce::assume_unique_for_key(tc.nonce);
-        let actual_plaintext =
-            key.open_in_place_separate_tag(nonce, tc.aad, tag, &mut in_out, 0..)?;
+        let actual_plaintext = open_separate_tag(nonce, tag, &mut in_out, 0..)?;
 
         assert_eq!(actual_plaintext, tc.plaintext);
         assert_eq!(&in_out[..tc.plaintext.len()], tc.plaintext);
@@ -213,8 +235,7 @@ fn test_open_in_place_seperate_tag(
         in_out.extend_from_slice(tc.ciphertext);
 
         let nonce = aead::Nonce::assume_unique_for_key(tc.nonce);
-        let actual_plaintext =
-            key.open_in_place_separate_tag(nonce, tc.aad, tag, &mut in_out, range)?;
+        let actual_plaintext = open_separate_tag(nonce, tag, &mut in_out, range)?;
 
         assert_eq!(actual_plaintext, tc.plaintext);
         assert_eq!(&in_out[..tc.plaintext.len()], tc.plaintext);
@@ -357,6 +378,16 @@ fn opening_key_open_within(
     })
 }
 
+fn opening_key_open_in_place_separate_tag(
+    alg: &'static aead::Algorithm,
+    tc: KnownAnswerTestCase,
+) -> Result<(), error::Unspecified> {
+    test_open_separate_tag(alg, &tc, |nonce, tag, in_out, ciphertext| {
+        let mut key: aead::OpeningKey<OneNonceSequence> = make_key(alg, tc.key, nonce);
+        key.open_in_place_separate_tag(tc.aad, tag, in_out, ciphertext)
+    })
+}
+
 fn less_safe_key_seal_in_place_append_tag(
     alg: &'static aead::Algorithm,
     tc: KnownAnswerTestCase,
@@ -387,6 +418,61 @@ fn less_safe_key_seal_in_place_separate_tag(
     })
 }
 
+fn less_safe_key_seal_gather_separate_tag(
+    alg: &'static aead::Algorithm,
+    tc: KnownAnswerTestCase,
+) -> Result<(), error::Unspecified> {
+    let key = make_less_safe_key(alg, tc.key);
+    let plaintext = tc.plaintext;
+
+    // Split the plaintext into three fragments, some of them possibly empty,
+    // at block boundaries and elsewhere.
+    let len = plaintext.len();
+    let points = [0, 1, 15, 16, 17, 64, len / 2, len];
+    for &first in points.iter().filter(|&&p| p <= len) {
+        for &second in points.iter().filter(|&&p| first <= p && p <= len) {
+            let fragments = [
+                &plaintext[..first],
+                &plaintext[first..second],
+                &plaintext[second..],
+            ];
+            let mut out = vec![0; len];
+            let tag = key.seal_gather_separate_tag(
+                aead::Nonce::assume_unique_for_key(tc.nonce),
+                tc.aad,
+                &fragments,
+                &mut out,
+            )?;
+            assert_eq!(tag.as_ref(), tc.tag);
+            assert_eq!(out, tc.ciphertext);
+        }
+    }
+
+    // `out` must be exactly as long as the plaintext.
+    let mut out = vec![0; len + 1];
+    assert!(key
+        .seal_gather_separate_tag(
+            aead::Nonce::assume_unique_for_key(tc.nonce),
+            tc.aad,
+            &[plaintext],
+            &mut out,
+        )
+        .is_err());
+    if len > 0 {
+        let mut out = vec![0; len - 1];
+        assert!(key
+            .seal_gather_separate_tag(
+                aead::Nonce::assume_unique_for_key(tc.nonce),
+                tc.aad,
+                &[plaintext],
+                &mut out,
+            )
+            .is_err());
+    }
+
+    Ok(())
+}
+
 fn less_safe_key_open_within(
     alg: &'static aead::Algorithm,
     tc: KnownAnswerTestCase,
@@ -490,6 +576,19 @@ fn aead_chacha20_poly1305_openssh() {
     );
 }
 
+#[test]
+fn aead_chacha20_poly1305_poly1305_key_for() {
+    // https://tools.ietf.org/html/rfc8439#section-2.6.2
+    let key: [u8; aead::chacha20_poly1305::KEY_LEN] = core::array::from_fn(|i| 0x80 + i as u8);
+    let nonce = aead::Nonce::assume_unique_for_key([0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7]);
+    let expected =
+        test::from_hex("8ad5a08b905f81cc815040274ab29471a833b637e3fd0da508dbb8e2fdd1a646").unwrap();
+    assert_eq!(
+        &aead::chacha20_poly1305::poly1305_key_for(&key, nonce)[..],
+        &expected[..]
+    );
+}
+
 #[test]
 fn aead_test_aad_traits() {
     test::compile_time_assert_send::<aead::Aad<&'_ [u8]>>();
@@ -505,6 +604,319 @@ fn aead_test_aad_traits() {
     );
 }
 
+#[test]
+fn test_tls_record_aad() {
+    // The header of the server's first encrypted record in RFC 8448
+    // Section 3.
+    assert_eq!(
+        aead::tls13_record_aad(23, 0x02a2).as_ref(),
+        &[0x17, 0x03, 0x03, 0x02, 0xa2]
+    );
+    assert_eq!(
+        aead::tls13_record_aad(23, u16::MAX),
+        aead::Aad::from([0x17, 0x03, 0x03, 0xff, 0xff])
+    );
+
+    assert_eq!(
+        aead::tls12_record_aad(0x0102030405060708, 22, 0x0010).as_ref(),
+        &[
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // seq_num
+            0x16, // type
+            0x03, 0x03, // version
+            0x00, 0x10, // length
+        ]
+    );
+    assert_eq!(
+        aead::tls12_record_aad(u64::MAX, 23, 0).as_ref(),
+        &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x17, 0x03, 0x03, 0x00, 0x00]
+    );
+}
+
+#[test]
+fn test_nonce_from_counter() {
+    let nonce = |key_id: &[u8], counter| *aead::nonce_from_counter(key_id, counter).as_ref();
+
+    // The same counter always yields the same nonce.
+    assert_eq!(nonce(b"log", 1), nonce(b"log", 1));
+
+    // Distinct counters yield distinct nonces.
+    let counters = [0, 1, 2, 0xff, 0x100, 1 << 32, u64::MAX - 1, u64::MAX];
+    for (i, &a) in counters.iter().enumerate() {
+        for &b in &counters[(i + 1)..] {
+            assert_ne!(nonce(b"log", a), nonce(b"log", b));
+        }
+    }
+
+    // The counter is encoded in the last 64 bits of the nonce.
+    let n = nonce(b"log", 0x0102030405060708);
+    assert_eq!(&n[4..], &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+    // Different key IDs use different prefixes.
+    assert_ne!(nonce(b"log", 1), nonce(b"other log", 1));
+}
+
+#[test]
+fn test_sequence_nonce() {
+    use aead::{BoundKey, NonceSequence};
+
+    // The server handshake IV from RFC 8448 Section 3.
+    let iv = [
+        0x5d, 0x31, 0x3e, 0xb2, 0x67, 0x12, 0x76, 0xee, 0x13, 0x00, 0x0b, 0x30,
+    ];
+
+    // The TLS 1.3 per-record nonce: the IV XORed with the left-padded
+    // sequence number.
+    let expected = |sequence_number: u64| {
+        let mut padded = [0u8; aead::NONCE_LEN];
+        padded[4..].copy_from_slice(&sequence_number.to_be_bytes());
+        let mut nonce = iv;
+        nonce.iter_mut().zip(&padded).for_each(|(n, p)| *n ^= p);
+        nonce
+    };
+
+    let mut sequence = aead::SequenceNonce::new(iv);
+    for sequence_number in 0..=0x101 {
+        assert_eq!(sequence.sequence_number(), sequence_number);
+        let nonce = sequence.advance().unwrap();
+        assert_eq!(nonce.as_ref(), &expected(sequence_number));
+    }
+    assert_eq!(expected(0), iv);
+    assert_eq!(
+        expected(1),
+        [0x5d, 0x31, 0x3e, 0xb2, 0x67, 0x12, 0x76, 0xee, 0x13, 0x00, 0x0b, 0x31]
+    );
+    assert_eq!(
+        expected(0x101),
+        [0x5d, 0x31, 0x3e, 0xb2, 0x67, 0x12, 0x76, 0xee, 0x13, 0x00, 0x0a, 0x31]
+    );
+
+    // A sealing key and an opening key with the same IV agree.
+    for &alg in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let key_bytes = vec![0x42; alg.key_len()];
+        let mut sealing_key = aead::SealingKey::new(
+            aead::UnboundKey::new(alg, &key_bytes).unwrap(),
+            aead::SequenceNonce::new(iv),
+        );
+        let mut opening_key = aead::OpeningKey::new(
+            aead::UnboundKey::new(alg, &key_bytes).unwrap(),
+            aead::SequenceNonce::new(iv),
+        );
+        let less_safe_key = make_less_safe_key(alg, &key_bytes);
+        for sequence_number in 0..3 {
+            let plaintext = [sequence_number as u8; 20];
+            let mut in_out = plaintext.to_vec();
+            sealing_key
+                .seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+                .unwrap();
+
+            let mut expected_in_out = plaintext.to_vec();
+            less_safe_key
+                .seal_in_place_append_tag(
+                    aead::Nonce::assume_unique_for_key(expected(sequence_number)),
+                    aead::Aad::empty(),
+                    &mut expected_in_out,
+                )
+                .unwrap();
+            assert_eq!(in_out, expected_in_out);
+
+            let opened = opening_key
+                .open_in_place(aead::Aad::empty(), &mut in_out)
+                .unwrap();
+            assert_eq!(opened, &plaintext[..]);
+        }
+    }
+}
+
+#[test]
+fn test_channel_keys() {
+    let master = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, &[0x42; 32]);
+    for &alg in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let (mut initiator_sealing, mut initiator_opening) =
+            aead::channel_keys(&master, alg, aead::ChannelSide::Initiator);
+        let (mut responder_sealing, mut responder_opening) =
+            aead::channel_keys(&master, alg, aead::ChannelSide::Responder);
+
+        for i in 0u8..3 {
+            let plaintext = [i; 20];
+
+            let mut in_out = plaintext.to_vec();
+            initiator_sealing
+                .seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+                .unwrap();
+            let opened = responder_opening
+                .open_in_place(aead::Aad::empty(), &mut in_out)
+                .unwrap();
+            assert_eq!(opened, &plaintext[..]);
+
+            let mut in_out = plaintext.to_vec();
+            responder_sealing
+                .seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+                .unwrap();
+            let opened = initiator_opening
+                .open_in_place(aead::Aad::empty(), &mut in_out)
+                .unwrap();
+            assert_eq!(opened, &plaintext[..]);
+        }
+
+        // A record can't be reflected back to its sender.
+        let (mut sealing, mut opening) =
+            aead::channel_keys(&master, alg, aead::ChannelSide::Initiator);
+        let mut in_out = b"hello".to_vec();
+        sealing
+            .seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+            .unwrap();
+        assert!(opening
+            .open_in_place(aead::Aad::empty(), &mut in_out)
+            .is_err());
+    }
+}
+
+#[test]
+fn test_key_update() {
+    // RFC 8448 Section 3, {server} derive write traffic keys for application
+    // data. The keys for the updated secret were computed with an independent
+    // implementation of HKDF-Expand-Label.
+    let traffic_secret =
+        test::from_hex("a11af9f05531f856ad47116b45a950328204b4f44bfb6b3a4b4f1f3fcb631643").unwrap();
+    let expected = [
+        (
+            "9f02283b6c9c07efc26bb9f2ac92e356",
+            "cf782b88dd83549aadf1e984",
+        ),
+        (
+            "2e63be99d67b39097feb9786cf7a15a0",
+            "628a0a8298ac953baef4255a",
+        ),
+    ];
+
+    let mut key_update =
+        aead::KeyUpdate::new(&aead::AES_128_GCM, hkdf::HKDF_SHA256, &traffic_secret);
+    assert_eq!(key_update.algorithm(), &aead::AES_128_GCM);
+
+    let mut previous = Vec::new();
+    for (key, iv) in expected.iter() {
+        let key = test::from_hex(key).unwrap();
+        let iv = test::from_hex(iv).unwrap();
+        let expected_key =
+            aead::LessSafeKey::new(aead::UnboundKey::new(&aead::AES_128_GCM, &key).unwrap());
+
+        let mut sealing_key = key_update.sealing_key();
+        let mut opening_key = key_update.opening_key();
+        for sequence_number in 0u8..3 {
+            let mut nonce: [u8; aead::NONCE_LEN] = iv[..].try_into().unwrap();
+            nonce[aead::NONCE_LEN - 1] ^= sequence_number;
+            let nonce = aead::Nonce::assume_unique_for_key(nonce);
+
+            let mut expected_in_out = b"hello".to_vec();
+            expected_key
+                .seal_in_place_append_tag(nonce, aead::Aad::empty(), &mut expected_in_out)
+                .unwrap();
+
+            let mut in_out = b"hello".to_vec();
+            sealing_key
+                .seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+                .unwrap();
+            assert_eq!(in_out, expected_in_out);
+            assert!(!previous.contains(&in_out));
+            previous.push(in_out.clone());
+
+            let opened = opening_key
+                .open_in_place(aead::Aad::empty(), &mut in_out)
+                .unwrap();
+            assert_eq!(opened, b"hello");
+        }
+
+        key_update.update();
+    }
+
+    // A record sealed with the old key can't be opened with the new one.
+    let mut old = aead::KeyUpdate::new(&aead::AES_128_GCM, hkdf::HKDF_SHA256, &traffic_secret);
+    let mut in_out = b"hello".to_vec();
+    old.sealing_key()
+        .seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+        .unwrap();
+    old.update();
+    assert!(old
+        .opening_key()
+        .open_in_place(aead::Aad::empty(), &mut in_out)
+        .is_err());
+}
+
+#[test]
+fn test_forward_secure_sealer() {
+    const N: u64 = 5;
+
+    let root = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, &[0x42; 32]);
+    for &alg in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let mut sealer = aead::ForwardSecureSealer::new(alg, root.clone());
+        let opener = aead::ForwardSecureOpener::new(alg, root.clone());
+        assert_eq!(sealer.algorithm(), alg);
+        assert_eq!(opener.algorithm(), alg);
+
+        let records = (0..N)
+            .map(|i| {
+                assert_eq!(sealer.next_index(), i);
+                let mut in_out = vec![0u8; 20];
+                let index = sealer
+                    .seal_in_place_append_tag(aead::Aad::from(b"log"), &mut in_out)
+                    .unwrap();
+                assert_eq!(index, i);
+                in_out
+            })
+            .collect::<Vec<_>>();
+
+        // Every record has its own key, so the same plaintext gives different
+        // ciphertexts.
+        for (i, a) in records.iter().enumerate() {
+            for b in &records[(i + 1)..] {
+                assert_ne!(a, b);
+            }
+        }
+
+        for (i, record) in (0..).zip(records.iter()) {
+            let mut in_out = record.clone();
+            let opened = opener
+                .open_in_place(i, aead::Aad::from(b"log"), &mut in_out)
+                .unwrap();
+            assert_eq!(opened, &[0u8; 20][..]);
+
+            let mut in_out = record.clone();
+            assert!(opener
+                .open_in_place(i, aead::Aad::from(b"other"), &mut in_out)
+                .is_err());
+
+            // The sealer's current state can only derive the keys for records
+            // `N` and later, and none of those keys opens an earlier record.
+            for j in (i + 1)..=N {
+                let mut in_out = record.clone();
+                assert!(opener
+                    .open_in_place(j, aead::Aad::from(b"log"), &mut in_out)
+                    .is_err());
+            }
+        }
+
+        // Indexes past the end of the chain are rejected without walking it.
+        for index in [aead::FORWARD_SECURE_MAX_RECORDS, u64::MAX] {
+            let mut in_out = records[0].clone();
+            assert!(opener
+                .open_in_place(index, aead::Aad::from(b"log"), &mut in_out)
+                .is_err());
+        }
+    }
+}
+
 #[test]
 fn test_nonce_traits() {
     test::compile_time_assert_send::<aead::Nonce>();
@@ -522,6 +934,211 @@ fn test_tag_traits() {
     let tag = aead::Tag::from([4u8; 16]);
     let _tag_2 = tag; // Cover `Copy`
     assert_eq!(tag.as_ref(), tag.clone().as_ref()); // Cover `Clone`
+
+    // `Tag::try_from()` only accepts full-length tags; truncated tags are
+    // only accepted for the algorithms that use them.
+    for len in 0..=17 {
+        let bytes = vec![5u8; len];
+        let tag = aead::Tag::try_from(&bytes[..]);
+        assert_eq!(tag.is_ok(), len == 16, "{}", len);
+        if let Ok(tag) = tag {
+            assert_eq!(tag.as_ref(), &bytes[..]);
+        }
+
+        for &(alg, tag_len) in &[
+            (&aead::AES_256_GCM, 16),
+            (&aead::AES_256_GCM_TAG96, 12),
+            (&aead::AES_256_GCM_TAG64, 8),
+            (&aead::CHACHA20_POLY1305, 16),
+        ] {
+            let tag = aead::Tag::try_for_algorithm(alg, &bytes);
+            assert_eq!(tag.is_ok(), len == tag_len, "{:?} {}", alg, len);
+            if let Ok(tag) = tag {
+                assert_eq!(tag.as_ref(), &bytes[..]);
+            }
+        }
+    }
+}
+
+// This allocates a ~64 GiB buffer, though none of it is touched unless the
+// length check is missing.
+#[cfg(target_pointer_width = "64")]
+#[test]
+#[ignore]
+fn test_aead_aes_gcm_max_input_len() {
+    const MAX_IN_OUT_LEN: usize = (1 << 36) - 32;
+
+    for &alg in &[&aead::AES_128_GCM, &aead::AES_256_GCM] {
+        let key = aead::LessSafeKey::new(
+            aead::UnboundKey::new(alg, &[0u8; 32][..alg.key_len()]).unwrap(),
+        );
+        let nonce = || aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]);
+
+        let mut in_out = vec![0u8; MAX_IN_OUT_LEN + 1];
+        assert!(key
+            .seal_in_place_separate_tag(nonce(), aead::Aad::empty(), &mut in_out)
+            .is_err());
+        let tag = aead::Tag::from([0u8; 16]);
+        assert!(key
+            .open_in_place_separate_tag(nonce(), aead::Aad::empty(), tag, &mut in_out, 0..)
+            .is_err());
+    }
+}
+
+#[test]
+fn test_aead_usage_limits() {
+    // NIST SP 800-38D Sections 5.2.1.1 and 8.3.
+    let aes_gcm = aead::UsageLimits {
+        max_messages: 1 << 32,
+        max_bytes_per_message: (1 << 36) - 32,
+    };
+    assert_eq!(aead::AES_128_GCM.usage_limits(), aes_gcm);
+    assert_eq!(aead::AES_256_GCM.usage_limits(), aes_gcm);
+    assert_eq!(aead::AES_256_GCM_TAG96.usage_limits(), aes_gcm);
+
+    // NIST SP 800-38D Appendix C.
+    assert_eq!(
+        aead::AES_256_GCM_TAG64.usage_limits(),
+        aead::UsageLimits {
+            max_messages: 1 << 32,
+            max_bytes_per_message: 1 << 15,
+        }
+    );
+
+    // RFC 8452 Section 6, with AES-GCM's limit on the number of messages.
+    let aes_gcm_siv = aead::UsageLimits {
+        max_messages: 1 << 32,
+        max_bytes_per_message: 1 << 36,
+    };
+    assert_eq!(aead::AES_128_GCM_SIV.usage_limits(), aes_gcm_siv);
+    assert_eq!(aead::AES_256_GCM_SIV.usage_limits(), aes_gcm_siv);
+
+    // RFC 8439 Section 2.8: 2**32 - 1 64-byte blocks.
+    assert_eq!(
+        aead::CHACHA20_POLY1305.usage_limits(),
+        aead::UsageLimits {
+            max_messages: u64::MAX,
+            max_bytes_per_message: ((1 << 32) - 1) * 64,
+        }
+    );
+}
+
+#[test]
+fn test_aead_aes_gcm_siv_nonce_reuse() {
+    for &algorithm in &[&aead::AES_128_GCM_SIV, &aead::AES_256_GCM_SIV] {
+        let key = make_less_safe_key(algorithm, &[0x42; 32][..algorithm.key_len()]);
+        let seal = |plaintext: &[u8]| {
+            let mut in_out = plaintext.to_vec();
+            key.seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key([1; aead::NONCE_LEN]),
+                aead::Aad::empty(),
+                &mut in_out,
+            )
+            .unwrap();
+            in_out
+        };
+
+        // Reusing a nonce only reveals whether the messages are equal. Unlike
+        // with AES-GCM, messages that differ only in their last byte have
+        // unrelated ciphertexts, since the tag is the initial counter block.
+        let a = seal(b"attack at dawn!!");
+        assert_eq!(a, seal(b"attack at dawn!!"));
+        let b = seal(b"attack at dawn!?");
+        assert!(a.iter().zip(&b).filter(|(a, b)| a == b).count() < 8);
+    }
+}
+
+#[test]
+fn test_aead_truncated_tags() {
+    let key_bytes = [0x42; 32];
+    let mut full_sealed = b"hello, world".to_vec();
+    let full_key =
+        aead::LessSafeKey::new(aead::UnboundKey::new(&aead::AES_256_GCM, &key_bytes).unwrap());
+    full_key
+        .seal_in_place_append_tag(
+            aead::Nonce::assume_unique_for_key([1; aead::NONCE_LEN]),
+            aead::Aad::from(b"aad"),
+            &mut full_sealed,
+        )
+        .unwrap();
+
+    for &(alg, tag_len) in &[
+        (&aead::AES_256_GCM_TAG96, 12),
+        (&aead::AES_256_GCM_TAG64, 8),
+    ] {
+        assert_eq!(alg.tag_len(), tag_len);
+        let key = aead::LessSafeKey::new(aead::UnboundKey::new(alg, &key_bytes).unwrap());
+        let nonce = || aead::Nonce::assume_unique_for_key([1; aead::NONCE_LEN]);
+
+        // The tag is the truncated AES-256-GCM tag.
+        let mut sealed = b"hello, world".to_vec();
+        key.seal_in_place_append_tag(nonce(), aead::Aad::from(b"aad"), &mut sealed)
+            .unwrap();
+        assert_eq!(sealed.len(), 12 + tag_len);
+        assert_eq!(&sealed[..], &full_sealed[..(12 + tag_len)]);
+
+        let mut in_out = sealed.clone();
+        let opened = key
+            .open_in_place(nonce(), aead::Aad::from(b"aad"), &mut in_out)
+            .unwrap();
+        assert_eq!(opened, b"hello, world");
+
+        // A tampered truncated tag is rejected.
+        for i in 12..sealed.len() {
+            let mut in_out = sealed.clone();
+            in_out[i] ^= 0x80;
+            assert!(key
+                .open_in_place(nonce(), aead::Aad::from(b"aad"), &mut in_out)
+                .is_err());
+        }
+
+        // The full-length tag isn't accepted in place of the truncated tag,
+        // and vice versa.
+        let mut in_out = full_sealed.clone();
+        assert!(key
+            .open_in_place(nonce(), aead::Aad::from(b"aad"), &mut in_out)
+            .is_err());
+        let mut in_out = sealed.clone();
+        assert!(full_key
+            .open_in_place(nonce(), aead::Aad::from(b"aad"), &mut in_out)
+            .is_err());
+
+        // The same goes for separate tags.
+        let (ciphertext, truncated_tag) = sealed.split_at(12);
+        let truncated_tag = aead::Tag::try_for_algorithm(alg, truncated_tag).unwrap();
+        let full_tag = aead::Tag::try_from(&full_sealed[12..]).unwrap();
+        let mut in_out = ciphertext.to_vec();
+        let opened = key
+            .open_in_place_separate_tag(
+                nonce(),
+                aead::Aad::from(b"aad"),
+                truncated_tag,
+                &mut in_out,
+                0..,
+            )
+            .unwrap();
+        assert_eq!(opened, b"hello, world");
+        let mut in_out = ciphertext.to_vec();
+        assert!(key
+            .open_in_place_separate_tag(
+                nonce(),
+                aead::Aad::from(b"aad"),
+                full_tag,
+                &mut in_out,
+                0..
+            )
+            .is_err());
+        let mut in_out = ciphertext.to_vec();
+        assert!(full_key
+            .open_in_place_separate_tag(
+                nonce(),
+                aead::Aad::from(b"aad"),
+                truncated_tag,
+                &mut in_out,
+                0..
+            )
+            .is_err());
+    }
 }
 
 fn test_aead_key_traits<T: Send + Sync>() {}
@@ -533,6 +1150,88 @@ fn test_aead_key_traits_all() {
     test_aead_key_traits::<aead::LessSafeKey>();
 }
 
+// A single `LessSafeKey` can be used to seal concurrently from many threads.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn test_aead_lesssafekey_shared_across_threads() {
+    use std::{sync::Arc, thread};
+
+    const THREADS: u8 = 8;
+    const MESSAGES_PER_THREAD: u8 = 16;
+
+    // Each thread uses a disjoint set of nonces.
+    fn nonce(thread: u8, message: u8) -> aead::Nonce {
+        let mut nonce = [0; aead::NONCE_LEN];
+        nonce[0] = thread;
+        nonce[aead::NONCE_LEN - 1] = message;
+        aead::Nonce::assume_unique_for_key(nonce)
+    }
+
+    fn seal_all(key: &aead::LessSafeKey, thread: u8) -> Vec<Vec<u8>> {
+        (0..MESSAGES_PER_THREAD)
+            .map(|message| {
+                let mut in_out = vec![thread ^ message; 100 + usize::from(message)];
+                key.seal_in_place_append_tag(
+                    nonce(thread, message),
+                    aead::Aad::from([thread, message]),
+                    &mut in_out,
+                )
+                .unwrap();
+                in_out
+            })
+            .collect()
+    }
+
+    for &alg in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let key = make_less_safe_key(alg, &vec![0x42; alg.key_len()]);
+
+        // Shared by reference.
+        let sealed: Vec<Vec<Vec<u8>>> = thread::scope(|s| {
+            let threads: Vec<_> = (0..THREADS)
+                .map(|thread| {
+                    let key = &key;
+                    s.spawn(move || seal_all(key, thread))
+                })
+                .collect();
+            threads.into_iter().map(|t| t.join().unwrap()).collect()
+        });
+
+        // Shared in an `Arc`.
+        let shared = Arc::new(key.clone());
+        let threads: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                let key = Arc::clone(&shared);
+                thread::spawn(move || seal_all(&key, thread))
+            })
+            .collect();
+        for (thread, t) in threads.into_iter().enumerate() {
+            assert_eq!(t.join().unwrap(), sealed[thread]);
+        }
+
+        for (thread, sealed) in (0..THREADS).zip(&sealed) {
+            assert_eq!(sealed, &seal_all(&key, thread));
+            for (message, sealed) in (0..MESSAGES_PER_THREAD).zip(sealed) {
+                let mut in_out = sealed.clone();
+                let opened = key
+                    .open_in_place(
+                        nonce(thread, message),
+                        aead::Aad::from([thread, message]),
+                        &mut in_out,
+                    )
+                    .unwrap();
+                assert_eq!(
+                    opened,
+                    &vec![thread ^ message; 100 + usize::from(message)][..]
+                );
+            }
+        }
+    }
+}
+
 #[test]
 fn test_aead_key_debug() {
     let key_bytes = [0; 32];
@@ -569,6 +1268,12 @@ fn test_aead_key_debug() {
         "LessSafeKey { algorithm: AES_256_GCM }",
         format!("{:?}", key)
     );
+
+    let key_bytes = [0x5a; aead::chacha20_poly1305_openssh::KEY_LEN];
+    let key = aead::chacha20_poly1305_openssh::SealingKey::new(&key_bytes);
+    assert_eq!("SealingKey { .. }", format!("{:?}", key));
+    let key = aead::chacha20_poly1305_openssh::OpeningKey::new(&key_bytes);
+    assert_eq!("OpeningKey { .. }", format!("{:?}", key));
 }
 
 fn test_aead_lesssafekey_clone_for_algorithm(algorithm: &'static aead::Algorithm) {
@@ -612,11 +1317,249 @@ fn test_aead_lesssafekey_clone_aes_256_gcm() {
     test_aead_lesssafekey_clone_for_algorithm(&aead::AES_256_GCM);
 }
 
+#[test]
+fn test_aead_lesssafekey_clone_aes_256_gcm_siv() {
+    test_aead_lesssafekey_clone_for_algorithm(&aead::AES_256_GCM_SIV);
+}
+
 #[test]
 fn test_aead_lesssafekey_clone_chacha20_poly1305() {
     test_aead_lesssafekey_clone_for_algorithm(&aead::CHACHA20_POLY1305);
 }
 
+#[test]
+fn test_aead_lesssafekey_authenticating_nonce() {
+    const PLAINTEXT: &[u8] = b"hello, world";
+    const EXTRA_AAD: &[u8] = b"header";
+
+    let test_bytes: Vec<u8> = (0..32).collect();
+    for &algorithm in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::AES_128_GCM_SIV,
+        &aead::AES_256_GCM_SIV,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let key = make_less_safe_key(algorithm, &test_bytes[..algorithm.key_len()]);
+        let nonce_bytes = &test_bytes[..algorithm.nonce_len()];
+        let nonce = || aead::Nonce::try_assume_unique_for_key(nonce_bytes).unwrap();
+
+        let mut sealed = PLAINTEXT.to_vec();
+        key.seal_in_place_authenticating_nonce(nonce(), aead::Aad::from(EXTRA_AAD), &mut sealed)
+            .unwrap();
+
+        // The associated data is the nonce followed by the extra AAD.
+        let mut aad = nonce_bytes.to_vec();
+        aad.extend_from_slice(EXTRA_AAD);
+        let mut expected = PLAINTEXT.to_vec();
+        key.seal_in_place_append_tag(nonce(), aead::Aad::from(&aad), &mut expected)
+            .unwrap();
+        assert_eq!(sealed, expected);
+
+        let mut in_out = sealed.clone();
+        let opened = key
+            .open_in_place_authenticating_nonce(nonce(), aead::Aad::from(EXTRA_AAD), &mut in_out)
+            .unwrap();
+        assert_eq!(opened, PLAINTEXT);
+
+        // A tampered nonce is detected.
+        let mut tampered_nonce = nonce_bytes.to_vec();
+        tampered_nonce[0] ^= 1;
+        let mut in_out = sealed.clone();
+        assert!(key
+            .open_in_place_authenticating_nonce(
+                aead::Nonce::try_assume_unique_for_key(&tampered_nonce).unwrap(),
+                aead::Aad::from(EXTRA_AAD),
+                &mut in_out
+            )
+            .is_err());
+
+        // So is tampered extra AAD.
+        let mut in_out = sealed.clone();
+        assert!(key
+            .open_in_place_authenticating_nonce(nonce(), aead::Aad::from(b"other"), &mut in_out)
+            .is_err());
+
+        // Opening without the nonce in the associated data fails.
+        let mut in_out = sealed.clone();
+        assert!(key
+            .open_in_place(nonce(), aead::Aad::from(EXTRA_AAD), &mut in_out)
+            .is_err());
+    }
+}
+
+#[test]
+fn test_aead_lesssafekey_seal_in_place_vec() {
+    const PLAINTEXT: &[u8] = b"hello, world";
+
+    let test_bytes: Vec<u8> = (0..32).collect();
+    for &algorithm in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::AES_128_GCM_SIV,
+        &aead::AES_256_GCM_SIV,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let key = make_less_safe_key(algorithm, &test_bytes[..algorithm.key_len()]);
+        let nonce_bytes = &test_bytes[..algorithm.nonce_len()];
+        let nonce = || aead::Nonce::try_assume_unique_for_key(nonce_bytes).unwrap();
+
+        let mut in_out = Vec::with_capacity(PLAINTEXT.len());
+        in_out.extend_from_slice(PLAINTEXT);
+        assert_eq!(in_out.capacity(), PLAINTEXT.len());
+
+        key.seal_in_place_vec(nonce(), aead::Aad::empty(), &mut in_out)
+            .unwrap();
+        assert_eq!(in_out.len(), PLAINTEXT.len() + algorithm.tag_len());
+        assert!(in_out.capacity() >= in_out.len());
+
+        // The result is the same as `seal_in_place_append_tag`'s.
+        let mut expected = PLAINTEXT.to_vec();
+        key.seal_in_place_append_tag(nonce(), aead::Aad::empty(), &mut expected)
+            .unwrap();
+        assert_eq!(in_out, expected);
+
+        let opened = key
+            .open_in_place(nonce(), aead::Aad::empty(), &mut in_out)
+            .unwrap();
+        assert_eq!(opened, PLAINTEXT);
+    }
+}
+
+#[test]
+fn test_aead_seal_gather_io_slices() {
+    let key = make_less_safe_key(&aead::CHACHA20_POLY1305, &[0x42; 32]);
+    let nonce = || aead::Nonce::assume_unique_for_key([1; aead::NONCE_LEN]);
+    let header = [0x17, 0x03, 0x03];
+    let payload = b"hello, world".to_vec();
+
+    let mut expected = [&header[..], &payload].concat();
+    let expected_tag = key
+        .seal_in_place_separate_tag(nonce(), aead::Aad::from(b"aad"), &mut expected)
+        .unwrap();
+
+    let fragments = [
+        std::io::IoSlice::new(&header),
+        std::io::IoSlice::new(&payload),
+    ];
+    let mut out = vec![0; header.len() + payload.len()];
+    let tag = key
+        .seal_gather_separate_tag(nonce(), aead::Aad::from(b"aad"), &fragments, &mut out)
+        .unwrap();
+    assert_eq!(out, expected);
+    assert_eq!(tag.as_ref(), expected_tag.as_ref());
+}
+
+#[test]
+fn test_aead_ciphertext_lens() {
+    for &algorithm in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::AES_256_GCM_TAG96,
+        &aead::AES_256_GCM_TAG64,
+        &aead::AES_128_GCM_SIV,
+        &aead::AES_256_GCM_SIV,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let tag_len = algorithm.tag_len();
+
+        assert_eq!(algorithm.max_ciphertext_len(0), Some(tag_len));
+        assert_eq!(algorithm.max_ciphertext_len(100), Some(100 + tag_len));
+        assert_eq!(algorithm.max_ciphertext_len(usize::MAX), None);
+        assert_eq!(
+            algorithm.max_ciphertext_len(usize::MAX - tag_len),
+            Some(usize::MAX)
+        );
+
+        assert_eq!(algorithm.plaintext_len_from_ciphertext(tag_len), Some(0));
+        assert_eq!(
+            algorithm.plaintext_len_from_ciphertext(100 + tag_len),
+            Some(100)
+        );
+        assert_eq!(algorithm.plaintext_len_from_ciphertext(tag_len - 1), None);
+        assert_eq!(algorithm.plaintext_len_from_ciphertext(0), None);
+
+        // The lengths match what sealing actually produces.
+        let key_bytes = vec![0; algorithm.key_len()];
+        let key = make_less_safe_key(algorithm, &key_bytes);
+        for &len in &[0, 100] {
+            let mut in_out = vec![0; len];
+            key.seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]),
+                aead::Aad::empty(),
+                &mut in_out,
+            )
+            .unwrap();
+            assert_eq!(Some(in_out.len()), algorithm.max_ciphertext_len(len));
+            assert_eq!(
+                algorithm.plaintext_len_from_ciphertext(in_out.len()),
+                Some(len)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_aead_failed_open_leaves_no_plaintext() {
+    const PLAINTEXT: &[u8] = b"the plaintext must not leak after a failed open";
+    const PREFIX_LEN: usize = 5;
+
+    let test_bytes: Vec<u8> = (0..32).collect();
+    for &algorithm in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::AES_128_GCM_SIV,
+        &aead::AES_256_GCM_SIV,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let key = make_less_safe_key(algorithm, &test_bytes[..algorithm.key_len()]);
+        let nonce = || {
+            aead::Nonce::try_assume_unique_for_key(&test_bytes[..algorithm.nonce_len()]).unwrap()
+        };
+
+        let mut ciphertext = PLAINTEXT.to_vec();
+        let tag = key
+            .seal_in_place_separate_tag(nonce(), aead::Aad::empty(), &mut ciphertext)
+            .unwrap();
+        let mut bad_tag: [u8; aead::MAX_TAG_LEN] = tag.as_ref().try_into().unwrap();
+        bad_tag[0] ^= 1;
+        let bad_tag = aead::Tag::from(bad_tag);
+
+        // Detached tag.
+        let mut in_out = ciphertext.clone();
+        assert!(key
+            .open_in_place_separate_tag(nonce(), aead::Aad::empty(), bad_tag, &mut in_out, 0..)
+            .is_err());
+        assert_ne!(in_out, PLAINTEXT);
+        assert!(in_out.iter().all(|b| *b == 0));
+
+        // Detached tag, with a shift.
+        let mut in_out = vec![0xff; PREFIX_LEN];
+        in_out.extend_from_slice(&ciphertext);
+        assert!(key
+            .open_in_place_separate_tag(
+                nonce(),
+                aead::Aad::empty(),
+                bad_tag,
+                &mut in_out,
+                PREFIX_LEN..
+            )
+            .is_err());
+        assert!(in_out[..PLAINTEXT.len()].iter().all(|b| *b == 0));
+        assert!(!in_out
+            .windows(PLAINTEXT.len())
+            .any(|window| window == PLAINTEXT));
+
+        // Appended tag.
+        let mut in_out = ciphertext.clone();
+        in_out.extend_from_slice(bad_tag.as_ref());
+        assert!(key
+            .open_in_place(nonce(), aead::Aad::empty(), &mut in_out)
+            .is_err());
+        assert!(in_out[..PLAINTEXT.len()].iter().all(|b| *b == 0));
+    }
+}
+
 fn make_key<K: aead::BoundKey<OneNonceSequence>>(
     algorithm: &'static aead::Algorithm,
     key: &[u8],
@@ -0,0 +1,137 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+use ring::{
+    aead::{self, xchacha20_poly1305},
+    test, test_file,
+};
+
+fn nonce(value: &[u8]) -> xchacha20_poly1305::Nonce {
+    xchacha20_poly1305::Nonce::try_assume_unique_for_key(value).unwrap()
+}
+
+#[test]
+fn xchacha20_poly1305_test() {
+    test::run(
+        test_file!("aead_xchacha20_poly1305_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let key_bytes = test_case.consume_bytes("KEY");
+            let nonce_bytes = test_case.consume_bytes("NONCE");
+            let plaintext = test_case.consume_bytes("IN");
+            let aad = test_case.consume_bytes("AD");
+            let ct = test_case.consume_bytes("CT");
+            let tag = test_case.consume_bytes("TAG");
+
+            let key = xchacha20_poly1305::Key::new(&aead::XCHACHA20_POLY1305, &key_bytes).unwrap();
+
+            let mut in_out = plaintext.clone();
+            key.seal_in_place_append_tag(nonce(&nonce_bytes), aead::Aad::from(&aad), &mut in_out)
+                .unwrap();
+            assert_eq!(&in_out[..ct.len()], &ct[..]);
+            assert_eq!(&in_out[ct.len()..], &tag[..]);
+
+            let mut in_out_separate = plaintext.clone();
+            let separate_tag = key
+                .seal_in_place_separate_tag(
+                    nonce(&nonce_bytes),
+                    aead::Aad::from(&aad),
+                    &mut in_out_separate,
+                )
+                .unwrap();
+            assert_eq!(&in_out_separate[..], &ct[..]);
+            assert_eq!(separate_tag.as_ref(), &tag[..]);
+
+            let opened = key
+                .open_in_place(nonce(&nonce_bytes), aead::Aad::from(&aad), &mut in_out)
+                .unwrap();
+            assert_eq!(opened, &plaintext[..]);
+
+            // The ciphertext may be preceded by a prefix that is discarded.
+            let mut in_out = vec![0xff; 3];
+            in_out.extend_from_slice(&ct);
+            in_out.extend_from_slice(&tag);
+            let opened = key
+                .open_within(nonce(&nonce_bytes), aead::Aad::from(&aad), &mut in_out, 3..)
+                .unwrap();
+            assert_eq!(opened, &plaintext[..]);
+
+            // Tampering with the ciphertext, the tag, the nonce, or the AAD is
+            // detected, and the plaintext isn't released.
+            let mut sealed = ct.clone();
+            sealed.extend_from_slice(&tag);
+            for i in 0..sealed.len() {
+                let mut in_out = sealed.clone();
+                in_out[i] ^= 1;
+                assert!(key
+                    .open_in_place(nonce(&nonce_bytes), aead::Aad::from(&aad), &mut in_out)
+                    .is_err());
+                assert!(in_out[..ct.len()].iter().all(|&b| b == 0));
+            }
+            for i in 0..nonce_bytes.len() {
+                let mut wrong_nonce = nonce_bytes.clone();
+                wrong_nonce[i] ^= 1;
+                let mut in_out = sealed.clone();
+                assert!(key
+                    .open_in_place(nonce(&wrong_nonce), aead::Aad::from(&aad), &mut in_out)
+                    .is_err());
+            }
+            let mut wrong_aad = aad.clone();
+            wrong_aad.push(0);
+            let mut in_out = sealed.clone();
+            assert!(key
+                .open_in_place(
+                    nonce(&nonce_bytes),
+                    aead::Aad::from(&wrong_aad),
+                    &mut in_out
+                )
+                .is_err());
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn xchacha20_poly1305_key_and_nonce_lengths_test() {
+    let algorithm = &aead::XCHACHA20_POLY1305;
+    assert_eq!(algorithm.key_len(), xchacha20_poly1305::KEY_LEN);
+    assert_eq!(algorithm.nonce_len(), xchacha20_poly1305::NONCE_LEN);
+    assert_eq!(algorithm.nonce_len(), 24);
+    assert_eq!(algorithm.tag_len(), aead::MAX_TAG_LEN);
+
+    assert!(xchacha20_poly1305::Key::new(algorithm, &[0; 31]).is_err());
+    assert!(xchacha20_poly1305::Key::new(algorithm, &[0; 33]).is_err());
+    assert!(xchacha20_poly1305::Nonce::try_assume_unique_for_key(&[0; 12]).is_err());
+    assert!(xchacha20_poly1305::Nonce::try_assume_unique_for_key(&[0; 25]).is_err());
+
+    let key = xchacha20_poly1305::Key::new(algorithm, &[0; 32]).unwrap();
+    assert_eq!(key.algorithm(), algorithm);
+    assert_eq!(
+        format!("{:?}", key),
+        "Key { algorithm: XCHACHA20_POLY1305 }"
+    );
+
+    // Too short to contain a tag.
+    let mut in_out = [0; 15];
+    assert!(key
+        .open_in_place(nonce(&[0; 24]), aead::Aad::empty(), &mut in_out)
+        .is_err());
+}
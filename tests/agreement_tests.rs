@@ -90,6 +90,10 @@ fn agreement_agree_ephemeral() {
                 let output = test_case.consume_bytes("Output");
 
                 assert_eq!(my_private.algorithm(), alg);
+                assert_eq!(
+                    agreement::validate_public_key(alg, peer_public.bytes()),
+                    Ok(())
+                );
 
                 let computed_public = my_private.compute_public_key().unwrap();
                 assert_eq!(computed_public.as_ref(), &my_public[..]);
@@ -103,6 +107,8 @@ fn agreement_agree_ephemeral() {
             }
 
             Some(_) => {
+                assert!(agreement::validate_public_key(alg, peer_public.bytes()).is_err());
+
                 // In the no-heap mode, some algorithms aren't supported so
                 // we have to skip those algorithms' test cases.
                 let dummy_private_key = agreement::EphemeralPrivateKey::generate(alg, &rng)?;
@@ -125,6 +131,75 @@ fn agreement_agree_ephemeral() {
     });
 }
 
+#[test]
+fn agreement_validate_public_key_x25519() {
+    // The points of small order, with both canonical and non-canonical
+    // encodings; see https://cr.yp.to/ecdh.html#validate.
+    const SMALL_ORDER: &[&str] = &[
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "e0eb7a7c3b41b8ae1656e3faf19fc46ada098deb9c32b1fd866205165f49b800",
+        "5f9c95bca3508c24b1d0b1559c83ef5b04445cc4581c8e86d8224eddd09f1157",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "0000000000000000000000000000000000000000000000000000000000000080",
+        "0100000000000000000000000000000000000000000000000000000000000080",
+        "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+    ];
+    for public_key in SMALL_ORDER {
+        assert!(agreement::validate_public_key(&agreement::X25519, &h(public_key)).is_err());
+    }
+
+    let base_point = h("0900000000000000000000000000000000000000000000000000000000000000");
+    assert_eq!(
+        agreement::validate_public_key(&agreement::X25519, &base_point),
+        Ok(())
+    );
+    assert!(agreement::validate_public_key(&agreement::X25519, &base_point[..31]).is_err());
+}
+
+#[test]
+fn agreement_x25519_keypair_from_seed() {
+    // From RFC 7748 Section 6.1.
+    let alice_seed = h("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+    let alice_public = h("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a");
+    let bob_seed = h("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+    let bob_public = h("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f");
+    let shared = h("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+
+    let (alice_private_key, alice_public_key) =
+        agreement::x25519_keypair_from_seed(alice_seed.as_slice().try_into().unwrap());
+    let (bob_private_key, bob_public_key) =
+        agreement::x25519_keypair_from_seed(bob_seed.as_slice().try_into().unwrap());
+    assert_eq!(alice_public_key.as_ref(), &alice_public[..]);
+    assert_eq!(bob_public_key.as_ref(), &bob_public[..]);
+    assert_eq!(alice_public_key.algorithm(), &agreement::X25519);
+    assert_eq!(
+        alice_private_key.compute_public_key().unwrap().as_ref(),
+        &alice_public[..]
+    );
+
+    let alice_shared = agreement::agree_ephemeral(
+        alice_private_key,
+        &agreement::UnparsedPublicKey::new(&agreement::X25519, bob_public_key),
+        |shared| shared.to_vec(),
+    )
+    .unwrap();
+    let bob_shared = agreement::agree_ephemeral(
+        bob_private_key,
+        &agreement::UnparsedPublicKey::new(&agreement::X25519, alice_public_key),
+        |shared| shared.to_vec(),
+    )
+    .unwrap();
+    assert_eq!(alice_shared, shared);
+    assert_eq!(bob_shared, shared);
+
+    // The same seed always gives the same key pair.
+    let (_, again) = agreement::x25519_keypair_from_seed(alice_seed.as_slice().try_into().unwrap());
+    assert_eq!(again.as_ref(), &alice_public[..]);
+}
+
 #[test]
 fn test_agreement_ecdh_x25519_rfc_iterated() {
     let mut k = h("0900000000000000000000000000000000000000000000000000000000000000");
@@ -0,0 +1,68 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use core::num::NonZeroU32;
+use ring::{bcrypt_pbkdf, test, test_file};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn bcrypt_pbkdf_tests() {
+    test::run(
+        test_file!("bcrypt_pbkdf_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let password = test_case.consume_bytes("Password");
+            let salt = test_case.consume_bytes("Salt");
+            let rounds: u32 = test_case.consume_usize("Rounds").try_into().unwrap();
+            let rounds = NonZeroU32::new(rounds).unwrap();
+            let expected = test_case.consume_bytes("Out");
+
+            let mut out = vec![0u8; expected.len()];
+            bcrypt_pbkdf::derive(rounds, &salt, &password, &mut out).unwrap();
+            assert_eq!(out, expected);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn bcrypt_pbkdf_limits() {
+    let one = NonZeroU32::new(1).unwrap();
+    let mut out = [0u8; bcrypt_pbkdf::MAX_OUTPUT_LEN + 1];
+
+    assert!(bcrypt_pbkdf::derive(one, b"salt", b"password", &mut out[..1]).is_ok());
+    assert!(bcrypt_pbkdf::derive(
+        one,
+        b"salt",
+        b"password",
+        &mut out[..bcrypt_pbkdf::MAX_OUTPUT_LEN]
+    )
+    .is_ok());
+
+    // Empty inputs or output.
+    assert!(bcrypt_pbkdf::derive(one, b"", b"password", &mut out[..1]).is_err());
+    assert!(bcrypt_pbkdf::derive(one, b"salt", b"", &mut out[..1]).is_err());
+    assert!(bcrypt_pbkdf::derive(one, b"salt", b"password", &mut []).is_err());
+
+    // Too-long salt or output.
+    let long_salt = vec![0u8; bcrypt_pbkdf::MAX_SALT_LEN + 1];
+    assert!(bcrypt_pbkdf::derive(one, &long_salt, b"password", &mut out[..1]).is_err());
+    assert!(bcrypt_pbkdf::derive(one, b"salt", b"password", &mut out).is_err());
+}
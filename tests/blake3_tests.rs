@@ -0,0 +1,115 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{blake3, test, test_file};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+const KEY: &[u8; blake3::KEY_LEN] = b"whats the Elvish word for friend";
+const CONTEXT: &str = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+#[test]
+fn blake3_tests() {
+    test::run(test_file!("blake3_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let input = input(test_case.consume_usize("InputLen"));
+
+        for (name, hasher) in [
+            ("Hash", blake3::Hasher::new()),
+            ("KeyedHash", blake3::Hasher::new_keyed(KEY)),
+            ("DeriveKey", blake3::Hasher::new_derive_key(CONTEXT)),
+        ] {
+            let expected = test_case.consume_bytes(name);
+            let mut hasher = hasher;
+            hasher.update(&input);
+            assert_eq!(hasher.finalize(), expected[..blake3::OUT_LEN]);
+
+            let mut actual = vec![0u8; expected.len()];
+            hasher.finalize_xof(&mut actual);
+            assert_eq!(actual, expected);
+
+            // Every length of output is a prefix of longer outputs.
+            for len in [0, 1, 63, 64, 65] {
+                let mut actual = vec![0u8; len];
+                hasher.finalize_xof(&mut actual);
+                assert_eq!(actual, expected[..len]);
+            }
+        }
+
+        Ok(())
+    })
+}
+
+#[test]
+fn blake3_one_shot_tests() {
+    test::run(test_file!("blake3_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let input = input(test_case.consume_usize("InputLen"));
+        let hash = test_case.consume_bytes("Hash");
+        let keyed_hash = test_case.consume_bytes("KeyedHash");
+        let derive_key = test_case.consume_bytes("DeriveKey");
+
+        assert_eq!(blake3::hash(&input), hash[..blake3::OUT_LEN]);
+        assert_eq!(
+            blake3::keyed_hash(KEY, &input),
+            keyed_hash[..blake3::OUT_LEN]
+        );
+        assert_eq!(
+            blake3::derive_key(CONTEXT, &input),
+            derive_key[..blake3::OUT_LEN]
+        );
+
+        assert!(blake3::verify_keyed_hash(KEY, &input, &keyed_hash[..blake3::OUT_LEN]).is_ok());
+        assert!(blake3::verify_keyed_hash(KEY, &input, &hash[..blake3::OUT_LEN]).is_err());
+        assert!(blake3::verify_keyed_hash(KEY, &input, &keyed_hash[..16]).is_err());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn blake3_multi_update_equals_one_shot() {
+    // Long enough to use the SIMD implementations several times, with
+    // a partial chunk at the end.
+    let input = input(16 * 1024 + 100);
+    let expected = blake3::hash(&input);
+
+    for split in [1, 63, 64, 65, 1023, 1024, 1025, 3000, 8192] {
+        let mut hasher = blake3::Hasher::new();
+        input.chunks(split).for_each(|chunk| hasher.update(chunk));
+        assert_eq!(hasher.finalize(), expected, "split: {}", split);
+    }
+
+    // Uneven pieces, including empty ones.
+    let mut hasher = blake3::Hasher::new();
+    let mut rest = &input[..];
+    for len in (0..).step_by(97) {
+        let len = core::cmp::min(len, rest.len());
+        let (piece, tail) = rest.split_at(len);
+        hasher.update(piece);
+        rest = tail;
+        if rest.is_empty() {
+            break;
+        }
+    }
+    assert_eq!(hasher.finalize(), expected);
+}
+
+fn input(len: usize) -> Vec<u8> {
+    (0..251u8).cycle().take(len).collect()
+}
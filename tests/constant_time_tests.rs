@@ -78,3 +78,42 @@ fn test_verify_slices_are_equal() {
         }
     }
 }
+
+#[test]
+fn test_table_lookup() {
+    const N: usize = 48;
+    const ENTRIES: usize = 16;
+
+    use rand::SecureRandom;
+
+    let rng = rand::SystemRandom::new();
+    let mut table = [[0u8; N]; ENTRIES];
+    table.iter_mut().for_each(|entry| rng.fill(entry).unwrap());
+
+    for (index, expected) in (0..=u8::MAX).zip(table.iter()) {
+        let mut out = [0xa5; N];
+        constant_time::table_lookup(&table, index, &mut out);
+        assert_eq!(&out, expected);
+    }
+
+    // Out-of-range indices select nothing.
+    for index in [16, 17, 0x80, 0xff] {
+        let mut out = [0xa5; N];
+        constant_time::table_lookup(&table, index, &mut out);
+        assert_eq!(out, [0; N]);
+    }
+
+    // Only the first 256 entries are reachable.
+    let large: Vec<[u8; 1]> = (0..300)
+        .map(|i: u16| i.to_be_bytes()[1..].try_into().unwrap())
+        .collect();
+    for index in 0..=u8::MAX {
+        let mut out = [0; 1];
+        constant_time::table_lookup(&large, index, &mut out);
+        assert_eq!(out, [index]);
+    }
+
+    let mut out = [0xa5; N];
+    constant_time::table_lookup(&[], 0, &mut out);
+    assert_eq!(out, [0; N]);
+}
@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use ring::{digest, test, test_file};
+use ring::{digest, hmac, test, test_file};
 
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
@@ -183,6 +183,128 @@ test_large_digest!(
 // TODO: test_large_digest!(digest_test_large_digest_sha512_256,
 //                            digest::SHA512_256, 256 / 8, [ ... ]);
 
+#[test]
+fn digest_is_length_extendable() {
+    assert!(digest::is_length_extendable(
+        &digest::SHA1_FOR_LEGACY_USE_ONLY
+    ));
+    assert!(digest::is_length_extendable(&digest::SHA256));
+    assert!(digest::is_length_extendable(&digest::SHA512));
+    assert!(!digest::is_length_extendable(&digest::SHA384));
+    assert!(!digest::is_length_extendable(&digest::SHA512_256));
+}
+
+#[test]
+fn digest_keyed_prefix_mac() {
+    const KEY: &[u8] = b"key";
+    const MSG: &[u8] = b"hello, world";
+    let tag = digest::keyed_prefix_mac(hmac::HMAC_SHA256, KEY, MSG);
+    let key = hmac::Key::new(hmac::HMAC_SHA256, KEY);
+    assert_eq!(tag.as_ref(), hmac::sign(&key, MSG).as_ref());
+    assert!(hmac::verify(&key, MSG, tag.as_ref()).is_ok());
+
+    // It isn't the insecure `SHA256(key || msg)`.
+    let mut ctx = digest::Context::new(&digest::SHA256);
+    ctx.update(KEY);
+    ctx.update(MSG);
+    assert_ne!(tag.as_ref(), ctx.finish().as_ref());
+}
+
+#[test]
+fn digest_fixed() {
+    // FIPS 180-2 Appendices B and C and the digests of the empty string.
+    const TESTS: &[(&[u8], &str, &str, &str)] = &[
+        (
+            b"abc",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+             8086072ba1e7cc2358baeca134c825a7",
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        ),
+        (
+            b"",
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da\
+             274edebfe76f65fbd51ad2f14898b95b",
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+             47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        ),
+    ];
+    for &(data, sha256, sha384, sha512) in TESTS {
+        let actual = digest::sha256(data);
+        assert_eq!(&actual[..], &test::from_hex(sha256).unwrap()[..]);
+        assert_eq!(&actual[..], digest::digest(&digest::SHA256, data).as_ref());
+
+        let actual = digest::sha384(data);
+        assert_eq!(&actual[..], &test::from_hex(sha384).unwrap()[..]);
+        assert_eq!(&actual[..], digest::digest(&digest::SHA384, data).as_ref());
+
+        let actual = digest::sha512(data);
+        assert_eq!(&actual[..], &test::from_hex(sha512).unwrap()[..]);
+        assert_eq!(&actual[..], digest::digest(&digest::SHA512, data).as_ref());
+    }
+}
+
+#[test]
+fn digest_into() {
+    const DATA: &[u8] = b"hello, world";
+    for algorithm in [
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        &digest::SHA256,
+        &digest::SHA384,
+        &digest::SHA512,
+        &digest::SHA512_256,
+    ] {
+        let expected = digest::digest(algorithm, DATA);
+        let len = algorithm.output_len();
+
+        // Exactly-sized.
+        let mut out = vec![0u8; len];
+        assert_eq!(digest::digest_into(algorithm, DATA, &mut out), Ok(len));
+        assert_eq!(&out[..], expected.as_ref());
+
+        // Over-sized; the excess is left alone.
+        let mut out = [0xffu8; digest::MAX_OUTPUT_LEN + 1];
+        assert_eq!(digest::digest_into(algorithm, DATA, &mut out), Ok(len));
+        assert_eq!(&out[..len], expected.as_ref());
+        assert!(out[len..].iter().all(|&b| b == 0xff));
+
+        // Undersized.
+        let mut out = vec![0u8; len - 1];
+        assert!(digest::digest_into(algorithm, DATA, &mut out).is_err());
+        assert!(digest::digest_into(algorithm, DATA, &mut []).is_err());
+    }
+}
+
+#[test]
+fn digest_verify_eq() {
+    for algorithm in [
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        &digest::SHA256,
+        &digest::SHA384,
+        &digest::SHA512,
+        &digest::SHA512_256,
+    ] {
+        let d = digest::digest(algorithm, b"hello, world");
+        let expected = d.as_ref().to_vec();
+        assert!(d.verify_eq(&expected).is_ok());
+
+        // Any difference in the value.
+        let mut wrong = expected.clone();
+        *wrong.last_mut().unwrap() ^= 1;
+        assert!(d.verify_eq(&wrong).is_err());
+        assert!(digest::digest(algorithm, b"hello, world!")
+            .verify_eq(&expected)
+            .is_err());
+
+        // Wrong lengths.
+        assert!(d.verify_eq(&expected[..expected.len() - 1]).is_err());
+        assert!(d.verify_eq(&[expected.as_slice(), &[0]].concat()).is_err());
+        assert!(d.verify_eq(&[]).is_err());
+    }
+}
+
 #[test]
 fn test_fmt_algorithm() {
     assert_eq!("SHA1", &format!("{:?}", digest::SHA1_FOR_LEGACY_USE_ONLY));
@@ -225,3 +347,156 @@ fn digest_test_fmt() {
         &format!("{:?}", digest::digest(&digest::SHA512_256, b"hello, world"))
     );
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn digest_reader_writer_test() {
+    use std::io::{Read, Write};
+
+    // Longer than a block, and not a multiple of the block length.
+    let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+
+    for &alg in &[
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        &digest::SHA256,
+        &digest::SHA384,
+        &digest::SHA512,
+        &digest::SHA512_256,
+    ] {
+        let expected = digest::digest(alg, &data);
+
+        // Read in small, uneven chunks.
+        let mut reader = digest::DigestingReader::new(alg, &data[..]);
+        let mut copy = Vec::new();
+        let mut buf = [0u8; 7];
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            copy.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(copy, data);
+        assert_eq!(reader.into_digest().as_ref(), expected.as_ref());
+
+        let mut writer = digest::DigestingWriter::new(alg, Vec::new());
+        for chunk in data.chunks(13) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.flush().unwrap();
+        let (copy, actual) = writer.into_parts();
+        assert_eq!(copy, data);
+        assert_eq!(actual.as_ref(), expected.as_ref());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn digest_parallel_hash_test() {
+    use core::num::NonZeroUsize;
+    use digest::parallel;
+
+    // The construction documented in `digest::parallel`, computed serially.
+    fn tree_hash(alg: &'static digest::Algorithm, data: &[u8], leaf_size: usize) -> Vec<u8> {
+        let mut root = digest::Context::new(alg);
+        root.update(&[0x01]);
+        root.update(&(leaf_size as u64).to_be_bytes());
+        root.update(&(data.len() as u64).to_be_bytes());
+        let mut leaves: Vec<&[u8]> = data.chunks(leaf_size).collect();
+        if leaves.is_empty() {
+            leaves.push(&[]);
+        }
+        for leaf in leaves {
+            let mut ctx = digest::Context::new(alg);
+            ctx.update(&[0x00]);
+            ctx.update(leaf);
+            root.update(ctx.finish().as_ref());
+        }
+        root.finish().as_ref().to_vec()
+    }
+
+    let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+
+    for &alg in &[&digest::SHA256, &digest::SHA384, &digest::SHA512] {
+        for len in [0, 1, 999, 1000, 1001, data.len()] {
+            let data = &data[..len];
+            for leaf_size in [1, 64, 1000, 4096, 1 << 20] {
+                let expected = tree_hash(alg, data, leaf_size);
+                for threads in 1..=9 {
+                    let threads = NonZeroUsize::new(threads).unwrap();
+                    let actual =
+                        parallel::hash_with_threads(alg, data, leaf_size, threads).unwrap();
+                    assert_eq!(actual.as_ref(), &expected[..]);
+                }
+                let actual = parallel::hash(alg, data, leaf_size).unwrap();
+                assert_eq!(actual.as_ref(), &expected[..]);
+            }
+        }
+    }
+
+    // The tree hash isn't the digest, and it depends on the leaf size.
+    let a = parallel::hash(&digest::SHA256, &data, 1000).unwrap();
+    let b = parallel::hash(&digest::SHA256, &data, 1024).unwrap();
+    assert_ne!(a.as_ref(), b.as_ref());
+    assert_ne!(a.as_ref(), digest::digest(&digest::SHA256, &data).as_ref());
+
+    assert!(parallel::hash(&digest::SHA256, &data, 0).is_err());
+}
+
+#[cfg(all(feature = "std", unix))]
+#[test]
+fn digest_update_mmap_test() {
+    use std::os::unix::io::AsRawFd;
+
+    // Large enough to span many chunks, and not a multiple of the page size.
+    let data: Vec<u8> = (0..((4 << 20) + 123)).map(|i| (i * 7) as u8).collect();
+
+    let path = std::env::temp_dir().join(format!(
+        "ring_digest_update_mmap_test_{}",
+        std::process::id()
+    ));
+    std::fs::write(&path, &data).unwrap();
+    let file = std::fs::File::open(&path);
+    std::fs::remove_file(&path).unwrap();
+    let file = file.unwrap();
+
+    // SAFETY: The file isn't modified while it is mapped, and the mapping is
+    // only read through `contents`, which doesn't outlive it.
+    let mapping = unsafe {
+        libc::mmap(
+            core::ptr::null_mut(),
+            data.len(),
+            libc::PROT_READ,
+            libc::MAP_PRIVATE,
+            file.as_raw_fd(),
+            0,
+        )
+    };
+    assert_ne!(mapping, libc::MAP_FAILED);
+    let contents = unsafe { core::slice::from_raw_parts(mapping.cast::<u8>(), data.len()) };
+    assert_eq!(contents, &data[..]);
+
+    for &alg in &[&digest::SHA256, &digest::SHA512] {
+        let expected = digest::digest(alg, &data);
+        // Unaligned starts exercise the short first chunk.
+        for start in [0, 1, 4095] {
+            let expected = if start == 0 {
+                expected
+            } else {
+                digest::digest(alg, &data[start..])
+            };
+            for chunk_size in [1, 4096, 10_000, 1 << 20, usize::MAX / 2] {
+                let mut ctx = digest::Context::new(alg);
+                ctx.update_mmap(&contents[start..], chunk_size).unwrap();
+                assert_eq!(ctx.finish().as_ref(), expected.as_ref());
+            }
+        }
+    }
+
+    let mut ctx = digest::Context::new(&digest::SHA256);
+    assert!(ctx.update_mmap(contents, 0).is_err());
+    assert!(ctx.update_mmap(contents, usize::MAX).is_err());
+
+    // SAFETY: `contents` isn't used after this.
+    assert_eq!(unsafe { libc::munmap(mapping, data.len()) }, 0);
+}
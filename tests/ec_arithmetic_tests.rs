@@ -0,0 +1,215 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+use ring::{
+    ec_arithmetic::{self, Curve, Point, Scalar, P256, P384},
+    test, test_file,
+};
+
+static CURVES: [&Curve; 2] = [&P256, &P384];
+
+fn curve_from_name(name: &str) -> &'static Curve {
+    match name {
+        "P-256" => &P256,
+        "P-384" => &P384,
+        _ => panic!("Unsupported curve: {}", name),
+    }
+}
+
+fn scalar(curve: &'static Curve, value: u8) -> Scalar {
+    let mut bytes = vec![0u8; curve.scalar_len()];
+    *bytes.last_mut().unwrap() = value;
+    Scalar::from_big_endian_bytes(curve, &bytes).unwrap()
+}
+
+fn n_minus_one(curve: &'static Curve) -> Vec<u8> {
+    let hex = if curve == &P256 {
+        "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632550"
+    } else {
+        "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52972"
+    };
+    test::from_hex(hex).unwrap()
+}
+
+fn encoded(p: &Point) -> Vec<u8> {
+    let mut out = vec![0u8; p.curve().uncompressed_point_len()];
+    p.write_uncompressed(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn ec_arithmetic_point_mul_test() {
+    test::run(
+        test_file!("ec_arithmetic_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let curve = curve_from_name(&test_case.consume_string("Curve"));
+            let k = test_case.consume_bytes("Scalar");
+            let point = test_case.consume_optional_bytes("Point");
+            let expected = test_case.consume_bytes("Result");
+
+            let k = Scalar::from_big_endian_bytes(curve, &k).unwrap();
+            let actual = match point {
+                Some(point) => Point::from_uncompressed(curve, &point)?.mul(&k)?,
+                None => Point::base_mul(&k)?,
+            };
+            assert_eq!(encoded(&actual), expected);
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn ec_arithmetic_base_mul_test() {
+    for &curve in CURVES.iter() {
+        let one = scalar(curve, 1);
+        let two = scalar(curve, 2);
+        let three = scalar(curve, 3);
+        let g = Point::base_mul(&one).unwrap();
+
+        // G + G == 2G, using the point-doubling case of addition.
+        assert_eq!(
+            encoded(&g.add(&g).unwrap()),
+            encoded(&Point::base_mul(&two).unwrap())
+        );
+
+        // (1 + 2)G == G + 2G and (2 * 3)G == 3(2G).
+        let two_g = g.mul(&two).unwrap();
+        assert_eq!(
+            encoded(&Point::base_mul(&one.add(&two).unwrap()).unwrap()),
+            encoded(&g.add(&two_g).unwrap())
+        );
+        assert_eq!(
+            encoded(&Point::base_mul(&two.mul(&three).unwrap()).unwrap()),
+            encoded(&two_g.mul(&three).unwrap())
+        );
+
+        // Round trip through the encoding.
+        let parsed = Point::from_uncompressed(curve, &encoded(&two_g)).unwrap();
+        assert_eq!(encoded(&parsed), encoded(&two_g));
+    }
+}
+
+#[test]
+fn ec_arithmetic_infinity_test() {
+    for &curve in CURVES.iter() {
+        let zero = scalar(curve, 0);
+        let one = scalar(curve, 1);
+        let g = Point::base_mul(&one).unwrap();
+
+        // 0 * P is the point at infinity.
+        assert!(Point::base_mul(&zero).is_err());
+        assert!(g.mul(&zero).is_err());
+
+        // G + (n - 1)G is the point at infinity, and 1 + (n - 1) == 0 (mod n).
+        let minus_one = Scalar::from_big_endian_bytes(curve, &n_minus_one(curve)).unwrap();
+        let mut sum = vec![0xff; curve.scalar_len()];
+        minus_one
+            .add(&one)
+            .unwrap()
+            .write_big_endian_bytes(&mut sum)
+            .unwrap();
+        assert!(sum.iter().all(|&b| b == 0));
+        let minus_g = Point::base_mul(&minus_one).unwrap();
+        assert!(g.add(&minus_g).is_err());
+    }
+}
+
+#[test]
+fn ec_arithmetic_validation_test() {
+    for &curve in CURVES.iter() {
+        let len = curve.scalar_len();
+
+        // Scalars must be exactly `scalar_len()` bytes and less than n.
+        assert!(Scalar::from_big_endian_bytes(curve, &vec![0; len - 1]).is_err());
+        assert!(Scalar::from_big_endian_bytes(curve, &vec![0; len + 1]).is_err());
+        assert!(Scalar::from_big_endian_bytes(curve, &vec![0xff; len]).is_err());
+        let mut n = n_minus_one(curve);
+        *n.last_mut().unwrap() += 1;
+        assert!(Scalar::from_big_endian_bytes(curve, &n).is_err());
+
+        // Points must be on the curve.
+        let mut g = encoded(&Point::base_mul(&scalar(curve, 1)).unwrap());
+        assert!(Point::from_uncompressed(curve, &g).is_ok());
+        *g.last_mut().unwrap() ^= 1;
+        assert!(Point::from_uncompressed(curve, &g).is_err());
+
+        // Output buffers must be exactly the right length.
+        let s = scalar(curve, 1);
+        assert!(s.write_big_endian_bytes(&mut vec![0; len + 1]).is_err());
+        let p = Point::base_mul(&s).unwrap();
+        assert!(p.write_uncompressed(&mut vec![0; 2 * len]).is_err());
+    }
+
+    // Mixing curves fails.
+    let p256_one = scalar(&P256, 1);
+    let p384_one = scalar(&P384, 1);
+    assert!(p256_one.add(&p384_one).is_err());
+    assert!(p256_one.mul(&p384_one).is_err());
+    let p256_g = Point::base_mul(&p256_one).unwrap();
+    let p384_g = Point::base_mul(&p384_one).unwrap();
+    assert!(p256_g.mul(&p384_one).is_err());
+    assert!(p256_g.add(&p384_g).is_err());
+}
+
+#[test]
+fn ec_arithmetic_validate_private_scalar_test() {
+    for &curve in CURVES.iter() {
+        let len = curve.scalar_len();
+        let one = {
+            let mut one = vec![0; len];
+            *one.last_mut().unwrap() = 1;
+            one
+        };
+        let n_minus_one = n_minus_one(curve);
+        let n = {
+            let mut n = n_minus_one.clone();
+            *n.last_mut().unwrap() += 1;
+            n
+        };
+        let n_plus_one = {
+            let mut n_plus_one = n.clone();
+            *n_plus_one.last_mut().unwrap() += 1;
+            n_plus_one
+        };
+
+        assert!(ec_arithmetic::validate_private_scalar(curve, &one).is_ok());
+        assert!(ec_arithmetic::validate_private_scalar(curve, &n_minus_one).is_ok());
+
+        assert!(ec_arithmetic::validate_private_scalar(curve, &vec![0; len]).is_err());
+        assert!(ec_arithmetic::validate_private_scalar(curve, &n).is_err());
+        assert!(ec_arithmetic::validate_private_scalar(curve, &n_plus_one).is_err());
+        assert!(ec_arithmetic::validate_private_scalar(curve, &vec![0xff; len]).is_err());
+
+        // The encoding must be exactly `scalar_len()` bytes.
+        assert!(ec_arithmetic::validate_private_scalar(curve, &one[1..]).is_err());
+        let mut padded = vec![0];
+        padded.extend_from_slice(&one);
+        assert!(ec_arithmetic::validate_private_scalar(curve, &padded).is_err());
+    }
+}
+
+#[test]
+fn ec_arithmetic_debug_test() {
+    let s = scalar(&P384, 1);
+    assert_eq!(format!("{:?}", s), "Scalar { curve: P384, .. }");
+    let p = Point::base_mul(&s).unwrap();
+    assert_eq!(format!("{:?}", p), "Point { curve: P384, .. }");
+}
@@ -13,7 +13,7 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use ring::{
-    rand,
+    pkcs8, rand,
     signature::{self, KeyPair},
     test, test_file,
 };
@@ -110,6 +110,64 @@ fn ecdsa_generate_pkcs8_test() {
     }
 }
 
+#[test]
+fn ecdsa_pkcs8_from_private_scalar_test() {
+    const P256_N_MINUS_1: &str = "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632550";
+    const P256_N: &str = "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551";
+    // The uncompressed encoding of the P-256 generator.
+    const P256_G: &str = "04\
+        6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296\
+        4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5";
+
+    let rng = rand::SystemRandom::new();
+
+    for alg in &[
+        &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+    ] {
+        // The public key for the private key 1 is the generator.
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let pkcs8 = signature::ecdsa_pkcs8_from_private_scalar(alg, &one).unwrap();
+        let key_pair = signature::EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng).unwrap();
+        assert_eq!(
+            key_pair.public_key().as_ref(),
+            &test::from_hex(P256_G).unwrap()[..]
+        );
+
+        // The largest valid private key.
+        let n_minus_1 = test::from_hex(P256_N_MINUS_1).unwrap();
+        let pkcs8 = signature::ecdsa_pkcs8_from_private_scalar(alg, &n_minus_1).unwrap();
+        let _ = signature::EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng).unwrap();
+
+        // Out of range or the wrong length.
+        let n = test::from_hex(P256_N).unwrap();
+        assert!(signature::ecdsa_pkcs8_from_private_scalar(alg, &n).is_err());
+        assert!(signature::ecdsa_pkcs8_from_private_scalar(alg, &[0u8; 32]).is_err());
+        assert!(signature::ecdsa_pkcs8_from_private_scalar(alg, &one[1..]).is_err());
+        assert!(signature::ecdsa_pkcs8_from_private_scalar(alg, &[0u8; 48]).is_err());
+    }
+
+    // The public key in the document matches the private key, for arbitrary
+    // private keys on both curves.
+    for (alg, len) in [
+        (&signature::ECDSA_P256_SHA256_ASN1_SIGNING, 32),
+        (&signature::ECDSA_P384_SHA384_ASN1_SIGNING, 48),
+    ] {
+        let private_key = vec![0x42; len];
+        let pkcs8 = signature::ecdsa_pkcs8_from_private_scalar(alg, &private_key).unwrap();
+        let from_pkcs8 = signature::EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng).unwrap();
+        let public_key = from_pkcs8.public_key().as_ref();
+        assert!(signature::EcdsaKeyPair::from_private_key_and_public_key(
+            alg,
+            &private_key,
+            public_key,
+            &rng
+        )
+        .is_ok());
+    }
+}
+
 #[test]
 fn signature_ecdsa_verify_asn1_test() {
     test::run(
@@ -172,11 +230,76 @@ fn signature_ecdsa_verify_fixed_test() {
                 signature::UnparsedPublicKey::new(alg, &public_key).verify(&msg, &sig);
             assert_eq!(actual_result.is_ok(), is_valid);
 
+            // The same signature given as separate (JWS-style) components,
+            // when it is well-formed enough to be split.
+            let scalar_len = if curve_name == "P-256" { 32 } else { 48 };
+            if sig.len() == 2 * scalar_len {
+                let (r, s) = sig.split_at(scalar_len);
+                let actual_result =
+                    signature::ecdsa_verify_components(alg, &public_key, &msg, r, s);
+                assert_eq!(actual_result.is_ok(), is_valid);
+            }
+
             Ok(())
         },
     );
 }
 
+#[test]
+fn signature_ecdsa_verify_components_range_test() {
+    // P-256 and P-384 test vectors from `ecdsa_verify_fixed_tests.txt`.
+    struct TestCase {
+        alg: &'static signature::EcdsaVerificationAlgorithm,
+        public_key: &'static str,
+        sig: &'static str,
+        n: &'static str,
+    }
+    let test_cases = [
+        TestCase {
+            alg: &signature::ECDSA_P256_SHA256_FIXED,
+            public_key: "0430345fd47ea21a11129be651b0884bfac698377611acc9f689458e13b9ed7d4b9d7599a68dcf125e7f31055ccb374cd04f6d6fd2b217438a63f6f667d50ef2f0",
+            sig: "341f6779b75e98bb42e01095dd48356cbf9002dc704ac8bd2a8240b88d3796c6555843b1b4e264fe6ffe6e2b705a376c05c09404303ffe5d2711f3e3b3a010a1",
+            n: "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+        },
+        TestCase {
+            alg: &signature::ECDSA_P384_SHA384_FIXED,
+            public_key: "045c5e788a805c77d34128b8401cb59b2373b8b468336c9318252bf39fd31d2507557987a5180a9435f9fb8eb971c426f1c485170dcb18fb688a257f89387a09fc4c5b8bd4b320616b54a0a7b1d1d7c6a0c59f6dff78c78ad4e3d6fca9c9a17b96",
+            sig: "85ac708d4b0126bac1f5eeebdf911409070a286fdde5649582611b60046de353761660dd03903f58b44148f25142eef8183475ec1f1392f3d6838abc0c01724709c446888bed7f2ce4642c6839dc18044a2a6ab9ddc960bfac79f6988e62d452",
+            n: "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973",
+        },
+    ];
+
+    for test_case in test_cases.iter() {
+        let alg = test_case.alg;
+        let public_key = test::from_hex(test_case.public_key).unwrap();
+        let sig = test::from_hex(test_case.sig).unwrap();
+        let n = test::from_hex(test_case.n).unwrap();
+        let (r, s) = sig.split_at(sig.len() / 2);
+        let verify =
+            |r: &[u8], s: &[u8]| signature::ecdsa_verify_components(alg, &public_key, b"", r, s);
+
+        assert!(verify(r, s).is_ok());
+
+        // Leading zeros are allowed, but not beyond the scalar length.
+        let mut padded_s = s.to_vec();
+        padded_s.insert(0, 0);
+        assert!(verify(r, &padded_s).is_err());
+        let stripped_r: Vec<u8> = r.iter().copied().skip_while(|&b| b == 0).collect();
+        assert!(verify(&stripped_r, s).is_ok());
+
+        // s == 0, s == n, and s > n are rejected; likewise for r.
+        let zero = vec![0u8; n.len()];
+        let max = vec![0xffu8; n.len()];
+        let mut n_plus_one = n.clone();
+        *n_plus_one.last_mut().unwrap() += 1;
+        for bad in [&zero[..], &n[..], &n_plus_one[..], &max[..]].iter() {
+            assert!(verify(r, bad).is_err());
+            assert!(verify(bad, s).is_err());
+        }
+        assert!(verify(r, &[]).is_err());
+    }
+}
+
 #[test]
 fn ecdsa_test_public_key_coverage() {
     const PRIVATE_KEY: &[u8] = include_bytes!("ecdsa_test_private_key_p256.p8");
@@ -209,6 +332,143 @@ fn ecdsa_test_public_key_coverage() {
     );
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn ecdsa_public_key_as_compressed_test() {
+    // Computed with `openssl ec -conv_form compressed -pubout`.
+    const COMPRESSED: &str = "03fc116698a3e3236550c4c9efa9bd4d0619602a65d2930e9150ab33e84dbc83f8";
+
+    let rng = rand::SystemRandom::new();
+    let key_pair = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        include_bytes!("ecdsa_test_private_key_p256.p8"),
+        &rng,
+    )
+    .unwrap();
+    let uncompressed = key_pair.public_key().as_ref();
+    assert_eq!(
+        uncompressed,
+        include_bytes!("ecdsa_test_public_key_p256.der")
+    );
+    assert_eq!(
+        key_pair.public_key().as_compressed(),
+        test::from_hex(COMPRESSED).unwrap()
+    );
+
+    // Both forms encode the same x coordinate, and the prefix of the
+    // compressed form encodes the parity of y. Try enough keys to see both
+    // parities on both curves.
+    for (alg, len) in [
+        (&signature::ECDSA_P256_SHA256_ASN1_SIGNING, 32),
+        (&signature::ECDSA_P384_SHA384_ASN1_SIGNING, 48),
+    ] {
+        let mut seen = [false; 2];
+        for i in 1..=32u8 {
+            let pkcs8 = signature::ecdsa_pkcs8_from_private_scalar(alg, &vec![i; len]).unwrap();
+            let key_pair = signature::EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng).unwrap();
+            let uncompressed = key_pair.public_key().as_ref();
+            let compressed = key_pair.public_key().as_compressed();
+            assert_eq!(uncompressed.len(), 1 + 2 * len);
+            assert_eq!(compressed.len(), 1 + len);
+            assert_eq!(uncompressed[0], 0x04);
+            assert_eq!(&compressed[1..], &uncompressed[1..][..len]);
+            let y_is_odd = uncompressed.last().unwrap() & 1;
+            assert_eq!(compressed[0], 0x02 + y_is_odd);
+            seen[usize::from(y_is_odd)] = true;
+        }
+        assert_eq!(seen, [true, true]);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn ecdsa_from_encrypted_pkcs8_test() {
+    // `ecdsa_test_private_key_p256.p8` encrypted with PBES2 using PBKDF2 with
+    // HMAC-SHA256 and AES-256-GCM.
+    const ENCRYPTED: &[u8] = include_bytes!("ecdsa_test_private_key_p256_encrypted.p8");
+    const PUBLIC_KEY: &[u8] = include_bytes!("ecdsa_test_public_key_p256.der");
+    const PASSPHRASE: &[u8] = b"correct horse battery staple";
+
+    let rng = rand::SystemRandom::new();
+    let alg = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+
+    let key_pair =
+        signature::EcdsaKeyPair::from_encrypted_pkcs8(alg, ENCRYPTED, PASSPHRASE, &rng).unwrap();
+    assert_eq!(key_pair.public_key().as_ref(), PUBLIC_KEY);
+
+    assert_eq!(
+        signature::EcdsaKeyPair::from_encrypted_pkcs8(alg, ENCRYPTED, b"wrong", &rng)
+            .unwrap_err()
+            .to_string(),
+        "DecryptionFailed"
+    );
+
+    // The tag is verified.
+    let mut corrupted = ENCRYPTED.to_vec();
+    *corrupted.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        signature::EcdsaKeyPair::from_encrypted_pkcs8(alg, &corrupted, PASSPHRASE, &rng)
+            .unwrap_err()
+            .to_string(),
+        "DecryptionFailed"
+    );
+
+    // The key is still checked against the algorithm once decrypted.
+    assert_eq!(
+        signature::EcdsaKeyPair::from_encrypted_pkcs8(
+            &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+            ENCRYPTED,
+            PASSPHRASE,
+            &rng
+        )
+        .unwrap_err()
+        .to_string(),
+        "WrongAlgorithm"
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn ecdsa_encrypted_pkcs8_round_trip_test() {
+    const PASSPHRASE: &[u8] = b"correct horse battery staple";
+    const MSG: &[u8] = b"hello, world";
+
+    let rng = rand::SystemRandom::new();
+    let iterations = core::num::NonZeroU32::new(1000).unwrap();
+
+    for &(alg, verify_alg) in &[
+        (
+            &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+            &signature::ECDSA_P256_SHA256_ASN1,
+        ),
+        (
+            &signature::ECDSA_P384_SHA384_ASN1_SIGNING,
+            &signature::ECDSA_P384_SHA384_ASN1,
+        ),
+    ] {
+        let document = signature::EcdsaKeyPair::generate_pkcs8(alg, &rng).unwrap();
+        let encrypted = pkcs8::encrypt(document.as_ref(), PASSPHRASE, iterations, &rng).unwrap();
+
+        let expected = signature::EcdsaKeyPair::from_pkcs8(alg, document.as_ref(), &rng).unwrap();
+        let key_pair = signature::EcdsaKeyPair::from_encrypted_pkcs8(
+            alg,
+            encrypted.as_ref(),
+            PASSPHRASE,
+            &rng,
+        )
+        .unwrap();
+        assert_eq!(
+            key_pair.public_key().as_ref(),
+            expected.public_key().as_ref()
+        );
+
+        let sig = key_pair.sign(&rng, MSG).unwrap();
+        signature::UnparsedPublicKey::new(verify_alg, expected.public_key())
+            .verify(MSG, sig.as_ref())
+            .unwrap();
+    }
+}
+
 // This test is not a known-answer test, though it re-uses the known-answer
 // test vectors. Because the nonce is randomized, the signature will be
 // different each time. Because of that, here we simply verify that the
@@ -316,3 +576,174 @@ fn signature_ecdsa_sign_asn1_test() {
         },
     );
 }
+
+#[test]
+fn ecdsa_public_key_matches_test() {
+    fn compress(uncompressed: &[u8]) -> Vec<u8> {
+        let elem_len = (uncompressed.len() - 1) / 2;
+        let y_is_odd = uncompressed[uncompressed.len() - 1] & 1;
+        let mut r = vec![2 | y_is_odd];
+        r.extend_from_slice(&uncompressed[1..][..elem_len]);
+        r
+    }
+
+    let mut previous_q: Option<(String, Vec<u8>)> = None;
+
+    test::run(
+        test_file!("../src/ec/suite_b/ecdsa/ecdsa_sign_fixed_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let curve_name = test_case.consume_string("Curve");
+            let _digest_name = test_case.consume_string("Digest");
+            let _msg = test_case.consume_bytes("Msg");
+            let _d = test_case.consume_bytes("d");
+            let q = test_case.consume_bytes("Q");
+            let _k = test_case.consume_bytes("k");
+            let _sig = test_case.consume_bytes("Sig");
+
+            let (alg, other_alg) = match curve_name.as_str() {
+                "P-256" => (
+                    &signature::ECDSA_P256_SHA256_FIXED,
+                    &signature::ECDSA_P256_SHA256_ASN1,
+                ),
+                "P-384" => (
+                    &signature::ECDSA_P384_SHA384_FIXED,
+                    &signature::ECDSA_P384_SHA384_ASN1,
+                ),
+                _ => {
+                    panic!("Unsupported curve: {}", curve_name);
+                }
+            };
+
+            let uncompressed = signature::UnparsedPublicKey::new(alg, &q);
+            let compressed_q = compress(&q);
+            let compressed = signature::UnparsedPublicKey::new(alg, &compressed_q);
+
+            // The same point matches itself in either encoding.
+            assert!(uncompressed.matches(&uncompressed));
+            assert!(uncompressed.matches(&compressed));
+            assert!(compressed.matches(&uncompressed));
+            assert!(compressed.matches(&compressed));
+
+            // (x, -y) is a different point with the same x coordinate.
+            let mut negated_q = compressed_q.clone();
+            negated_q[0] ^= 1;
+            let negated = signature::UnparsedPublicKey::new(alg, &negated_q);
+            assert!(!uncompressed.matches(&negated));
+            assert!(!negated.matches(&compressed));
+
+            // Keys for different algorithms never match.
+            let other = signature::UnparsedPublicKey::new(other_alg, &q);
+            assert!(!uncompressed.matches(&other));
+
+            // Invalid encodings never match, not even themselves.
+            let mut invalid_q = q.clone();
+            invalid_q[0] = 5;
+            let invalid = signature::UnparsedPublicKey::new(alg, &invalid_q);
+            assert!(!invalid.matches(&invalid));
+            let truncated = signature::UnparsedPublicKey::new(alg, &q[..q.len() - 1]);
+            assert!(!truncated.matches(&uncompressed));
+
+            if let Some((previous_curve_name, previous_q)) = &previous_q {
+                if *previous_curve_name == curve_name && *previous_q != q {
+                    let previous = signature::UnparsedPublicKey::new(alg, previous_q);
+                    let previous_compressed_q = compress(previous_q);
+                    let previous_compressed =
+                        signature::UnparsedPublicKey::new(alg, &previous_compressed_q);
+                    assert!(!uncompressed.matches(&previous));
+                    assert!(!compressed.matches(&previous_compressed));
+                    assert!(!uncompressed.matches(&previous_compressed));
+                }
+            }
+            previous_q = Some((curve_name, q));
+
+            Ok(())
+        },
+    );
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn ecdsa_from_test_label_test() {
+    let rng = rand::SystemRandom::new();
+    for &(signing_alg, verification_alg) in &[
+        (
+            &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+            &signature::ECDSA_P256_SHA256_FIXED,
+        ),
+        (
+            &signature::ECDSA_P384_SHA384_ASN1_SIGNING,
+            &signature::ECDSA_P384_SHA384_ASN1,
+        ),
+    ] {
+        let a = signature::EcdsaKeyPair::from_test_label(signing_alg, "alice");
+        assert_eq!(
+            a.public_key().as_ref(),
+            signature::EcdsaKeyPair::from_test_label(signing_alg, "alice")
+                .public_key()
+                .as_ref()
+        );
+        assert_ne!(
+            a.public_key().as_ref(),
+            signature::EcdsaKeyPair::from_test_label(signing_alg, "bob")
+                .public_key()
+                .as_ref()
+        );
+
+        let sig = a.sign(&rng, b"hello").unwrap();
+        assert!(
+            signature::UnparsedPublicKey::new(verification_alg, a.public_key())
+                .verify(b"hello", sig.as_ref())
+                .is_ok()
+        );
+    }
+
+    // The key depends on the curve, not the encoding of the signatures.
+    assert_eq!(
+        signature::EcdsaKeyPair::from_test_label(&signature::ECDSA_P256_SHA256_FIXED_SIGNING, "a")
+            .public_key()
+            .as_ref(),
+        signature::EcdsaKeyPair::from_test_label(&signature::ECDSA_P256_SHA256_ASN1_SIGNING, "a")
+            .public_key()
+            .as_ref()
+    );
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn ecdsa_sign_with_faulty_rng_test() {
+    use ring::rand::{Fault, FaultyRandom};
+
+    let rng = rand::SystemRandom::new();
+    for &(signing_alg, verification_alg) in &[
+        (
+            &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+            &signature::ECDSA_P256_SHA256_FIXED,
+        ),
+        (
+            &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+            &signature::ECDSA_P384_SHA384_FIXED,
+        ),
+    ] {
+        let failing = FaultyRandom::new(Fault::Fail);
+        assert!(signature::EcdsaKeyPair::generate_pkcs8(signing_alg, &failing).is_err());
+
+        let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(signing_alg, &rng).unwrap();
+        let key_pair =
+            signature::EcdsaKeyPair::from_pkcs8(signing_alg, pkcs8.as_ref(), &rng).unwrap();
+        assert!(key_pair.sign(&failing, b"hello").is_err());
+
+        // The nonce is hedged with the private key and the message, so
+        // signing with an RNG that only returns zeros produces valid
+        // signatures that don't reuse `r` for different messages.
+        let zeros = FaultyRandom::new(Fault::Constant(0));
+        let public_key = signature::UnparsedPublicKey::new(verification_alg, key_pair.public_key());
+        let a = key_pair.sign(&zeros, b"hello").unwrap();
+        let b = key_pair.sign(&zeros, b"world").unwrap();
+        assert!(public_key.verify(b"hello", a.as_ref()).is_ok());
+        assert!(public_key.verify(b"world", b.as_ref()).is_ok());
+        let r_len = a.as_ref().len() / 2;
+        assert_ne!(&a.as_ref()[..r_len], &b.as_ref()[..r_len]);
+    }
+}
@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use ring::{digest, error, hkdf, test, test_file};
+use ring::{digest, error, hkdf, hmac, test, test_file};
 
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
@@ -55,6 +55,59 @@ fn hkdf_tests() {
     });
 }
 
+#[test]
+fn hkdf_salt_from_hmac_key_tests() {
+    test::run(test_file!("hkdf_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let digest_alg = test_case.consume_digest_alg("Hash");
+        let secret = test_case.consume_bytes("IKM");
+        let salt = test_case.consume_bytes("salt");
+        let info = test_case.consume_bytes("info");
+        let _ = test_case.consume_bytes("PRK");
+        let expected_out = test_case.consume_bytes("OKM");
+
+        let digest_alg = match digest_alg {
+            Some(digest_alg) => digest_alg,
+            None => {
+                return Ok(());
+            } // Unsupported digest algorithm
+        };
+        let (hmac_alg, hkdf_alg) = if digest_alg == &digest::SHA1_FOR_LEGACY_USE_ONLY {
+            (
+                hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+                hkdf::HKDF_SHA1_FOR_LEGACY_USE_ONLY,
+            )
+        } else if digest_alg == &digest::SHA256 {
+            (hmac::HMAC_SHA256, hkdf::HKDF_SHA256)
+        } else if digest_alg == &digest::SHA384 {
+            (hmac::HMAC_SHA384, hkdf::HKDF_SHA384)
+        } else if digest_alg == &digest::SHA512 {
+            (hmac::HMAC_SHA512, hkdf::HKDF_SHA512)
+        } else {
+            return Ok(()); // No HKDF algorithm for this digest algorithm.
+        };
+
+        let from_bytes = hkdf::Salt::new(hkdf_alg, &salt);
+        let from_key = hkdf::Salt::from(hmac::Key::new(hmac_alg, &salt));
+        assert_eq!(from_key.algorithm(), hkdf_alg);
+
+        let My(out_from_bytes) = from_bytes
+            .extract(&secret)
+            .expand(&[&info], My(expected_out.len()))
+            .unwrap()
+            .into();
+        let My(out_from_key) = from_key
+            .extract(&secret)
+            .expand(&[&info], My(expected_out.len()))
+            .unwrap()
+            .into();
+        assert_eq!(out_from_bytes, expected_out);
+        assert_eq!(out_from_key, expected_out);
+
+        Ok(())
+    });
+}
+
 #[test]
 fn hkdf_output_len_tests() {
     for &alg in &[hkdf::HKDF_SHA256, hkdf::HKDF_SHA384, hkdf::HKDF_SHA512] {
@@ -107,6 +160,115 @@ fn hkdf_output_len_tests() {
     }
 }
 
+#[test]
+fn hkdf_extract_concatenated_tests() {
+    for &alg in &[hkdf::HKDF_SHA256, hkdf::HKDF_SHA384, hkdf::HKDF_SHA512] {
+        let classical = [0x11u8; 32];
+        let pq = [0x22u8; 32];
+        let concatenated = [&classical[..], &pq[..]].concat();
+
+        let okm = |prk: &hkdf::Prk| -> Vec<u8> {
+            let My(okm) = prk.expand(&[b"info"], My(32)).unwrap().into();
+            okm
+        };
+
+        for salt in &[&b""[..], &b"salt"[..]] {
+            let salt = hkdf::Salt::new(alg, salt);
+            let expected = okm(&salt.extract(&concatenated));
+
+            assert_eq!(
+                okm(&salt.extract_concatenated(&[&classical, &pq])),
+                expected
+            );
+
+            // How the input is split doesn't matter.
+            assert_eq!(okm(&salt.extract_concatenated(&[&concatenated])), expected);
+            assert_eq!(
+                okm(&salt.extract_concatenated(&[&[], &concatenated[..5], &concatenated[5..]])),
+                expected
+            );
+
+            // The order of the secrets does.
+            assert_ne!(
+                okm(&salt.extract_concatenated(&[&pq, &classical])),
+                expected
+            );
+
+            assert_eq!(
+                okm(&salt.extract_concatenated(&[])),
+                okm(&salt.extract(&[]))
+            );
+        }
+    }
+}
+
+#[test]
+fn hkdf_expand_into_tests() {
+    for &alg in &[hkdf::HKDF_SHA256, hkdf::HKDF_SHA384, hkdf::HKDF_SHA512] {
+        let prk = hkdf::Salt::new(alg, b"salt").extract(b"secret");
+        let info: &[&[u8]] = &[b"in", b"fo"];
+
+        let My(expected) = prk.expand(info, My(76)).unwrap().into();
+
+        let mut enc_key = [0u8; 32];
+        let mut iv = [0u8; 12];
+        let mut mac_key = [0u8; 32];
+        hkdf::expand_into(&prk, info, &mut [&mut enc_key, &mut iv, &mut mac_key]).unwrap();
+        assert_eq!(&enc_key[..], &expected[..32]);
+        assert_eq!(&iv[..], &expected[32..44]);
+        assert_eq!(&mac_key[..], &expected[44..]);
+
+        // Empty slices are allowed anywhere.
+        let mut a = [0u8; 44];
+        let mut b = [0u8; 32];
+        hkdf::expand_into(&prk, info, &mut [&mut [], &mut a, &mut [], &mut b, &mut []]).unwrap();
+        assert_eq!(&a[..], &expected[..44]);
+        assert_eq!(&b[..], &expected[44..]);
+        hkdf::expand_into(&prk, info, &mut []).unwrap();
+
+        // The total length is validated.
+        let max_out_len = 255 * alg.hmac_algorithm().digest_algorithm().output_len();
+        let mut a = vec![0u8; max_out_len - 1];
+        let mut b = [0u8; 1];
+        assert!(hkdf::expand_into(&prk, info, &mut [&mut a, &mut b]).is_ok());
+        let mut b = [0u8; 2];
+        assert_eq!(
+            hkdf::expand_into(&prk, info, &mut [&mut a, &mut b]),
+            Err(error::Unspecified)
+        );
+    }
+}
+
+#[test]
+fn hkdf_derived_key_cache_tests() {
+    for &alg in &[hkdf::HKDF_SHA256, hkdf::HKDF_SHA384, hkdf::HKDF_SHA512] {
+        let prk = hkdf::Salt::new(alg, b"salt").extract(b"secret");
+        let mut cache = hkdf::DerivedKeyCache::new(prk.clone());
+
+        for (info, len) in [(&b"key"[..], 32), (b"iv", 12), (b"hp", 16), (b"key", 16)] {
+            let My(expected) = prk.expand(&[info], My(len)).unwrap().into();
+            let first = cache.get(&[info], len).unwrap().to_vec();
+            assert_eq!(first, expected);
+            assert_eq!(cache.get(&[info], len).unwrap(), &first[..]);
+        }
+
+        // `info` is concatenated, as in `Prk::expand`.
+        let My(expected) = prk.expand(&[b"k", b"ey"], My(32)).unwrap().into();
+        assert_eq!(cache.get(&[b"k", b"ey"], 32).unwrap(), &expected[..]);
+        assert_eq!(cache.get(&[b"key"], 32).unwrap(), &expected[..]);
+
+        // The length is validated.
+        let max_out_len = 255 * alg.hmac_algorithm().digest_algorithm().output_len();
+        assert!(cache.get(&[b"max"], max_out_len).is_ok());
+        assert_eq!(
+            cache.get(&[b"max"], max_out_len + 1),
+            Err(error::Unspecified)
+        );
+        // The length is validated before the output is allocated.
+        assert_eq!(cache.get(&[b"huge"], usize::MAX), Err(error::Unspecified));
+    }
+}
+
 /// Generic newtype wrapper that lets us implement traits for externally-defined
 /// types.
 #[derive(Debug, PartialEq)]
@@ -125,3 +287,15 @@ impl From<hkdf::Okm<'_, My<usize>>> for My<Vec<u8>> {
         Self(r)
     }
 }
+
+#[test]
+fn hkdf_debug() {
+    let salt = hkdf::Salt::new(hkdf::HKDF_SHA256, &[0x5a; 32]);
+    assert_eq!("Salt(Key { algorithm: SHA256 })", format!("{:?}", salt));
+
+    let prk = salt.extract(&[0xa5; 32]);
+    assert_eq!("Prk(Key { algorithm: SHA256 })", format!("{:?}", prk));
+
+    let prk = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, &[0xa5; 32]);
+    assert_eq!("Prk(Key { algorithm: SHA256 })", format!("{:?}", prk));
+}
@@ -0,0 +1,194 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+use ring::{aead, hpke, rand, test, test_file};
+
+#[test]
+fn hpke_test() {
+    test::run(test_file!("hpke_tests.txt"), |section, test_case| {
+        match section {
+            "DeriveKeyPair" => {
+                let kem = consume_kem(test_case);
+                let ikm = test_case.consume_bytes("Ikm");
+                let expected = test_case.consume_bytes("Pk");
+                let private_key = hpke::PrivateKey::derive(kem, &ikm)?;
+                assert_eq!(private_key.public_key().as_ref(), &expected[..]);
+            }
+
+            "Encryption" => {
+                let (mut sender, mut receiver) = consume_setup(test_case);
+                let seq = test_case.consume_usize("Seq");
+                let aad = test_case.consume_bytes("Aad");
+                let pt = test_case.consume_bytes("Pt");
+                let ct = test_case.consume_bytes("Ct");
+
+                // Advance both contexts to `seq`.
+                for _ in 0..seq {
+                    let mut in_out = Vec::new();
+                    sender.seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)?;
+                    let _ = receiver.open_in_place(aead::Aad::empty(), &mut in_out)?;
+                }
+
+                let mut in_out = pt.clone();
+                sender.seal_in_place_append_tag(aead::Aad::from(&aad), &mut in_out)?;
+                assert_eq!(in_out, ct);
+
+                let opened = receiver.open_in_place(aead::Aad::from(&aad), &mut in_out)?;
+                assert_eq!(opened, &pt[..]);
+            }
+
+            "Export" => {
+                let (sender, receiver) = consume_setup(test_case);
+                let exporter_context = test_case.consume_bytes("ExporterContext");
+                let len = test_case.consume_usize("L");
+                let expected = test_case.consume_bytes("Exported");
+
+                let mut exported = vec![0u8; len];
+                sender.export(&exporter_context, &mut exported)?;
+                assert_eq!(exported, expected);
+
+                let mut exported = vec![0u8; len];
+                receiver.export(&exporter_context, &mut exported)?;
+                assert_eq!(exported, expected);
+            }
+
+            _ => unreachable!(),
+        }
+        Ok(())
+    });
+}
+
+fn consume_setup(test_case: &mut test::TestCase) -> (hpke::SenderContext, hpke::ReceiverContext) {
+    let suite = consume_suite(test_case);
+    let info = test_case.consume_bytes("Info");
+    let sk_e = test_case.consume_bytes("SkEm");
+    let pk_r = test_case.consume_bytes("PkRm");
+    let sk_r = test_case.consume_bytes("SkRm");
+    let expected_enc = test_case.consume_bytes("Enc");
+
+    let rng = test::rand::FixedSliceRandom { bytes: &sk_e };
+    let (enc, sender) = hpke::setup_sender(&suite, &pk_r, &info, &rng).unwrap();
+    assert_eq!(enc.as_ref(), &expected_enc[..]);
+
+    let recipient_key = hpke::PrivateKey::from_bytes(suite.kem(), &sk_r).unwrap();
+    assert_eq!(recipient_key.public_key().as_ref(), &pk_r[..]);
+    let receiver = hpke::setup_receiver(&suite, enc.as_ref(), &recipient_key, &info).unwrap();
+
+    (sender, receiver)
+}
+
+fn consume_suite(test_case: &mut test::TestCase) -> hpke::Suite {
+    let kem = consume_kem(test_case);
+    let kdf = match test_case.consume_string("KDF").as_str() {
+        "HKDF-SHA256" => &hpke::HKDF_SHA256,
+        kdf => panic!("Unsupported KDF: {}", kdf),
+    };
+    let aead = match test_case.consume_string("AEAD").as_str() {
+        "AES-128-GCM" => &hpke::AES_128_GCM,
+        "AES-256-GCM" => &hpke::AES_256_GCM,
+        "ChaCha20Poly1305" => &hpke::CHACHA20_POLY1305,
+        aead => panic!("Unsupported AEAD: {}", aead),
+    };
+    hpke::Suite::new(kem, kdf, aead)
+}
+
+fn consume_kem(test_case: &mut test::TestCase) -> &'static hpke::Kem {
+    match test_case.consume_string("KEM").as_str() {
+        "DHKEM(X25519, HKDF-SHA256)" => &hpke::DHKEM_X25519_HKDF_SHA256,
+        "DHKEM(P-256, HKDF-SHA256)" => &hpke::DHKEM_P256_HKDF_SHA256,
+        kem => panic!("Unsupported KEM: {}", kem),
+    }
+}
+
+#[test]
+fn hpke_round_trip_test() {
+    for &kem in &[
+        &hpke::DHKEM_X25519_HKDF_SHA256,
+        &hpke::DHKEM_P256_HKDF_SHA256,
+    ] {
+        hpke_round_trip_test_(kem);
+    }
+}
+
+fn hpke_round_trip_test_(kem: &'static hpke::Kem) {
+    let rng = rand::SystemRandom::new();
+    let suite = hpke::Suite::new(kem, &hpke::HKDF_SHA256, &hpke::AES_256_GCM);
+    let info = b"info";
+
+    let recipient_key = hpke::PrivateKey::generate(suite.kem(), &rng).unwrap();
+    let (enc, mut sender) =
+        hpke::setup_sender(&suite, recipient_key.public_key().as_ref(), info, &rng).unwrap();
+
+    let messages: Vec<Vec<u8>> = (0..3u8)
+        .map(|i| {
+            let mut in_out = vec![i; 10];
+            sender
+                .seal_in_place_append_tag(aead::Aad::from([i]), &mut in_out)
+                .unwrap();
+            in_out
+        })
+        .collect();
+
+    // The messages must be opened in order.
+    {
+        let mut receiver =
+            hpke::setup_receiver(&suite, enc.as_ref(), &recipient_key, info).unwrap();
+        let mut in_out = messages[1].clone();
+        assert!(receiver
+            .open_in_place(aead::Aad::from([1]), &mut in_out)
+            .is_err());
+    }
+
+    // A failed open doesn't advance the sequence.
+    let mut receiver = hpke::setup_receiver(&suite, enc.as_ref(), &recipient_key, info).unwrap();
+    let mut tampered = messages[0].clone();
+    tampered[0] ^= 1;
+    assert!(receiver
+        .open_in_place(aead::Aad::from([0]), &mut tampered)
+        .is_err());
+    for (i, message) in messages.iter().enumerate() {
+        let i = i as u8;
+        let mut in_out = message.clone();
+        let opened = receiver
+            .open_in_place(aead::Aad::from([i]), &mut in_out)
+            .unwrap();
+        assert_eq!(opened, &vec![i; 10][..]);
+    }
+
+    // A different `info` results in a different context.
+    let mut receiver =
+        hpke::setup_receiver(&suite, enc.as_ref(), &recipient_key, b"other").unwrap();
+    let mut in_out = messages[0].clone();
+    assert!(receiver
+        .open_in_place(aead::Aad::from([0]), &mut in_out)
+        .is_err());
+
+    // The wrong recipient can't open the messages.
+    let other_key = hpke::PrivateKey::generate(suite.kem(), &rng).unwrap();
+    let mut receiver = hpke::setup_receiver(&suite, enc.as_ref(), &other_key, info).unwrap();
+    let mut in_out = messages[0].clone();
+    assert!(receiver
+        .open_in_place(aead::Aad::from([0]), &mut in_out)
+        .is_err());
+
+    // Malformed encapsulated keys are rejected.
+    assert!(hpke::setup_receiver(&suite, &enc.as_ref()[1..], &recipient_key, info).is_err());
+    assert!(hpke::setup_sender(&suite, &[0u8; 31], info, &rng).is_err());
+}
@@ -0,0 +1,115 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{error, kmac, test, test_file};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn kmac_tests() {
+    test::run(test_file!("kmac_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let algorithm = match test_case.consume_string("Algorithm").as_str() {
+            "KMAC128" => &kmac::KMAC128,
+            "KMAC256" => &kmac::KMAC256,
+            _ => unreachable!(),
+        };
+        let key = test_case.consume_bytes("Key");
+        let input = test_case.consume_bytes("Input");
+        let customization = test_case.consume_bytes("Customization");
+        let expected = test_case.consume_bytes("Tag");
+
+        let new_ctx = || kmac::Context::new(algorithm, &key, &customization);
+
+        // One-shot.
+        let mut ctx = new_ctx();
+        ctx.update(&input);
+        let mut tag = vec![0u8; expected.len()];
+        ctx.finish(&mut tag)?;
+        assert_eq!(tag, expected);
+
+        // Incremental, at every split point.
+        for i in 0..=input.len() {
+            let mut ctx = new_ctx();
+            let (a, b) = input.split_at(i);
+            ctx.update(a);
+            ctx.update(b);
+            ctx.verify(&expected)?;
+        }
+
+        // Any single-bit change to the tag is rejected.
+        for i in 0..(expected.len() * 8) {
+            let mut tag = expected.clone();
+            tag[i / 8] ^= 1 << (i % 8);
+            let mut ctx = new_ctx();
+            ctx.update(&input);
+            assert_eq!(ctx.verify(&tag), Err(error::Unspecified));
+        }
+
+        // The output length is bound into the tag, so a truncated tag isn't
+        // valid.
+        let mut ctx = new_ctx();
+        ctx.update(&input);
+        assert_eq!(
+            ctx.verify(&expected[..(expected.len() - 1)]),
+            Err(error::Unspecified)
+        );
+
+        // A different customization string gives a different tag.
+        let mut ctx = kmac::Context::new(algorithm, &key, b"Another Application");
+        ctx.update(&input);
+        assert_eq!(ctx.verify(&expected), Err(error::Unspecified));
+
+        // A modified message is rejected.
+        let mut ctx = new_ctx();
+        ctx.update(&input);
+        ctx.update(&[0]);
+        assert_eq!(ctx.verify(&expected), Err(error::Unspecified));
+
+        Ok(())
+    });
+}
+
+#[test]
+fn kmac_long_tag_test() {
+    // Tags longer than the rate require more than one squeeze.
+    for algorithm in [&kmac::KMAC128, &kmac::KMAC256] {
+        let mut tag = [0u8; 1000];
+        let ctx = kmac::Context::new(algorithm, b"key", b"");
+        ctx.clone().finish(&mut tag).unwrap();
+        assert!(ctx.verify(&tag).is_ok());
+    }
+}
+
+#[test]
+fn kmac_tag_too_short_test() {
+    for algorithm in [&kmac::KMAC128, &kmac::KMAC256] {
+        let ctx = kmac::Context::new(algorithm, b"key", b"");
+        let mut tag = [0u8; kmac::MIN_TAG_LEN - 1];
+        assert!(ctx.clone().finish(&mut tag).is_err());
+        assert!(ctx.clone().verify(&tag).is_err());
+        assert!(ctx.verify(&[]).is_err());
+    }
+}
+
+#[test]
+fn kmac_debug_test() {
+    assert_eq!("KMAC256", format!("{:?}", &kmac::KMAC256));
+    let ctx = kmac::Context::new(&kmac::KMAC128, b"key", b"");
+    assert_eq!("Context { algorithm: KMAC128 }", format!("{:?}", ctx));
+}
@@ -0,0 +1,164 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{merkle, rand, signature, test, test_file};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn hash(bytes: &[u8]) -> merkle::Hash {
+    bytes.try_into().unwrap()
+}
+
+#[test]
+fn merkle_verify_inclusion_test() {
+    test::run(test_file!("merkle_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let leaf = test_case.consume_bytes("Leaf");
+        let index = test_case.consume_usize("Index") as u64;
+        let tree_size = test_case.consume_usize("TreeSize") as u64;
+        let proof: Vec<merkle::Hash> = test_case
+            .consume_bytes("Proof")
+            .chunks(merkle::HASH_LEN)
+            .map(hash)
+            .collect();
+        let root = hash(&test_case.consume_bytes("Root"));
+
+        assert!(merkle::verify_inclusion(
+            &leaf, &proof, index, tree_size, &root
+        ));
+
+        // Each hash in the proof is checked.
+        for i in 0..proof.len() {
+            let mut tampered = proof.clone();
+            tampered[i][0] ^= 1;
+            assert!(!merkle::verify_inclusion(
+                &leaf, &tampered, index, tree_size, &root
+            ));
+        }
+
+        // The leaf, its position, the tree size, and the root are checked.
+        let mut tampered_leaf = leaf.clone();
+        tampered_leaf.push(0);
+        assert!(!merkle::verify_inclusion(
+            &tampered_leaf,
+            &proof,
+            index,
+            tree_size,
+            &root
+        ));
+        assert!(!merkle::verify_inclusion(
+            &leaf,
+            &proof,
+            index ^ 1,
+            tree_size,
+            &root
+        ));
+        assert!(!merkle::verify_inclusion(
+            &leaf, &proof, index, tree_size, &[0; 32]
+        ));
+        assert!(!merkle::verify_inclusion(
+            &leaf,
+            &proof,
+            index,
+            tree_size * 2,
+            &root
+        ));
+        assert!(!merkle::verify_inclusion(
+            &leaf, &proof, tree_size, tree_size, &root
+        ));
+
+        // The proof must be exactly the audit path.
+        let mut longer = proof.clone();
+        longer.push([0; 32]);
+        assert!(!merkle::verify_inclusion(
+            &leaf, &longer, index, tree_size, &root
+        ));
+        if let Some((_, shorter)) = proof.split_last() {
+            assert!(!merkle::verify_inclusion(
+                &leaf, shorter, index, tree_size, &root
+            ));
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn merkle_verify_signed_tree_head_test() {
+    const TIMESTAMP: u64 = 0x0000_018c_8b1a_2f00;
+    const TREE_SIZE: u64 = 8;
+    let root = hash(
+        &test::from_hex("5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328")
+            .unwrap(),
+    );
+
+    // The `TreeHeadSignature` structure of RFC 6962 Section 3.5.
+    let mut tree_head = vec![0, 1];
+    tree_head.extend_from_slice(&TIMESTAMP.to_be_bytes());
+    tree_head.extend_from_slice(&TREE_SIZE.to_be_bytes());
+    tree_head.extend_from_slice(&root);
+
+    let rng = rand::SystemRandom::new();
+    let pkcs8 =
+        signature::EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+            .unwrap();
+    let key_pair = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+        pkcs8.as_ref(),
+        &rng,
+    )
+    .unwrap();
+    let sig = key_pair.sign(&rng, &tree_head).unwrap();
+
+    use signature::KeyPair;
+    let log_key = signature::UnparsedPublicKey::new(
+        &signature::ECDSA_P256_SHA256_ASN1,
+        key_pair.public_key().as_ref(),
+    );
+    assert!(
+        merkle::verify_signed_tree_head(&log_key, TIMESTAMP, TREE_SIZE, &root, sig.as_ref())
+            .is_ok()
+    );
+
+    assert!(merkle::verify_signed_tree_head(
+        &log_key,
+        TIMESTAMP + 1,
+        TREE_SIZE,
+        &root,
+        sig.as_ref()
+    )
+    .is_err());
+    assert!(merkle::verify_signed_tree_head(
+        &log_key,
+        TIMESTAMP,
+        TREE_SIZE + 1,
+        &root,
+        sig.as_ref()
+    )
+    .is_err());
+    let mut tampered_root = root;
+    tampered_root[31] ^= 1;
+    assert!(merkle::verify_signed_tree_head(
+        &log_key,
+        TIMESTAMP,
+        TREE_SIZE,
+        &tampered_root,
+        sig.as_ref()
+    )
+    .is_err());
+}
@@ -0,0 +1,171 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+use ring::{aead, aead::multi_recipient, agreement, error, rand};
+
+const PLAINTEXT: &[u8] = b"attack at dawn";
+const AAD: &[u8] = b"header";
+
+#[test]
+fn multi_recipient_test() {
+    let rng = rand::SystemRandom::new();
+
+    for &key_alg in &[
+        &agreement::X25519,
+        &agreement::ECDH_P256,
+        &agreement::ECDH_P384,
+    ] {
+        for &content_alg in &[
+            &aead::AES_128_GCM,
+            &aead::AES_256_GCM,
+            &aead::CHACHA20_POLY1305,
+        ] {
+            multi_recipient_test_(key_alg, content_alg, &rng);
+        }
+    }
+}
+
+fn multi_recipient_test_(
+    key_alg: &'static agreement::Algorithm,
+    content_alg: &'static aead::Algorithm,
+    rng: &dyn rand::SecureRandom,
+) {
+    let alice = multi_recipient::RecipientPrivateKey::generate(key_alg, rng).unwrap();
+    let bob = multi_recipient::RecipientPrivateKey::generate(key_alg, rng).unwrap();
+    let eve = multi_recipient::RecipientPrivateKey::generate(key_alg, rng).unwrap();
+
+    let mut sealed = PLAINTEXT.to_vec();
+    let content_key = multi_recipient::seal_in_place_append_tag(
+        content_alg,
+        aead::Aad::from(AAD),
+        &mut sealed,
+        rng,
+    )
+    .unwrap();
+    assert_eq!(content_key.algorithm(), content_alg);
+    assert_eq!(sealed.len(), PLAINTEXT.len() + content_alg.tag_len());
+
+    let wrap = |recipient: &multi_recipient::RecipientPrivateKey| {
+        let public_key = agreement::UnparsedPublicKey::new(key_alg, recipient.public_key());
+        content_key.wrap(&public_key, rng).unwrap()
+    };
+    let for_alice = wrap(&alice);
+    let for_bob = wrap(&bob);
+
+    // Each recipient can unwrap its own key and open the payload.
+    for (recipient, wrapped) in &[(&alice, &for_alice), (&bob, &for_bob)] {
+        let content_key = recipient.unwrap(content_alg, wrapped.as_ref()).unwrap();
+        let mut in_out = sealed.clone();
+        let opened = content_key
+            .open_in_place(aead::Aad::from(AAD), &mut in_out)
+            .unwrap();
+        assert_eq!(opened, PLAINTEXT);
+    }
+
+    // A recipient can't unwrap a key wrapped for somebody else.
+    for wrapped in &[&for_alice, &for_bob] {
+        assert_eq!(
+            eve.unwrap(content_alg, wrapped.as_ref()).unwrap_err(),
+            error::Unspecified
+        );
+    }
+    assert!(alice.unwrap(content_alg, for_bob.as_ref()).is_err());
+
+    // Tampering with any byte of the wrapped key is detected.
+    for i in 0..for_alice.as_ref().len() {
+        let mut tampered = for_alice.as_ref().to_vec();
+        tampered[i] ^= 1;
+        assert!(alice.unwrap(content_alg, &tampered).is_err());
+    }
+
+    // Truncated and extended wrapped keys are rejected.
+    let wrapped = for_alice.as_ref();
+    assert!(alice
+        .unwrap(content_alg, &wrapped[..(wrapped.len() - 1)])
+        .is_err());
+    let mut extended = wrapped.to_vec();
+    extended.push(0);
+    assert!(alice.unwrap(content_alg, &extended).is_err());
+
+    // A key can only be unwrapped for the algorithm it was generated for,
+    // even one with the same key length.
+    for &other_alg in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::AES_256_GCM_SIV,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        if other_alg != content_alg {
+            assert!(alice.unwrap(other_alg, for_alice.as_ref()).is_err());
+        }
+    }
+
+    // The AAD is authenticated.
+    let content_key = alice.unwrap(content_alg, for_alice.as_ref()).unwrap();
+    let mut in_out = sealed.clone();
+    assert!(content_key
+        .open_in_place(aead::Aad::from(b"other"), &mut in_out)
+        .is_err());
+}
+
+#[test]
+fn multi_recipient_from_private_key_test() {
+    let rng = rand::SystemRandom::new();
+
+    let private_key: [u8; 32] = rand::generate(&rng).unwrap().expose();
+    let recipient =
+        multi_recipient::RecipientPrivateKey::from_private_key(&agreement::X25519, &private_key)
+            .unwrap();
+    let same =
+        multi_recipient::RecipientPrivateKey::from_private_key(&agreement::X25519, &private_key)
+            .unwrap();
+    assert_eq!(recipient.public_key().as_ref(), same.public_key().as_ref());
+
+    let mut sealed = PLAINTEXT.to_vec();
+    let content_key = multi_recipient::seal_in_place_append_tag(
+        &aead::AES_256_GCM,
+        aead::Aad::empty(),
+        &mut sealed,
+        &rng,
+    )
+    .unwrap();
+    let public_key = agreement::UnparsedPublicKey::new(&agreement::X25519, recipient.public_key());
+    let wrapped = content_key.wrap(&public_key, &rng).unwrap();
+
+    // A recipient reconstructed from the same private key can unwrap.
+    let content_key = same.unwrap(&aead::AES_256_GCM, wrapped.as_ref()).unwrap();
+    let opened = content_key
+        .open_in_place(aead::Aad::empty(), &mut sealed)
+        .unwrap();
+    assert_eq!(opened, PLAINTEXT);
+
+    // Wrong length.
+    assert!(multi_recipient::RecipientPrivateKey::from_private_key(
+        &agreement::X25519,
+        &private_key[1..]
+    )
+    .is_err());
+    // Out of range for P-256.
+    assert!(multi_recipient::RecipientPrivateKey::from_private_key(
+        &agreement::ECDH_P256,
+        &[0xff; 32]
+    )
+    .is_err());
+}
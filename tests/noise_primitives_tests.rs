@@ -0,0 +1,282 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{
+    aead, hkdf,
+    noise_primitives::{
+        AesGcm, Blake2s, ChaChaPoly, Cipher, Dh, Hash, Sha256, Sha512, CIPHER_KEY_LEN, X25519,
+    },
+    rand, test,
+};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn noise_dh_x25519() {
+    let dh: &dyn Dh = &X25519;
+    assert_eq!(dh.name(), "25519");
+    assert_eq!(dh.dh_len(), 32);
+
+    // From RFC 7748 Section 6.1.
+    let alice = dh
+        .keypair_from_private_key(&h(
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        ))
+        .unwrap();
+    let bob = dh
+        .keypair_from_private_key(&h(
+            "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+        ))
+        .unwrap();
+    assert_eq!(
+        alice.public_key(),
+        &h("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")[..]
+    );
+    assert_eq!(
+        bob.public_key(),
+        &h("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")[..]
+    );
+
+    let expected = h("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+    let mut out = [0u8; 32];
+    dh.dh(&alice, bob.public_key(), &mut out).unwrap();
+    assert_eq!(&out[..], &expected[..]);
+    // A key pair can be used any number of times.
+    dh.dh(&bob, alice.public_key(), &mut out).unwrap();
+    assert_eq!(&out[..], &expected[..]);
+    dh.dh(&bob, alice.public_key(), &mut out).unwrap();
+    assert_eq!(&out[..], &expected[..]);
+
+    // Generated key pairs agree too.
+    let rng = rand::SystemRandom::new();
+    let a = dh.generate_keypair(&rng).unwrap();
+    let b = dh.generate_keypair(&rng).unwrap();
+    let mut ab = [0u8; 32];
+    let mut ba = [0u8; 32];
+    dh.dh(&a, b.public_key(), &mut ab).unwrap();
+    dh.dh(&b, a.public_key(), &mut ba).unwrap();
+    assert_eq!(ab, ba);
+
+    // Small-order points and wrong lengths are rejected.
+    assert!(dh.dh(&a, &[0u8; 32], &mut out).is_err());
+    assert!(dh.dh(&a, &b.public_key()[..31], &mut out).is_err());
+    assert!(dh.dh(&a, b.public_key(), &mut [0u8; 31]).is_err());
+    assert!(dh.keypair_from_private_key(&[0u8; 31]).is_err());
+}
+
+#[test]
+fn noise_cipher() {
+    for (cipher, algorithm, expected_name) in [
+        (
+            &ChaChaPoly as &dyn Cipher,
+            &aead::CHACHA20_POLY1305,
+            "ChaChaPoly",
+        ),
+        (&AesGcm as &dyn Cipher, &aead::AES_256_GCM, "AESGCM"),
+    ] {
+        assert_eq!(cipher.name(), expected_name);
+
+        let k = [0x42u8; CIPHER_KEY_LEN];
+        let key = cipher.key(&k).unwrap();
+        let reference = aead::LessSafeKey::new(aead::UnboundKey::new(algorithm, &k).unwrap());
+
+        const N: u64 = 0x0102_0304_0506_0708;
+        const AD: &[u8] = b"associated data";
+        const PLAINTEXT: &[u8] = b"plaintext";
+
+        let mut sealed = PLAINTEXT.to_vec();
+        key.seal_in_place_append_tag(N, AD, &mut sealed).unwrap();
+
+        // The nonce is 32 bits of zeros followed by `n`, little-endian for
+        // ChaChaPoly and big-endian for AESGCM.
+        let n = if expected_name == "ChaChaPoly" {
+            N.to_le_bytes()
+        } else {
+            N.to_be_bytes()
+        };
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        nonce[4..].copy_from_slice(&n);
+        let mut expected = PLAINTEXT.to_vec();
+        reference
+            .seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(AD),
+                &mut expected,
+            )
+            .unwrap();
+        assert_eq!(sealed, expected);
+
+        let mut in_out = sealed.clone();
+        assert_eq!(key.open_in_place(N, AD, &mut in_out).unwrap(), PLAINTEXT);
+        let mut in_out = sealed.clone();
+        assert!(key.open_in_place(N + 1, AD, &mut in_out).is_err());
+        let mut in_out = sealed.clone();
+        assert!(key.open_in_place(N, b"", &mut in_out).is_err());
+
+        // The maximum nonce is reserved for `rekey()`.
+        let mut in_out = PLAINTEXT.to_vec();
+        assert!(key
+            .seal_in_place_append_tag(u64::MAX, AD, &mut in_out)
+            .is_err());
+        assert!(key.open_in_place(u64::MAX, AD, &mut sealed).is_err());
+
+        // REKEY(k) = ENCRYPT(k, maxnonce, zerolen, zeros)[..32].
+        let mut max_nonce = [0u8; aead::NONCE_LEN];
+        max_nonce[4..].fill(0xff);
+        let mut new_k = [0u8; CIPHER_KEY_LEN];
+        let _: aead::Tag = reference
+            .seal_in_place_separate_tag(
+                aead::Nonce::assume_unique_for_key(max_nonce),
+                aead::Aad::empty(),
+                &mut new_k,
+            )
+            .unwrap();
+        let mut rekeyed = cipher.key(&k).unwrap();
+        rekeyed.rekey().unwrap();
+        let expected_key = cipher.key(&new_k).unwrap();
+        let mut a = PLAINTEXT.to_vec();
+        let mut b = PLAINTEXT.to_vec();
+        rekeyed.seal_in_place_append_tag(0, AD, &mut a).unwrap();
+        expected_key
+            .seal_in_place_append_tag(0, AD, &mut b)
+            .unwrap();
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn noise_hash() {
+    // (hash, name, HASHLEN, BLOCKLEN, HASH(""), HASH("abc"))
+    let cases: [(&dyn Hash, &str, usize, usize, &str, &str); 3] = [
+        (
+            &Sha256,
+            "SHA256",
+            32,
+            64,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ),
+        (
+            &Sha512,
+            "SHA512",
+            64,
+            128,
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+             47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        ),
+        (
+            &Blake2s,
+            "BLAKE2s",
+            32,
+            64,
+            "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9",
+            "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982",
+        ),
+    ];
+    for (hash, name, hash_len, block_len, empty, abc) in cases {
+        assert_eq!(hash.name(), name);
+        assert_eq!(hash.hash_len(), hash_len);
+        assert_eq!(hash.block_len(), block_len);
+        assert_eq!(hash.hash(&[]).as_ref(), &h(empty)[..]);
+        assert_eq!(hash.hash(&[b""]).as_ref(), &h(empty)[..]);
+        assert_eq!(hash.hash(&[b"abc"]).as_ref(), &h(abc)[..]);
+        assert_eq!(hash.hash(&[b"a", b"", b"bc"]).as_ref(), &h(abc)[..]);
+    }
+}
+
+#[test]
+fn noise_hash_blake2s() {
+    // Multi-block inputs, including one that is exactly one block.
+    let input: Vec<u8> = (0..251u8).cycle().take(200).collect();
+    assert_eq!(
+        Blake2s.hash(&[&input]).as_ref(),
+        &h("6d244e1a06ce4ef578dd0f63aff0936706735119ca9c8d22d86c801414ab9741")[..]
+    );
+    assert_eq!(
+        Blake2s
+            .hash(&[&input[..10], &input[10..150], &input[150..]])
+            .as_ref(),
+        &h("6d244e1a06ce4ef578dd0f63aff0936706735119ca9c8d22d86c801414ab9741")[..]
+    );
+    assert_eq!(
+        Blake2s.hash(&[&input[..64]]).as_ref(),
+        &h("56f34e8b96557e90c1f24b52d0c89d51086acf1b00f634cf1dde9233b8eaaa3e")[..]
+    );
+
+    // HMAC-BLAKE2s, including with a key longer than a block.
+    let key: Vec<u8> = (0..32).collect();
+    assert_eq!(
+        Blake2s.hmac(&key, &[b"Hi ", b"There"]).as_ref(),
+        &h("65043ae3113da6f99a94d81b790a6a08dcddef85a14bb01d1aae2ae6d7250146")[..]
+    );
+    assert_eq!(
+        Blake2s.hmac(&[0xaa; 100], &[b"Hi There"]).as_ref(),
+        &h("c5940c3de6ec9ab10fc5e3404c8d7e7d3745a5ee5db29c17498da70617525596")[..]
+    );
+}
+
+#[test]
+fn noise_hkdf() {
+    // Noise's HKDF() is RFC 5869 HKDF with the chaining key as the salt and
+    // empty info.
+    for (hash, algorithm) in [
+        (&Sha256 as &dyn Hash, hkdf::HKDF_SHA256),
+        (&Sha512 as &dyn Hash, hkdf::HKDF_SHA512),
+    ] {
+        let chaining_key = hash.hash(&[b"Noise_XX_25519_AESGCM_SHA256"]);
+        let input_key_material = [0x0b; 32];
+
+        let len = 3 * hash.hash_len();
+        let mut expected = vec![0u8; len];
+        hkdf::Salt::new(algorithm, chaining_key.as_ref())
+            .extract(&input_key_material)
+            .expand(&[], Len(len))
+            .unwrap()
+            .fill(&mut expected)
+            .unwrap();
+        let expected: Vec<&[u8]> = expected.chunks(hash.hash_len()).collect();
+
+        let (output1, output2) = hash.hkdf2(chaining_key.as_ref(), &input_key_material);
+        assert_eq!(output1.as_ref(), expected[0]);
+        assert_eq!(output2.as_ref(), expected[1]);
+
+        let (output1, output2, output3) = hash.hkdf3(chaining_key.as_ref(), &input_key_material);
+        assert_eq!(output1.as_ref(), expected[0]);
+        assert_eq!(output2.as_ref(), expected[1]);
+        assert_eq!(output3.as_ref(), expected[2]);
+    }
+
+    // The outputs are secret, so they aren't shown.
+    let (output1, _) = Blake2s.hkdf2(&[0; 32], &[1; 32]);
+    assert_eq!(output1.as_ref().len(), 32);
+    assert_eq!(format!("{:?}", output1), "HashOutput { .. }");
+}
+
+struct Len(usize);
+
+impl hkdf::KeyType for Len {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+fn h(s: &str) -> Vec<u8> {
+    test::from_hex(s).unwrap()
+}
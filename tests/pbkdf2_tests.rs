@@ -13,7 +13,7 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use core::num::NonZeroU32;
-use ring::{digest, error, pbkdf2, test, test_file};
+use ring::{digest, error, hmac, pbkdf2, test, test_file};
 
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
@@ -66,3 +66,52 @@ pub fn pbkdf2_tests() {
         Ok(())
     });
 }
+
+/// A multi-block output is the concatenation of the blocks
+/// T_1 || T_2 || ..., where T_i is computed from the block index `i` as
+/// described in RFC 2898 Section 5.2.
+#[test]
+pub fn pbkdf2_multi_block_test() {
+    const ITERATIONS: u32 = 3;
+    const SECRET: &[u8] = b"password";
+    const SALT: &[u8] = b"salt";
+
+    for (algorithm, hmac_algorithm) in [
+        (
+            pbkdf2::PBKDF2_HMAC_SHA1,
+            hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+        ),
+        (pbkdf2::PBKDF2_HMAC_SHA256, hmac::HMAC_SHA256),
+        (pbkdf2::PBKDF2_HMAC_SHA512, hmac::HMAC_SHA512),
+    ] {
+        let key = hmac::Key::new(hmac_algorithm, SECRET);
+        let expected: Vec<u8> = (1u32..)
+            .flat_map(|i| {
+                let mut ctx = hmac::Context::with_key(&key);
+                ctx.update(SALT);
+                ctx.update(&i.to_be_bytes());
+                let mut u = ctx.sign();
+                let mut t = u.as_ref().to_vec();
+                for _ in 1..ITERATIONS {
+                    u = hmac::sign(&key, u.as_ref());
+                    t.iter_mut().zip(u.as_ref()).for_each(|(t, u)| *t ^= u);
+                }
+                t
+            })
+            .take(100)
+            .collect();
+
+        let iterations = NonZeroU32::new(ITERATIONS).unwrap();
+        let mut out = [0u8; 100];
+        pbkdf2::derive(algorithm, iterations, SALT, SECRET, &mut out);
+        assert_eq!(&out[..], &expected[..]);
+        assert!(pbkdf2::verify(algorithm, iterations, SALT, SECRET, &out).is_ok());
+
+        // Each prefix of the output is the output of that length.
+        for len in [1, 20, 32, 33, 64, 65, 99] {
+            let mut prefix = vec![0u8; len];
+            pbkdf2::derive(algorithm, iterations, SALT, SECRET, &mut prefix);
+            assert_eq!(&prefix[..], &out[..len]);
+        }
+    }
+}
@@ -29,6 +29,15 @@ fn quic_chacha20() {
     test_quic(&quic::CHACHA20, test_file!("quic_chacha20_tests.txt"));
 }
 
+#[test]
+fn quic_key_debug() {
+    let key = quic::HeaderProtectionKey::new(&quic::AES_128, &[0x5a; 16]).unwrap();
+    assert_eq!(
+        "HeaderProtectionKey { algorithm: AES_128 }",
+        format!("{:?}", key)
+    );
+}
+
 fn test_quic(alg: &'static quic::Algorithm, test_file: test::File) {
     test_sample_len(alg);
 
@@ -76,3 +76,83 @@ fn test_system_random_traits() {
         format!("{:?}", rand::SystemRandom::new())
     );
 }
+
+#[cfg(feature = "rand_core")]
+#[test]
+fn test_system_random_rand_core() {
+    use rand_core::RngCore;
+
+    fn assert_crypto_rng<R: rand_core::CryptoRng + RngCore>(_: &R) {}
+
+    let mut rng = rand::SystemRandom::new();
+    assert_crypto_rng(&rng);
+
+    for len in [0, 1, 31, 32, 33, 1000] {
+        let mut buf = vec![0u8; len];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf.len(), len);
+        if len >= 96 {
+            assert!(buf.iter().any(|x| *x != 0));
+        }
+
+        let mut buf = vec![0u8; len];
+        rng.try_fill_bytes(&mut buf).unwrap();
+        if len >= 96 {
+            assert!(buf.iter().any(|x| *x != 0));
+        }
+    }
+
+    // The chance that these are all equal is negligible.
+    let a = rng.next_u64();
+    assert!((0..4).any(|_| rng.next_u64() != a));
+    let a = rng.next_u32();
+    assert!((0..4).any(|_| rng.next_u32() != a));
+}
+
+#[test]
+fn test_system_random_with_additional_entropy() {
+    let a = rand::SystemRandom::with_additional_entropy(b"seed a");
+    let b = rand::SystemRandom::with_additional_entropy(b"seed b");
+
+    // The output is usable wherever a `SecureRandom` is expected.
+    let rngs: [&dyn rand::SecureRandom; 2] = [&a, &b];
+
+    // Use lengths that span multiple internal chunks.
+    for &len in &[0, 1, 32, 1023, 1024, 1025, 4096] {
+        let mut outputs = rngs.iter().map(|rng| {
+            let mut buf = vec![0; len];
+            rng.fill(&mut buf).unwrap();
+            buf
+        });
+        let (out_a, out_b) = (outputs.next().unwrap(), outputs.next().unwrap());
+        if len >= 32 {
+            assert_ne!(out_a, out_b);
+            assert!(out_a.iter().any(|x| *x != 0));
+        }
+    }
+
+    // Successive outputs of the same instance differ.
+    let first: [u8; 32] = rand::generate(&a).unwrap().expose();
+    let second: [u8; 32] = rand::generate(&a).unwrap().expose();
+    assert_ne!(first, second);
+
+    test::compile_time_assert_clone::<rand::SupplementedRandom>();
+    test::compile_time_assert_send::<rand::SupplementedRandom>();
+    test::compile_time_assert_sync::<rand::SupplementedRandom>();
+    assert_eq!("SupplementedRandom", format!("{:?}", a));
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn test_faulty_random() {
+    use ring::rand::{Fault, FaultyRandom};
+
+    let mut buf = [0u8; 5];
+
+    FaultyRandom::new(Fault::Constant(0xab))
+        .fill(&mut buf)
+        .unwrap();
+    assert_eq!(buf, [0xab; 5]);
+
+    assert!(FaultyRandom::new(Fault::Fail).fill(&mut buf).is_err());
+}
@@ -50,6 +50,49 @@ fn rsa_from_pkcs8_test() {
     );
 }
 
+#[test]
+fn rsa_from_encrypted_pkcs8_test() {
+    // `rsa_test_private_key_2048.p8` encrypted using
+    // `openssl pkcs8 -topk8 -v2 aes-256-cbc -v2prf hmacWithSHA256`.
+    const ENCRYPTED: &[u8] = include_bytes!("rsa_test_private_key_2048_encrypted.p8");
+    const PUBLIC_KEY: &[u8] = include_bytes!("rsa_test_public_key_2048.der");
+    const PASSPHRASE: &[u8] = b"correct horse battery staple";
+
+    let key_pair = rsa::KeyPair::from_encrypted_pkcs8(ENCRYPTED, PASSPHRASE).unwrap();
+    assert_eq!(key_pair.public_key().as_ref(), PUBLIC_KEY);
+
+    assert!(rsa::KeyPair::from_encrypted_pkcs8(ENCRYPTED, b"").is_err());
+}
+
+#[test]
+fn rsa_from_der_test() {
+    test::run(
+        test_file!("rsa_from_der_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let input = test_case.consume_bytes("Input");
+            let error = test_case.consume_optional_string("Error");
+
+            for result in [
+                rsa::KeyPair::from_der(&input),
+                rsa::KeyPair::from_pkcs1_der(&input),
+            ] {
+                match (result, &error) {
+                    (Ok(_), None) => {}
+                    (Err(e), None) => {
+                        panic!("Failed with error \"{}\", but expected to succeed", e)
+                    }
+                    (Ok(_), Some(e)) => panic!("Succeeded, but expected error \"{}\"", e),
+                    (Err(actual), Some(expected)) => assert_eq!(&format!("{}", actual), expected),
+                };
+            }
+
+            Ok(())
+        },
+    );
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn test_signature_rsa_pkcs1_sign() {
@@ -128,6 +171,93 @@ fn test_signature_rsa_pss_sign() {
     );
 }
 
+// PSS signatures differ only because of the random salt; the private key
+// operation itself is deterministic.
+#[test]
+fn test_signature_rsa_pss_sign_deterministic_given_salt() {
+    const MESSAGE: &[u8] = b"hello, world";
+    const PRIVATE_KEY_DER: &[u8] =
+        include_bytes!("../src/rsa/signature_rsa_example_private_key.der");
+    let key_pair = rsa::KeyPair::from_der(PRIVATE_KEY_DER).unwrap();
+    let public_key = signature::UnparsedPublicKey::new(
+        &signature::RSA_PSS_2048_8192_SHA256,
+        key_pair.public().as_ref(),
+    );
+
+    let sign = |rng: &dyn rand::SecureRandom| {
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(&signature::RSA_PSS_SHA256, rng, MESSAGE, &mut signature)
+            .unwrap();
+        public_key.verify(MESSAGE, &signature).unwrap();
+        signature
+    };
+
+    // With a random salt, the signatures differ.
+    let rng = rand::SystemRandom::new();
+    assert_ne!(sign(&rng), sign(&rng));
+
+    // With the same salt, the signatures are identical.
+    let salt = [0x5a; 32];
+    let rng = test::rand::FixedSliceRandom { bytes: &salt };
+    assert_eq!(sign(&rng), sign(&rng));
+}
+
+#[test]
+fn test_signature_rsa_pss_sha256_mgf1_sha1_verify() {
+    test::run(
+        test_file!("rsa_pss_sha256_mgf1_sha1_verify_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let mgf1_digest_name = test_case.consume_string("MGF1");
+            let salt_len = test_case.consume_usize("SaltLen");
+            let public_key = test_case.consume_bytes("Key");
+            let msg = test_case.consume_bytes("Msg");
+            let sig = test_case.consume_bytes("Sig");
+
+            // The MGF1 digest algorithm must match exactly.
+            let verify =
+                |alg| signature::UnparsedPublicKey::new(alg, &public_key).verify(&msg, &sig);
+            assert_eq!(
+                verify(&signature::RSA_PSS_2048_8192_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY).is_ok(),
+                mgf1_digest_name == "SHA1" && salt_len == 32
+            );
+            assert_eq!(
+                verify(&signature::RSA_PSS_2048_8192_SHA256).is_ok(),
+                mgf1_digest_name == "SHA256" && salt_len == 32
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_signature_rsa_pss_sha256_mgf1_sha1_sign() {
+    const MESSAGE: &[u8] = b"hello, world";
+    const PRIVATE_KEY_DER: &[u8] =
+        include_bytes!("../src/rsa/signature_rsa_example_private_key.der");
+    let key_pair = rsa::KeyPair::from_der(PRIVATE_KEY_DER).unwrap();
+    let rng = rand::SystemRandom::new();
+
+    let mut sig = vec![0; key_pair.public().modulus_len()];
+    key_pair
+        .sign(
+            &signature::RSA_PSS_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY,
+            &rng,
+            MESSAGE,
+            &mut sig,
+        )
+        .unwrap();
+
+    let verify = |alg| {
+        signature::UnparsedPublicKey::new(alg, key_pair.public().as_ref()).verify(MESSAGE, &sig)
+    };
+    assert!(verify(&signature::RSA_PSS_2048_8192_SHA256_MGF1_SHA1_FOR_LEGACY_USE_ONLY).is_ok());
+    assert!(verify(&signature::RSA_PSS_2048_8192_SHA256).is_err());
+}
+
 // `KeyPair::sign` requires that the output buffer is the same length as
 // the public key modulus. Test what happens when it isn't the same length.
 #[test]
@@ -307,6 +437,76 @@ fn test_signature_rsa_primitive_verification() {
 }
 
 #[cfg(feature = "alloc")]
+#[test]
+fn test_rsa_public_key_from_jwk() {
+    // The public key of `rsa_test_private_key_2048.p8`.
+    const N: &str = "yKeFAKWiUNuO02yFuNz4PEvhlTEU-qrHYW4Ookki-mt6sB-FWCyBXMO9617UZ2K8U2rMqot\
+                     ycFsAzvMWsuxQj7lpckG540I4QZzM9zOe64sGIUevT1ky9hPZvArnC_bVbUQy6D4TdnWHUx\
+                     v6ndVlMXQSRL516LySJrn6RLS4oQE1jX6Lt10MckpPEeznd3YmP67-eWEusdcWRud-iYKGa\
+                     -FADq_DWA0xObQaqnOAGHNy8i41vVWyiElhZciB7RVNWBEkXFLVbMCdSRbU8qULz1rgomN_\
+                     TPpr-dqvwRPbqDg7bdfabdjbIthRCo0xFZgzCJCaGgMyUXqlXoluFUJJsw";
+    // An RSASSA-PKCS1-v1_5 SHA-256 signature of "hello, world".
+    const SIGNATURE: &str =
+        "1c08b2694ec34767f94e147fdc2fe7c3986c1d75c58e07906b19f256aa889e8b92721fc6b7b063dfc6cded95\
+         2b26a9132d667f994acbdce3e878e2130df8e8aadfe9c3d542ee667791eece200fb4269c787d3fbcebd8fdcc\
+         980843b0a0f16df92db052d84cb2f1594803d96595f8ab88bd505d94b903375e746bedc6999ece8ed848f40e\
+         9d6f44a110f524e5cbe5b9a35f1da3e8508a4acccf34922bd83009e0e65e4b751bf13bece4a35689d95b1566\
+         31dd5e8f8ce3120dc0d955d2dcb6c1d3cbc746180c3b239fc914f68d2760b8cd61c211f5a000f344d953e7bb\
+         ea4beb5a49b797b8d5e5ed5351bc14db09fdc609475fd872ba22611a8de32eea156e4bae";
+    let signature = test::from_hex(SIGNATURE).unwrap();
+
+    let jwk = format!(
+        r#"{{ "kty": "RSA", "alg": "RS256", "key_ops": ["verify"], "n": "{}", "e": "AQAB" }}"#,
+        N
+    );
+    let public_key = signature::rsa_public_key_from_jwk(jwk.as_bytes()).unwrap();
+    assert_eq!(
+        public_key.as_ref(),
+        &include_bytes!("rsa_test_public_key_2048.der")[..]
+    );
+    let public_key =
+        signature::UnparsedPublicKey::new(&signature::RSA_PKCS1_2048_8192_SHA256, &public_key);
+    assert_eq!(public_key.verify(b"hello, world", &signature), Ok(()));
+    assert!(public_key.verify(b"hello, world!", &signature).is_err());
+
+    for jwk in &[
+        // Not JSON.
+        String::new(),
+        format!(r#"{{ "kty": "RSA", "n": "{}", "e": "AQAB", }}"#, N),
+        format!(r#"{{ "kty": "RSA", "n": "{}", "e": "AQAB" }} x"#, N),
+        format!(
+            r#"{{ "kty": "RSA", "x": [1, 2,], "n": "{}", "e": "AQAB" }}"#,
+            N
+        ),
+        format!(r#"{{ "kty": "RSA", "x": 01, "n": "{}", "e": "AQAB" }}"#, N),
+        // Missing or wrong members.
+        format!(r#"{{ "kty": "RSA", "n": "{}" }}"#, N),
+        format!(r#"{{ "kty": "EC", "n": "{}", "e": "AQAB" }}"#, N),
+        format!(r#"{{ "kty": "RSA", "n": "{}", "e": 65537 }}"#, N),
+        format!(
+            r#"{{ "kty": "RSA", "n": "{}", "e": "AQAB", "e": "Aw" }}"#,
+            N
+        ),
+        format!(r#"{{ "kty": "RSA", "\u006e": "{}", "e": "AQAB" }}"#, N),
+        // Not unpadded base64url.
+        format!(
+            r#"{{ "kty": "RSA", "n": "{}", "e": "AQAB" }}"#,
+            N.replace('-', "+")
+        ),
+        format!(r#"{{ "kty": "RSA", "n": "{}", "e": "AQAB=" }}"#, N),
+        // A leading zero in the exponent.
+        format!(r#"{{ "kty": "RSA", "n": "{}", "e": "AAEAAQ" }}"#, N),
+        // The modulus is too small.
+        r#"{ "kty": "RSA", "n": "AQAB", "e": "AQAB" }"#.to_string(),
+    ] {
+        assert!(
+            signature::rsa_public_key_from_jwk(jwk.as_bytes()).is_err(),
+            "{}",
+            jwk
+        );
+    }
+}
+
 #[test]
 fn rsa_test_keypair_coverage() {
     const PRIVATE_KEY: &[u8] = include_bytes!("rsa_test_private_key_2048.p8");
@@ -1,4 +1,8 @@
-use ring::{signature, test};
+use ring::{
+    error, rand,
+    signature::{self, KeyPair},
+    test,
+};
 
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
@@ -24,3 +28,339 @@ fn signature_impl_test() {
     // Test `AsRef<[u8]>`
     assert_eq!(unparsed_public_key.as_ref(), &[0x01, 0x02, 0x03]);
 }
+
+#[test]
+fn test_parse_public_key_ecdsa() {
+    let rng = rand::SystemRandom::new();
+    let pkcs8 =
+        signature::EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+            .unwrap();
+    let key_pair = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        pkcs8.as_ref(),
+        &rng,
+    )
+    .unwrap();
+    let sig = key_pair.sign(&rng, b"hello").unwrap();
+    let public_key = key_pair.public_key().as_ref();
+
+    let parsed =
+        signature::parse_public_key(&signature::ECDSA_P256_SHA256_FIXED, public_key).unwrap();
+    assert_eq!(parsed.as_ref(), public_key);
+    assert!(parsed.verify(b"hello", sig.as_ref()).is_ok());
+    assert!(parsed.verify(b"goodbye", sig.as_ref()).is_err());
+
+    // Off the curve.
+    let mut off_curve = public_key.to_vec();
+    *off_curve.last_mut().unwrap() ^= 1;
+    assert!(signature::parse_public_key(&signature::ECDSA_P256_SHA256_FIXED, &off_curve).is_err());
+
+    // Malformed encodings.
+    let mut wrong_form = public_key.to_vec();
+    wrong_form[0] = 0x05;
+    assert!(signature::parse_public_key(&signature::ECDSA_P256_SHA256_FIXED, &wrong_form).is_err());
+    assert!(signature::parse_public_key(
+        &signature::ECDSA_P256_SHA256_FIXED,
+        &public_key[..(public_key.len() - 1)]
+    )
+    .is_err());
+    assert!(signature::parse_public_key(&signature::ECDSA_P256_SHA256_FIXED, &[]).is_err());
+
+    // Wrong curve.
+    assert!(signature::parse_public_key(&signature::ECDSA_P384_SHA384_FIXED, public_key).is_err());
+}
+
+#[test]
+fn test_parse_public_key_ed25519() {
+    let key_pair = signature::Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let sig = key_pair.sign(b"hello");
+    let public_key = key_pair.public_key().as_ref();
+
+    let parsed = signature::parse_public_key(&signature::ED25519, public_key).unwrap();
+    assert!(parsed.verify(b"hello", sig.as_ref()).is_ok());
+    assert!(parsed.verify(b"goodbye", sig.as_ref()).is_err());
+
+    // Wrong lengths.
+    assert!(signature::parse_public_key(&signature::ED25519, &public_key[1..]).is_err());
+    assert!(signature::parse_public_key(&signature::ED25519, &[0; 33][..]).is_err());
+
+    // y = 2 doesn't correspond to any point on the curve.
+    let mut not_a_point = [0u8; 32];
+    not_a_point[0] = 2;
+    assert!(signature::parse_public_key(&signature::ED25519, &not_a_point[..]).is_err());
+
+    // `ED25519_VERIFY_STRICT` also rejects small-order and non-canonical keys.
+    assert!(signature::parse_public_key(&signature::ED25519_VERIFY_STRICT, public_key).is_ok());
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    assert!(signature::parse_public_key(&signature::ED25519, &identity[..]).is_ok());
+    assert!(signature::parse_public_key(&signature::ED25519_VERIFY_STRICT, &identity[..]).is_err());
+    // y = q + 3, a non-canonical encoding of a point of large order.
+    let mut non_canonical = [0xffu8; 32];
+    non_canonical[0] = 0xf0;
+    non_canonical[31] = 0x7f;
+    assert!(signature::parse_public_key(&signature::ED25519, &non_canonical[..]).is_ok());
+    assert!(
+        signature::parse_public_key(&signature::ED25519_VERIFY_STRICT, &non_canonical[..]).is_err()
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_parse_public_key_rsa() {
+    let key_pair =
+        signature::RsaKeyPair::from_pkcs8(include_bytes!("rsa_test_private_key_2048.p8")).unwrap();
+    let mut sig = vec![0; key_pair.public().modulus_len()];
+    key_pair
+        .sign(
+            &signature::RSA_PKCS1_SHA256,
+            &rand::SystemRandom::new(),
+            b"hello",
+            &mut sig,
+        )
+        .unwrap();
+    let public_key = include_bytes!("rsa_test_public_key_2048.der");
+
+    let parsed =
+        signature::parse_public_key(&signature::RSA_PKCS1_2048_8192_SHA256, &public_key[..])
+            .unwrap();
+    assert!(parsed.verify(b"hello", &sig).is_ok());
+    assert!(parsed.verify(b"goodbye", &sig).is_err());
+
+    // The key is too small for the algorithm.
+    assert!(
+        signature::parse_public_key(&signature::RSA_PKCS1_3072_8192_SHA384, &public_key[..])
+            .is_err()
+    );
+
+    // Malformed encodings.
+    assert!(signature::parse_public_key(
+        &signature::RSA_PKCS1_2048_8192_SHA256,
+        &public_key[..(public_key.len() - 1)]
+    )
+    .is_err());
+    assert!(signature::parse_public_key(&signature::RSA_PKCS1_2048_8192_SHA256, &[]).is_err());
+}
+
+#[test]
+fn test_verify_batch() {
+    test::compile_time_assert_send::<signature::VerifyItem>();
+    test::compile_time_assert_sync::<signature::VerifyItem>();
+
+    let rng = rand::SystemRandom::new();
+
+    let ed25519_pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    let ed25519 = signature::Ed25519KeyPair::from_pkcs8(ed25519_pkcs8.as_ref()).unwrap();
+    let ed25519_sig = ed25519.sign(b"ed25519");
+    let ed25519_public_key =
+        signature::UnparsedPublicKey::new(&signature::ED25519, ed25519.public_key().as_ref());
+
+    let ecdsa_pkcs8 =
+        signature::EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+            .unwrap();
+    let ecdsa = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+        ecdsa_pkcs8.as_ref(),
+        &rng,
+    )
+    .unwrap();
+    let ecdsa_sig = ecdsa.sign(&rng, b"ecdsa").unwrap();
+    let ecdsa_public_key = signature::UnparsedPublicKey::new(
+        &signature::ECDSA_P256_SHA256_ASN1,
+        ecdsa.public_key().as_ref(),
+    );
+
+    let rsa =
+        signature::RsaKeyPair::from_pkcs8(include_bytes!("rsa_test_private_key_2048.p8")).unwrap();
+    let mut rsa_sig = vec![0; rsa.public().modulus_len()];
+    rsa.sign(&signature::RSA_PSS_SHA256, &rng, b"rsa", &mut rsa_sig)
+        .unwrap();
+    let rsa_public_key = signature::UnparsedPublicKey::new(
+        &signature::RSA_PSS_2048_8192_SHA256,
+        rsa.public_key().as_ref(),
+    );
+
+    let mut bad_ecdsa_sig = ecdsa_sig.as_ref().to_vec();
+    *bad_ecdsa_sig.last_mut().unwrap() ^= 1;
+
+    let items = [
+        signature::VerifyItem::new(ed25519_public_key, b"ed25519", ed25519_sig.as_ref()),
+        signature::VerifyItem::new(ecdsa_public_key, b"ecdsa", ecdsa_sig.as_ref()),
+        signature::VerifyItem::new(rsa_public_key, b"rsa", &rsa_sig),
+        // Wrong message.
+        signature::VerifyItem::new(ed25519_public_key, b"ecdsa", ed25519_sig.as_ref()),
+        // Corrupted signature.
+        signature::VerifyItem::new(ecdsa_public_key, b"ecdsa", &bad_ecdsa_sig),
+        // Signature from a different algorithm.
+        signature::VerifyItem::new(rsa_public_key, b"ed25519", ed25519_sig.as_ref()),
+        signature::VerifyItem::new(ed25519_public_key, b"ed25519", ed25519_sig.as_ref()),
+    ];
+    let results = signature::verify_batch(&items);
+    assert_eq!(
+        results,
+        [
+            Ok(()),
+            Ok(()),
+            Ok(()),
+            Err(error::Unspecified),
+            Err(error::Unspecified),
+            Err(error::Unspecified),
+            Ok(()),
+        ]
+    );
+    #[cfg(feature = "std")]
+    for threads in 1..=(items.len() + 1) {
+        let threads = core::num::NonZeroUsize::new(threads).unwrap();
+        assert_eq!(
+            signature::verify_batch_with_threads(&items, threads),
+            results
+        );
+        assert!(signature::verify_batch_with_threads(&[], threads).is_empty());
+    }
+
+    assert!(signature::verify_batch(&[]).is_empty());
+}
+
+#[test]
+fn signature_is_known_weak_test() {
+    // Encodings of the Edwards25519 points of order 1, 2, 4, and 8.
+    const SMALL_ORDER_POINTS: &[&str] = &[
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000080",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc85",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+    ];
+    for point in SMALL_ORDER_POINTS {
+        let point = test::from_hex(point).unwrap();
+        assert!(signature::is_known_weak(&signature::ED25519, &point));
+    }
+
+    let ed25519_public_key = include_bytes!("ed25519_test_public_key.bin");
+    assert!(!signature::is_known_weak(
+        &signature::ED25519,
+        ed25519_public_key
+    ));
+    // Not a valid encoding.
+    assert!(!signature::is_known_weak(&signature::ED25519, &[1; 31]));
+
+    // u = 0 corresponds to the Edwards25519 point of order 2.
+    assert!(signature::is_known_weak(&signature::XED25519, &[0; 32]));
+    // u = 9 is the base point.
+    let mut base_point = [0; 32];
+    base_point[0] = 9;
+    assert!(!signature::is_known_weak(&signature::XED25519, &base_point));
+
+    let ecdsa_public_key = include_bytes!("ecdsa_test_public_key_p256.der");
+    assert!(!signature::is_known_weak(
+        &signature::ECDSA_P256_SHA256_ASN1,
+        ecdsa_public_key
+    ));
+
+    let rsa =
+        signature::RsaKeyPair::from_pkcs8(include_bytes!("rsa_test_private_key_2048.p8")).unwrap();
+    assert!(!signature::is_known_weak(
+        &signature::RSA_PKCS1_2048_8192_SHA256,
+        rsa.public_key().as_ref()
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_ec_public_key_from_jwk() {
+    const MESSAGE: &[u8] = b"hello, world";
+
+    // The public key of `ecdsa_test_private_key_p256.p8`.
+    const P256_X: &str = "_BFmmKPjI2VQxMnvqb1NBhlgKmXSkw6RUKsz6E28g_g";
+    const P256_Y: &str = "pqa5kz81q1kkXltaevXcp2szy-eu7lmBs8o1C-v1Ls0";
+    const P256_SIGNATURE: &str = "6c351020c82861e9091017c5b35e87f10d07d70c9f998691b8993e2875e17238\
+                                  de9f91a5257389db8d4dc40e96783f3668112f86fbdfcf76de579fb4c9679d51";
+
+    const P384_X: &str = "AO8trs3Jqy0VokJsqM315SE_tL2Nny5uYZCCyfo7Dyq-8wciQHn0m5N_v50yq0cn";
+    const P384_Y: &str = "9MBzkKpdVcfrULxPNRoFSWcHUR7PzmK5AbwiayZXjZcLLqAAoVSWVH1P016H3pmw";
+    const P384_SIGNATURE: &str =
+        "b1fbd75df35fb617a273a36192cef6a5d7628429c1e789e77bfb6b1a9252427f55c4232f609ca155884c108bf51baaeb\
+         7b9c5b802fce4afbad8607065a06ec9d04eb99be21425b2f32e0ada35fc05b468d44281bb527c730a4664f50c90f9f75";
+
+    // RFC 8037 Appendix A.2 and A.4.
+    const ED25519_X: &str = "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo";
+    const ED25519_MESSAGE: &[u8] = b"eyJhbGciOiJFZERTQSJ9.RXhhbXBsZSBvZiBFZDI1NTE5IHNpZ25pbmc";
+    const ED25519_SIGNATURE: &str = "860c98d2297f3060a33f42739672d61b53cf3adefed3d3c672f320dc021b411e\
+                                     9d59b8628dc351e248b88b29468e0e41855b0fb7d83bb15be902bfccb8cd0a02";
+
+    let cases: &[(String, &'static signature::EcdsaVerificationAlgorithm, &str)] = &[
+        (
+            format!(
+                r#"{{"kty":"EC","crv":"P-256","x":"{}","y":"{}"}}"#,
+                P256_X, P256_Y
+            ),
+            &signature::ECDSA_P256_SHA256_FIXED,
+            P256_SIGNATURE,
+        ),
+        (
+            format!(
+                r#"{{"kty":"EC","crv":"P-384","x":"{}","y":"{}","alg":"ES384"}}"#,
+                P384_X, P384_Y
+            ),
+            &signature::ECDSA_P384_SHA384_FIXED,
+            P384_SIGNATURE,
+        ),
+    ];
+    for (jwk, expected_algorithm, sig) in cases {
+        let public_key = signature::ec_public_key_from_jwk(jwk.as_bytes()).unwrap();
+        assert_eq!(
+            format!("{:?}", public_key.algorithm()),
+            format!("{:?}", expected_algorithm)
+        );
+        let sig = test::from_hex(sig).unwrap();
+        assert_eq!(public_key.verify(MESSAGE, &sig), Ok(()));
+        assert!(public_key.verify(b"goodbye", &sig).is_err());
+    }
+
+    let jwk = format!(r#"{{"kty":"OKP","crv":"Ed25519","x":"{}"}}"#, ED25519_X);
+    let public_key = signature::ec_public_key_from_jwk(jwk.as_bytes()).unwrap();
+    let sig = test::from_hex(ED25519_SIGNATURE).unwrap();
+    assert_eq!(public_key.verify(ED25519_MESSAGE, &sig), Ok(()));
+    assert!(public_key.verify(MESSAGE, &sig).is_err());
+
+    for jwk in &[
+        // Not on the curve.
+        format!(
+            r#"{{"kty":"EC","crv":"P-256","x":"{}","y":"q{}"}}"#,
+            P256_X,
+            &P256_Y[1..]
+        ),
+        // Unknown or mismatched curves.
+        format!(
+            r#"{{"kty":"EC","crv":"P-521","x":"{}","y":"{}"}}"#,
+            P256_X, P256_Y
+        ),
+        format!(
+            r#"{{"kty":"EC","crv":"P-384","x":"{}","y":"{}"}}"#,
+            P256_X, P256_Y
+        ),
+        format!(r#"{{"kty":"OKP","crv":"X25519","x":"{}"}}"#, ED25519_X),
+        format!(r#"{{"kty":"EC","crv":"Ed25519","x":"{}"}}"#, ED25519_X),
+        // Missing or extra coordinates.
+        format!(r#"{{"kty":"EC","crv":"P-256","x":"{}"}}"#, P256_X),
+        format!(
+            r#"{{"kty":"OKP","crv":"Ed25519","x":"{}","y":"{}"}}"#,
+            ED25519_X, P256_Y
+        ),
+        // A coordinate that isn't full-length.
+        format!(
+            r#"{{"kty":"EC","crv":"P-384","x":"{}","y":"{}"}}"#,
+            &P384_X[4..],
+            P384_Y
+        ),
+    ] {
+        assert!(
+            signature::ec_public_key_from_jwk(jwk.as_bytes()).is_err(),
+            "{}",
+            jwk
+        );
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+use ring::{aead, agreement, digest, signature};
+
+#[test]
+fn supported_algorithms_test() {
+    let supported = ring::supported_algorithms();
+
+    let aes_256_gcm = supported
+        .aeads()
+        .iter()
+        .find(|aead| aead.name() == "AES_256_GCM")
+        .unwrap();
+    assert_eq!(aes_256_gcm.algorithm(), &aead::AES_256_GCM);
+    assert_eq!(aes_256_gcm.key_len(), 32);
+    assert_eq!(aes_256_gcm.nonce_len(), aead::NONCE_LEN);
+    assert_eq!(aes_256_gcm.tag_len(), aead::MAX_TAG_LEN);
+
+    let sha256 = supported
+        .digests()
+        .iter()
+        .find(|digest| digest.name() == "SHA256")
+        .unwrap();
+    assert_eq!(sha256.algorithm(), &digest::SHA256);
+    assert_eq!(sha256.output_len(), digest::SHA256_OUTPUT_LEN);
+    assert_eq!(sha256.block_len(), 64);
+
+    let ecdsa = supported
+        .signature_verification()
+        .iter()
+        .find(|alg| alg.name() == "ECDSA_P256_SHA256_ASN1")
+        .unwrap();
+    assert_eq!(
+        format!("{:?}", ecdsa.algorithm()),
+        format!("{:?}", signature::ECDSA_P256_SHA256_ASN1)
+    );
+
+    let x25519 = supported
+        .agreements()
+        .iter()
+        .find(|alg| alg.name() == "X25519")
+        .unwrap();
+    assert_eq!(x25519.algorithm(), &agreement::X25519);
+    assert_eq!(x25519.public_key_len(), 32);
+
+    // Every entry is listed once.
+    fn assert_unique<'a>(names: impl Iterator<Item = &'a str>) {
+        let mut names: Vec<&str> = names.collect();
+        let len = names.len();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), len);
+    }
+    assert_unique(supported.aeads().iter().map(|a| a.name()));
+    assert_unique(supported.digests().iter().map(|a| a.name()));
+    assert_unique(supported.signature_verification().iter().map(|a| a.name()));
+    assert_unique(supported.agreements().iter().map(|a| a.name()));
+}
@@ -0,0 +1,416 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Statistical timing tests, in the style of dudect ("Dude, is my code
+//! constant time?", Reparaz, Balasch, and Verbauwhede, 2017).
+//!
+//! Each test measures the execution time of an operation for two classes of
+//! secret inputs: a fixed, deliberately "special" value (e.g. a low-weight
+//! scalar) and uniformly random values. The classes are interleaved randomly
+//! so that environmental noise affects both equally. Welch's t-test is then
+//! applied to the two timing distributions, both as measured and after
+//! cropping outliers at several percentiles. If any |t| exceeds
+//! `T_THRESHOLD` then the timing of the operation depends on the secret
+//! input, and the test fails.
+//!
+//! A passing test is evidence, not proof, of constant-time behavior; it only
+//! says that no leak was detected with the given number of measurements.
+//!
+//! These tests are slow and sensitive to noise from other processes, so they
+//! only run when the `timing-tests` feature is enabled. Run them in release
+//! mode on an otherwise-idle machine:
+//!
+//! ```sh
+//! cargo test --release --features=timing-tests,legacy-cbc --test timing_tests
+//! ```
+//!
+//! Operations covered:
+//!
+//! * `constant_time::verify_slices_are_equal`: the contents of the slices.
+//! * `constant_time::table_lookup`: the index.
+//! * X25519 (`agreement::agree_ephemeral`): the private scalar.
+//! * Ed25519 signing: the private key seed.
+//! * ECDSA P-256 signing: the private key.
+//! * AES-128-CBC-HMAC-SHA256 opening (with the `legacy-cbc` feature): whether
+//!   the padding of an authentic ciphertext is valid.
+
+#![cfg(feature = "timing-tests")]
+
+use ring::{agreement, constant_time, rand, signature, test};
+use std::time::Instant;
+
+/// dudect considers |t| > 10 to be a definite leak; smaller values are
+/// common on noisy machines even for constant-time code.
+const T_THRESHOLD: f64 = 10.0;
+
+#[test]
+fn timing_constant_time_verify_slices_are_equal() {
+    const LEN: usize = 256;
+    const REPETITIONS: usize = 16;
+
+    let rng = rand::SystemRandom::new();
+    let a: [u8; LEN] = rand::generate(&rng).unwrap().expose();
+
+    assert_constant_time(
+        "constant_time::verify_slices_are_equal",
+        200_000,
+        |class| match class {
+            Class::Fixed => a,
+            Class::Random => rand::generate(&rng).unwrap().expose(),
+        },
+        |b| {
+            for _ in 0..REPETITIONS {
+                let _ = core::hint::black_box(constant_time::verify_slices_are_equal(
+                    core::hint::black_box(&a),
+                    core::hint::black_box(&b),
+                ));
+            }
+        },
+    );
+}
+
+#[test]
+fn timing_constant_time_table_lookup() {
+    const N: usize = 96;
+    const ENTRIES: usize = 16;
+
+    use rand::SecureRandom;
+
+    let rng = rand::SystemRandom::new();
+    let mut table = [[0u8; N]; ENTRIES];
+    table.iter_mut().for_each(|entry| rng.fill(entry).unwrap());
+
+    assert_constant_time(
+        "constant_time::table_lookup",
+        200_000,
+        |class| match class {
+            Class::Fixed => 0,
+            Class::Random => rand::generate::<[u8; 1]>(&rng).unwrap().expose()[0] % 16,
+        },
+        |index| {
+            let mut out = [0u8; N];
+            constant_time::table_lookup(
+                core::hint::black_box(&table),
+                core::hint::black_box(index),
+                &mut out,
+            );
+            let _ = core::hint::black_box(out);
+        },
+    );
+}
+
+#[test]
+fn timing_x25519() {
+    let rng = rand::SystemRandom::new();
+    let peer_private_key =
+        agreement::EphemeralPrivateKey::generate(&agreement::X25519, &rng).unwrap();
+    let peer_public_key = peer_private_key.compute_public_key().unwrap();
+    let peer_public_key =
+        agreement::UnparsedPublicKey::new(&agreement::X25519, peer_public_key.as_ref().to_vec());
+
+    assert_constant_time(
+        "X25519",
+        20_000,
+        |class| {
+            let scalar = match class {
+                // Clamping makes this 2**254.
+                Class::Fixed => [0u8; 32],
+                Class::Random => rand::generate(&rng).unwrap().expose(),
+            };
+            agreement::EphemeralPrivateKey::generate(
+                &agreement::X25519,
+                &test::rand::FixedSliceRandom { bytes: &scalar },
+            )
+            .unwrap()
+        },
+        |private_key| {
+            agreement::agree_ephemeral(private_key, &peer_public_key, |shared| {
+                let _ = core::hint::black_box(shared);
+            })
+            .unwrap()
+        },
+    );
+}
+
+#[test]
+fn timing_ed25519_sign() {
+    const MESSAGE: &[u8] = b"message";
+
+    let rng = rand::SystemRandom::new();
+
+    assert_constant_time(
+        "Ed25519 signing",
+        20_000,
+        |class| {
+            let seed = match class {
+                Class::Fixed => [0u8; 32],
+                Class::Random => rand::generate(&rng).unwrap().expose(),
+            };
+            signature::Ed25519KeyPair::from_seed_unchecked(&seed).unwrap()
+        },
+        |key_pair| {
+            let _ = core::hint::black_box(key_pair.sign(MESSAGE));
+        },
+    );
+}
+
+#[test]
+fn timing_ecdsa_p256_sign() {
+    const MESSAGE: &[u8] = b"message";
+    const ALG: &signature::EcdsaSigningAlgorithm = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+
+    let rng = rand::SystemRandom::new();
+
+    assert_constant_time(
+        "ECDSA P-256 signing",
+        20_000,
+        |class| {
+            let private_key = match class {
+                Class::Fixed => {
+                    let mut one = [0u8; 32];
+                    one[31] = 1;
+                    one
+                }
+                Class::Random => rand::generate(&rng).unwrap().expose(),
+            };
+            let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(
+                ALG,
+                &test::rand::FixedSliceRandom {
+                    bytes: &private_key,
+                },
+            )
+            .unwrap();
+            signature::EcdsaKeyPair::from_pkcs8(ALG, pkcs8.as_ref(), &rng).unwrap()
+        },
+        |key_pair| {
+            let _ = core::hint::black_box(key_pair.sign(&rng, MESSAGE).unwrap());
+        },
+    );
+}
+
+#[cfg(feature = "legacy-cbc")]
+#[test]
+fn timing_cbc_hmac_open_padding() {
+    use ring::{aead, aead::cbc_hmac, hmac};
+
+    const PLAINTEXT_LEN: usize = 63;
+
+    let rng = rand::SystemRandom::new();
+    let key_bytes: [u8; cbc_hmac::KEY_LEN] = rand::generate(&rng).unwrap().expose();
+    let key = cbc_hmac::Aes128CbcHmacSha256::new(&key_bytes).unwrap();
+
+    // A ciphertext whose tag is valid but whose padding isn't. The plaintext
+    // ends in one byte of padding (0x01); flipping bits in the previous
+    // ciphertext block turns it into 0x81, and then the tag is recomputed.
+    let bad_padding = {
+        let mut sealed = key
+            .seal(&rng, aead::Aad::empty(), &[0u8; PLAINTEXT_LEN])
+            .unwrap();
+        let tag_start = sealed.len() - cbc_hmac::TAG_LEN;
+        sealed[tag_start - 16 - 1] ^= 0x80;
+        let mac_key = hmac::Key::new(hmac::HMAC_SHA256, &key_bytes[..16]);
+        let mut ctx = hmac::Context::with_key(&mac_key);
+        ctx.update(&sealed[..tag_start]);
+        ctx.update(&0u64.to_be_bytes());
+        let tag = ctx.sign();
+        sealed[tag_start..].copy_from_slice(&tag.as_ref()[..cbc_hmac::TAG_LEN]);
+        assert!(key
+            .open_in_place(aead::Aad::empty(), &mut sealed.clone())
+            .is_err());
+        sealed
+    };
+
+    assert_constant_time(
+        "AES-128-CBC-HMAC-SHA256 opening",
+        100_000,
+        |class| match class {
+            Class::Fixed => bad_padding.clone(),
+            Class::Random => {
+                let plaintext: [u8; PLAINTEXT_LEN] = rand::generate(&rng).unwrap().expose();
+                key.seal(&rng, aead::Aad::empty(), &plaintext).unwrap()
+            }
+        },
+        |mut in_out| {
+            let _ = core::hint::black_box(key.open_in_place(aead::Aad::empty(), &mut in_out));
+        },
+    );
+}
+
+#[derive(Clone, Copy)]
+enum Class {
+    Fixed,
+    Random,
+}
+
+/// Measures `operation` `num_measurements` times, each time with an input
+/// constructed by `prepare` for a randomly-chosen class, and panics if the
+/// t-test detects a difference between the classes.
+///
+/// Inputs are prepared in batches ahead of the measurements, as dudect does,
+/// so that the work done to prepare an input (e.g. calling into the operating
+/// system's RNG) doesn't disturb the caches differently for each class.
+fn assert_constant_time<I>(
+    name: &str,
+    num_measurements: usize,
+    mut prepare: impl FnMut(Class) -> I,
+    mut operation: impl FnMut(I),
+) {
+    const BATCH_SIZE: usize = 1000;
+
+    let rng = rand::SystemRandom::new();
+
+    let mut measurements = Vec::with_capacity(num_measurements);
+    while measurements.len() < num_measurements {
+        let batch_size = core::cmp::min(BATCH_SIZE, num_measurements - measurements.len());
+        let batch: Vec<(Class, I)> = (0..batch_size)
+            .map(|_| {
+                let coin: [u8; 1] = rand::generate(&rng).unwrap().expose();
+                let class = if coin[0] & 1 == 0 {
+                    Class::Fixed
+                } else {
+                    Class::Random
+                };
+                (class, prepare(class))
+            })
+            .collect();
+
+        for (class, input) in batch {
+            let start = Instant::now();
+            operation(input);
+            let elapsed = start.elapsed();
+
+            measurements.push((class, elapsed.as_nanos() as f64));
+        }
+    }
+
+    let t = max_abs_t(&measurements);
+    assert!(
+        t < T_THRESHOLD,
+        "{}: max |t| = {:.2} >= {}; timing depends on the secret input",
+        name,
+        t,
+        T_THRESHOLD
+    );
+}
+
+/// Returns the largest |t| statistic over the uncropped measurements and the
+/// measurements cropped at each of a set of percentiles, following dudect.
+fn max_abs_t(measurements: &[(Class, f64)]) -> f64 {
+    const PERCENTILES: &[f64] = &[0.5, 0.75, 0.9, 0.95, 0.99, 0.999];
+
+    let mut sorted: Vec<f64> = measurements.iter().map(|&(_, time)| time).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let thresholds = PERCENTILES
+        .iter()
+        .map(|&p| sorted[((sorted.len() as f64) * p) as usize])
+        .chain(core::iter::once(f64::INFINITY));
+
+    thresholds
+        .map(|threshold| {
+            let mut t_test = WelchTTest::default();
+            measurements
+                .iter()
+                .filter(|&&(_, time)| time <= threshold)
+                .for_each(|&(class, time)| t_test.push(class, time));
+            t_test.t().abs()
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Welch's t-test, using Welford's online algorithm for the mean and
+/// variance of each class.
+#[derive(Default)]
+struct WelchTTest {
+    classes: [Moments; 2],
+}
+
+#[derive(Default)]
+struct Moments {
+    n: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl WelchTTest {
+    fn push(&mut self, class: Class, x: f64) {
+        let moments = &mut self.classes[class as usize];
+        moments.n += 1.0;
+        let delta = x - moments.mean;
+        moments.mean += delta / moments.n;
+        moments.m2 += delta * (x - moments.mean);
+    }
+
+    fn t(&self) -> f64 {
+        let [a, b] = &self.classes;
+        if a.n < 2.0 || b.n < 2.0 {
+            return 0.0;
+        }
+        let var_a = a.m2 / (a.n - 1.0);
+        let var_b = b.m2 / (b.n - 1.0);
+        let denominator = (var_a / a.n + var_b / b.n).sqrt();
+        if denominator == 0.0 {
+            return 0.0;
+        }
+        (a.mean - b.mean) / denominator
+    }
+}
+
+#[test]
+fn welch_t_test_test() {
+    // Identical distributions.
+    let mut t_test = WelchTTest::default();
+    for i in 0..1000 {
+        t_test.push(Class::Fixed, f64::from(i % 10));
+        t_test.push(Class::Random, f64::from(i % 10));
+    }
+    assert!(t_test.t().abs() < 1e-9);
+
+    // Clearly different distributions.
+    let mut t_test = WelchTTest::default();
+    for i in 0..1000 {
+        t_test.push(Class::Fixed, f64::from(i % 10));
+        t_test.push(Class::Random, f64::from(i % 10) + 5.0);
+    }
+    assert!(t_test.t().abs() > T_THRESHOLD);
+}
+
+#[test]
+fn timing_harness_detects_leak() {
+    // An early-exit comparison is the canonical non-constant-time operation.
+    const LEN: usize = 4096;
+
+    let rng = rand::SystemRandom::new();
+    let a: [u8; LEN] = [0u8; LEN];
+
+    let measurements: Vec<(Class, f64)> = (0..20_000)
+        .map(|_| {
+            let coin: [u8; 1] = rand::generate(&rng).unwrap().expose();
+            let (class, b) = if coin[0] & 1 == 0 {
+                (Class::Fixed, a)
+            } else {
+                let mut b = a;
+                b[0] = 1;
+                (Class::Random, b)
+            };
+            let start = Instant::now();
+            let _ = core::hint::black_box(
+                core::hint::black_box(&a[..]) == core::hint::black_box(&b[..]),
+            );
+            (class, start.elapsed().as_nanos() as f64)
+        })
+        .collect();
+
+    assert!(max_abs_t(&measurements) >= T_THRESHOLD);
+}
@@ -0,0 +1,109 @@
+// Copyright 2024 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{
+    rand,
+    signature::{self, KeyPair, XEd25519KeyPair},
+    test, test_file,
+};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn test_signature_xed25519_sign() {
+    test::run(
+        test_file!("xed25519_sign_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let private_key = test_case.consume_bytes("PrivateKey");
+            let public_key = test_case.consume_bytes("PublicKey");
+            let msg = test_case.consume_bytes("Msg");
+            let random = test_case.consume_bytes("Random");
+            let expected_sig = test_case.consume_bytes("Sig");
+
+            let key_pair = XEd25519KeyPair::from_x25519_private_key(&private_key).unwrap();
+            assert_eq!(key_pair.public_key().as_ref(), &public_key[..]);
+
+            let rng = test::rand::FixedSliceRandom { bytes: &random };
+            let actual_sig = key_pair.sign(&rng, &msg).unwrap();
+            assert_eq!(actual_sig.as_ref(), &expected_sig[..]);
+
+            let public_key = signature::UnparsedPublicKey::new(&signature::XED25519, &public_key);
+            assert!(public_key.verify(&msg, &expected_sig).is_ok());
+            assert!(public_key.verify(b"tampered", &expected_sig).is_err());
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_signature_xed25519_verify() {
+    test::run(
+        test_file!("xed25519_verify_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let public_key = test_case.consume_bytes("PublicKey");
+            let msg = test_case.consume_bytes("Msg");
+            let sig = test_case.consume_bytes("Sig");
+            let expected_result = test_case.consume_string("Result");
+
+            let actual_result =
+                signature::UnparsedPublicKey::new(&signature::XED25519, &public_key)
+                    .verify(&msg, &sig);
+            assert_eq!(actual_result.is_ok(), expected_result == "P");
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_signature_xed25519_round_trip() {
+    let rng = rand::SystemRandom::new();
+    for i in 0u8..16 {
+        let private_key: [u8; 32] = rand::generate(&rng).unwrap().expose();
+        let key_pair = XEd25519KeyPair::from_x25519_private_key(&private_key).unwrap();
+        let msg = [i; 17];
+        let sig = key_pair.sign(&rng, &msg).unwrap();
+
+        let public_key =
+            signature::UnparsedPublicKey::new(&signature::XED25519, key_pair.public_key().as_ref());
+        assert!(public_key.verify(&msg, sig.as_ref()).is_ok());
+        assert!(public_key.verify(&msg[1..], sig.as_ref()).is_err());
+
+        // Signing is randomized.
+        let sig2 = key_pair.sign(&rng, &msg).unwrap();
+        assert_ne!(sig.as_ref(), sig2.as_ref());
+        assert!(public_key.verify(&msg, sig2.as_ref()).is_ok());
+    }
+}
+
+#[test]
+fn test_xed25519_key_pair_from_bad_lengths() {
+    assert!(XEd25519KeyPair::from_x25519_private_key(&[0; 31]).is_err());
+    assert!(XEd25519KeyPair::from_x25519_private_key(&[0; 33]).is_err());
+}
+
+#[test]
+fn test_xed25519_debug() {
+    assert_eq!(
+        "ring::signature::XED25519",
+        format!("{:?}", signature::XED25519)
+    );
+}